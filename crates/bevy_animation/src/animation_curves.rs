@@ -83,6 +83,7 @@
 //! [there]: AnimatableProperty
 //! [`animated_field`]: crate::animated_field
 
+use alloc::sync::Arc;
 use core::{
     any::TypeId,
     fmt::{self, Debug, Formatter},
@@ -92,6 +93,7 @@ use core::{
 #[cfg(feature = "bevy_mesh")]
 pub use crate::morph::*;
 use crate::{
+    compression::{compress_curve, KeyframeCompressionSettings},
     graph::AnimationNodeIndex,
     prelude::{Animatable, BlendInput},
     AnimationEntityMut, AnimationEvaluationError,
@@ -102,7 +104,7 @@ use bevy_math::curve::{
     Curve, Interval,
 };
 use bevy_platform::hash::Hashed;
-use bevy_reflect::{FromReflect, Reflect, Reflectable, TypeInfo, Typed};
+use bevy_reflect::{FromReflect, ParsedPath, Reflect, Reflectable, TypeInfo, Typed};
 use downcast_rs::{impl_downcast, Downcast};
 
 /// A trait for exposing a value in an entity so that it can be animated.
@@ -362,10 +364,7 @@ where
     }
 
     fn create_evaluator(&self) -> Box<dyn AnimationCurveEvaluator> {
-        Box::new(AnimatableCurveEvaluator::<P::Property> {
-            evaluator: BasicAnimationCurveEvaluator::default(),
-            property: Box::new(self.property.clone()),
-        })
+        create_animatable_evaluator(&self.property)
     }
 
     fn apply(
@@ -389,6 +388,43 @@ where
             });
         Ok(())
     }
+
+    fn compress(&self, settings: &KeyframeCompressionSettings) -> Option<Box<dyn AnimationCurve>> {
+        compress_curve(&self.property, &self.curve, settings)
+    }
+}
+
+/// Creates an [`AnimatableCurveEvaluator`] for the given `property`.
+pub(crate) fn create_animatable_evaluator<P>(property: &P) -> Box<dyn AnimationCurveEvaluator>
+where
+    P: AnimatableProperty + Clone,
+{
+    Box::new(AnimatableCurveEvaluator::<P::Property> {
+        evaluator: BasicAnimationCurveEvaluator::default(),
+        property: Box::new(property.clone()),
+    })
+}
+
+/// Pushes `value` onto the evaluation stack of `curve_evaluator`.
+///
+/// Returns `None` if `curve_evaluator` isn't an [`AnimatableCurveEvaluator`]
+/// for properties of type `A`.
+pub(crate) fn push_sample<A: Animatable>(
+    curve_evaluator: &mut dyn AnimationCurveEvaluator,
+    value: A,
+    weight: f32,
+    graph_node: AnimationNodeIndex,
+) -> Option<()> {
+    let curve_evaluator = curve_evaluator.downcast_mut::<AnimatableCurveEvaluator<A>>()?;
+    curve_evaluator
+        .evaluator
+        .stack
+        .push(BasicAnimationCurveEvaluatorStackElement {
+            value,
+            weight,
+            graph_node,
+        });
+    Some(())
 }
 
 impl<A: Animatable> AnimationCurveEvaluator for AnimatableCurveEvaluator<A> {
@@ -558,7 +594,7 @@ where
 /// mutated in the implementation of [`apply`].
 ///
 /// [`apply`]: AnimationCurve::apply
-pub trait AnimationCurve: Debug + Send + Sync + 'static {
+pub trait AnimationCurve: Downcast + Debug + Send + Sync + 'static {
     /// Returns a boxed clone of this value.
     fn clone_value(&self) -> Box<dyn AnimationCurve>;
 
@@ -600,11 +636,22 @@ pub trait AnimationCurve: Debug + Send + Sync + 'static {
         weight: f32,
         graph_node: AnimationNodeIndex,
     ) -> Result<(), AnimationEvaluationError>;
+
+    /// Returns a copy of this curve with redundant keyframes removed according
+    /// to `settings`.
+    ///
+    /// Returns `None` if this curve doesn't support compression, in which case
+    /// it's left as is. See [`AnimationClip::compress_keyframes`].
+    ///
+    /// [`AnimationClip::compress_keyframes`]: crate::AnimationClip::compress_keyframes
+    fn compress(&self, _settings: &KeyframeCompressionSettings) -> Option<Box<dyn AnimationCurve>> {
+        None
+    }
 }
 
 /// The [`EvaluatorId`] is used to look up the [`AnimationCurveEvaluator`] for an [`AnimatableProperty`].
 /// For a given animated property, this ID should always be the same to allow things like animation blending to occur.
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum EvaluatorId<'a> {
     /// Corresponds to a specific field on a specific component type.
     /// The `TypeId` should correspond to the component type, and the `usize`
@@ -613,11 +660,52 @@ pub enum EvaluatorId<'a> {
     // IMPLEMENTATION NOTE: The Hashed<(TypeId, usize) is intentionally cheap to clone, as it will be cloned per frame by the evaluator
     // Switching the field index `usize` for something like a field name `String` would probably be too expensive to justify
     ComponentField(&'a Hashed<(TypeId, usize)>),
+    /// Corresponds to a property reached through a reflection path from a
+    /// specific component type. The `TypeId` should correspond to the component
+    /// type.
+    ///
+    /// This is used by [`ReflectPathProperty`](crate::reflect_curve::ReflectPathProperty).
+    /// The path is reference-counted so that cloning the ID stays cheap.
+    ComponentPath(&'a Hashed<(TypeId, Arc<ParsedPath>)>),
     /// Corresponds to a custom property of a given type. This should be the [`TypeId`]
     /// of the custom [`AnimatableProperty`].
     Type(TypeId),
 }
 
+/// Samples `curve` at time `t` using `curve_evaluator`, returning the value
+/// directly instead of pushing it onto the evaluation stack.
+///
+/// Returns `None` if `curve` doesn't animate a property of type `A` through an
+/// [`AnimatableCurveEvaluator`]. The `curve_evaluator` must have been created
+/// by `curve` and must be empty.
+pub(crate) fn sample_animatable<A: Animatable>(
+    curve: &dyn AnimationCurve,
+    curve_evaluator: &mut dyn AnimationCurveEvaluator,
+    t: f32,
+) -> Option<A> {
+    curve
+        .apply(curve_evaluator, t, 1.0, AnimationNodeIndex::default())
+        .ok()?;
+    let curve_evaluator = curve_evaluator.downcast_mut::<AnimatableCurveEvaluator<A>>()?;
+    let value = curve_evaluator.evaluator.stack.pop()?.value;
+    curve_evaluator.evaluator.stack.clear();
+    Some(value)
+}
+
+/// Calls `f` on the value on top of the evaluation stack of `curve_evaluator`.
+///
+/// Returns `None` if `curve_evaluator` isn't an [`AnimatableCurveEvaluator`]
+/// for properties of type `A` or if its stack is empty.
+pub(crate) fn map_top_sample<A: Animatable>(
+    curve_evaluator: &mut dyn AnimationCurveEvaluator,
+    f: impl FnOnce(&mut A),
+) -> Option<()> {
+    let curve_evaluator = curve_evaluator.downcast_mut::<AnimatableCurveEvaluator<A>>()?;
+    let element = curve_evaluator.evaluator.stack.last_mut()?;
+    f(&mut element.value);
+    Some(())
+}
+
 /// A low-level trait for use in [`VariableCurve`](`crate::VariableCurve`) that provides fine
 /// control over how animations are evaluated.
 ///
@@ -703,6 +791,7 @@ pub trait AnimationCurveEvaluator: Downcast + Send + Sync + 'static {
     fn commit(&mut self, entity: AnimationEntityMut) -> Result<(), AnimationEvaluationError>;
 }
 
+impl_downcast!(AnimationCurve);
 impl_downcast!(AnimationCurveEvaluator);
 
 /// A [curve] defined by keyframes with values in an [animatable] type.
@@ -751,6 +840,11 @@ where
             core: UnevenCore::new(keyframes)?,
         })
     }
+
+    /// The keyframes underlying this [`AnimatableKeyframeCurve`].
+    pub(crate) fn core(&self) -> &UnevenCore<T> {
+        &self.core
+    }
 }
 
 fn inconsistent<P>() -> AnimationEvaluationError
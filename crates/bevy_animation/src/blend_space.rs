@@ -0,0 +1,520 @@
+//! Blend spaces, which weight animations according to one or two parameters.
+//!
+//! A blend space is a node in an [`AnimationGraph`](crate::graph::AnimationGraph)
+//! whose children are clips placed at positions in a one- or two-dimensional
+//! parameter space. Every frame, the *blend position* of the blend space (for
+//! example the speed and direction of a character) is used to pick the clips
+//! closest to it and to interpolate their weights, so that the application
+//! doesn't have to set the weights by hand.
+//!
+//! The clips of a blend space play in lockstep: they are time-scaled so that
+//! they all start and finish their cycles together, which keeps things like
+//! footfalls aligned while blending between walking and running.
+
+use bevy_math::{DVec2, Vec2};
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::graph::AnimationNodeIndex;
+
+/// The nodes that a blend space blends at a given blend position, along with
+/// their weights.
+///
+/// The weights are positive and sum to 1.0. At most three nodes are ever
+/// blended at once.
+pub type BlendSpaceWeights = SmallVec<[(AnimationNodeIndex, f32); 3]>;
+
+/// A sample of a [`BlendSpace1d`]: a node placed at a position on a line.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct BlendSpace1dSample {
+    /// The node played at this position, typically a clip.
+    pub node: AnimationNodeIndex,
+    /// The position of the sample.
+    pub position: f32,
+}
+
+/// A *1D blend space*, which blends its samples according to a single
+/// parameter.
+///
+/// The two samples surrounding the blend position are linearly interpolated.
+/// Blend positions before the first sample or after the last one play only
+/// that sample.
+#[derive(Clone, Debug, Default, Reflect)]
+#[reflect(Clone, Debug, Default)]
+pub struct BlendSpace1d {
+    /// The samples, sorted by position.
+    samples: Vec<BlendSpace1dSample>,
+}
+
+impl BlendSpace1d {
+    /// Creates a new 1D blend space from the given samples.
+    pub fn new(samples: impl IntoIterator<Item = BlendSpace1dSample>) -> Self {
+        let mut samples: Vec<_> = samples.into_iter().collect();
+        samples.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self { samples }
+    }
+
+    /// Returns the samples of this blend space, sorted by position.
+    pub fn samples(&self) -> &[BlendSpace1dSample] {
+        &self.samples
+    }
+
+    /// Returns the nodes to blend at the given position, along with their
+    /// weights.
+    pub fn weights(&self, position: f32) -> BlendSpaceWeights {
+        let mut weights = BlendSpaceWeights::new();
+        let (Some(first), Some(last)) = (self.samples.first(), self.samples.last()) else {
+            return weights;
+        };
+
+        if position.is_nan() || position <= first.position {
+            weights.push((first.node, 1.0));
+        } else if position >= last.position {
+            weights.push((last.node, 1.0));
+        } else {
+            // `first.position < position < last.position`, so this is in
+            // `1..samples.len()` and `a.position <= position < b.position`.
+            let index = self
+                .samples
+                .partition_point(|sample| sample.position <= position);
+            let (a, b) = (self.samples[index - 1], self.samples[index]);
+            let t = (position - a.position) / (b.position - a.position);
+            push_weight(&mut weights, a.node, 1.0 - t);
+            push_weight(&mut weights, b.node, t);
+        }
+        weights
+    }
+}
+
+/// A sample of a [`BlendSpace2d`]: a node placed at a position on a plane.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct BlendSpace2dSample {
+    /// The node played at this position, typically a clip.
+    pub node: AnimationNodeIndex,
+    /// The position of the sample.
+    pub position: Vec2,
+}
+
+/// A *2D blend space*, which blends its samples according to two parameters.
+///
+/// The samples are triangulated when the blend space is created. The three
+/// samples of the triangle containing the blend position are blended with
+/// barycentric interpolation. Blend positions outside of all triangles are
+/// projected onto the closest edge, and the two samples of that edge are
+/// linearly interpolated.
+#[derive(Clone, Debug, Default, Reflect)]
+#[reflect(Clone, Debug, Default)]
+pub struct BlendSpace2d {
+    samples: Vec<BlendSpace2dSample>,
+    /// The Delaunay triangulation of the sample positions, as indices into
+    /// `samples`.
+    triangles: Vec<[u32; 3]>,
+}
+
+impl BlendSpace2d {
+    /// Creates a new 2D blend space from the given samples.
+    pub fn new(samples: impl IntoIterator<Item = BlendSpace2dSample>) -> Self {
+        let samples: Vec<_> = samples.into_iter().collect();
+        let triangles = triangulate(&samples);
+        Self { samples, triangles }
+    }
+
+    /// Returns the samples of this blend space.
+    pub fn samples(&self) -> &[BlendSpace2dSample] {
+        &self.samples
+    }
+
+    /// Returns the nodes to blend at the given position, along with their
+    /// weights.
+    pub fn weights(&self, position: Vec2) -> BlendSpaceWeights {
+        let mut weights = BlendSpaceWeights::new();
+        match *self.samples.as_slice() {
+            [] => return weights,
+            [sample] => {
+                weights.push((sample.node, 1.0));
+                return weights;
+            }
+            _ => {}
+        }
+
+        let point = |index: u32| self.samples[index as usize].position;
+        for &[a, b, c] in &self.triangles {
+            let Some([u, v, w]) = barycentric(position, point(a), point(b), point(c)) else {
+                continue;
+            };
+            if u >= -BARYCENTRIC_EPSILON && v >= -BARYCENTRIC_EPSILON && w >= -BARYCENTRIC_EPSILON {
+                let (u, v, w) = (u.max(0.0), v.max(0.0), w.max(0.0));
+                let sum = u + v + w;
+                push_weight(&mut weights, self.samples[a as usize].node, u / sum);
+                push_weight(&mut weights, self.samples[b as usize].node, v / sum);
+                push_weight(&mut weights, self.samples[c as usize].node, w / sum);
+                return weights;
+            }
+        }
+
+        // The position is outside of the triangulation, or there isn't one
+        // because the samples are collinear: blend along the closest edge.
+        let mut closest: Option<(f32, u32, u32, f32)> = None;
+        let mut consider = |a: u32, b: u32| {
+            let (start, end) = (point(a), point(b));
+            let segment = end - start;
+            let t = match segment.length_squared() {
+                0.0 => 0.0,
+                length_squared => {
+                    ((position - start).dot(segment) / length_squared).clamp(0.0, 1.0)
+                }
+            };
+            let distance = position.distance_squared(start + segment * t);
+            if closest.is_none_or(|(closest_distance, ..)| distance < closest_distance) {
+                closest = Some((distance, a, b, t));
+            }
+        };
+        if self.triangles.is_empty() {
+            let count = self.samples.len() as u32;
+            for a in 0..count {
+                for b in (a + 1)..count {
+                    consider(a, b);
+                }
+            }
+        } else {
+            for &[a, b, c] in &self.triangles {
+                consider(a, b);
+                consider(b, c);
+                consider(c, a);
+            }
+        }
+
+        if let Some((_, a, b, t)) = closest {
+            push_weight(&mut weights, self.samples[a as usize].node, 1.0 - t);
+            push_weight(&mut weights, self.samples[b as usize].node, t);
+        }
+        weights
+    }
+}
+
+/// How far outside of a triangle a position may be, in barycentric
+/// coordinates, and still be considered inside it.
+const BARYCENTRIC_EPSILON: f32 = 1e-5;
+
+/// Adds `node` to `weights`, unless its weight is zero.
+fn push_weight(weights: &mut BlendSpaceWeights, node: AnimationNodeIndex, weight: f32) {
+    if weight > 0.0 {
+        weights.push((node, weight));
+    }
+}
+
+/// Returns the barycentric coordinates of `p` with respect to the triangle
+/// `abc`, or `None` if the triangle is degenerate.
+fn barycentric(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> Option<[f32; 3]> {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let denominator = ab.perp_dot(ac);
+    if denominator == 0.0 {
+        return None;
+    }
+    let v = ap.perp_dot(ac) / denominator;
+    let w = ab.perp_dot(ap) / denominator;
+    Some([1.0 - v - w, v, w])
+}
+
+/// Computes the Delaunay triangulation of the positions of `samples` with the
+/// Bowyer–Watson algorithm.
+///
+/// The samples are inserted one at a time into a triangulation of a triangle
+/// enclosing all of them. Each insertion removes the triangles whose
+/// circumcircles contain the new sample and connects it to the boundary of the
+/// resulting cavity. Triangles touching the enclosing triangle are discarded at
+/// the end. Samples at the same position as an earlier sample are skipped.
+fn triangulate(samples: &[BlendSpace2dSample]) -> Vec<[u32; 3]> {
+    let count = samples.len();
+    if count < 3 {
+        return vec![];
+    }
+
+    // Work in coordinates normalized to `[-1, 1]`, which keeps the enclosing
+    // triangle and the epsilons independent of the scale of the blend space.
+    let (min, max) = samples.iter().fold(
+        (DVec2::INFINITY, DVec2::NEG_INFINITY),
+        |(min, max), sample| {
+            let position = sample.position.as_dvec2();
+            (min.min(position), max.max(position))
+        },
+    );
+    if !min.is_finite() || !max.is_finite() {
+        return vec![];
+    }
+    let center = (min + max) * 0.5;
+    let half_extent = ((max - min) * 0.5).max_element().max(f64::MIN_POSITIVE);
+    let mut points: Vec<DVec2> = samples
+        .iter()
+        .map(|sample| (sample.position.as_dvec2() - center) / half_extent)
+        .collect();
+    // The enclosing triangle, whose vertices have indices `count..count + 3`.
+    points.extend([
+        DVec2::new(-ENCLOSING_SIZE, -ENCLOSING_SIZE),
+        DVec2::new(ENCLOSING_SIZE, -ENCLOSING_SIZE),
+        DVec2::new(0.0, ENCLOSING_SIZE),
+    ]);
+
+    // Triangles are kept in counterclockwise order.
+    let mut triangles = vec![[count, count + 1, count + 2]];
+    let mut edges: Vec<[usize; 2]> = vec![];
+    for (index, &point) in points[..count].iter().enumerate() {
+        if points[..index]
+            .iter()
+            .any(|&other| other.distance_squared(point) <= DUPLICATE_EPSILON)
+        {
+            continue;
+        }
+
+        // Remove the triangles whose circumcircles contain the point, and
+        // collect the edges of the cavity they leave behind. Edges shared by
+        // two removed triangles appear in both directions and cancel out.
+        edges.clear();
+        triangles.retain(|&[a, b, c]| {
+            if in_circle(points[a], points[b], points[c], point) <= CIRCLE_EPSILON {
+                return true;
+            }
+            for edge in [[a, b], [b, c], [c, a]] {
+                match edges.iter().position(|&[x, y]| [y, x] == edge) {
+                    Some(twin) => {
+                        edges.swap_remove(twin);
+                    }
+                    None => edges.push(edge),
+                }
+            }
+            false
+        });
+        triangles.extend(edges.iter().map(|&[a, b]| [a, b, index]));
+    }
+
+    triangles
+        .into_iter()
+        .filter(|triangle| triangle.iter().all(|&index| index < count))
+        .filter(|&[a, b, c]| (points[b] - points[a]).perp_dot(points[c] - points[a]) > AREA_EPSILON)
+        .map(|triangle| triangle.map(|index| index as u32))
+        .collect()
+}
+
+/// The half-size of the triangle enclosing the normalized sample positions
+/// during triangulation.
+const ENCLOSING_SIZE: f64 = 1e3;
+
+/// How close two normalized sample positions have to be, squared, to be
+/// considered the same during triangulation.
+const DUPLICATE_EPSILON: f64 = 1e-18;
+
+/// How far inside of a circumcircle a normalized sample position has to be to
+/// invalidate a triangle during triangulation.
+const CIRCLE_EPSILON: f64 = 1e-12;
+
+/// The smallest doubled area of a triangle kept by the triangulation, in
+/// normalized coordinates.
+const AREA_EPSILON: f64 = 1e-12;
+
+/// Returns a value that is positive if `d` lies inside the circumcircle of the
+/// counterclockwise triangle `abc`, negative if it lies outside of it, and zero
+/// if it lies on it.
+fn in_circle(a: DVec2, b: DVec2, c: DVec2, d: DVec2) -> f64 {
+    let (a, b, c) = (a - d, b - d, c - d);
+    a.length_squared() * b.perp_dot(c) - b.length_squared() * a.perp_dot(c)
+        + c.length_squared() * a.perp_dot(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_asset::Assets;
+    use bevy_time::Time;
+    use core::time::Duration;
+
+    use super::*;
+    use crate::{
+        advance_animations,
+        graph::{AnimationGraph, AnimationGraphHandle, AnimationNodeType},
+        AnimationClip, AnimationPlayer,
+    };
+
+    fn node(index: u32) -> AnimationNodeIndex {
+        AnimationNodeIndex::new(index as usize)
+    }
+
+    fn weight_of(weights: &BlendSpaceWeights, index: u32) -> f32 {
+        weights
+            .iter()
+            .find(|(node_index, _)| *node_index == node(index))
+            .map_or(0.0, |(_, weight)| *weight)
+    }
+
+    #[test]
+    fn blend_space_1d_weights() {
+        let blend_space = BlendSpace1d::new([
+            BlendSpace1dSample {
+                node: node(2),
+                position: 4.0,
+            },
+            BlendSpace1dSample {
+                node: node(0),
+                position: 0.0,
+            },
+            BlendSpace1dSample {
+                node: node(1),
+                position: 1.0,
+            },
+        ]);
+
+        assert_eq!(blend_space.weights(-1.0).as_slice(), &[(node(0), 1.0)]);
+        assert_eq!(blend_space.weights(0.0).as_slice(), &[(node(0), 1.0)]);
+        assert_eq!(blend_space.weights(1.0).as_slice(), &[(node(1), 1.0)]);
+        assert_eq!(blend_space.weights(10.0).as_slice(), &[(node(2), 1.0)]);
+
+        let weights = blend_space.weights(2.5);
+        assert_eq!(weights.len(), 2);
+        assert!((weight_of(&weights, 1) - 0.5).abs() < 1e-6);
+        assert!((weight_of(&weights, 2) - 0.5).abs() < 1e-6);
+
+        assert!(BlendSpace1d::default().weights(0.0).is_empty());
+    }
+
+    #[test]
+    fn blend_space_2d_weights() {
+        // A typical directional locomotion layout: idle in the middle and
+        // four directions around it.
+        let blend_space = BlendSpace2d::new(
+            [
+                (0, Vec2::ZERO),
+                (1, Vec2::Y),
+                (2, Vec2::NEG_Y),
+                (3, Vec2::X),
+                (4, Vec2::NEG_X),
+            ]
+            .map(|(index, position)| BlendSpace2dSample {
+                node: node(index),
+                position,
+            }),
+        );
+        assert_eq!(blend_space.triangles.len(), 4);
+
+        assert_eq!(
+            blend_space.weights(Vec2::ZERO).as_slice(),
+            &[(node(0), 1.0)]
+        );
+
+        let weights = blend_space.weights(Vec2::new(0.25, 0.25));
+        assert_eq!(weights.len(), 3);
+        assert!((weight_of(&weights, 0) - 0.5).abs() < 1e-6);
+        assert!((weight_of(&weights, 1) - 0.25).abs() < 1e-6);
+        assert!((weight_of(&weights, 3) - 0.25).abs() < 1e-6);
+
+        // Outside of the triangulation, the closest edge is used.
+        let weights = blend_space.weights(Vec2::new(1.0, 1.0));
+        assert_eq!(weights.len(), 2);
+        assert!((weight_of(&weights, 1) - 0.5).abs() < 1e-6);
+        assert!((weight_of(&weights, 3) - 0.5).abs() < 1e-6);
+
+        let sum: f32 = blend_space
+            .weights(Vec2::new(-0.1, 0.3))
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blend_space_2d_collinear() {
+        let blend_space = BlendSpace2d::new([
+            BlendSpace2dSample {
+                node: node(0),
+                position: Vec2::ZERO,
+            },
+            BlendSpace2dSample {
+                node: node(1),
+                position: Vec2::X,
+            },
+        ]);
+        assert!(blend_space.triangles.is_empty());
+
+        let weights = blend_space.weights(Vec2::new(0.75, 1.0));
+        assert!((weight_of(&weights, 0) - 0.25).abs() < 1e-6);
+        assert!((weight_of(&weights, 1) - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn blend_space_2d_grid() {
+        // A grid has many cocircular samples; the triangulation must still
+        // cover the convex hull exactly once.
+        let blend_space = BlendSpace2d::new((0..9).map(|index| BlendSpace2dSample {
+            node: node(index),
+            position: Vec2::new((index % 3) as f32, (index / 3) as f32) * 10.0,
+        }));
+        assert_eq!(blend_space.triangles.len(), 8);
+        let point = |index: u32| blend_space.samples[index as usize].position;
+        let area: f32 = blend_space
+            .triangles
+            .iter()
+            .map(|&[a, b, c]| (point(b) - point(a)).perp_dot(point(c) - point(a)) * 0.5)
+            .sum();
+        assert!((area - 400.0).abs() < 1e-3);
+
+        let weights = blend_space.weights(Vec2::new(12.0, 3.0));
+        let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+        assert!(weight_of(&weights, 1) > 0.0);
+    }
+
+    #[test]
+    fn advance_blend_space_animations() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<Assets<AnimationClip>>()
+            .init_resource::<Assets<AnimationGraph>>()
+            .add_systems(Update, advance_animations);
+
+        let mut clips = app.world_mut().resource_mut::<Assets<AnimationClip>>();
+        let mut clip = |duration| {
+            let mut clip = AnimationClip::default();
+            clip.set_duration(duration);
+            clips.add(clip)
+        };
+        let (walk, run) = (clip(1.0), clip(3.0));
+
+        let mut graph = AnimationGraph::new();
+        let root = graph.root;
+        let blend_space = graph.add_blend_space_1d([(walk, 0.0), (run, 1.0)], 1.0, root);
+        let AnimationNodeType::BlendSpace1d(ref samples) = graph[blend_space].node_type else {
+            unreachable!();
+        };
+        let [walk, run] = [0, 1].map(|index| samples.samples()[index].node);
+        let graph = app
+            .world_mut()
+            .resource_mut::<Assets<AnimationGraph>>()
+            .add(graph);
+
+        let mut player = AnimationPlayer::default();
+        player
+            .play(blend_space)
+            .repeat()
+            .set_blend_position(Vec2::new(0.5, 0.0));
+        let entity = app
+            .world_mut()
+            .spawn((player, AnimationGraphHandle(graph)))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(0.5));
+        app.update();
+
+        // Both clips are weighted equally, so the cycle lasts two seconds and
+        // a quarter of it has elapsed. The clips follow it at their own speed.
+        let player = app.world().get::<AnimationPlayer>(entity).unwrap();
+        let animation = |node| player.animation(node).unwrap();
+        assert!((animation(blend_space).seek_time() - 0.25).abs() < 1e-5);
+        assert!((animation(walk).seek_time() - 0.25).abs() < 1e-5);
+        assert!((animation(run).seek_time() - 0.75).abs() < 1e-5);
+        assert!((animation(walk).weight() - 0.5).abs() < 1e-5);
+        assert!((animation(run).weight() - 0.5).abs() < 1e-5);
+    }
+}
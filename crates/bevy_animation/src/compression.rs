@@ -0,0 +1,629 @@
+//! Keyframe compression for animation clips.
+//!
+//! Clips imported from motion capture typically store a keyframe for every
+//! bone on every frame, even though most of those keyframes could be
+//! reconstructed by interpolating their neighbors. Compressing such a clip
+//! drops every keyframe that lies within a configurable tolerance of the
+//! interpolation between the keyframes that are kept, and can optionally store
+//! rotations in a quantized form that takes half the memory.
+//!
+//! Compression can be applied directly with [`AnimationClip::compress_keyframes`]
+//! or as part of the asset pipeline with [`AnimationClipCompressor`].
+//!
+//! Only curves built from keyframes are compressed: [`AnimatableCurve`]s over
+//! an [`AnimatableKeyframeCurve`], an [`UnevenSampleAutoCurve`] or a
+//! [`SteppedKeyframeCurve`] animating an [`f32`], [`Vec2`], [`Vec3`], [`Vec3A`],
+//! [`Vec4`] or [`Quat`] property. These are the curves that `bevy_gltf`
+//! produces for linear and step interpolation. Every other curve is left as is.
+//!
+//! [`AnimatableCurve`]: crate::animation_curves::AnimatableCurve
+
+use core::{
+    any::{Any, TypeId},
+    convert::Infallible,
+    fmt::{self, Debug, Formatter},
+};
+
+use bevy_asset::transformer::{AssetTransformer, TransformedAsset};
+use bevy_math::{
+    curve::{
+        cores::{InterpolationDatum, UnevenCore},
+        Interval, UnevenSampleAutoCurve,
+    },
+    Quat, Vec2, Vec3, Vec3A, Vec4,
+};
+use bevy_reflect::TypePath;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animatable::Animatable,
+    animation_curves::{
+        create_animatable_evaluator, push_sample, AnimatableKeyframeCurve, AnimatableProperty,
+        AnimationCurve, AnimationCurveEvaluator, EvaluatorId,
+    },
+    gltf_curves::SteppedKeyframeCurve,
+    graph::AnimationNodeIndex,
+    AnimationClip, AnimationEvaluationError,
+};
+
+/// Settings that control how [`AnimationClip::compress_keyframes`] reduces
+/// keyframes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyframeCompressionSettings {
+    /// The maximum distance between an original keyframe and the compressed
+    /// curve, for scalar and vector properties.
+    ///
+    /// For translations, this is in world units.
+    pub tolerance: f32,
+
+    /// The maximum angle, in radians, between an original rotation keyframe
+    /// and the compressed curve.
+    pub rotation_tolerance: f32,
+
+    /// Whether rotations should be stored as 16-bit integers instead of
+    /// floats.
+    ///
+    /// This halves the memory used by rotation keyframes, at the cost of an
+    /// additional error of about 10⁻⁴ radians on top of
+    /// [`Self::rotation_tolerance`].
+    pub quantize_rotations: bool,
+}
+
+impl Default for KeyframeCompressionSettings {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-3,
+            rotation_tolerance: 1e-3,
+            quantize_rotations: false,
+        }
+    }
+}
+
+/// An [`AssetTransformer`] that compresses the keyframes of an
+/// [`AnimationClip`].
+///
+/// See [`AnimationClip::compress_keyframes`].
+#[derive(Clone, Copy, Default, Debug, TypePath)]
+pub struct AnimationClipCompressor;
+
+impl AssetTransformer for AnimationClipCompressor {
+    type AssetInput = AnimationClip;
+    type AssetOutput = AnimationClip;
+    type Settings = KeyframeCompressionSettings;
+    type Error = Infallible;
+
+    async fn transform<'a>(
+        &'a self,
+        mut asset: TransformedAsset<Self::AssetInput>,
+        settings: &'a Self::Settings,
+    ) -> Result<TransformedAsset<Self::AssetOutput>, Self::Error> {
+        asset.compress_keyframes(settings);
+        Ok(asset)
+    }
+}
+
+impl AnimationClip {
+    /// Removes redundant keyframes from the curves of this clip.
+    ///
+    /// A keyframe is removed when interpolating between the keyframes around
+    /// it reproduces it within the tolerances given by `settings`. The first
+    /// and last keyframes of each curve are always kept, so the duration of the
+    /// clip doesn't change.
+    ///
+    /// Curves that don't support compression are left as is; see the
+    /// [module-level documentation](crate::compression) for the supported
+    /// curves.
+    pub fn compress_keyframes(&mut self, settings: &KeyframeCompressionSettings) {
+        for curves in self.curves.values_mut() {
+            for curve in curves {
+                if let Some(compressed) = curve.0.compress(settings) {
+                    curve.0 = compressed;
+                }
+            }
+        }
+    }
+}
+
+/// An [`AnimationCurve`] produced by [`AnimationClip::compress_keyframes`].
+///
+/// The property `P` is the property that the original curve animated.
+#[derive(Clone)]
+pub struct CompressedKeyframeCurve<P> {
+    property: P,
+    keyframes: CompressedKeyframes,
+    stepped: bool,
+}
+
+impl<P> CompressedKeyframeCurve<P> {
+    /// The number of keyframes that were kept.
+    pub fn keyframe_count(&self) -> usize {
+        match &self.keyframes {
+            CompressedKeyframes::F32(core) => core.samples.len(),
+            CompressedKeyframes::Vec2(core) => core.samples.len(),
+            CompressedKeyframes::Vec3(core) => core.samples.len(),
+            CompressedKeyframes::Vec3A(core) => core.samples.len(),
+            CompressedKeyframes::Vec4(core) => core.samples.len(),
+            CompressedKeyframes::Quat(core) => core.samples.len(),
+            CompressedKeyframes::QuantizedQuat(core) => core.samples.len(),
+        }
+    }
+
+    /// Whether this curve holds each keyframe until the next one instead of
+    /// interpolating between them.
+    pub fn is_stepped(&self) -> bool {
+        self.stepped
+    }
+
+    /// Whether the rotations of this curve are stored quantized.
+    pub fn is_quantized(&self) -> bool {
+        matches!(self.keyframes, CompressedKeyframes::QuantizedQuat(_))
+    }
+}
+
+impl<P> Debug for CompressedKeyframeCurve<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressedKeyframeCurve")
+            .field("keyframes", &self.keyframes)
+            .field("stepped", &self.stepped)
+            .finish()
+    }
+}
+
+impl<P> AnimationCurve for CompressedKeyframeCurve<P>
+where
+    P: AnimatableProperty + Clone,
+{
+    fn clone_value(&self) -> Box<dyn AnimationCurve> {
+        Box::new(self.clone())
+    }
+
+    fn domain(&self) -> Interval {
+        match &self.keyframes {
+            CompressedKeyframes::F32(core) => core.domain(),
+            CompressedKeyframes::Vec2(core) => core.domain(),
+            CompressedKeyframes::Vec3(core) => core.domain(),
+            CompressedKeyframes::Vec3A(core) => core.domain(),
+            CompressedKeyframes::Vec4(core) => core.domain(),
+            CompressedKeyframes::Quat(core) => core.domain(),
+            CompressedKeyframes::QuantizedQuat(core) => core.domain(),
+        }
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        self.property.evaluator_id()
+    }
+
+    fn create_evaluator(&self) -> Box<dyn AnimationCurveEvaluator> {
+        create_animatable_evaluator(&self.property)
+    }
+
+    fn apply(
+        &self,
+        curve_evaluator: &mut dyn AnimationCurveEvaluator,
+        t: f32,
+        weight: f32,
+        graph_node: AnimationNodeIndex,
+    ) -> Result<(), AnimationEvaluationError> {
+        let stepped = self.stepped;
+        let value = match &self.keyframes {
+            CompressedKeyframes::F32(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::Vec2(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::Vec3(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::Vec3A(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::Vec4(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::Quat(core) => cast(sample(core, t, stepped, |v| *v)),
+            CompressedKeyframes::QuantizedQuat(core) => {
+                cast(sample(core, t, stepped, dequantize_rotation))
+            }
+        };
+        value
+            .and_then(|value| {
+                push_sample::<P::Property>(curve_evaluator, value, weight, graph_node)
+            })
+            .ok_or(AnimationEvaluationError::InconsistentEvaluatorImplementation(TypeId::of::<P>()))
+    }
+}
+
+/// The keyframes of a [`CompressedKeyframeCurve`].
+#[derive(Clone, Debug)]
+enum CompressedKeyframes {
+    F32(UnevenCore<f32>),
+    Vec2(UnevenCore<Vec2>),
+    Vec3(UnevenCore<Vec3>),
+    Vec3A(UnevenCore<Vec3A>),
+    Vec4(UnevenCore<Vec4>),
+    Quat(UnevenCore<Quat>),
+    /// Rotations stored as their components scaled to the range of an `i16`.
+    QuantizedQuat(UnevenCore<[i16; 4]>),
+}
+
+/// A keyframe value that [`AnimationClip::compress_keyframes`] knows how to
+/// compress.
+trait CompressibleKeyframe: Animatable + Clone {
+    /// The error introduced by replacing `self` with `other`.
+    fn error(&self, other: &Self) -> f32;
+
+    /// The maximum [`CompressibleKeyframe::error`] allowed by `settings`.
+    fn tolerance(settings: &KeyframeCompressionSettings) -> f32 {
+        settings.tolerance
+    }
+
+    /// Whether [`CompressibleKeyframe::store`] changes the representation of
+    /// the keyframes, such that compressing is worthwhile even if no keyframe
+    /// can be removed.
+    fn is_quantized(_settings: &KeyframeCompressionSettings) -> bool {
+        false
+    }
+
+    /// Stores the simplified keyframes.
+    fn store(core: UnevenCore<Self>, settings: &KeyframeCompressionSettings)
+        -> CompressedKeyframes;
+}
+
+impl CompressibleKeyframe for f32 {
+    fn error(&self, other: &Self) -> f32 {
+        (self - other).abs()
+    }
+
+    fn store(core: UnevenCore<Self>, _: &KeyframeCompressionSettings) -> CompressedKeyframes {
+        CompressedKeyframes::F32(core)
+    }
+}
+
+macro_rules! impl_compressible_vector {
+    ($ty: ident) => {
+        impl CompressibleKeyframe for $ty {
+            fn error(&self, other: &Self) -> f32 {
+                self.distance(*other)
+            }
+
+            fn store(
+                core: UnevenCore<Self>,
+                _: &KeyframeCompressionSettings,
+            ) -> CompressedKeyframes {
+                CompressedKeyframes::$ty(core)
+            }
+        }
+    };
+}
+
+impl_compressible_vector!(Vec2);
+impl_compressible_vector!(Vec3);
+impl_compressible_vector!(Vec3A);
+impl_compressible_vector!(Vec4);
+
+impl CompressibleKeyframe for Quat {
+    fn error(&self, other: &Self) -> f32 {
+        self.angle_between(*other)
+    }
+
+    fn tolerance(settings: &KeyframeCompressionSettings) -> f32 {
+        settings.rotation_tolerance
+    }
+
+    fn is_quantized(settings: &KeyframeCompressionSettings) -> bool {
+        settings.quantize_rotations
+    }
+
+    fn store(
+        core: UnevenCore<Self>,
+        settings: &KeyframeCompressionSettings,
+    ) -> CompressedKeyframes {
+        if !settings.quantize_rotations {
+            return CompressedKeyframes::Quat(core);
+        }
+        CompressedKeyframes::QuantizedQuat(UnevenCore {
+            times: core.times,
+            samples: core.samples.iter().map(quantize_rotation).collect(),
+        })
+    }
+}
+
+fn quantize_rotation(rotation: &Quat) -> [i16; 4] {
+    rotation
+        .to_array()
+        .map(|component| (component * i16::MAX as f32).round() as i16)
+}
+
+fn dequantize_rotation(rotation: &[i16; 4]) -> Quat {
+    Quat::from_array(rotation.map(|component| component as f32 / i16::MAX as f32)).normalize()
+}
+
+/// Compresses `curve` if it's one of the supported keyframe curves, returning
+/// a [`CompressedKeyframeCurve`] that animates `property`.
+///
+/// Returns `None` if the curve isn't supported or if compressing it wouldn't
+/// change anything.
+pub(crate) fn compress_curve<P>(
+    property: &P,
+    curve: &dyn Any,
+    settings: &KeyframeCompressionSettings,
+) -> Option<Box<dyn AnimationCurve>>
+where
+    P: AnimatableProperty + Clone,
+{
+    let (keyframes, stepped) = compress_keyframes::<f32>(curve, settings)
+        .or_else(|| compress_keyframes::<Vec2>(curve, settings))
+        .or_else(|| compress_keyframes::<Vec3>(curve, settings))
+        .or_else(|| compress_keyframes::<Vec3A>(curve, settings))
+        .or_else(|| compress_keyframes::<Vec4>(curve, settings))
+        .or_else(|| compress_keyframes::<Quat>(curve, settings))?;
+    Some(Box::new(CompressedKeyframeCurve {
+        property: property.clone(),
+        keyframes,
+        stepped,
+    }))
+}
+
+fn compress_keyframes<T: CompressibleKeyframe>(
+    curve: &dyn Any,
+    settings: &KeyframeCompressionSettings,
+) -> Option<(CompressedKeyframes, bool)> {
+    let (core, stepped) = if let Some(curve) = curve.downcast_ref::<AnimatableKeyframeCurve<T>>() {
+        (curve.core(), false)
+    } else if let Some(curve) = curve.downcast_ref::<UnevenSampleAutoCurve<T>>() {
+        (curve.core(), false)
+    } else if let Some(curve) = curve.downcast_ref::<SteppedKeyframeCurve<T>>() {
+        (curve.core(), true)
+    } else {
+        return None;
+    };
+
+    let tolerance = T::tolerance(settings);
+    let kept = if stepped {
+        simplify_stepped(core, tolerance)
+    } else {
+        simplify_linear(core, tolerance)
+    };
+    if kept.len() == core.samples.len() && !T::is_quantized(settings) {
+        return None;
+    }
+
+    let core = UnevenCore {
+        times: kept.iter().map(|&index| core.times[index]).collect(),
+        samples: kept
+            .iter()
+            .map(|&index| core.samples[index].clone())
+            .collect(),
+    };
+    Some((T::store(core, settings), stepped))
+}
+
+/// Returns the indices of the keyframes of a linearly interpolated curve that
+/// need to be kept for every removed keyframe to lie within `tolerance` of the
+/// simplified curve.
+fn simplify_linear<T: CompressibleKeyframe>(core: &UnevenCore<T>, tolerance: f32) -> Vec<usize> {
+    let (times, samples) = (&core.times, &core.samples);
+    let mut kept = vec![0];
+    let mut anchor = 0;
+    let mut end = 2;
+    while end < samples.len() {
+        let span = times[end] - times[anchor];
+        let fits = (anchor + 1..end).all(|index| {
+            let s = (times[index] - times[anchor]) / span;
+            let interpolated = T::interpolate(&samples[anchor], &samples[end], s);
+            interpolated.error(&samples[index]) <= tolerance
+        });
+        if fits {
+            end += 1;
+        } else {
+            anchor = end - 1;
+            kept.push(anchor);
+            end = anchor + 2;
+        }
+    }
+    kept.push(samples.len() - 1);
+    kept
+}
+
+/// Returns the indices of the keyframes of a stepped curve that differ from
+/// the previously kept keyframe by more than `tolerance`, together with the
+/// last keyframe.
+fn simplify_stepped<T: CompressibleKeyframe>(core: &UnevenCore<T>, tolerance: f32) -> Vec<usize> {
+    let samples = &core.samples;
+    let mut kept = vec![0];
+    for index in 1..samples.len() - 1 {
+        if samples[index].error(&samples[*kept.last().unwrap()]) > tolerance {
+            kept.push(index);
+        }
+    }
+    kept.push(samples.len() - 1);
+    kept
+}
+
+/// Samples the keyframes in `core` at `t`, converting them with `decode`.
+fn sample<S, T: Animatable>(
+    core: &UnevenCore<S>,
+    t: f32,
+    stepped: bool,
+    decode: impl Fn(&S) -> T,
+) -> T {
+    match core.sample_interp(t) {
+        InterpolationDatum::Exact(value)
+        | InterpolationDatum::LeftTail(value)
+        | InterpolationDatum::RightTail(value) => decode(value),
+        InterpolationDatum::Between(before, after, s) if stepped => {
+            if s >= 1.0 {
+                decode(after)
+            } else {
+                decode(before)
+            }
+        }
+        InterpolationDatum::Between(before, after, s) => {
+            T::interpolate(&decode(before), &decode(after), s)
+        }
+    }
+}
+
+/// Converts `value` to `A`, returning `None` if `T` and `A` are different
+/// types.
+fn cast<T: 'static, A: 'static>(value: T) -> Option<A> {
+    let mut value = Some(value);
+    (&mut value as &mut dyn Any)
+        .downcast_mut::<Option<A>>()?
+        .take()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::{ops, Vec3};
+    use bevy_transform::components::Transform;
+
+    use super::*;
+    use crate::{
+        animation_curves::{sample_animatable, AnimatableCurve},
+        reflect_curve::ReflectPathProperty,
+        AnimationTargetId, VariableCurve,
+    };
+
+    fn property<A>(path: &str) -> ReflectPathProperty<A> {
+        ReflectPathProperty::new::<Transform>(path).unwrap()
+    }
+
+    fn compressed<P: AnimatableProperty + Clone>(
+        curve: &dyn AnimationCurve,
+        settings: &KeyframeCompressionSettings,
+    ) -> Box<dyn AnimationCurve> {
+        let compressed = curve.compress(settings).unwrap();
+        assert!(compressed.is::<CompressedKeyframeCurve<P>>());
+        compressed
+    }
+
+    fn keyframe_count<P: AnimatableProperty + Clone>(curve: &dyn AnimationCurve) -> usize {
+        curve
+            .downcast_ref::<CompressedKeyframeCurve<P>>()
+            .unwrap()
+            .keyframe_count()
+    }
+
+    fn sample<A: Animatable>(curve: &dyn AnimationCurve, t: f32) -> A {
+        let mut evaluator = curve.create_evaluator();
+        sample_animatable(curve, &mut *evaluator, t).unwrap()
+    }
+
+    #[test]
+    fn removes_collinear_keyframes() {
+        let keyframes = (0..=10).map(|i| (i as f32 * 0.1, Vec3::new(i as f32, 2.0, 0.0)));
+        let curve = AnimatableCurve::new(
+            property::<Vec3>(".translation"),
+            UnevenSampleAutoCurve::new(keyframes).unwrap(),
+        );
+        let compressed = compressed::<ReflectPathProperty<Vec3>>(
+            &curve,
+            &KeyframeCompressionSettings::default(),
+        );
+
+        assert_eq!(keyframe_count::<ReflectPathProperty<Vec3>>(&*compressed), 2);
+        assert_eq!(compressed.domain(), curve.domain());
+        let value: Vec3 = sample(&*compressed, 0.45);
+        assert!(value.abs_diff_eq(Vec3::new(4.5, 2.0, 0.0), 1e-4));
+    }
+
+    #[test]
+    fn keeps_keyframes_outside_tolerance() {
+        let keyframes = [
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 2.0),
+            (5.0, 1.0),
+            (6.0, 0.0),
+        ];
+        let curve = AnimatableCurve::new(
+            property::<Vec3>(".scale"),
+            AnimatableKeyframeCurve::new(keyframes.map(|(t, x)| (t, Vec3::splat(x)))).unwrap(),
+        );
+        let compressed = compressed::<ReflectPathProperty<Vec3>>(
+            &curve,
+            &KeyframeCompressionSettings::default(),
+        );
+
+        assert_eq!(keyframe_count::<ReflectPathProperty<Vec3>>(&*compressed), 3);
+        for (t, x) in keyframes {
+            let value: Vec3 = sample(&*compressed, t);
+            assert!(value.abs_diff_eq(Vec3::splat(x), 1e-3), "{t}: {value}");
+        }
+    }
+
+    #[test]
+    fn quantizes_rotations_within_tolerance() {
+        let settings = KeyframeCompressionSettings {
+            rotation_tolerance: 0.01,
+            quantize_rotations: true,
+            ..Default::default()
+        };
+        let keyframes = (0..=20).map(|i| {
+            let t = i as f32 * 0.05;
+            (t, Quat::from_rotation_y(ops::sin(t * 3.0)))
+        });
+        let curve = AnimatableCurve::new(
+            property::<Quat>(".rotation"),
+            UnevenSampleAutoCurve::new(keyframes.clone()).unwrap(),
+        );
+        let compressed = compressed::<ReflectPathProperty<Quat>>(&curve, &settings);
+        let compressed_curve = compressed
+            .downcast_ref::<CompressedKeyframeCurve<ReflectPathProperty<Quat>>>()
+            .unwrap();
+
+        assert!(compressed_curve.is_quantized());
+        assert!(compressed_curve.keyframe_count() < 21);
+        for (t, rotation) in keyframes {
+            let value: Quat = sample(&*compressed, t);
+            assert!(value.angle_between(rotation) < settings.rotation_tolerance + 1e-3);
+        }
+    }
+
+    #[test]
+    fn compresses_stepped_curves() {
+        let keyframes = [(0.0, 1.0), (1.0, 1.0), (2.0, 3.0), (3.0, 3.0), (4.0, 3.0)];
+        let curve = AnimatableCurve::new(
+            property::<f32>(".scale.x"),
+            SteppedKeyframeCurve::new(keyframes).unwrap(),
+        );
+        let compressed =
+            compressed::<ReflectPathProperty<f32>>(&curve, &KeyframeCompressionSettings::default());
+
+        assert!(compressed
+            .downcast_ref::<CompressedKeyframeCurve<ReflectPathProperty<f32>>>()
+            .unwrap()
+            .is_stepped());
+        assert_eq!(keyframe_count::<ReflectPathProperty<f32>>(&*compressed), 3);
+        assert_eq!(compressed.domain(), curve.domain());
+        for (t, value) in [(0.5, 1.0), (1.5, 1.0), (2.0, 3.0), (3.5, 3.0)] {
+            assert_eq!(sample::<f32>(&*compressed, t), value);
+        }
+    }
+
+    #[test]
+    fn compresses_clip_curves() {
+        let target = AnimationTargetId::from_name(&"bone".into());
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                property::<f32>(".translation.x"),
+                UnevenSampleAutoCurve::new((0..5).map(|i| (i as f32, 0.5))).unwrap(),
+            ),
+        );
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                property::<f32>(".translation.y"),
+                bevy_math::curve::ConstantCurve::new(Interval::UNIT, 1.0),
+            ),
+        );
+        let duration = clip.duration();
+
+        clip.compress_keyframes(&KeyframeCompressionSettings::default());
+
+        let curves: &[VariableCurve] = &clip.curves()[&target];
+        assert!(curves[0]
+            .0
+            .is::<CompressedKeyframeCurve<ReflectPathProperty<f32>>>());
+        assert!(!curves[1]
+            .0
+            .is::<CompressedKeyframeCurve<ReflectPathProperty<f32>>>());
+        assert_eq!(clip.duration(), duration);
+    }
+}
@@ -44,6 +44,11 @@ impl<T> SteppedKeyframeCurve<T> {
             core: UnevenCore::new(timed_samples)?,
         })
     }
+
+    /// The keyframes underlying this [`SteppedKeyframeCurve`].
+    pub(crate) fn core(&self) -> &UnevenCore<T> {
+        &self.core
+    }
 }
 
 /// A keyframe-defined curve that uses cubic spline interpolation, backed by a contiguous buffer.
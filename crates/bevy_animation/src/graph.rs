@@ -18,6 +18,7 @@ use bevy_ecs::{
     resource::Resource,
     system::{Res, ResMut},
 };
+use bevy_math::Vec2;
 use bevy_platform::collections::HashMap;
 use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
 use derive_more::derive::From;
@@ -30,7 +31,10 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use thiserror::Error;
 
-use crate::{AnimationClip, AnimationTargetId};
+use crate::{
+    blend_space::{BlendSpace1d, BlendSpace1dSample, BlendSpace2d, BlendSpace2dSample},
+    AnimationClip, AnimationTargetId,
+};
 
 /// A graph structure that describes how animation clips are to be blended
 /// together.
@@ -43,11 +47,13 @@ use crate::{AnimationClip, AnimationTargetId};
 /// the root and blends the animations together in a bottom-up fashion to
 /// produce the final pose.
 ///
-/// There are three types of nodes: *blend nodes*, *add nodes*, and *clip
-/// nodes*, all of which can have an associated weight. Blend nodes and add
-/// nodes have no associated animation clip and combine the animations of their
-/// children according to those children's weights. Clip nodes specify an
-/// animation clip to play. When a graph is created, it starts with only a
+/// There are four types of nodes: *blend nodes*, *add nodes*, *blend space
+/// nodes*, and *clip nodes*, all of which can have an associated weight. Blend
+/// nodes and add nodes have no associated animation clip and combine the
+/// animations of their children according to those children's weights. Blend
+/// space nodes weight their children automatically according to a blend
+/// position; see the [`blend_space`](crate::blend_space) module. Clip nodes
+/// specify an animation clip to play. When a graph is created, it starts with only a
 /// single blend node, the root node.
 ///
 /// For example, consider the following graph:
@@ -160,13 +166,15 @@ pub type AnimationNodeIndex = NodeIndex<u32>;
 /// An individual node within an animation graph.
 ///
 /// The [`AnimationGraphNode::node_type`] field specifies the type of node: one
-/// of a *clip node*, a *blend node*, or an *add node*. Clip nodes, the leaves
-/// of the graph, contain animation clips to play. Blend and add nodes describe
-/// how to combine their children to produce a final animation.
+/// of a *clip node*, a *blend node*, an *add node*, or a *blend space node*.
+/// Clip nodes, the leaves of the graph, contain animation clips to play. Blend,
+/// add, and blend space nodes describe how to combine their children to
+/// produce a final animation.
 #[derive(Clone, Reflect, Debug)]
 #[reflect(Clone)]
 pub struct AnimationGraphNode {
-    /// Animation node data specific to the type of node (clip, blend, or add).
+    /// Animation node data specific to the type of node (clip, blend, add, or
+    /// blend space).
     ///
     /// In the case of clip nodes, this contains the actual animation clip
     /// associated with the node.
@@ -201,7 +209,8 @@ pub struct AnimationGraphNode {
     pub weight: f32,
 }
 
-/// Animation node data specific to the type of node (clip, blend, or add).
+/// Animation node data specific to the type of node (clip, blend, add, or
+/// blend space).
 ///
 /// In the case of clip nodes, this contains the actual animation clip
 /// associated with the node.
@@ -232,6 +241,28 @@ pub enum AnimationNodeType {
     /// top of a running animation to produce an animation of a character
     /// attacking while running.
     Add,
+
+    /// A *1D blend space node*, which blends its children according to a
+    /// single parameter, such as the speed of a character.
+    ///
+    /// The parameter is the `x` coordinate of the [blend position] of the
+    /// node's [`ActiveAnimation`](crate::ActiveAnimation). The children are
+    /// weighted automatically and played in lockstep; they shouldn't be played
+    /// on their own.
+    ///
+    /// [blend position]: crate::ActiveAnimation::set_blend_position
+    BlendSpace1d(BlendSpace1d),
+
+    /// A *2D blend space node*, which blends its children according to two
+    /// parameters, such as the speed and direction of a character.
+    ///
+    /// The parameters are the [blend position] of the node's
+    /// [`ActiveAnimation`](crate::ActiveAnimation). The children are weighted
+    /// automatically and played in lockstep; they shouldn't be played on their
+    /// own.
+    ///
+    /// [blend position]: crate::ActiveAnimation::set_blend_position
+    BlendSpace2d(BlendSpace2d),
 }
 
 /// An [`AssetLoader`] that can load [`AnimationGraph`]s as assets.
@@ -412,6 +443,10 @@ pub enum SerializedAnimationNodeType {
     Blend,
     /// Corresponds to [`AnimationNodeType::Add`].
     Add,
+    /// Corresponds to [`AnimationNodeType::BlendSpace1d`].
+    BlendSpace1d(Vec<BlendSpace1dSample>),
+    /// Corresponds to [`AnimationNodeType::BlendSpace2d`].
+    BlendSpace2d(Vec<BlendSpace2dSample>),
 }
 
 /// The type of an animation mask bitfield.
@@ -608,6 +643,58 @@ impl AnimationGraph {
         node_index
     }
 
+    /// Adds a 1D blend space node to the animation graph with the given weight,
+    /// along with a clip node for each of the given samples, and returns the
+    /// index of the blend space node.
+    ///
+    /// Each sample is a clip and its position. The blend space node will be
+    /// placed under the supplied `parent` node, and the clip nodes will be its
+    /// children. Neither will have a mask.
+    pub fn add_blend_space_1d(
+        &mut self,
+        samples: impl IntoIterator<Item = (Handle<AnimationClip>, f32)>,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.add_blend(weight, parent);
+        let samples: Vec<_> = samples
+            .into_iter()
+            .map(|(clip, position)| BlendSpace1dSample {
+                node: self.add_clip(clip, 1.0, node_index),
+                position,
+            })
+            .collect();
+        self.graph[node_index].node_type =
+            AnimationNodeType::BlendSpace1d(BlendSpace1d::new(samples));
+        node_index
+    }
+
+    /// Adds a 2D blend space node to the animation graph with the given weight,
+    /// along with a clip node for each of the given samples, and returns the
+    /// index of the blend space node.
+    ///
+    /// Each sample is a clip and its position. The blend space node will be
+    /// placed under the supplied `parent` node, and the clip nodes will be its
+    /// children. Neither will have a mask.
+    pub fn add_blend_space_2d(
+        &mut self,
+        samples: impl IntoIterator<Item = (Handle<AnimationClip>, Vec2)>,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.add_blend(weight, parent);
+        let samples: Vec<_> = samples
+            .into_iter()
+            .map(|(clip, position)| BlendSpace2dSample {
+                node: self.add_clip(clip, 1.0, node_index),
+                position,
+            })
+            .collect();
+        self.graph[node_index].node_type =
+            AnimationNodeType::BlendSpace2d(BlendSpace2d::new(samples));
+        node_index
+    }
+
     /// Adds an edge from the edge `from` to `to`, making `to` a child of
     /// `from`.
     ///
@@ -779,6 +866,12 @@ impl AssetLoader for AnimationGraphAssetLoader {
                     }
                     SerializedAnimationNodeType::Blend => AnimationNodeType::Blend,
                     SerializedAnimationNodeType::Add => AnimationNodeType::Add,
+                    SerializedAnimationNodeType::BlendSpace1d(ref samples) => {
+                        AnimationNodeType::BlendSpace1d(BlendSpace1d::new(samples.iter().copied()))
+                    }
+                    SerializedAnimationNodeType::BlendSpace2d(ref samples) => {
+                        AnimationNodeType::BlendSpace2d(BlendSpace2d::new(samples.iter().copied()))
+                    }
                 },
                 mask: serialized_node.mask,
                 weight: serialized_node.weight,
@@ -822,6 +915,12 @@ impl TryFrom<AnimationGraph> for SerializedAnimationGraph {
                     },
                     AnimationNodeType::Blend => SerializedAnimationNodeType::Blend,
                     AnimationNodeType::Add => SerializedAnimationNodeType::Add,
+                    AnimationNodeType::BlendSpace1d(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace1d(blend_space.samples().to_vec())
+                    }
+                    AnimationNodeType::BlendSpace2d(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace2d(blend_space.samples().to_vec())
+                    }
                 },
             });
         }
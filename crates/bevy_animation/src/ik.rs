@@ -0,0 +1,694 @@
+//! Inverse kinematics, applied on top of animated poses.
+//!
+//! Inverse kinematics (IK) rotates a chain of bones so that the end of the
+//! chain reaches a target, such as a foot touching uneven ground or a hand
+//! holding a weapon. Add an [`InverseKinematics`] component next to an
+//! [`AnimationPlayer`](crate::AnimationPlayer) to adjust the pose it produces every frame, after
+//! animations have been applied and before transforms are propagated.
+
+use core::f32::consts::PI;
+
+use bevy_ecs::{
+    component::Component, entity::Entity, hierarchy::ChildOf, reflect::ReflectComponent,
+    system::Query,
+};
+use bevy_math::{ops, Quat, Vec3};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_transform::components::Transform;
+
+use crate::{AnimatedBy, AnimationTargetId};
+
+/// A set of inverse kinematics constraints applied to the pose produced by the
+/// [`AnimationPlayer`](crate::AnimationPlayer) on the same entity.
+///
+/// The constraints are solved in order, so a constraint can build on the
+/// result of the ones before it.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Clone, Debug, Default)]
+pub struct InverseKinematics {
+    /// The constraints to solve.
+    pub constraints: Vec<IkConstraint>,
+}
+
+impl InverseKinematics {
+    /// Creates a new [`InverseKinematics`] component with the given
+    /// constraints.
+    pub fn new(constraints: impl IntoIterator<Item = IkConstraint>) -> Self {
+        Self {
+            constraints: constraints.into_iter().collect(),
+        }
+    }
+}
+
+/// An inverse kinematics constraint that makes the end of a chain of bones
+/// reach for a target.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Clone, Debug)]
+pub struct IkConstraint {
+    /// The bones of the chain, from its root to its end effector.
+    ///
+    /// Each bone must be a child of the previous one. The end effector is
+    /// moved to the target but isn't rotated, unless
+    /// [`align_end_rotation`](Self::align_end_rotation) is set.
+    pub chain: Vec<AnimationTargetId>,
+
+    /// The solver used to compute the pose of the chain.
+    pub solver: IkSolver,
+
+    /// The point the end effector reaches for.
+    pub target: IkTarget,
+
+    /// An optional point that the chain bends toward, like the direction a
+    /// knee or an elbow points in.
+    pub pole: Option<IkTarget>,
+
+    /// How much the constraint overrides the animated pose, from 0.0 (not at
+    /// all) to 1.0 (fully).
+    pub weight: f32,
+
+    /// The limits of the joints of the chain, indexed like
+    /// [`chain`](Self::chain). Joints without a limit can rotate freely.
+    pub joint_limits: Vec<Option<IkJointLimit>>,
+
+    /// Whether the end effector takes the rotation of the target, for example
+    /// to make a hand match the grip of a weapon.
+    ///
+    /// This only has an effect if the target is an [`IkTarget::Entity`].
+    pub align_end_rotation: bool,
+
+    #[reflect(ignore)]
+    entities: Vec<Entity>,
+}
+
+impl IkConstraint {
+    /// Creates a new constraint with the given solver, chain, and target.
+    pub fn new(
+        solver: IkSolver,
+        chain: impl IntoIterator<Item = AnimationTargetId>,
+        target: IkTarget,
+    ) -> Self {
+        Self {
+            chain: chain.into_iter().collect(),
+            solver,
+            target,
+            pole: None,
+            weight: 1.0,
+            joint_limits: vec![],
+            align_end_rotation: false,
+            entities: vec![],
+        }
+    }
+
+    /// Creates a new [two-bone](IkSolver::TwoBone) constraint, such as for a
+    /// leg from its `upper` bone (thigh) to its `lower` bone (shin) and its
+    /// `end` effector (foot).
+    pub fn two_bone(
+        upper: AnimationTargetId,
+        lower: AnimationTargetId,
+        end: AnimationTargetId,
+        target: IkTarget,
+    ) -> Self {
+        Self::new(IkSolver::TwoBone, [upper, lower, end], target)
+    }
+
+    /// Sets the pole of this constraint.
+    pub fn with_pole(mut self, pole: IkTarget) -> Self {
+        self.pole = Some(pole);
+        self
+    }
+
+    /// Sets the weight of this constraint.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Limits the rotation of the joint at `index` in the chain.
+    pub fn with_joint_limit(mut self, index: usize, limit: IkJointLimit) -> Self {
+        if self.joint_limits.len() <= index {
+            self.joint_limits.resize(index + 1, None);
+        }
+        self.joint_limits[index] = Some(limit);
+        self
+    }
+
+    /// Makes the end effector take the rotation of the target.
+    pub fn with_end_rotation(mut self) -> Self {
+        self.align_end_rotation = true;
+        self
+    }
+}
+
+/// An algorithm used to solve an [`IkConstraint`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum IkSolver {
+    /// An analytic solver for chains of exactly three bones, such as legs and
+    /// arms.
+    ///
+    /// This is exact and cheap, and the chain bends toward the pole if there is
+    /// one.
+    TwoBone,
+
+    /// *Forward And Backward Reaching Inverse Kinematics*, an iterative solver
+    /// for chains of any length that moves joint positions and produces smooth,
+    /// natural-looking poses.
+    ///
+    /// Joint limits are applied once the positions have been solved.
+    Fabrik {
+        /// The maximum number of iterations.
+        iterations: u32,
+        /// The distance from the target at which the solver stops.
+        tolerance: f32,
+    },
+
+    /// *Cyclic Coordinate Descent*, an iterative solver for chains of any
+    /// length that rotates one joint at a time, starting from the end of the
+    /// chain.
+    ///
+    /// Joint limits are applied at every step, so this works well with
+    /// heavily constrained chains.
+    Ccd {
+        /// The maximum number of iterations.
+        iterations: u32,
+        /// The distance from the target at which the solver stops.
+        tolerance: f32,
+    },
+}
+
+/// A point that an [`IkConstraint`] reaches for or bends toward.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum IkTarget {
+    /// The position of an entity.
+    Entity(Entity),
+    /// A position in world space.
+    Position(Vec3),
+}
+
+/// A limit on the rotation of a joint of an [`IkConstraint`], relative to its
+/// animated rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum IkJointLimit {
+    /// The joint can rotate by at most `max_angle` radians in any direction.
+    Cone {
+        /// The maximum angle, in radians.
+        max_angle: f32,
+    },
+    /// The joint can only rotate around `axis`, by an angle between `min` and
+    /// `max` radians, like a knee or an elbow.
+    Hinge {
+        /// The axis of rotation, in the local space of the joint.
+        axis: Vec3,
+        /// The minimum angle, in radians.
+        min: f32,
+        /// The maximum angle, in radians.
+        max: f32,
+    },
+}
+
+impl IkJointLimit {
+    /// Constrains the local `rotation` of a joint whose animated local
+    /// rotation is `animated`.
+    pub fn constrain(&self, animated: Quat, rotation: Quat) -> Quat {
+        let mut delta = animated.inverse() * rotation;
+        if delta.w < 0.0 {
+            delta = -delta;
+        }
+        let delta = match *self {
+            IkJointLimit::Cone { max_angle } => {
+                let angle = delta.angle_between(Quat::IDENTITY);
+                if angle > max_angle {
+                    Quat::IDENTITY.slerp(delta, max_angle / angle)
+                } else {
+                    delta
+                }
+            }
+            IkJointLimit::Hinge { axis, min, max } => {
+                let axis = axis.normalize_or_zero();
+                if axis == Vec3::ZERO {
+                    return animated;
+                }
+                // Keep only the twist of the rotation around the axis.
+                let mut angle = 2.0 * ops::atan2(delta.xyz().dot(axis), delta.w);
+                if angle > PI {
+                    angle -= 2.0 * PI;
+                }
+                Quat::from_axis_angle(axis, angle.clamp(min, max))
+            }
+        };
+        (animated * delta).normalize()
+    }
+}
+
+/// A system that solves the [`InverseKinematics`] constraints of animation
+/// players, adjusting the poses of the bones they animate.
+///
+/// This runs after the animations have been applied, and computes world space
+/// positions from local [`Transform`]s since global transforms haven't been
+/// propagated yet.
+pub fn solve_inverse_kinematics(
+    mut players: Query<(Entity, &mut InverseKinematics)>,
+    targets: Query<(Entity, &AnimationTargetId, &AnimatedBy)>,
+    parents: Query<&ChildOf>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for (player_entity, mut inverse_kinematics) in &mut players {
+        let mut target_entities: Option<HashMap<AnimationTargetId, Entity>> = None;
+
+        for constraint in &mut inverse_kinematics.constraints {
+            if constraint.chain.len() < 2 || constraint.weight <= 0.0 {
+                continue;
+            }
+
+            // Resolve the bones of the chain, caching them across frames.
+            let is_resolved = constraint.entities.len() == constraint.chain.len()
+                && constraint
+                    .entities
+                    .iter()
+                    .zip(&constraint.chain)
+                    .all(|(&entity, &id)| {
+                        targets
+                            .get(entity)
+                            .is_ok_and(|(_, &target_id, &AnimatedBy(player))| {
+                                target_id == id && player == player_entity
+                            })
+                    });
+            if !is_resolved {
+                let target_entities = target_entities.get_or_insert_with(|| {
+                    targets
+                        .iter()
+                        .filter(|&(_, _, &AnimatedBy(player))| player == player_entity)
+                        .map(|(entity, &id, _)| (id, entity))
+                        .collect()
+                });
+                let Some(entities) = constraint
+                    .chain
+                    .iter()
+                    .map(|id| target_entities.get(id).copied())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+                constraint.entities = entities;
+            }
+
+            let Some(target) = resolve_target(constraint.target, &parents, &transforms) else {
+                continue;
+            };
+            let pole = constraint
+                .pole
+                .and_then(|pole| resolve_target(pole, &parents, &transforms))
+                .map(|pole| pole.translation);
+
+            let parent = parents
+                .get(constraint.entities[0])
+                .ok()
+                .and_then(|child_of| world_transform(child_of.parent(), &parents, &transforms))
+                .unwrap_or_default();
+            let Some(locals) = constraint
+                .entities
+                .iter()
+                .map(|&entity| transforms.get(entity).ok().copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            let mut chain = IkChain::new(parent, locals, &constraint.joint_limits);
+            chain.solve(constraint.solver, target.translation, pole);
+            chain.finish(
+                constraint.weight.min(1.0),
+                constraint
+                    .align_end_rotation
+                    .then_some(target.rotation)
+                    .filter(|_| matches!(constraint.target, IkTarget::Entity(_))),
+            );
+
+            for (&entity, local) in constraint.entities.iter().zip(&chain.locals) {
+                if let Ok(mut transform) = transforms.get_mut(entity) {
+                    transform.rotation = local.rotation;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the world space transform of `target`.
+fn resolve_target(
+    target: IkTarget,
+    parents: &Query<&ChildOf>,
+    transforms: &Query<&mut Transform>,
+) -> Option<Transform> {
+    match target {
+        IkTarget::Entity(entity) => world_transform(entity, parents, transforms),
+        IkTarget::Position(position) => Some(Transform::from_translation(position)),
+    }
+}
+
+/// Computes the world space transform of `entity` by composing the local
+/// transforms of its ancestors.
+fn world_transform(
+    entity: Entity,
+    parents: &Query<&ChildOf>,
+    transforms: &Query<&mut Transform>,
+) -> Option<Transform> {
+    let mut transform = *transforms.get(entity).ok()?;
+    let mut current = entity;
+    while let Ok(child_of) = parents.get(current) {
+        current = child_of.parent();
+        let Ok(parent_transform) = transforms.get(current) else {
+            break;
+        };
+        transform = parent_transform.mul_transform(transform);
+    }
+    Some(transform)
+}
+
+/// A chain of joints being solved.
+struct IkChain {
+    /// The world space transform of the parent of the first joint.
+    parent: Transform,
+    /// The local transforms of the joints.
+    locals: Vec<Transform>,
+    /// The animated local rotations of the joints.
+    animated: Vec<Quat>,
+    /// The limits of the joints.
+    limits: Vec<Option<IkJointLimit>>,
+}
+
+impl IkChain {
+    fn new(parent: Transform, locals: Vec<Transform>, limits: &[Option<IkJointLimit>]) -> Self {
+        let mut limits = limits.to_vec();
+        limits.resize(locals.len(), None);
+        Self {
+            parent,
+            animated: locals.iter().map(|local| local.rotation).collect(),
+            locals,
+            limits,
+        }
+    }
+
+    /// Returns the world space transforms of the joints.
+    fn world_transforms(&self) -> Vec<Transform> {
+        let mut parent = self.parent;
+        self.locals
+            .iter()
+            .map(|&local| {
+                parent = parent.mul_transform(local);
+                parent
+            })
+            .collect()
+    }
+
+    /// Returns the world space transform of the parent of the joint at `index`.
+    fn parent_of(&self, index: usize, world_transforms: &[Transform]) -> Transform {
+        match index {
+            0 => self.parent,
+            _ => world_transforms[index - 1],
+        }
+    }
+
+    /// Rotates the joint at `index` by `delta` in world space, respecting its
+    /// limit.
+    fn rotate(&mut self, index: usize, delta: Quat, world_transforms: &[Transform]) {
+        let parent_rotation = self.parent_of(index, world_transforms).rotation;
+        let world_rotation = delta * world_transforms[index].rotation;
+        let mut rotation = (parent_rotation.inverse() * world_rotation).normalize();
+        if let Some(limit) = self.limits[index] {
+            rotation = limit.constrain(self.animated[index], rotation);
+        }
+        self.locals[index].rotation = rotation;
+    }
+
+    fn solve(&mut self, solver: IkSolver, target: Vec3, pole: Option<Vec3>) {
+        match solver {
+            IkSolver::TwoBone => {
+                if self.locals.len() != 3 {
+                    return;
+                }
+                let mut positions = self.positions();
+                solve_two_bone(&mut positions, target, pole);
+                self.set_positions(&positions);
+            }
+            IkSolver::Fabrik {
+                iterations,
+                tolerance,
+            } => {
+                let mut positions = self.positions();
+                solve_fabrik(&mut positions, target, pole, iterations, tolerance);
+                self.set_positions(&positions);
+            }
+            IkSolver::Ccd {
+                iterations,
+                tolerance,
+            } => self.solve_ccd(target, iterations, tolerance),
+        }
+    }
+
+    /// Returns the world space positions of the joints.
+    fn positions(&self) -> Vec<Vec3> {
+        self.world_transforms()
+            .iter()
+            .map(|transform| transform.translation)
+            .collect()
+    }
+
+    /// Rotates the joints so that they reach the given world space positions
+    /// as closely as their limits allow.
+    fn set_positions(&mut self, positions: &[Vec3]) {
+        for index in 0..self.locals.len() - 1 {
+            let world_transforms = self.world_transforms();
+            let joint = world_transforms[index].translation;
+            let from = (world_transforms[index + 1].translation - joint).normalize_or_zero();
+            let to = (positions[index + 1] - joint).normalize_or_zero();
+            if from != Vec3::ZERO && to != Vec3::ZERO {
+                self.rotate(index, Quat::from_rotation_arc(from, to), &world_transforms);
+            }
+        }
+    }
+
+    fn solve_ccd(&mut self, target: Vec3, iterations: u32, tolerance: f32) {
+        let end = self.locals.len() - 1;
+        for _ in 0..iterations {
+            for index in (0..end).rev() {
+                let world_transforms = self.world_transforms();
+                let joint = world_transforms[index].translation;
+                let from = (world_transforms[end].translation - joint).normalize_or_zero();
+                let to = (target - joint).normalize_or_zero();
+                if from != Vec3::ZERO && to != Vec3::ZERO {
+                    self.rotate(index, Quat::from_rotation_arc(from, to), &world_transforms);
+                }
+            }
+            if self.world_transforms()[end].translation.distance(target) <= tolerance {
+                break;
+            }
+        }
+    }
+
+    /// Blends the solved pose with the animated pose according to `weight`,
+    /// and optionally sets the world space rotation of the end effector.
+    fn finish(&mut self, weight: f32, end_rotation: Option<Quat>) {
+        if let Some(end_rotation) = end_rotation {
+            let end = self.locals.len() - 1;
+            let world_transforms = self.world_transforms();
+            let parent_rotation = self.parent_of(end, &world_transforms).rotation;
+            self.locals[end].rotation = (parent_rotation.inverse() * end_rotation).normalize();
+        }
+        if weight < 1.0 {
+            for (local, &animated) in self.locals.iter_mut().zip(&self.animated) {
+                local.rotation = animated.slerp(local.rotation, weight);
+            }
+        }
+    }
+}
+
+/// Solves a two-bone chain analytically, moving the middle joint and the end
+/// effector of `positions`.
+fn solve_two_bone(positions: &mut [Vec3], target: Vec3, pole: Option<Vec3>) {
+    let [root, middle, end] = [positions[0], positions[1], positions[2]];
+    let upper = root.distance(middle);
+    let lower = middle.distance(end);
+    if upper <= 0.0 || lower <= 0.0 {
+        return;
+    }
+
+    let Some(direction) = (target - root)
+        .try_normalize()
+        .or_else(|| (end - root).try_normalize())
+    else {
+        return;
+    };
+    // Keep the chain slightly bent so that the bend direction stays stable.
+    let distance = root
+        .distance(target)
+        .clamp((upper - lower).abs() + 1e-4, (upper + lower) * 0.9999);
+
+    // The bend direction is the pole, or the current direction of the middle
+    // joint, made perpendicular to the direction of the target.
+    let hint = pole.map_or(middle - root, |pole| pole - root);
+    let bend = (hint - direction * hint.dot(direction))
+        .try_normalize()
+        .unwrap_or_else(|| direction.any_orthonormal_vector());
+
+    // Law of cosines.
+    let along = (upper * upper + distance * distance - lower * lower) / (2.0 * distance);
+    let across = (upper * upper - along * along).max(0.0).sqrt();
+    positions[1] = root + direction * along + bend * across;
+    positions[2] = root + direction * distance;
+}
+
+/// Solves a chain of any length with FABRIK, moving all joints of `positions`
+/// but the root.
+fn solve_fabrik(
+    positions: &mut [Vec3],
+    target: Vec3,
+    pole: Option<Vec3>,
+    iterations: u32,
+    tolerance: f32,
+) {
+    let lengths: Vec<f32> = positions
+        .windows(2)
+        .map(|joints| joints[0].distance(joints[1]))
+        .collect();
+    let root = positions[0];
+    let end = positions.len() - 1;
+
+    if root.distance(target) >= lengths.iter().sum::<f32>() {
+        // The target is out of reach: stretch toward it.
+        for index in 0..end {
+            let direction = (target - positions[index]).normalize_or_zero();
+            positions[index + 1] = positions[index] + direction * lengths[index];
+        }
+        return;
+    }
+
+    // Start from a pose bent toward the pole, so that the solver converges to
+    // a solution on that side.
+    if let Some(pole) = pole {
+        for index in 1..end {
+            let (previous, next) = (positions[index - 1], positions[index + 1]);
+            let Some(axis) = (next - previous).try_normalize() else {
+                continue;
+            };
+            let project = |point: Vec3| {
+                let offset = point - previous;
+                offset - axis * offset.dot(axis)
+            };
+            let (from, to) = (project(positions[index]), project(pole));
+            if let (Some(from), Some(to)) = (from.try_normalize(), to.try_normalize()) {
+                positions[index] =
+                    previous + Quat::from_rotation_arc(from, to) * (positions[index] - previous);
+            }
+        }
+    }
+
+    for _ in 0..iterations {
+        // Backward: pin the end effector to the target.
+        positions[end] = target;
+        for index in (0..end).rev() {
+            let direction = (positions[index] - positions[index + 1]).normalize_or_zero();
+            positions[index] = positions[index + 1] + direction * lengths[index];
+        }
+        // Forward: pin the root back to where it was.
+        positions[0] = root;
+        for index in 0..end {
+            let direction = (positions[index + 1] - positions[index]).normalize_or_zero();
+            positions[index + 1] = positions[index] + direction * lengths[index];
+        }
+        if positions[end].distance(target) <= tolerance {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    /// A straight chain of `count` joints spaced one unit apart along Y.
+    fn straight_chain(count: usize) -> IkChain {
+        let locals = (0..count)
+            .map(|index| Transform::from_translation(if index == 0 { Vec3::ZERO } else { Vec3::Y }))
+            .collect();
+        IkChain::new(Transform::IDENTITY, locals, &[])
+    }
+
+    #[test]
+    fn two_bone_reaches_target_toward_pole() {
+        let mut chain = straight_chain(3);
+        let target = Vec3::new(0.0, 1.0, 0.5);
+        chain.solve(IkSolver::TwoBone, target, Some(Vec3::new(1.0, 1.0, 0.0)));
+        let positions = chain.positions();
+        assert!(positions[2].abs_diff_eq(target, 1e-4));
+        assert!(positions[1].x > 0.5);
+        assert!((positions[0].distance(positions[1]) - 1.0).abs() < 1e-4);
+        assert!((positions[1].distance(positions[2]) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn two_bone_out_of_reach() {
+        let mut chain = straight_chain(3);
+        chain.solve(IkSolver::TwoBone, Vec3::new(5.0, 0.0, 0.0), None);
+        let positions = chain.positions();
+        assert!(positions[2].abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-2));
+    }
+
+    #[test]
+    fn iterative_solvers_reach_target() {
+        let target = Vec3::new(1.5, 1.5, 0.5);
+        for solver in [
+            IkSolver::Fabrik {
+                iterations: 20,
+                tolerance: 1e-4,
+            },
+            IkSolver::Ccd {
+                iterations: 50,
+                tolerance: 1e-4,
+            },
+        ] {
+            let mut chain = straight_chain(5);
+            chain.solve(solver, target, None);
+            let positions = chain.positions();
+            assert!(
+                positions[4].distance(target) < 1e-2,
+                "{solver:?} ended at {}",
+                positions[4]
+            );
+        }
+    }
+
+    #[test]
+    fn joint_limits() {
+        let hinge = IkJointLimit::Hinge {
+            axis: Vec3::X,
+            min: 0.0,
+            max: FRAC_PI_2 * 0.5,
+        };
+        let constrained = hinge.constrain(Quat::IDENTITY, Quat::from_rotation_x(FRAC_PI_2));
+        assert!(constrained.abs_diff_eq(Quat::from_rotation_x(FRAC_PI_2 * 0.5), 1e-5));
+        let constrained = hinge.constrain(Quat::IDENTITY, Quat::from_rotation_y(0.5));
+        assert!(constrained.abs_diff_eq(Quat::IDENTITY, 1e-5));
+
+        let cone = IkJointLimit::Cone { max_angle: 0.25 };
+        let constrained = cone.constrain(Quat::IDENTITY, Quat::from_rotation_z(1.0));
+        assert!(constrained.abs_diff_eq(Quat::from_rotation_z(0.25), 1e-5));
+
+        // With the base of the chain locked, CCD can't reach the target.
+        let mut chain = straight_chain(3);
+        chain.limits[0] = Some(IkJointLimit::Cone { max_angle: 0.0 });
+        chain.solve(
+            IkSolver::Ccd {
+                iterations: 10,
+                tolerance: 1e-4,
+            },
+            Vec3::new(1.0, 0.0, 0.0),
+            None,
+        );
+        assert_eq!(chain.locals[0].rotation, Quat::IDENTITY);
+    }
+}
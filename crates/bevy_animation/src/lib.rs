@@ -11,11 +11,19 @@ extern crate alloc;
 
 pub mod animatable;
 pub mod animation_curves;
+pub mod blend_space;
+pub mod compression;
 pub mod gltf_curves;
 pub mod graph;
+pub mod ik;
 #[cfg(feature = "bevy_mesh")]
 mod morph;
+pub mod reflect_curve;
+pub mod retarget;
+pub mod root_motion;
+pub mod state_machine;
 pub mod transition;
+pub mod tween;
 
 mod animation_event;
 mod util;
@@ -40,13 +48,14 @@ use crate::{
 use bevy_app::{AnimationSystems, App, Plugin, PostUpdate};
 use bevy_asset::{Asset, AssetApp, AssetEventSystems, Assets};
 use bevy_ecs::{prelude::*, resource::IsResource, world::EntityMutExcept};
-use bevy_math::FloatOrd;
+use bevy_math::{FloatOrd, Vec2};
 use bevy_platform::{collections::HashMap, hash::NoOpHash};
-use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
+use bevy_reflect::{prelude::ReflectDefault, ParsedPath, Reflect, TypePath};
 use bevy_time::Time;
 use bevy_transform::TransformSystems;
 use bevy_utils::{PreHashMap, PreHashMapExt, TypeIdMap};
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 use thread_local::ThreadLocal;
 use tracing::{trace, warn};
 use uuid::Uuid;
@@ -57,15 +66,25 @@ use uuid::Uuid;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        animatable::*, animation_curves::*, graph::*, transition::*, AnimationClip,
-        AnimationPlayer, AnimationPlugin, VariableCurve,
+        animatable::*, animation_curves::*, blend_space::*, compression::*, graph::*, ik::*,
+        reflect_curve::*, retarget::*, root_motion::*, state_machine::*, transition::*, tween::*,
+        AnimationClip, AnimationPlayer, AnimationPlugin, VariableCurve,
     };
 }
 
 use crate::{
     animation_curves::AnimationCurve,
+    blend_space::BlendSpaceWeights,
     graph::{AnimationGraph, AnimationGraphAssetLoader, AnimationNodeIndex},
+    ik::solve_inverse_kinematics,
+    reflect_curve::AnimationClipAssetLoader,
+    retarget::{RetargetMap, RetargetMapAssetLoader},
+    root_motion::extract_root_motion,
+    state_machine::{
+        advance_state_machines, AnimationStateMachine, AnimationStateMachineAssetLoader,
+    },
     transition::{advance_transitions, expire_completed_transitions},
+    tween::advance_tweens,
 };
 use alloc::sync::Arc;
 
@@ -193,6 +212,36 @@ impl Hash for AnimationTargetId {
     }
 }
 
+/// An [`AnimationTargetId`] as written in serialized assets.
+///
+/// Targets can be given either directly by ID or by the path of names from the
+/// animation root to the target, which is usually more convenient to author by
+/// hand.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SerializedAnimationTargetId {
+    /// The [`AnimationTargetId`] of the target.
+    Id(AnimationTargetId),
+    /// The names of the target and its ancestors, starting from the animation root.
+    ///
+    /// This is hashed into an [`AnimationTargetId`] with [`AnimationTargetId::from_iter`].
+    Path(Vec<String>),
+}
+
+impl From<SerializedAnimationTargetId> for AnimationTargetId {
+    fn from(serialized: SerializedAnimationTargetId) -> Self {
+        match serialized {
+            SerializedAnimationTargetId::Id(id) => id,
+            SerializedAnimationTargetId::Path(names) => AnimationTargetId::from_iter(names),
+        }
+    }
+}
+
+impl From<AnimationTargetId> for SerializedAnimationTargetId {
+    fn from(id: AnimationTargetId) -> Self {
+        SerializedAnimationTargetId::Id(id)
+    }
+}
+
 /// A component that links an animated entity to an entity containing an
 /// [`AnimationPlayer`]. Typically used alongside the [`AnimationTargetId`]
 /// component - the linked `AnimationPlayer` plays [`AnimationClip`] assets, and
@@ -484,6 +533,8 @@ pub struct ActiveAnimation {
     /// `true` if the animation was completed at least once this tick.
     just_completed: bool,
     paused: bool,
+    /// The blend position of a blend space node.
+    blend_position: Vec2,
 }
 
 impl Default for ActiveAnimation {
@@ -498,6 +549,7 @@ impl Default for ActiveAnimation {
             completions: 0,
             just_completed: false,
             paused: false,
+            blend_position: Vec2::ZERO,
         }
     }
 }
@@ -518,6 +570,14 @@ impl ActiveAnimation {
     /// Update the animation given the delta time and the duration of the clip being played.
     #[inline]
     fn update(&mut self, delta: f32, clip_duration: f32) {
+        self.update_scaled(delta, 1.0, clip_duration);
+    }
+
+    /// Update the animation given the delta time, the factor by which to scale
+    /// the delta time when advancing the seek time, and the duration of the
+    /// clip being played.
+    #[inline]
+    fn update_scaled(&mut self, delta: f32, time_scale: f32, clip_duration: f32) {
         self.just_completed = false;
         self.last_seek_time = Some(self.seek_time);
 
@@ -526,7 +586,7 @@ impl ActiveAnimation {
         }
 
         self.elapsed += delta;
-        self.seek_time += delta * self.speed;
+        self.seek_time += delta * time_scale * self.speed;
 
         let over_time = self.speed > 0.0 && self.seek_time >= clip_duration;
         let under_time = self.speed < 0.0 && self.seek_time < 0.0;
@@ -548,6 +608,21 @@ impl ActiveAnimation {
         }
     }
 
+    /// Makes this animation play in lockstep with `leader`, the active
+    /// animation of a blend space, whose seek time is normalized to `[0, 1]`.
+    fn follow(&mut self, leader: &ActiveAnimation, clip_duration: f32, weight: f32) {
+        *self = ActiveAnimation {
+            weight,
+            seek_time: leader.seek_time * clip_duration,
+            last_seek_time: leader.last_seek_time.map(|time| time * clip_duration),
+            // Samples that don't contribute are paused so that they don't
+            // trigger events.
+            paused: leader.paused || weight == 0.0,
+            blend_position: Vec2::ZERO,
+            ..*leader
+        };
+    }
+
     /// Reset back to the initial state as if no time has elapsed.
     pub fn replay(&mut self) {
         self.just_completed = false;
@@ -658,6 +733,29 @@ impl ActiveAnimation {
         self
     }
 
+    /// Returns the blend position of this animation.
+    ///
+    /// See [`set_blend_position`](Self::set_blend_position).
+    pub fn blend_position(&self) -> Vec2 {
+        self.blend_position
+    }
+
+    /// Sets the blend position of this animation.
+    ///
+    /// This only has an effect on animations of [blend space nodes], and
+    /// determines how their children are weighted. [1D blend spaces] only use
+    /// the `x` coordinate.
+    ///
+    /// The seek time of a blend space is normalized, going from 0.0 at the
+    /// start of the cycle of its children to 1.0 at the end of it.
+    ///
+    /// [blend space nodes]: crate::blend_space
+    /// [1D blend spaces]: AnimationNodeType::BlendSpace1d
+    pub fn set_blend_position(&mut self, blend_position: Vec2) -> &mut Self {
+        self.blend_position = blend_position;
+        self
+    }
+
     /// Seeks to the beginning of the animation.
     ///
     /// Note that any events between the current time and `0.0`
@@ -722,6 +820,8 @@ pub struct AnimationEvaluationState {
 struct AnimationCurveEvaluators {
     component_property_curve_evaluators:
         PreHashMap<(TypeId, usize), Box<dyn AnimationCurveEvaluator>>,
+    component_path_curve_evaluators:
+        PreHashMap<(TypeId, Arc<ParsedPath>), Box<dyn AnimationCurveEvaluator>>,
     type_id_curve_evaluators: TypeIdMap<Box<dyn AnimationCurveEvaluator>>,
 }
 
@@ -732,6 +832,9 @@ impl AnimationCurveEvaluators {
             EvaluatorId::ComponentField(component_property) => self
                 .component_property_curve_evaluators
                 .get_mut(component_property),
+            EvaluatorId::ComponentPath(component_path) => {
+                self.component_path_curve_evaluators.get_mut(component_path)
+            }
             EvaluatorId::Type(type_id) => self.type_id_curve_evaluators.get_mut(&type_id),
         }
         .map(|e| &mut **e)
@@ -747,6 +850,9 @@ impl AnimationCurveEvaluators {
             EvaluatorId::ComponentField(component_property) => &mut **self
                 .component_property_curve_evaluators
                 .get_or_insert_with(component_property, func),
+            EvaluatorId::ComponentPath(component_path) => &mut **self
+                .component_path_curve_evaluators
+                .get_or_insert_with(component_path, func),
             EvaluatorId::Type(type_id) => match self.type_id_curve_evaluators.entry(type_id) {
                 bevy_platform::collections::hash_map::Entry::Occupied(occupied_entry) => {
                     &mut **occupied_entry.into_mut()
@@ -762,6 +868,7 @@ impl AnimationCurveEvaluators {
 #[derive(Default)]
 struct CurrentEvaluators {
     component_properties: PreHashMap<(TypeId, usize), ()>,
+    component_paths: PreHashMap<(TypeId, Arc<ParsedPath>), ()>,
     type_ids: TypeIdMap<()>,
 }
 
@@ -770,6 +877,7 @@ impl CurrentEvaluators {
         self.component_properties
             .keys()
             .map(EvaluatorId::ComponentField)
+            .chain(self.component_paths.keys().map(EvaluatorId::ComponentPath))
             .chain(self.type_ids.keys().copied().map(EvaluatorId::Type))
     }
 
@@ -781,6 +889,10 @@ impl CurrentEvaluators {
             (visit)(EvaluatorId::ComponentField(&key))?;
         }
 
+        for (key, _) in self.component_paths.drain() {
+            (visit)(EvaluatorId::ComponentPath(&key))?;
+        }
+
         for (key, _) in self.type_ids.drain() {
             (visit)(EvaluatorId::Type(key))?;
         }
@@ -794,6 +906,9 @@ impl CurrentEvaluators {
             EvaluatorId::ComponentField(component_property) => {
                 self.component_properties.insert(*component_property, ());
             }
+            EvaluatorId::ComponentPath(component_path) => {
+                self.component_paths.insert(component_path.clone(), ());
+            }
             EvaluatorId::Type(type_id) => {
                 self.type_ids.insert(type_id, ());
             }
@@ -954,7 +1069,10 @@ fn trigger_untargeted_animation_events(
                 .get(*index)
                 .and_then(|node| match &node.node_type {
                     AnimationNodeType::Clip(handle) => Some(handle),
-                    AnimationNodeType::Blend | AnimationNodeType::Add => None,
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::Add
+                    | AnimationNodeType::BlendSpace1d(_)
+                    | AnimationNodeType::BlendSpace2d(_) => None,
                 })
                 .and_then(|id| clips.get(id))
             else {
@@ -996,6 +1114,38 @@ pub fn advance_animations(
                 ..
             } = *player;
 
+            // Blend spaces drive the clips they blend between, so tick them
+            // first and remember which clips they're responsible for.
+            let mut blend_space_samples: SmallVec<[AnimationNodeIndex; 8]> = SmallVec::new();
+            for node_index in animation_graph.graph.node_indices() {
+                let first_sample = blend_space_samples.len();
+                let blend_position = active_animations
+                    .get(&node_index)
+                    .map(|active_animation| active_animation.blend_position);
+                let weights = match animation_graph[node_index].node_type {
+                    AnimationNodeType::BlendSpace1d(ref blend_space) => {
+                        blend_space_samples
+                            .extend(blend_space.samples().iter().map(|sample| sample.node));
+                        blend_position.map(|position| blend_space.weights(position.x))
+                    }
+                    AnimationNodeType::BlendSpace2d(ref blend_space) => {
+                        blend_space_samples
+                            .extend(blend_space.samples().iter().map(|sample| sample.node));
+                        blend_position.map(|position| blend_space.weights(position))
+                    }
+                    _ => continue,
+                };
+                advance_blend_space(
+                    active_animations,
+                    node_index,
+                    &blend_space_samples[first_sample..],
+                    weights,
+                    animation_graph,
+                    &animation_clips,
+                    delta_seconds,
+                );
+            }
+
             for node_index in animation_graph.graph.node_indices() {
                 let node = &animation_graph[node_index];
 
@@ -1003,6 +1153,7 @@ pub fn advance_animations(
                     // Tick the animation if necessary.
                     if !active_animation.paused
                         && let AnimationNodeType::Clip(ref clip_handle) = node.node_type
+                        && !blend_space_samples.contains(&node_index)
                         && let Some(clip) = animation_clips.get(clip_handle)
                     {
                         active_animation.update(delta_seconds, clip.duration);
@@ -1012,6 +1163,63 @@ pub fn advance_animations(
         });
 }
 
+/// Ticks the active animation of a blend space, if any, and synchronizes the
+/// active animations of its samples with it.
+fn advance_blend_space(
+    active_animations: &mut HashMap<AnimationNodeIndex, ActiveAnimation>,
+    blend_space_index: AnimationNodeIndex,
+    samples: &[AnimationNodeIndex],
+    weights: Option<BlendSpaceWeights>,
+    animation_graph: &AnimationGraph,
+    animation_clips: &Assets<AnimationClip>,
+    delta_seconds: f32,
+) {
+    let (Some(weights), Some(mut blend_space)) =
+        (weights, active_animations.get(&blend_space_index).copied())
+    else {
+        // The blend space isn't playing, so neither are its samples.
+        for sample in samples {
+            active_animations.remove(sample);
+        }
+        return;
+    };
+
+    let clip_duration = |node_index| match animation_graph.get(node_index)?.node_type {
+        AnimationNodeType::Clip(ref clip_handle) => {
+            animation_clips.get(clip_handle).map(|clip| clip.duration)
+        }
+        _ => None,
+    };
+
+    // The seek time of the blend space is normalized. It advances at the rate
+    // of the weighted average of the durations of the blended clips.
+    let (weighted_duration, total_weight) = weights
+        .iter()
+        .filter_map(|&(node_index, weight)| Some((clip_duration(node_index)?, weight)))
+        .fold((0.0, 0.0), |(duration, total), (clip_duration, weight)| {
+            (duration + clip_duration * weight, total + weight)
+        });
+    if !blend_space.paused && weighted_duration > 0.0 {
+        blend_space.update_scaled(delta_seconds, total_weight / weighted_duration, 1.0);
+        active_animations.insert(blend_space_index, blend_space);
+    }
+
+    for &sample in samples {
+        let Some(clip_duration) = clip_duration(sample) else {
+            continue;
+        };
+        let weight = weights
+            .iter()
+            .find(|(node_index, _)| *node_index == sample)
+            .map_or(0.0, |&(_, weight)| weight);
+        active_animations.entry(sample).or_default().follow(
+            &blend_space,
+            clip_duration,
+            weight * blend_space.weight,
+        );
+    }
+}
+
 /// A type alias for [`EntityMutExcept`] as used in animation.
 pub type AnimationEntityMut<'w, 's> = EntityMutExcept<
     'w,
@@ -1106,6 +1314,34 @@ pub fn animate_targets(
                         }
                     }
 
+                    AnimationNodeType::BlendSpace1d(_) | AnimationNodeType::BlendSpace2d(_) => {
+                        // This is a blend space node. Its children have already
+                        // been weighted according to its blend position.
+                        for edge_index in threaded_animation_graph.sorted_edge_ranges
+                            [animation_graph_node_index.index()]
+                        .clone()
+                        {
+                            if let Err(err) = evaluation_state.blend_all(
+                                threaded_animation_graph.sorted_edges[edge_index as usize],
+                            ) {
+                                warn!("Failed to blend animation: {:?}", err);
+                            }
+                        }
+
+                        // Unlike other blend nodes, blend spaces are played, so
+                        // they can be faded in and out by their active weight.
+                        let active_weight = animation_player
+                            .active_animations
+                            .get(&animation_graph_node_index)
+                            .map_or(0.0, |active_animation| active_animation.weight);
+                        if let Err(err) = evaluation_state.push_blend_register_all(
+                            animation_graph_node.weight * active_weight,
+                            animation_graph_node_index,
+                        ) {
+                            warn!("Animation blending failed: {:?}", err);
+                        }
+                    }
+
                     AnimationNodeType::Add => {
                         // This is an additive blend node.
                         for edge_index in threaded_animation_graph.sorted_edge_ranges
@@ -1228,14 +1464,22 @@ impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<AnimationClip>()
             .init_asset::<AnimationGraph>()
+            .init_asset_loader::<AnimationClipAssetLoader>()
             .init_asset_loader::<AnimationGraphAssetLoader>()
             .register_asset_reflect::<AnimationClip>()
             .register_asset_reflect::<AnimationGraph>()
+            .init_asset::<AnimationStateMachine>()
+            .init_asset_loader::<AnimationStateMachineAssetLoader>()
+            .register_asset_reflect::<AnimationStateMachine>()
+            .init_asset::<RetargetMap>()
+            .init_asset_loader::<RetargetMapAssetLoader>()
+            .register_asset_reflect::<RetargetMap>()
             .init_resource::<ThreadedAnimationGraphs>()
             .add_systems(
                 PostUpdate,
                 (
                     graph::thread_animation_graphs.before(AssetEventSystems),
+                    advance_state_machines,
                     advance_transitions,
                     advance_animations,
                     // TODO: `animate_targets` can animate anything, so
@@ -1245,6 +1489,10 @@ impl Plugin for AnimationPlugin {
                     // `PostUpdate`. For now, we just disable ambiguity testing
                     // for this system.
                     animate_targets.ambiguous_with_all(),
+                    // Like `animate_targets`, tweens can animate anything.
+                    advance_tweens.ambiguous_with_all(),
+                    extract_root_motion,
+                    solve_inverse_kinematics,
                     trigger_untargeted_animation_events,
                     expire_completed_transitions,
                 )
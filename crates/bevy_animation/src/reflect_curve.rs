@@ -0,0 +1,710 @@
+//! Animation curves that target component properties through reflection.
+//!
+//! [`AnimatedField`] and custom [`AnimatableProperty`] implementations select the animated
+//! property with Rust closures, which can't be written to or read from files. A
+//! [`ReflectPathProperty`] instead describes the property by the [type path] of a component and
+//! a [reflection path] into it, and a [`ReflectPathCurve`] pairs such a property with keyframes
+//! of a known value type. Clips made up of [`ReflectPathCurve`]s can be saved with
+//! [`AnimationClip::save`] and loaded with the [`AnimationClipAssetLoader`]:
+//!
+//! ```ron
+//! (
+//!     curves: [
+//!         (
+//!             target: Path(["Door"]),
+//!             component: "my_game::Door",
+//!             path: ".openness",
+//!             keyframes: F32([(0.0, 0.0), (1.5, 1.0)]),
+//!         ),
+//!         (
+//!             target: Path(["Door", "Lamp"]),
+//!             component: "my_game::Lamp",
+//!             path: ".glow.offset",
+//!             keyframes: Vec2([(0.0, (0.0, 0.0)), (1.5, (0.0, 0.5))]),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Properties animated through reflection are evaluated separately from properties animated
+//! through [`AnimatedField`], even if both refer to the same field, so the two shouldn't be
+//! mixed on the same property.
+//!
+//! [`AnimatedField`]: crate::animation_curves::AnimatedField
+//! [type path]: bevy_reflect::TypePath
+//! [reflection path]: ParsedPath
+
+use alloc::sync::Arc;
+use core::{any::TypeId, fmt::Debug, fmt::Write, marker::PhantomData};
+use std::io;
+
+use bevy_asset::{io::Reader, AssetLoader, LoadContext};
+use bevy_ecs::{
+    component::{Component, Mutable},
+    reflect::{AppTypeRegistry, ReflectComponent},
+    world::{FromWorld, World},
+};
+use bevy_math::{
+    curve::{cores::UnevenCoreError, ConstantCurve, Interval},
+    Quat, Vec2, Vec3, Vec4,
+};
+use bevy_platform::hash::Hashed;
+use bevy_reflect::{
+    FromReflect, FromType, ParsedPath, Reflect, ReflectPath, Reflectable, TypePath, TypeRegistry,
+    TypeRegistryArc,
+};
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    animatable::Animatable,
+    animation_curves::{
+        AnimatableCurve, AnimatableKeyframeCurve, AnimatableProperty, AnimationCurve,
+        AnimationCurveEvaluator, EvaluatorId,
+    },
+    graph::AnimationNodeIndex,
+    AnimationClip, AnimationEntityMut, AnimationEvaluationError, AnimationTargetId,
+    SerializedAnimationTargetId, VariableCurve,
+};
+
+/// An [`AnimatableProperty`] that reaches a property of type `A` through a reflection path from
+/// a component.
+///
+/// The component must be registered with [`ReflectComponent`] type data when the property is
+/// created from a [`TypeRegistry`], and it must be mutable.
+pub struct ReflectPathProperty<A> {
+    target: ReflectPathTarget,
+    marker: PhantomData<fn() -> A>,
+}
+
+/// The untyped part of a [`ReflectPathProperty`].
+#[derive(Clone)]
+struct ReflectPathTarget {
+    component_type_path: &'static str,
+    reflect_component: ReflectComponent,
+    /// A pre-hashed (component-type-id, path) pair, uniquely identifying the property.
+    evaluator_id: Hashed<(TypeId, Arc<ParsedPath>)>,
+}
+
+/// An [`AnimationCurve`] that animates a [`ReflectPathProperty`] from keyframes, and that can be
+/// serialized as part of an [`AnimationClip`].
+#[derive(Clone, Debug)]
+pub struct ReflectPathCurve {
+    component_type_path: &'static str,
+    path: Arc<ParsedPath>,
+    keyframes: ReflectPathKeyframes,
+    curve: VariableCurve,
+}
+
+/// The keyframes of a [`ReflectPathCurve`], as `(time, value)` pairs.
+///
+/// Values between keyframes are interpolated with [`Animatable::interpolate`]. A curve with a
+/// single keyframe holds its value forever.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReflectPathKeyframes {
+    /// Keyframes of an `f32` property.
+    F32(Vec<(f32, f32)>),
+    /// Keyframes of a [`Vec2`] property.
+    Vec2(Vec<(f32, Vec2)>),
+    /// Keyframes of a [`Vec3`] property.
+    Vec3(Vec<(f32, Vec3)>),
+    /// Keyframes of a [`Vec4`] property.
+    Vec4(Vec<(f32, Vec4)>),
+    /// Keyframes of a [`Quat`] property.
+    Quat(Vec<(f32, Quat)>),
+}
+
+/// Errors that can occur when creating a [`ReflectPathProperty`] or [`ReflectPathCurve`].
+#[derive(Error, Debug)]
+pub enum ReflectPathCurveError {
+    /// The component type isn't registered in the type registry.
+    #[error("The type `{0}` isn't registered")]
+    UnregisteredType(String),
+    /// The component type has no [`ReflectComponent`] type data.
+    #[error("The type `{0}` isn't registered as a reflected component")]
+    NotAComponent(String),
+    /// The reflection path couldn't be parsed.
+    #[error("Invalid reflection path `{path}`: {message}")]
+    InvalidPath {
+        /// The path that failed to parse.
+        path: String,
+        /// A description of the parse error.
+        message: String,
+    },
+    /// The keyframes didn't form a valid curve.
+    #[error(transparent)]
+    Keyframes(#[from] UnevenCoreError),
+}
+
+/// A version of [`AnimationClip`] suitable for serializing as an asset.
+///
+/// Only clips made up entirely of [`ReflectPathCurve`]s can be serialized. Events aren't
+/// serialized.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedAnimationClip {
+    /// The duration of the clip.
+    ///
+    /// If this isn't given, the clip lasts until the end of its last curve.
+    #[serde(default)]
+    pub duration: Option<f32>,
+    /// The curves of the clip.
+    pub curves: Vec<SerializedReflectPathCurve>,
+}
+
+/// A version of [`ReflectPathCurve`] suitable for serializing as an asset.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedReflectPathCurve {
+    /// The animation target the curve applies to.
+    pub target: SerializedAnimationTargetId,
+    /// The type path of the animated component.
+    pub component: String,
+    /// The reflection path from the component to the animated property.
+    pub path: String,
+    /// The keyframes of the curve.
+    pub keyframes: ReflectPathKeyframes,
+}
+
+/// Errors that can occur when serializing animation clips to RON.
+#[derive(Error, Debug)]
+pub enum AnimationClipSaveError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON serialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// The clip contains a curve that isn't a [`ReflectPathCurve`].
+    #[error("The animation clip has a curve for target {0:?} that can't be serialized")]
+    UnserializableCurve(AnimationTargetId),
+}
+
+/// Errors that can occur when deserializing animation clips from RON.
+#[derive(Error, Debug)]
+pub enum AnimationClipLoadError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON deserialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// An error occurred in RON deserialization, and the location of the error
+    /// is supplied.
+    #[error(transparent)]
+    SpannedRon(#[from] SpannedError),
+    /// A curve couldn't be created.
+    #[error(transparent)]
+    Curve(#[from] ReflectPathCurveError),
+}
+
+/// An [`AssetLoader`] that can load [`AnimationClip`]s made up of [`ReflectPathCurve`]s as
+/// assets.
+///
+/// The canonical extension for these clips is `.animclip.ron`. Plain `.animclip` is supported
+/// as well.
+#[derive(TypePath)]
+pub struct AnimationClipAssetLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl<A> Clone for ReflectPathProperty<A> {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> ReflectPathProperty<A> {
+    /// Creates a property that reaches the value at `path` in the component `C`.
+    pub fn new<C>(path: &str) -> Result<Self, ReflectPathCurveError>
+    where
+        C: Component<Mutability = Mutable> + Reflect + TypePath,
+    {
+        Ok(Self {
+            target: ReflectPathTarget::new::<C>(path)?,
+            marker: PhantomData,
+        })
+    }
+
+    /// Creates a property that reaches the value at `path` in the component with the given
+    /// type path, looking the component up in `type_registry`.
+    pub fn from_registry(
+        type_registry: &TypeRegistry,
+        component_type_path: &str,
+        path: &str,
+    ) -> Result<Self, ReflectPathCurveError> {
+        Ok(Self {
+            target: ReflectPathTarget::from_registry(type_registry, component_type_path, path)?,
+            marker: PhantomData,
+        })
+    }
+
+    /// The type path of the animated component.
+    pub fn component_type_path(&self) -> &'static str {
+        self.target.component_type_path
+    }
+
+    /// The reflection path from the component to the animated property.
+    pub fn path(&self) -> &ParsedPath {
+        &self.target.evaluator_id.1
+    }
+}
+
+impl<A> AnimatableProperty for ReflectPathProperty<A>
+where
+    A: Animatable,
+{
+    type Property = A;
+
+    fn get_mut<'a>(
+        &self,
+        entity: &'a mut AnimationEntityMut,
+    ) -> Result<&'a mut A, AnimationEvaluationError> {
+        let (component_type_id, path) = &*self.target.evaluator_id;
+        let component = self
+            .target
+            .reflect_component
+            .reflect_mut(entity)
+            .ok_or(AnimationEvaluationError::ComponentNotPresent(
+                *component_type_id,
+            ))?
+            .into_inner();
+        (&**path)
+            .reflect_element_mut(component.as_partial_reflect_mut())
+            .ok()
+            .and_then(|property| property.try_downcast_mut::<A>())
+            .ok_or(AnimationEvaluationError::PropertyNotPresent(
+                TypeId::of::<A>(),
+            ))
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        EvaluatorId::ComponentPath(&self.target.evaluator_id)
+    }
+}
+
+impl ReflectPathTarget {
+    fn new<C>(path: &str) -> Result<Self, ReflectPathCurveError>
+    where
+        C: Component<Mutability = Mutable> + Reflect + TypePath,
+    {
+        Ok(Self {
+            component_type_path: C::type_path(),
+            reflect_component: <ReflectComponent as FromType<C>>::from_type(),
+            evaluator_id: Hashed::new((TypeId::of::<C>(), Arc::new(parse_path(path)?))),
+        })
+    }
+
+    fn from_registry(
+        type_registry: &TypeRegistry,
+        component_type_path: &str,
+        path: &str,
+    ) -> Result<Self, ReflectPathCurveError> {
+        let registration = type_registry
+            .get_with_type_path(component_type_path)
+            .ok_or_else(|| ReflectPathCurveError::UnregisteredType(component_type_path.into()))?;
+        let reflect_component = registration
+            .data::<ReflectComponent>()
+            .ok_or_else(|| ReflectPathCurveError::NotAComponent(component_type_path.into()))?;
+        Ok(Self {
+            component_type_path: registration.type_info().type_path(),
+            reflect_component: reflect_component.clone(),
+            evaluator_id: Hashed::new((registration.type_id(), Arc::new(parse_path(path)?))),
+        })
+    }
+}
+
+fn parse_path(path: &str) -> Result<ParsedPath, ReflectPathCurveError> {
+    ParsedPath::parse(path).map_err(|err| ReflectPathCurveError::InvalidPath {
+        path: path.into(),
+        message: err.to_string(),
+    })
+}
+
+impl ReflectPathCurve {
+    /// Creates a curve that animates the value at `path` in the component `C` from the given
+    /// keyframes.
+    pub fn new<C>(
+        path: &str,
+        keyframes: ReflectPathKeyframes,
+    ) -> Result<Self, ReflectPathCurveError>
+    where
+        C: Component<Mutability = Mutable> + Reflect + TypePath,
+    {
+        Self::from_target(ReflectPathTarget::new::<C>(path)?, keyframes)
+    }
+
+    /// Creates a curve that animates the value at `path` in the component with the given type
+    /// path from the given keyframes, looking the component up in `type_registry`.
+    pub fn from_registry(
+        type_registry: &TypeRegistry,
+        component_type_path: &str,
+        path: &str,
+        keyframes: ReflectPathKeyframes,
+    ) -> Result<Self, ReflectPathCurveError> {
+        Self::from_target(
+            ReflectPathTarget::from_registry(type_registry, component_type_path, path)?,
+            keyframes,
+        )
+    }
+
+    fn from_target(
+        target: ReflectPathTarget,
+        keyframes: ReflectPathKeyframes,
+    ) -> Result<Self, ReflectPathCurveError> {
+        let component_type_path = target.component_type_path;
+        let path = target.evaluator_id.1.clone();
+        let curve = match &keyframes {
+            ReflectPathKeyframes::F32(keyframes) => keyframe_curve(target, keyframes),
+            ReflectPathKeyframes::Vec2(keyframes) => keyframe_curve(target, keyframes),
+            ReflectPathKeyframes::Vec3(keyframes) => keyframe_curve(target, keyframes),
+            ReflectPathKeyframes::Vec4(keyframes) => keyframe_curve(target, keyframes),
+            ReflectPathKeyframes::Quat(keyframes) => keyframe_curve(target, keyframes),
+        }?;
+        Ok(Self {
+            component_type_path,
+            path,
+            keyframes,
+            curve,
+        })
+    }
+
+    /// The type path of the animated component.
+    pub fn component_type_path(&self) -> &'static str {
+        self.component_type_path
+    }
+
+    /// The reflection path from the component to the animated property.
+    pub fn path(&self) -> &ParsedPath {
+        &self.path
+    }
+
+    /// The keyframes of this curve.
+    pub fn keyframes(&self) -> &ReflectPathKeyframes {
+        &self.keyframes
+    }
+}
+
+fn keyframe_curve<A>(
+    target: ReflectPathTarget,
+    keyframes: &[(f32, A)],
+) -> Result<VariableCurve, ReflectPathCurveError>
+where
+    A: Animatable + Clone + Debug + FromReflect + Reflectable,
+{
+    let property = ReflectPathProperty::<A> {
+        target,
+        marker: PhantomData,
+    };
+    if let [(time, value)] = keyframes {
+        if !time.is_finite() {
+            return Err(UnevenCoreError::NotEnoughSamples { samples: 0 }.into());
+        }
+        return Ok(VariableCurve::new(AnimatableCurve::new(
+            property,
+            ConstantCurve::new(Interval::EVERYWHERE, value.clone()),
+        )));
+    }
+    Ok(VariableCurve::new(AnimatableCurve::new(
+        property,
+        AnimatableKeyframeCurve::new(keyframes.iter().cloned())?,
+    )))
+}
+
+impl AnimationCurve for ReflectPathCurve {
+    fn clone_value(&self) -> Box<dyn AnimationCurve> {
+        Box::new(self.clone())
+    }
+
+    fn domain(&self) -> Interval {
+        self.curve.0.domain()
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        self.curve.0.evaluator_id()
+    }
+
+    fn create_evaluator(&self) -> Box<dyn AnimationCurveEvaluator> {
+        self.curve.0.create_evaluator()
+    }
+
+    fn apply(
+        &self,
+        curve_evaluator: &mut dyn AnimationCurveEvaluator,
+        t: f32,
+        weight: f32,
+        graph_node: AnimationNodeIndex,
+    ) -> Result<(), AnimationEvaluationError> {
+        self.curve.0.apply(curve_evaluator, t, weight, graph_node)
+    }
+}
+
+impl AnimationClip {
+    /// Serializes this clip in [RON] format to the given writer.
+    ///
+    /// Every curve of the clip must be a [`ReflectPathCurve`]. Events aren't serialized.
+    ///
+    /// [RON]: https://github.com/ron-rs/ron
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), AnimationClipSaveError>
+    where
+        W: Write,
+    {
+        let mut ron_serializer = ron::ser::Serializer::new(writer, None)?;
+        let serialized = SerializedAnimationClip::try_from(self)?;
+        Ok(serialized.serialize(&mut ron_serializer)?)
+    }
+}
+
+impl SerializedAnimationClip {
+    /// Creates the [`AnimationClip`] described by this serialized clip, looking up the animated
+    /// components in `type_registry`.
+    pub fn into_animation_clip(
+        self,
+        type_registry: &TypeRegistry,
+    ) -> Result<AnimationClip, ReflectPathCurveError> {
+        let mut clip = AnimationClip::default();
+        for curve in self.curves {
+            clip.add_curve_to_target(
+                curve.target.into(),
+                ReflectPathCurve::from_registry(
+                    type_registry,
+                    &curve.component,
+                    &curve.path,
+                    curve.keyframes,
+                )?,
+            );
+        }
+        if let Some(duration) = self.duration {
+            clip.set_duration(duration);
+        }
+        Ok(clip)
+    }
+}
+
+impl TryFrom<&AnimationClip> for SerializedAnimationClip {
+    type Error = AnimationClipSaveError;
+
+    fn try_from(clip: &AnimationClip) -> Result<Self, Self::Error> {
+        // Sort by target so that saving the same clip always produces the same output.
+        let mut targets: Vec<_> = clip.curves.iter().collect();
+        targets.sort_unstable_by_key(|(target_id, _)| **target_id);
+
+        let mut curves = vec![];
+        for (&target_id, target_curves) in targets {
+            for curve in target_curves {
+                let curve = curve
+                    .0
+                    .downcast_ref::<ReflectPathCurve>()
+                    .ok_or(AnimationClipSaveError::UnserializableCurve(target_id))?;
+                curves.push(SerializedReflectPathCurve {
+                    target: target_id.into(),
+                    component: curve.component_type_path.into(),
+                    path: curve.path.to_string(),
+                    keyframes: curve.keyframes.clone(),
+                });
+            }
+        }
+        Ok(Self {
+            duration: Some(clip.duration),
+            curves,
+        })
+    }
+}
+
+impl FromWorld for AnimationClipAssetLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for AnimationClipAssetLoader {
+    type Asset = AnimationClip;
+
+    type Settings = ();
+
+    type Error = AnimationClipLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let serialized = SerializedAnimationClip::deserialize(&mut deserializer)
+            .map_err(|err| deserializer.span_error(err))?;
+        Ok(serialized.into_animation_clip(&self.type_registry.read())?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["animclip", "animclip.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        name::Name,
+        query::With,
+        system::{Query, RunSystemOnce},
+    };
+    use bevy_reflect::std_traits::ReflectDefault;
+
+    use super::*;
+    use crate::{animated_field, animation_curves::AnimatedField};
+
+    #[derive(Component, Reflect, Clone, Default)]
+    #[reflect(Component, Default)]
+    struct Lamp {
+        brightness: f32,
+        glow: Glow,
+    }
+
+    #[derive(Reflect, Clone, Default)]
+    struct Glow {
+        offset: Vec2,
+    }
+
+    fn registry() -> TypeRegistry {
+        let mut type_registry = TypeRegistry::new();
+        type_registry.register::<Lamp>();
+        type_registry
+    }
+
+    /// Samples `curve` at `t` and writes the result to the only [`Lamp`] in `world`.
+    fn apply(world: &mut World, curve: &dyn AnimationCurve, t: f32) {
+        let mut evaluator = curve.create_evaluator();
+        curve
+            .apply(&mut *evaluator, t, 1.0, AnimationNodeIndex::default())
+            .unwrap();
+        world
+            .run_system_once(move |mut query: Query<AnimationEntityMut, With<Lamp>>| {
+                evaluator.commit(query.single_mut().unwrap()).unwrap();
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn animate_nested_field() {
+        let mut world = World::new();
+        world.spawn(Lamp::default());
+
+        let curve = ReflectPathCurve::new::<Lamp>(
+            ".glow.offset",
+            ReflectPathKeyframes::Vec2(vec![(0.0, Vec2::ZERO), (2.0, Vec2::new(2.0, 4.0))]),
+        )
+        .unwrap();
+        apply(&mut world, &curve, 1.0);
+
+        let lamp = world.query::<&Lamp>().single(&world).unwrap();
+        assert_eq!(lamp.glow.offset, Vec2::new(1.0, 2.0));
+
+        let constant = ReflectPathCurve::from_registry(
+            &registry(),
+            Lamp::type_path(),
+            "brightness",
+            ReflectPathKeyframes::F32(vec![(0.5, 3.0)]),
+        )
+        .unwrap();
+        apply(&mut world, &constant, 2.0);
+        let lamp = world.query::<&Lamp>().single(&world).unwrap();
+        assert_eq!(lamp.brightness, 3.0);
+    }
+
+    #[test]
+    fn mismatched_property_type() {
+        let mut world = World::new();
+        world.spawn(Lamp::default());
+        let curve = ReflectPathCurve::new::<Lamp>(
+            ".brightness",
+            ReflectPathKeyframes::Vec3(vec![(0.0, Vec3::ONE)]),
+        )
+        .unwrap();
+        let mut evaluator = curve.create_evaluator();
+        curve
+            .apply(&mut *evaluator, 0.0, 1.0, AnimationNodeIndex::default())
+            .unwrap();
+        let result = world
+            .run_system_once(move |mut query: Query<AnimationEntityMut, With<Lamp>>| {
+                evaluator.commit(query.single_mut().unwrap())
+            })
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(AnimationEvaluationError::PropertyNotPresent(_))
+        ));
+    }
+
+    #[test]
+    fn clip_round_trip() {
+        let source = format!(
+            r#"(
+                curves: [
+                    (
+                        target: Path(["Lamp"]),
+                        component: "{}",
+                        path: ".glow.offset",
+                        keyframes: Vec2([(0.0, (0.0, 0.0)), (1.5, (0.0, 0.5))]),
+                    ),
+                ],
+            )"#,
+            Lamp::type_path()
+        );
+        let serialized: SerializedAnimationClip = ron::de::from_str(&source).unwrap();
+        let clip = serialized.into_animation_clip(&registry()).unwrap();
+        let target = AnimationTargetId::from_name(&Name::new("Lamp"));
+        assert_eq!(clip.duration(), 1.5);
+        assert_eq!(clip.curves_for_target(target).unwrap().len(), 1);
+
+        let mut ron = String::new();
+        clip.save(&mut ron).unwrap();
+        let reloaded: SerializedAnimationClip = ron::de::from_str(&ron).unwrap();
+        let reloaded = reloaded.into_animation_clip(&registry()).unwrap();
+        let curve = reloaded.curves_for_target(target).unwrap()[0]
+            .0
+            .downcast_ref::<ReflectPathCurve>()
+            .unwrap();
+        assert_eq!(curve.path(), &ParsedPath::parse(".glow.offset").unwrap());
+        assert_eq!(
+            curve.keyframes(),
+            &ReflectPathKeyframes::Vec2(vec![(0.0, Vec2::ZERO), (1.5, Vec2::new(0.0, 0.5))])
+        );
+    }
+
+    #[test]
+    fn load_errors() {
+        let registry = registry();
+        assert!(matches!(
+            ReflectPathProperty::<f32>::from_registry(&registry, "my_game::Missing", ".x"),
+            Err(ReflectPathCurveError::UnregisteredType(_))
+        ));
+        assert!(matches!(
+            ReflectPathProperty::<f32>::from_registry(&registry, Lamp::type_path(), ".glow["),
+            Err(ReflectPathCurveError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            ReflectPathCurve::new::<Lamp>(".brightness", ReflectPathKeyframes::F32(vec![])),
+            Err(ReflectPathCurveError::Keyframes(_))
+        ));
+
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            AnimationTargetId::from_name(&Name::new("Lamp")),
+            AnimatableCurve::new(
+                animated_field!(Lamp::brightness),
+                ConstantCurve::new(Interval::UNIT, 1.0),
+            ),
+        );
+        assert!(matches!(
+            clip.save(&mut String::new()),
+            Err(AnimationClipSaveError::UnserializableCurve(_))
+        ));
+    }
+}
@@ -0,0 +1,735 @@
+//! Retargeting animation clips between skeletons.
+//!
+//! An [`AnimationClip`] refers to the bones it animates by [`AnimationTargetId`], so a clip
+//! authored for one rig only plays on rigs whose bones have the same names and hierarchy. Even
+//! then, the clip stores *local* rotations and translations that only look right on a skeleton
+//! with the same rest pose and proportions as the one it was authored for.
+//!
+//! A [`RetargetMap`] describes how the bones of a source skeleton correspond to the bones of a
+//! target skeleton, together with the rest pose of each bone on both rigs. Calling
+//! [`RetargetMap::retarget`] produces a new [`AnimationClip`] that animates the target
+//! skeleton: rotations are compensated for the difference in rest poses, and translations are
+//! either discarded or rescaled to the target's proportions.
+
+use core::{any::TypeId, fmt::Write};
+use std::io;
+
+use bevy_asset::{io::Reader, Asset, AssetLoader, LoadContext};
+use bevy_ecs::{entity::Entity, hierarchy::ChildOf, world::World};
+use bevy_math::{curve::Interval, Quat, Vec3};
+use bevy_platform::collections::HashSet;
+use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
+use bevy_transform::components::Transform;
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    animated_field,
+    animation_curves::{
+        map_top_sample, AnimatableCurveEvaluator, AnimatableProperty, AnimatedField,
+        AnimationCurve, AnimationCurveEvaluator, EvaluatorId,
+    },
+    graph::AnimationNodeIndex,
+    AnimationClip, AnimationEvaluationError, AnimationEventTarget, AnimationPlayer,
+    AnimationTargetId, SerializedAnimationTargetId, VariableCurve,
+};
+
+/// A mapping from the bones of one skeleton to the bones of another, used to play
+/// [`AnimationClip`]s authored for the first skeleton on the second one.
+///
+/// Each [`RetargetBone`] maps a single source bone to a single target bone and records the rest
+/// pose of both. Bones of the target skeleton that aren't mapped keep their own pose.
+///
+/// Retarget maps can be loaded from [RON] files with the `.retarget.ron` extension. Bones can be
+/// referred to either by [`AnimationTargetId`] or by the path of names from the animation root
+/// to the bone, as used by glTF and most other loaders:
+///
+/// ```ron
+/// (
+///     bones: [
+///         (
+///             source: Path(["mixamorig:Hips"]),
+///             target: Path(["Armature", "Hips"]),
+///             source_rest: (translation: (0.0, 1.0, 0.0)),
+///             target_rest: (translation: (0.0, 0.8, 0.0)),
+///             translation: Proportional,
+///         ),
+///         (
+///             source: Path(["mixamorig:Hips", "mixamorig:Spine"]),
+///             target: Path(["Armature", "Hips", "Spine"]),
+///             source_rest: (rotation: (0.0, 0.0, 0.0, 1.0)),
+///             target_rest: (rotation: (0.087, 0.0, 0.0, 0.996)),
+///         ),
+///     ],
+/// )
+/// ```
+///
+/// [RON]: https://github.com/ron-rs/ron
+#[derive(Asset, Reflect, Clone, Debug, Default)]
+#[reflect(Debug, Clone, Default)]
+pub struct RetargetMap {
+    /// The bone mappings.
+    pub bones: Vec<RetargetBone>,
+}
+
+/// Maps one bone of the source skeleton of a [`RetargetMap`] to a bone of the target skeleton.
+#[derive(Reflect, Clone, Debug, PartialEq)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct RetargetBone {
+    /// The bone that the original [`AnimationClip`] animates.
+    pub source: AnimationTargetId,
+
+    /// The bone that the retargeted [`AnimationClip`] animates.
+    pub target: AnimationTargetId,
+
+    /// The rest pose of the source bone.
+    pub source_rest: RetargetRestPose,
+
+    /// The rest pose of the target bone.
+    pub target_rest: RetargetRestPose,
+
+    /// How the translation of the source bone carries over to the target bone.
+    pub translation: RetargetTranslation,
+}
+
+/// The rest pose of a bone, as needed for retargeting.
+///
+/// Rotations are compensated in the space of the skeleton, so besides the local transform of the
+/// bone this also records the accumulated rotation of all its ancestors up to the skeleton root.
+/// [`RetargetRestPose::from_world`] computes this from a spawned skeleton.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RetargetRestPose {
+    /// The rotation of the parent of the bone, relative to the root of the skeleton.
+    pub parent_rotation: Quat,
+
+    /// The local rotation of the bone.
+    pub rotation: Quat,
+
+    /// The local translation of the bone.
+    pub translation: Vec3,
+}
+
+/// Determines how [`RetargetMap::retarget`] treats translation curves of a bone.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq, Default)]
+pub enum RetargetTranslation {
+    /// Translation curves are dropped and the target bone keeps its own translation.
+    ///
+    /// This is usually what you want for every bone but the root or hips, as it keeps the bone
+    /// lengths of the target skeleton intact.
+    #[default]
+    Discard,
+
+    /// The offset of the animated translation from the source rest translation is scaled by
+    /// the given factor and applied on top of the target rest translation.
+    Scaled(f32),
+
+    /// Like [`RetargetTranslation::Scaled`], with the factor being the ratio between the
+    /// lengths of the target and source rest translations.
+    ///
+    /// For a hips bone whose rest translation is its height above the root, this scales
+    /// movement by the difference in leg length between the two skeletons.
+    Proportional,
+}
+
+/// Errors that can occur when serializing retarget maps to RON.
+#[derive(Error, Debug)]
+pub enum RetargetMapSaveError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON serialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+}
+
+/// Errors that can occur when deserializing retarget maps from RON.
+#[derive(Error, Debug)]
+pub enum RetargetMapLoadError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON deserialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// An error occurred in RON deserialization, and the location of the error
+    /// is supplied.
+    #[error(transparent)]
+    SpannedRon(#[from] SpannedError),
+    /// More than one source bone was mapped to the same target bone.
+    #[error("The retarget map maps more than one bone to the target {0:?}")]
+    DuplicateTarget(AnimationTargetId),
+}
+
+/// A version of [`RetargetMap`] suitable for serializing as an asset.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedRetargetMap {
+    /// Corresponds to the `bones` field on [`RetargetMap`].
+    pub bones: Vec<SerializedRetargetBone>,
+}
+
+/// A version of [`RetargetBone`] suitable for serializing as an asset.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedRetargetBone {
+    /// Corresponds to the `source` field on [`RetargetBone`].
+    pub source: SerializedAnimationTargetId,
+    /// Corresponds to the `target` field on [`RetargetBone`].
+    pub target: SerializedAnimationTargetId,
+    /// Corresponds to the `source_rest` field on [`RetargetBone`].
+    #[serde(default)]
+    pub source_rest: RetargetRestPose,
+    /// Corresponds to the `target_rest` field on [`RetargetBone`].
+    #[serde(default)]
+    pub target_rest: RetargetRestPose,
+    /// Corresponds to the `translation` field on [`RetargetBone`].
+    #[serde(default)]
+    pub translation: RetargetTranslation,
+}
+
+/// An [`AssetLoader`] that can load [`RetargetMap`]s as assets.
+///
+/// The canonical extension for [`RetargetMap`]s is `.retarget.ron`. Plain `.retarget` is
+/// supported as well.
+#[derive(Default, TypePath)]
+pub struct RetargetMapAssetLoader;
+
+/// An [`AnimationCurve`] that remaps the values of a curve authored for a source bone so that
+/// they apply to a target bone.
+#[derive(Debug, Clone)]
+struct RetargetedCurve {
+    curve: VariableCurve,
+    remap: RetargetRemap,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RetargetRemap {
+    Rotation {
+        pre: Quat,
+        post: Quat,
+    },
+    Translation {
+        pre: Quat,
+        source_rest: Vec3,
+        target_rest: Vec3,
+        scale: f32,
+    },
+}
+
+impl RetargetMap {
+    /// Creates an empty retarget map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a bone mapping to this retarget map.
+    pub fn add_bone(&mut self, bone: RetargetBone) -> &mut Self {
+        self.bones.push(bone);
+        self
+    }
+
+    /// Returns the mapping for the given source bone, if it is mapped.
+    pub fn bone(&self, source: AnimationTargetId) -> Option<&RetargetBone> {
+        self.bones.iter().find(|bone| bone.source == source)
+    }
+
+    /// Creates a copy of `clip` that animates the target skeleton of this map instead of the
+    /// source skeleton.
+    ///
+    /// For each mapped bone:
+    /// - rotation curves are compensated so that the target bone deviates from its rest pose in
+    ///   the same way, in skeleton space, as the source bone deviates from its own;
+    /// - translation curves are handled according to [`RetargetBone::translation`];
+    /// - all other curves are copied unchanged.
+    ///
+    /// Curves and events of targets that aren't the source of any mapping are copied unchanged,
+    /// so that clips animating props or morph targets alongside the skeleton keep working.
+    pub fn retarget(&self, clip: &AnimationClip) -> AnimationClip {
+        let translation_field = animated_field!(Transform::translation);
+        let rotation_field = animated_field!(Transform::rotation);
+        let translation_id = translation_field.evaluator_id();
+        let rotation_id = rotation_field.evaluator_id();
+
+        let mut retargeted = AnimationClip {
+            duration: clip.duration,
+            ..AnimationClip::default()
+        };
+
+        for (&target_id, curves) in &clip.curves {
+            let Some(bone) = self.bone(target_id) else {
+                retargeted
+                    .curves
+                    .entry(target_id)
+                    .or_default()
+                    .extend(curves.iter().cloned());
+                continue;
+            };
+            for curve in curves {
+                if let Some(curve) = bone.retarget_curve(curve, &translation_id, &rotation_id) {
+                    retargeted
+                        .curves
+                        .entry(bone.target)
+                        .or_default()
+                        .push(curve);
+                }
+            }
+        }
+
+        for (event_target, events) in &clip.events {
+            let event_target = match *event_target {
+                AnimationEventTarget::Node(target_id) => AnimationEventTarget::Node(
+                    self.bone(target_id).map_or(target_id, |bone| bone.target),
+                ),
+                AnimationEventTarget::Root => AnimationEventTarget::Root,
+            };
+            retargeted
+                .events
+                .entry(event_target)
+                .or_default()
+                .extend(events.iter().cloned());
+        }
+
+        retargeted
+    }
+
+    /// Serializes this retarget map in [RON] format to the given writer.
+    ///
+    /// Bones are written as [`SerializedAnimationTargetId::Id`], as the names they were created
+    /// from aren't retained.
+    ///
+    /// [RON]: https://github.com/ron-rs/ron
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), RetargetMapSaveError>
+    where
+        W: Write,
+    {
+        let mut ron_serializer = ron::ser::Serializer::new(writer, None)?;
+        let serialized = SerializedRetargetMap::from(self.clone());
+        Ok(serialized.serialize(&mut ron_serializer)?)
+    }
+}
+
+impl RetargetBone {
+    /// Creates a mapping from `source` to `target` where both bones are at the identity rest
+    /// pose and translation is discarded.
+    pub fn new(source: AnimationTargetId, target: AnimationTargetId) -> Self {
+        Self {
+            source,
+            target,
+            source_rest: RetargetRestPose::default(),
+            target_rest: RetargetRestPose::default(),
+            translation: RetargetTranslation::default(),
+        }
+    }
+
+    /// Sets the rest poses of the source and target bones.
+    pub fn with_rest_poses(mut self, source: RetargetRestPose, target: RetargetRestPose) -> Self {
+        self.source_rest = source;
+        self.target_rest = target;
+        self
+    }
+
+    /// Sets how translation curves of this bone are retargeted.
+    pub fn with_translation(mut self, translation: RetargetTranslation) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    /// The rotation that takes directions from the parent space of the source bone to the
+    /// parent space of the target bone.
+    fn parent_correction(&self) -> Quat {
+        self.target_rest.parent_rotation.inverse() * self.source_rest.parent_rotation
+    }
+
+    fn retarget_curve(
+        &self,
+        curve: &VariableCurve,
+        translation_id: &EvaluatorId,
+        rotation_id: &EvaluatorId,
+    ) -> Option<VariableCurve> {
+        let evaluator_id = curve.0.evaluator_id();
+        let remap = if evaluator_id == *rotation_id {
+            // The skeleton-space rotation of the source bone is `Pₛ·q`, and its deviation from
+            // the rest pose is `Pₛ·q·(Pₛ·rₛ)⁻¹`. Applying the same deviation to the target rest
+            // pose `Pₜ·rₜ` and converting back into the target's parent space yields
+            // `Pₜ⁻¹·Pₛ·q·(Pₛ·rₛ)⁻¹·Pₜ·rₜ`.
+            let source_rest = self.source_rest.parent_rotation * self.source_rest.rotation;
+            let target_rest = self.target_rest.parent_rotation * self.target_rest.rotation;
+            RetargetRemap::Rotation {
+                pre: self.parent_correction(),
+                post: source_rest.inverse() * target_rest,
+            }
+        } else if evaluator_id == *translation_id {
+            let scale = match self.translation {
+                RetargetTranslation::Discard => return None,
+                RetargetTranslation::Scaled(scale) => scale,
+                RetargetTranslation::Proportional => {
+                    let source_length = self.source_rest.translation.length();
+                    if source_length > f32::EPSILON {
+                        self.target_rest.translation.length() / source_length
+                    } else {
+                        1.0
+                    }
+                }
+            };
+            RetargetRemap::Translation {
+                pre: self.parent_correction(),
+                source_rest: self.source_rest.translation,
+                target_rest: self.target_rest.translation,
+                scale,
+            }
+        } else {
+            return Some(curve.clone());
+        };
+
+        Some(VariableCurve::new(RetargetedCurve {
+            curve: curve.clone(),
+            remap,
+        }))
+    }
+}
+
+impl Default for RetargetRestPose {
+    fn default() -> Self {
+        Self {
+            parent_rotation: Quat::IDENTITY,
+            rotation: Quat::IDENTITY,
+            translation: Vec3::ZERO,
+        }
+    }
+}
+
+impl RetargetRestPose {
+    /// Creates a rest pose from the local [`Transform`] of a bone and the rotation of its parent
+    /// relative to the skeleton root.
+    pub fn new(parent_rotation: Quat, local: &Transform) -> Self {
+        Self {
+            parent_rotation,
+            rotation: local.rotation,
+            translation: local.translation,
+        }
+    }
+
+    /// Reads the rest pose of `bone` from its current [`Transform`] and those of its ancestors.
+    ///
+    /// Ancestors are accumulated up to, but not including, the nearest one with an
+    /// [`AnimationPlayer`], which is taken to be the skeleton root. Call this on a freshly
+    /// spawned skeleton, before any animation has been applied to it.
+    ///
+    /// Returns `None` if `bone` doesn't exist or has no [`Transform`].
+    pub fn from_world(world: &World, bone: Entity) -> Option<Self> {
+        let local = world.get::<Transform>(bone)?;
+        let mut parent_rotation = Quat::IDENTITY;
+        let mut ancestor = world.get::<ChildOf>(bone).map(ChildOf::parent);
+        while let Some(entity) = ancestor {
+            if world.get::<AnimationPlayer>(entity).is_some() {
+                break;
+            }
+            if let Some(transform) = world.get::<Transform>(entity) {
+                parent_rotation = transform.rotation * parent_rotation;
+            }
+            ancestor = world.get::<ChildOf>(entity).map(ChildOf::parent);
+        }
+        Some(Self::new(parent_rotation, local))
+    }
+}
+
+impl AnimationCurve for RetargetedCurve {
+    fn clone_value(&self) -> Box<dyn AnimationCurve> {
+        Box::new(self.clone())
+    }
+
+    fn domain(&self) -> Interval {
+        self.curve.0.domain()
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        self.curve.0.evaluator_id()
+    }
+
+    fn create_evaluator(&self) -> Box<dyn AnimationCurveEvaluator> {
+        self.curve.0.create_evaluator()
+    }
+
+    fn apply(
+        &self,
+        curve_evaluator: &mut dyn AnimationCurveEvaluator,
+        t: f32,
+        weight: f32,
+        graph_node: AnimationNodeIndex,
+    ) -> Result<(), AnimationEvaluationError> {
+        self.curve.0.apply(curve_evaluator, t, weight, graph_node)?;
+        match self.remap {
+            RetargetRemap::Rotation { pre, post } => {
+                map_top_sample::<Quat>(curve_evaluator, |rotation| {
+                    *rotation = (pre * *rotation * post).normalize();
+                })
+                .ok_or(
+                    AnimationEvaluationError::InconsistentEvaluatorImplementation(TypeId::of::<
+                        AnimatableCurveEvaluator<Quat>,
+                    >(
+                    )),
+                )
+            }
+            RetargetRemap::Translation {
+                pre,
+                source_rest,
+                target_rest,
+                scale,
+            } => map_top_sample::<Vec3>(curve_evaluator, |translation| {
+                *translation = target_rest + pre * ((*translation - source_rest) * scale);
+            })
+            .ok_or(
+                AnimationEvaluationError::InconsistentEvaluatorImplementation(TypeId::of::<
+                    AnimatableCurveEvaluator<Vec3>,
+                >()),
+            ),
+        }
+    }
+}
+
+impl From<RetargetMap> for SerializedRetargetMap {
+    fn from(map: RetargetMap) -> Self {
+        Self {
+            bones: map
+                .bones
+                .into_iter()
+                .map(|bone| SerializedRetargetBone {
+                    source: bone.source.into(),
+                    target: bone.target.into(),
+                    source_rest: bone.source_rest,
+                    target_rest: bone.target_rest,
+                    translation: bone.translation,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<SerializedRetargetMap> for RetargetMap {
+    type Error = RetargetMapLoadError;
+
+    fn try_from(serialized: SerializedRetargetMap) -> Result<Self, Self::Error> {
+        let mut targets = HashSet::new();
+        let mut map = RetargetMap::new();
+        for bone in serialized.bones {
+            let target = bone.target.into();
+            if !targets.insert(target) {
+                return Err(RetargetMapLoadError::DuplicateTarget(target));
+            }
+            map.add_bone(RetargetBone {
+                source: bone.source.into(),
+                target,
+                source_rest: bone.source_rest,
+                target_rest: bone.target_rest,
+                translation: bone.translation,
+            });
+        }
+        Ok(map)
+    }
+}
+
+impl AssetLoader for RetargetMapAssetLoader {
+    type Asset = RetargetMap;
+
+    type Settings = ();
+
+    type Error = RetargetMapLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let serialized = SerializedRetargetMap::deserialize(&mut deserializer)
+            .map_err(|err| deserializer.span_error(err))?;
+        serialized.try_into()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["retarget", "retarget.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::name::Name;
+    use bevy_math::curve::ConstantCurve;
+
+    use super::*;
+    use crate::animation_curves::{sample_animatable, AnimatableCurve};
+
+    fn sample<A: crate::animatable::Animatable>(
+        clip: &AnimationClip,
+        target: AnimationTargetId,
+    ) -> Option<A> {
+        clip.curves_for_target(target)?.iter().find_map(|curve| {
+            let mut evaluator = curve.0.create_evaluator();
+            sample_animatable::<A>(&*curve.0, &mut *evaluator, 0.5)
+        })
+    }
+
+    #[test]
+    fn rotation_is_compensated_for_rest_pose() {
+        let source = AnimationTargetId::from_name(&Name::new("source"));
+        let target = AnimationTargetId::from_name(&Name::new("target"));
+        let source_rest = RetargetRestPose {
+            parent_rotation: Quat::from_rotation_z(0.5),
+            rotation: Quat::from_rotation_x(0.3),
+            translation: Vec3::ZERO,
+        };
+        let target_rest = RetargetRestPose {
+            parent_rotation: Quat::from_rotation_y(-1.0),
+            rotation: Quat::from_rotation_z(0.7),
+            translation: Vec3::ZERO,
+        };
+        let mut map = RetargetMap::new();
+        map.add_bone(RetargetBone::new(source, target).with_rest_poses(source_rest, target_rest));
+
+        let rotation_of = |rotation: Quat| {
+            let mut clip = AnimationClip::default();
+            clip.add_curve_to_target(
+                source,
+                AnimatableCurve::new(
+                    animated_field!(Transform::rotation),
+                    ConstantCurve::new(Interval::UNIT, rotation),
+                ),
+            );
+            let retargeted = map.retarget(&clip);
+            assert!(retargeted.curves_for_target(source).is_none());
+            sample::<Quat>(&retargeted, target).unwrap()
+        };
+
+        // The rest pose maps onto the rest pose.
+        let rest = rotation_of(source_rest.rotation);
+        assert!(rest.angle_between(target_rest.rotation) < 1e-4);
+
+        // A deviation from the rest pose in skeleton space carries over unchanged.
+        let deviation = Quat::from_rotation_x(0.4);
+        let animated = rotation_of(
+            source_rest.parent_rotation.inverse()
+                * deviation
+                * source_rest.parent_rotation
+                * source_rest.rotation,
+        );
+        let source_world = deviation * source_rest.parent_rotation * source_rest.rotation;
+        let target_world = target_rest.parent_rotation * animated;
+        assert!(
+            (target_world * (target_rest.parent_rotation * target_rest.rotation).inverse())
+                .angle_between(
+                    source_world * (source_rest.parent_rotation * source_rest.rotation).inverse()
+                )
+                < 1e-4
+        );
+    }
+
+    #[test]
+    fn translation_is_discarded_or_scaled() {
+        let hips = AnimationTargetId::from_name(&Name::new("hips"));
+        let spine = AnimationTargetId::from_name(&Name::new("spine"));
+        let prop = AnimationTargetId::from_name(&Name::new("prop"));
+        let mut map = RetargetMap::new();
+        map.add_bone(
+            RetargetBone::new(hips, hips)
+                .with_rest_poses(
+                    RetargetRestPose::new(Quat::IDENTITY, &Transform::from_xyz(0.0, 1.0, 0.0)),
+                    RetargetRestPose::new(Quat::IDENTITY, &Transform::from_xyz(0.0, 2.0, 0.0)),
+                )
+                .with_translation(RetargetTranslation::Proportional),
+        )
+        .add_bone(RetargetBone::new(spine, spine));
+
+        let mut clip = AnimationClip::default();
+        for target in [hips, spine, prop] {
+            clip.add_curve_to_target(
+                target,
+                AnimatableCurve::new(
+                    animated_field!(Transform::translation),
+                    ConstantCurve::new(Interval::UNIT, Vec3::new(1.0, 1.5, 0.0)),
+                ),
+            );
+        }
+        let retargeted = map.retarget(&clip);
+
+        assert_eq!(
+            sample::<Vec3>(&retargeted, hips),
+            Some(Vec3::new(2.0, 3.0, 0.0))
+        );
+        assert!(retargeted.curves_for_target(spine).is_none());
+        assert_eq!(
+            sample::<Vec3>(&retargeted, prop),
+            Some(Vec3::new(1.0, 1.5, 0.0))
+        );
+        assert_eq!(retargeted.duration(), clip.duration());
+    }
+
+    #[test]
+    fn rest_pose_from_world_stops_at_player() {
+        let mut world = World::new();
+        let player = world
+            .spawn((
+                AnimationPlayer::default(),
+                Transform::from_rotation(Quat::from_rotation_y(1.0)),
+            ))
+            .id();
+        let hips = world
+            .spawn((
+                Transform::from_rotation(Quat::from_rotation_x(0.5)),
+                ChildOf(player),
+            ))
+            .id();
+        let spine = world
+            .spawn((Transform::from_xyz(0.0, 0.5, 0.0), ChildOf(hips)))
+            .id();
+
+        let rest = RetargetRestPose::from_world(&world, spine).unwrap();
+        assert!(
+            rest.parent_rotation
+                .angle_between(Quat::from_rotation_x(0.5))
+                < 1e-6
+        );
+        assert_eq!(rest.translation, Vec3::new(0.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn load_by_path() {
+        let serialized: SerializedRetargetMap = ron::de::from_str(
+            r#"(
+                bones: [
+                    (source: Path(["Root", "Hips"]), target: Path(["Hips"]), translation: Scaled(0.5)),
+                    (source: Path(["Root", "Hips", "Spine"]), target: Path(["Hips"])),
+                ],
+            )"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            RetargetMap::try_from(serialized),
+            Err(RetargetMapLoadError::DuplicateTarget(_))
+        ));
+
+        let serialized: SerializedRetargetMap = ron::de::from_str(
+            r#"(bones: [(source: Path(["Root", "Hips"]), target: Path(["Hips"]), translation: Scaled(0.5))])"#,
+        )
+        .unwrap();
+        let map = RetargetMap::try_from(serialized).unwrap();
+        let bone = map
+            .bone(AnimationTargetId::from_iter(["Root", "Hips"]))
+            .unwrap();
+        assert_eq!(
+            bone.target,
+            AnimationTargetId::from_name(&Name::new("Hips"))
+        );
+        assert_eq!(bone.translation, RetargetTranslation::Scaled(0.5));
+
+        let mut ron = String::new();
+        map.save(&mut ron).unwrap();
+        let reloaded: SerializedRetargetMap = ron::de::from_str(&ron).unwrap();
+        assert_eq!(RetargetMap::try_from(reloaded).unwrap().bones, map.bones);
+    }
+}
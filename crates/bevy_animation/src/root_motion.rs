@@ -0,0 +1,453 @@
+//! Extraction of root motion from animation clips.
+//!
+//! Locomotion clips often move the whole character by animating its root bone.
+//! Played as-is, that motion is baked into the pose: the mesh walks away from
+//! the entity, and from anything attached to it, like a collider. Adding a
+//! [`RootMotion`] component next to an [`AnimationPlayer`] removes the motion
+//! of the root bone from the pose every frame and exposes it instead, so that
+//! gameplay code or a character controller can move the entity itself.
+
+use bevy_asset::Assets;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    reflect::ReflectComponent,
+    system::{Query, Res},
+};
+use bevy_math::{BVec3, Quat, Vec3};
+use bevy_reflect::Reflect;
+use bevy_transform::components::Transform;
+
+use crate::{
+    animated_field,
+    animation_curves::{
+        sample_animatable, AnimatableProperty, AnimatedField, AnimationCurve,
+        AnimationCurveEvaluator,
+    },
+    graph::{AnimationGraph, AnimationGraphHandle, AnimationNodeType},
+    ActiveAnimation, AnimatedBy, AnimationClip, AnimationPlayer, AnimationTargetId,
+};
+
+/// Extracts the motion of a root bone from the animations played by the
+/// [`AnimationPlayer`] on the same entity.
+///
+/// Every frame, after animations have been applied, the motion of the
+/// [`target`](Self::target) bone since the previous frame is computed from the
+/// clips that are playing, and removed from the bone's [`Transform`]. It can
+/// then be read with [`RootMotion::translation_delta`] and
+/// [`RootMotion::rotation_delta`], or applied to a [`Transform`] with
+/// [`RootMotion::apply_to`].
+///
+/// Only the `translation` and `rotation` fields of the target's [`Transform`]
+/// are considered. Rotation is extracted around the Y axis only, so the bone
+/// keeps any leaning or bobbing, and the deltas of looping clips are computed
+/// across the loop point. When several clips are playing, their deltas are
+/// averaged according to their active weights; the weights of blend nodes and
+/// masks aren't taken into account.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Clone, Debug)]
+pub struct RootMotion {
+    /// The animation target whose motion is extracted, typically the root
+    /// bone of a skeleton.
+    pub target: AnimationTargetId,
+
+    /// The axes along which translation is extracted.
+    ///
+    /// Translation along the other axes stays in the pose. By default, only
+    /// horizontal movement (along X and Z) is extracted, so vertical movement
+    /// such as jumps stays in the pose.
+    pub translation_axes: BVec3,
+
+    /// Whether rotation around the Y axis is extracted.
+    pub extract_rotation: bool,
+
+    translation_delta: Vec3,
+    rotation_delta: Quat,
+    #[reflect(ignore)]
+    target_entity: Option<Entity>,
+}
+
+impl RootMotion {
+    /// Creates a new [`RootMotion`] that extracts horizontal translation and
+    /// rotation around the Y axis from the given `target`.
+    pub fn new(target: AnimationTargetId) -> Self {
+        Self {
+            target,
+            translation_axes: BVec3::new(true, false, true),
+            extract_rotation: true,
+            translation_delta: Vec3::ZERO,
+            rotation_delta: Quat::IDENTITY,
+            target_entity: None,
+        }
+    }
+
+    /// Sets the axes along which translation is extracted.
+    pub fn with_translation_axes(mut self, translation_axes: BVec3) -> Self {
+        self.translation_axes = translation_axes;
+        self
+    }
+
+    /// Sets whether rotation around the Y axis is extracted.
+    pub fn with_rotation(mut self, extract_rotation: bool) -> Self {
+        self.extract_rotation = extract_rotation;
+        self
+    }
+
+    /// Returns the translation extracted during the last update.
+    ///
+    /// This is expressed in the space of the target's parent. If rotation is
+    /// extracted as well, it is relative to the orientation of the target at
+    /// the start of the update, which is the orientation of the entity when
+    /// root motion is applied to it.
+    pub fn translation_delta(&self) -> Vec3 {
+        self.translation_delta
+    }
+
+    /// Returns the rotation extracted during the last update.
+    pub fn rotation_delta(&self) -> Quat {
+        self.rotation_delta
+    }
+
+    /// Moves and rotates `transform` by the motion extracted during the last
+    /// update.
+    ///
+    /// This is typically applied to the entity that the skeleton is parented
+    /// to.
+    pub fn apply_to(&self, transform: &mut Transform) {
+        transform.translation += transform.rotation * (transform.scale * self.translation_delta);
+        transform.rotation = (transform.rotation * self.rotation_delta).normalize();
+    }
+}
+
+/// A system that extracts the motion of the root bones of [`RootMotion`]
+/// entities from their poses.
+///
+/// This runs after the animations have been applied.
+pub fn extract_root_motion(
+    animation_clips: Res<Assets<AnimationClip>>,
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    mut players: Query<(
+        Entity,
+        &AnimationPlayer,
+        &AnimationGraphHandle,
+        &mut RootMotion,
+    )>,
+    targets: Query<(Entity, &AnimationTargetId, &AnimatedBy)>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let translation_field = animated_field!(Transform::translation);
+    let rotation_field = animated_field!(Transform::rotation);
+    let translation_id = translation_field.evaluator_id();
+    let rotation_id = rotation_field.evaluator_id();
+
+    for (player_entity, player, graph_handle, mut root_motion) in &mut players {
+        root_motion.translation_delta = Vec3::ZERO;
+        root_motion.rotation_delta = Quat::IDENTITY;
+        let Some(animation_graph) = animation_graphs.get(graph_handle) else {
+            continue;
+        };
+
+        // Find the root bone, caching it across frames.
+        let target = root_motion.target;
+        let is_target =
+            |(_, &id, &AnimatedBy(player)): (Entity, &AnimationTargetId, &AnimatedBy)| {
+                id == target && player == player_entity
+            };
+        let target_entity = match root_motion.target_entity {
+            Some(entity) if targets.get(entity).is_ok_and(is_target) => entity,
+            _ => {
+                let Some((entity, ..)) = targets.iter().find(|&item| is_target(item)) else {
+                    continue;
+                };
+                root_motion.target_entity = Some(entity);
+                entity
+            }
+        };
+
+        let mut motion = WeightedMotion::default();
+        for (&node_index, active_animation) in player.playing_animations() {
+            if active_animation.weight == 0.0 || active_animation.paused {
+                continue;
+            }
+            let Some(clip) =
+                animation_graph
+                    .get(node_index)
+                    .and_then(|node| match node.node_type {
+                        AnimationNodeType::Clip(ref clip_handle) => {
+                            animation_clips.get(clip_handle)
+                        }
+                        _ => None,
+                    })
+            else {
+                continue;
+            };
+            let Some(curves) = clip.curves_for_target(root_motion.target) else {
+                continue;
+            };
+
+            let mut sampler = RootSampler {
+                translation: None,
+                rotation: None,
+                extract_rotation: root_motion.extract_rotation,
+            };
+            for curve in curves {
+                let curve = &*curve.0;
+                if curve.evaluator_id() == translation_id {
+                    sampler.translation = Some((curve, curve.create_evaluator()));
+                } else if curve.evaluator_id() == rotation_id {
+                    sampler.rotation = Some((curve, curve.create_evaluator()));
+                }
+            }
+            if sampler.translation.is_none() && sampler.rotation.is_none() {
+                continue;
+            }
+
+            let (translation_delta, rotation_delta) =
+                sampler.clip_motion(active_animation, clip.duration());
+            let (reference_translation, reference_rotation) = sampler.sample(0.0);
+            motion.add(
+                active_animation.weight,
+                translation_delta,
+                rotation_delta,
+                reference_translation,
+                reference_rotation,
+            );
+        }
+
+        let Some((translation_delta, rotation_delta, reference_translation, reference_rotation)) =
+            motion.finish()
+        else {
+            continue;
+        };
+        let Ok(mut transform) = transforms.get_mut(target_entity) else {
+            continue;
+        };
+
+        // Remove the extracted motion from the pose.
+        let axes = root_motion.translation_axes;
+        transform.translation = Vec3::select(axes, reference_translation, transform.translation);
+        if root_motion.extract_rotation {
+            transform.rotation =
+                (reference_rotation * yaw(transform.rotation).inverse() * transform.rotation)
+                    .normalize();
+        }
+
+        root_motion.translation_delta = Vec3::select(axes, translation_delta, Vec3::ZERO);
+        root_motion.rotation_delta = rotation_delta;
+    }
+}
+
+/// Samples the root translation and rotation curves of a clip.
+struct RootSampler<'a> {
+    translation: Option<(&'a dyn AnimationCurve, Box<dyn AnimationCurveEvaluator>)>,
+    rotation: Option<(&'a dyn AnimationCurve, Box<dyn AnimationCurveEvaluator>)>,
+    extract_rotation: bool,
+}
+
+impl RootSampler<'_> {
+    /// Returns the translation of the root and its rotation around the Y axis
+    /// at time `t`.
+    fn sample(&mut self, t: f32) -> (Vec3, Quat) {
+        let translation = self
+            .translation
+            .as_mut()
+            .and_then(|(curve, evaluator)| sample_animatable(*curve, &mut **evaluator, t))
+            .unwrap_or(Vec3::ZERO);
+        let rotation = if self.extract_rotation {
+            self.rotation
+                .as_mut()
+                .and_then(|(curve, evaluator)| sample_animatable(*curve, &mut **evaluator, t))
+                .map_or(Quat::IDENTITY, yaw)
+        } else {
+            Quat::IDENTITY
+        };
+        (translation, rotation)
+    }
+
+    /// Returns the motion of the root between times `from` and `to`, relative
+    /// to its orientation at `from`.
+    fn motion(&mut self, from: f32, to: f32) -> (Vec3, Quat) {
+        let (from_translation, from_rotation) = self.sample(from);
+        let (to_translation, to_rotation) = self.sample(to);
+        let inverse_from_rotation = from_rotation.inverse();
+        (
+            inverse_from_rotation * (to_translation - from_translation),
+            inverse_from_rotation * to_rotation,
+        )
+    }
+
+    /// Returns the motion of the root during the last update of
+    /// `active_animation`.
+    fn clip_motion(&mut self, active_animation: &ActiveAnimation, duration: f32) -> (Vec3, Quat) {
+        let Some(last_seek_time) = active_animation.last_seek_time else {
+            return (Vec3::ZERO, Quat::IDENTITY);
+        };
+        let seek_time = active_animation.seek_time;
+        if !active_animation.just_completed || active_animation.is_finished() {
+            return self.motion(last_seek_time, seek_time);
+        }
+
+        // The animation looped: move to the end of the clip, then from the
+        // start of the clip to the current time, continuing from where the end
+        // of the clip left off.
+        let (end, start) = if active_animation.is_playback_reversed() {
+            (0.0, duration)
+        } else {
+            (duration, 0.0)
+        };
+        let (first_translation, first_rotation) = self.motion(last_seek_time, end);
+        let (second_translation, second_rotation) = self.motion(start, seek_time);
+        (
+            first_translation + first_rotation * second_translation,
+            first_rotation * second_rotation,
+        )
+    }
+}
+
+/// Accumulates the root motion of several clips, weighted by their active
+/// weights.
+struct WeightedMotion {
+    weight: f32,
+    translation_delta: Vec3,
+    rotation_delta: Quat,
+    reference_translation: Vec3,
+    reference_rotation: Quat,
+}
+
+impl Default for WeightedMotion {
+    fn default() -> Self {
+        // The rotations are summed, so they start out as zero rather than as
+        // the identity.
+        let zero = Quat::from_xyzw(0.0, 0.0, 0.0, 0.0);
+        Self {
+            weight: 0.0,
+            translation_delta: Vec3::ZERO,
+            rotation_delta: zero,
+            reference_translation: Vec3::ZERO,
+            reference_rotation: zero,
+        }
+    }
+}
+
+impl WeightedMotion {
+    fn add(
+        &mut self,
+        weight: f32,
+        translation_delta: Vec3,
+        rotation_delta: Quat,
+        reference_translation: Vec3,
+        reference_rotation: Quat,
+    ) {
+        // Keep all rotations in the same hemisphere so that they can be
+        // averaged by summing them.
+        let align = |rotation: Quat| {
+            if rotation.w < 0.0 {
+                -rotation
+            } else {
+                rotation
+            }
+        };
+        self.weight += weight;
+        self.translation_delta += translation_delta * weight;
+        self.rotation_delta += align(rotation_delta) * weight;
+        self.reference_translation += reference_translation * weight;
+        self.reference_rotation += align(reference_rotation) * weight;
+    }
+
+    /// Returns the weighted averages of the deltas and reference poses, if
+    /// anything was added.
+    fn finish(self) -> Option<(Vec3, Quat, Vec3, Quat)> {
+        if self.weight <= 0.0 {
+            return None;
+        }
+        Some((
+            self.translation_delta / self.weight,
+            normalize_or_identity(self.rotation_delta),
+            self.reference_translation / self.weight,
+            normalize_or_identity(self.reference_rotation),
+        ))
+    }
+}
+
+/// Returns the rotation of `rotation` around the Y axis.
+fn yaw(rotation: Quat) -> Quat {
+    normalize_or_identity(Quat::from_xyzw(0.0, rotation.y, 0.0, rotation.w))
+}
+
+/// Normalizes `rotation`, or returns the identity if it is degenerate.
+fn normalize_or_identity(rotation: Quat) -> Quat {
+    let length_squared = rotation.length_squared();
+    if length_squared > f32::EPSILON && length_squared.is_finite() {
+        rotation / length_squared.sqrt()
+    } else {
+        Quat::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::FRAC_PI_2;
+
+    use bevy_math::curve::UnevenSampleAutoCurve;
+
+    use super::*;
+    use crate::animation_curves::AnimatableCurve;
+
+    #[test]
+    fn root_motion_across_loop() {
+        // Walks 2 units forward over a 1 second cycle.
+        let curve = AnimatableCurve::new(
+            animated_field!(Transform::translation),
+            UnevenSampleAutoCurve::new([(0.0, Vec3::Y), (1.0, Vec3::new(0.0, 1.0, -2.0))]).unwrap(),
+        );
+        let mut sampler = RootSampler {
+            translation: Some((&curve, curve.create_evaluator())),
+            rotation: None,
+            extract_rotation: true,
+        };
+
+        let mut active_animation = ActiveAnimation::default();
+        active_animation.repeat();
+        active_animation.update(0.25, 1.0);
+        active_animation.update(0.5, 1.0);
+        let (translation, rotation) = sampler.clip_motion(&active_animation, 1.0);
+        assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-5));
+        assert_eq!(rotation, Quat::IDENTITY);
+
+        // 0.75 -> 1.0 -> 0.0 -> 0.25 covers half a cycle.
+        active_animation.update(0.5, 1.0);
+        assert!(active_animation.just_completed);
+        let (translation, _) = sampler.clip_motion(&active_animation, 1.0);
+        assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-5));
+    }
+
+    #[test]
+    fn root_motion_rotation_is_local() {
+        let translation_curve = AnimatableCurve::new(
+            animated_field!(Transform::translation),
+            UnevenSampleAutoCurve::new([(0.0, Vec3::ZERO), (1.0, Vec3::new(0.0, 0.0, -2.0))])
+                .unwrap(),
+        );
+        // Turns a quarter turn to the left over the cycle.
+        let rotation_curve = AnimatableCurve::new(
+            animated_field!(Transform::rotation),
+            UnevenSampleAutoCurve::new([
+                (0.0, Quat::IDENTITY),
+                (1.0, Quat::from_rotation_y(FRAC_PI_2)),
+            ])
+            .unwrap(),
+        );
+        let mut sampler = RootSampler {
+            translation: Some((&translation_curve, translation_curve.create_evaluator())),
+            rotation: Some((&rotation_curve, rotation_curve.create_evaluator())),
+            extract_rotation: true,
+        };
+
+        let (translation, rotation) = sampler.motion(0.5, 1.0);
+        assert!(rotation.abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2 * 0.5), 1e-5));
+        // The curve moves along -Z, which is expressed in the frame of the
+        // root at `t = 0.5`.
+        let expected = Quat::from_rotation_y(-FRAC_PI_2 * 0.5) * Vec3::new(0.0, 0.0, -1.0);
+        assert!(translation.abs_diff_eq(expected, 1e-5));
+    }
+}
@@ -0,0 +1,989 @@
+//! Data-driven animation state machines.
+//!
+//! An [`AnimationStateMachine`] is an asset that describes a set of states, each of which plays
+//! a node of an [`AnimationGraph`], together with typed parameters and the transitions between
+//! states. Add an [`AnimationStateMachineHandle`] next to an [`AnimationPlayer`] and an
+//! [`AnimationGraphHandle`] to have the state machine drive the player, and set parameters on the
+//! [`AnimationStateMachineInstance`] component to steer it.
+
+use core::{fmt::Write, time::Duration};
+use std::io;
+
+use bevy_asset::{io::Reader, Asset, AssetLoader, Assets, Handle, LoadContext};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    component::Component,
+    reflect::ReflectComponent,
+    system::{Query, Res},
+};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
+use bevy_time::Time;
+use derive_more::derive::From;
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    graph::{AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType},
+    transition::AnimationTransitions,
+    AnimationClip, AnimationPlayer,
+};
+
+/// An asset describing an animation state machine.
+///
+/// Each [state](AnimationState) plays a node of the [`AnimationGraph`] used by the
+/// [`AnimationPlayer`] it drives. The state machine moves between states by evaluating its
+/// [transitions](AnimationStateTransition) every frame: the first transition out of the current
+/// state (or out of any state) whose exit time has passed and whose conditions all hold is taken,
+/// cross-fading to the target state over the transition's duration.
+///
+/// Conditions are expressed in terms of named, typed [parameters](AnimationParameter), whose
+/// values are set at runtime on the [`AnimationStateMachineInstance`] component.
+///
+/// State machines are assets and can be serialized to and loaded from [RON] files, referencing
+/// states and parameters by name. Canonically, such files have an `.animstates.ron` extension:
+///
+/// ```ron
+/// (
+///     states: [
+///         (name: "idle", node: 1),
+///         (name: "run", node: 2),
+///         (name: "jump", node: 3, repeat: false),
+///     ],
+///     initial_state: Some("idle"),
+///     parameters: {
+///         "speed": Float(0.0),
+///         "jump": Trigger(false),
+///     },
+///     transitions: [
+///         (from: Some("idle"), to: "run", conditions: [Greater(parameter: "speed", value: 0.1)], duration: 0.2),
+///         (from: Some("run"), to: "idle", conditions: [Less(parameter: "speed", value: 0.1)], duration: 0.2),
+///         (from: None, to: "jump", conditions: [Triggered("jump")], duration: 0.1),
+///         (from: Some("jump"), to: "idle", exit_time: Some(1.0), duration: 0.2),
+///     ],
+/// )
+/// ```
+///
+/// [RON]: https://github.com/ron-rs/ron
+#[derive(Asset, Reflect, Clone, Debug, Default)]
+#[reflect(Debug, Clone, Default)]
+pub struct AnimationStateMachine {
+    /// The states of the state machine.
+    pub states: Vec<AnimationState>,
+
+    /// The index of the state the state machine starts in.
+    pub initial_state: AnimationStateIndex,
+
+    /// The parameters of the state machine, along with their default values.
+    pub parameters: HashMap<String, AnimationParameter>,
+
+    /// The transitions between states, in the order they are checked in.
+    pub transitions: Vec<AnimationStateTransition>,
+}
+
+/// The index of an [`AnimationState`] in an [`AnimationStateMachine`].
+pub type AnimationStateIndex = usize;
+
+/// A state of an [`AnimationStateMachine`], which plays a node of an [`AnimationGraph`].
+#[derive(Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Debug, Clone)]
+pub struct AnimationState {
+    /// The name of the state, used to refer to it in serialized state machines.
+    pub name: String,
+
+    /// The node of the [`AnimationGraph`] to play while in this state.
+    pub node: AnimationNodeIndex,
+
+    /// The playback speed of the node.
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+
+    /// Whether the node repeats forever, or plays once and holds its last pose.
+    #[serde(default = "default_repeat")]
+    pub repeat: bool,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+fn default_repeat() -> bool {
+    true
+}
+
+impl AnimationState {
+    /// Creates a new repeating state with the given `name` that plays `node` at normal speed.
+    pub fn new(name: impl Into<String>, node: AnimationNodeIndex) -> Self {
+        Self {
+            name: name.into(),
+            node,
+            speed: 1.0,
+            repeat: true,
+        }
+    }
+
+    /// Sets the playback speed of this state.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets whether this state repeats forever.
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+}
+
+/// The value of a parameter of an [`AnimationStateMachine`].
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum AnimationParameter {
+    /// A floating point value, for example the movement speed of a character.
+    Float(f32),
+    /// An integer value.
+    Int(i32),
+    /// A boolean value, for example whether a character is grounded.
+    Bool(bool),
+    /// A boolean that is reset to `false` when a transition that checks it is taken.
+    ///
+    /// Use this for one-off actions, like jumping.
+    Trigger(bool),
+}
+
+impl AnimationParameter {
+    /// Returns the value of a [`Float`](Self::Float) or [`Int`](Self::Int) parameter as an `f32`.
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            AnimationParameter::Float(value) => Some(value),
+            AnimationParameter::Int(value) => Some(value as f32),
+            AnimationParameter::Bool(_) | AnimationParameter::Trigger(_) => None,
+        }
+    }
+
+    /// Returns the value of a [`Bool`](Self::Bool) or [`Trigger`](Self::Trigger) parameter.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            AnimationParameter::Bool(value) | AnimationParameter::Trigger(value) => Some(value),
+            AnimationParameter::Float(_) | AnimationParameter::Int(_) => None,
+        }
+    }
+}
+
+/// A condition on the parameters of an [`AnimationStateMachine`] that must hold for an
+/// [`AnimationStateTransition`] to be taken.
+///
+/// Conditions referring to a parameter that doesn't exist, or that has the wrong type,
+/// never hold.
+#[derive(Reflect, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum TransitionCondition {
+    /// A numeric parameter is greater than `value`.
+    Greater {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare against.
+        value: f32,
+    },
+    /// A numeric parameter is less than `value`.
+    Less {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare against.
+        value: f32,
+    },
+    /// A parameter is equal to `value`.
+    Equals {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare against.
+        value: AnimationParameter,
+    },
+    /// A parameter is not equal to `value`.
+    NotEquals {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare against.
+        value: AnimationParameter,
+    },
+    /// A [`Bool`](AnimationParameter::Bool) parameter is `true`.
+    If(String),
+    /// A [`Bool`](AnimationParameter::Bool) parameter is `false`.
+    IfNot(String),
+    /// A [`Trigger`](AnimationParameter::Trigger) parameter is set.
+    ///
+    /// The trigger is reset when the transition is taken.
+    Triggered(String),
+}
+
+impl TransitionCondition {
+    /// Returns `true` if this condition holds for the given parameter values.
+    pub fn evaluate(&self, parameters: &HashMap<String, AnimationParameter>) -> bool {
+        match self {
+            TransitionCondition::Greater { parameter, value } => parameters
+                .get(parameter)
+                .and_then(AnimationParameter::as_f32)
+                .is_some_and(|parameter| parameter > *value),
+            TransitionCondition::Less { parameter, value } => parameters
+                .get(parameter)
+                .and_then(AnimationParameter::as_f32)
+                .is_some_and(|parameter| parameter < *value),
+            TransitionCondition::Equals { parameter, value } => {
+                parameters.get(parameter) == Some(value)
+            }
+            TransitionCondition::NotEquals { parameter, value } => parameters
+                .get(parameter)
+                .is_some_and(|parameter| parameter != value),
+            TransitionCondition::If(parameter) => {
+                parameters.get(parameter) == Some(&AnimationParameter::Bool(true))
+            }
+            TransitionCondition::IfNot(parameter) => {
+                parameters.get(parameter) == Some(&AnimationParameter::Bool(false))
+            }
+            TransitionCondition::Triggered(parameter) => {
+                parameters.get(parameter) == Some(&AnimationParameter::Trigger(true))
+            }
+        }
+    }
+}
+
+/// A transition between two states of an [`AnimationStateMachine`].
+#[derive(Reflect, Clone, Debug)]
+#[reflect(Debug, Clone)]
+pub struct AnimationStateTransition {
+    /// The state this transition leaves, or `None` if it can be taken from any state
+    /// other than `to`.
+    pub from: Option<AnimationStateIndex>,
+
+    /// The state this transition enters.
+    pub to: AnimationStateIndex,
+
+    /// The conditions that must all hold for this transition to be taken.
+    pub conditions: Vec<TransitionCondition>,
+
+    /// If set, the transition can only be taken once the normalized time of the current state
+    /// has reached this value.
+    ///
+    /// For states playing a clip, the normalized time is the time spent in the state divided by
+    /// the duration of the clip, so `1.0` is the end of the first playthrough. For other states,
+    /// it is the time spent in the state in seconds.
+    pub exit_time: Option<f32>,
+
+    /// How long to cross-fade from the current state to the target state.
+    pub duration: Duration,
+}
+
+impl AnimationStateTransition {
+    /// Creates a new instant, unconditional transition between two states.
+    pub fn new(from: Option<AnimationStateIndex>, to: AnimationStateIndex) -> Self {
+        Self {
+            from,
+            to,
+            conditions: Vec::new(),
+            exit_time: None,
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Adds a condition that must hold for this transition to be taken.
+    pub fn with_condition(mut self, condition: TransitionCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Sets the normalized exit time of this transition. See [`AnimationStateTransition::exit_time`].
+    pub fn with_exit_time(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+
+    /// Sets the cross-fade duration of this transition.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Returns `true` if this transition can be taken out of the `current` state.
+    pub fn can_transition(
+        &self,
+        current: AnimationStateIndex,
+        normalized_time: f32,
+        parameters: &HashMap<String, AnimationParameter>,
+    ) -> bool {
+        let from_matches = match self.from {
+            Some(from) => from == current,
+            None => self.to != current,
+        };
+        from_matches
+            && self
+                .exit_time
+                .is_none_or(|exit_time| normalized_time >= exit_time)
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.evaluate(parameters))
+    }
+}
+
+impl AnimationStateMachine {
+    /// Creates a new, empty state machine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state to the state machine and returns its index.
+    ///
+    /// The first state added becomes the initial state.
+    pub fn add_state(&mut self, state: AnimationState) -> AnimationStateIndex {
+        self.states.push(state);
+        self.states.len() - 1
+    }
+
+    /// Adds a parameter with the given default value.
+    pub fn add_parameter(&mut self, name: impl Into<String>, default: AnimationParameter) {
+        self.parameters.insert(name.into(), default);
+    }
+
+    /// Adds a transition. Transitions are checked in the order they were added in.
+    pub fn add_transition(&mut self, transition: AnimationStateTransition) {
+        self.transitions.push(transition);
+    }
+
+    /// Returns the index of the state with the given name.
+    pub fn state_index(&self, name: &str) -> Option<AnimationStateIndex> {
+        self.states.iter().position(|state| state.name == name)
+    }
+
+    /// Returns the first transition that can be taken out of the `current` state.
+    pub fn find_transition(
+        &self,
+        current: AnimationStateIndex,
+        normalized_time: f32,
+        parameters: &HashMap<String, AnimationParameter>,
+    ) -> Option<&AnimationStateTransition> {
+        self.transitions
+            .iter()
+            .find(|transition| transition.can_transition(current, normalized_time, parameters))
+    }
+
+    /// Serializes the state machine to the given [`Write`]r in RON format.
+    ///
+    /// If writing to a file, it can later be loaded with the
+    /// [`AnimationStateMachineAssetLoader`] to reconstruct the state machine.
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), AnimationStateMachineSaveError>
+    where
+        W: Write,
+    {
+        let mut ron_serializer = ron::ser::Serializer::new(writer, None)?;
+        let serialized = SerializedAnimationStateMachine::try_from(self.clone())?;
+        Ok(serialized.serialize(&mut ron_serializer)?)
+    }
+}
+
+/// A [`Handle`] to the [`AnimationStateMachine`] that drives the [`AnimationPlayer`] on the same entity.
+///
+/// The entity also needs an [`AnimationGraphHandle`] to the graph whose nodes the states refer to.
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut, Reflect, PartialEq, Eq, From)]
+#[reflect(Component, Default, Clone)]
+#[require(AnimationStateMachineInstance)]
+pub struct AnimationStateMachineHandle(pub Handle<AnimationStateMachine>);
+
+/// The runtime state of an [`AnimationStateMachine`] driving an [`AnimationPlayer`].
+///
+/// This is added automatically alongside an [`AnimationStateMachineHandle`]. Set parameters
+/// with [`AnimationStateMachineInstance::set_parameter`] and friends to steer the state machine.
+/// Parameters that haven't been set use the defaults from the asset.
+///
+/// Animations are played through the [`AnimationTransitions`] component, so they shouldn't be
+/// started manually on the [`AnimationPlayer`] while a state machine is driving it.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default, Clone)]
+#[require(AnimationTransitions)]
+pub struct AnimationStateMachineInstance {
+    current_state: Option<AnimationStateIndex>,
+    time_in_state: f32,
+    parameters: HashMap<String, AnimationParameter>,
+}
+
+impl AnimationStateMachineInstance {
+    /// Returns the index of the current state, or `None` if the state machine hasn't started yet.
+    pub fn current_state(&self) -> Option<AnimationStateIndex> {
+        self.current_state
+    }
+
+    /// Returns the time spent in the current state, in seconds, scaled by the state's speed.
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// Returns the value of the given parameter, if it has been set.
+    pub fn parameter(&self, name: &str) -> Option<AnimationParameter> {
+        self.parameters.get(name).copied()
+    }
+
+    /// Sets the value of the given parameter.
+    pub fn set_parameter(
+        &mut self,
+        name: impl Into<String>,
+        value: AnimationParameter,
+    ) -> &mut Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+
+    /// Sets the value of a [`Float`](AnimationParameter::Float) parameter.
+    pub fn set_float(&mut self, name: impl Into<String>, value: f32) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Float(value))
+    }
+
+    /// Sets the value of an [`Int`](AnimationParameter::Int) parameter.
+    pub fn set_int(&mut self, name: impl Into<String>, value: i32) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Int(value))
+    }
+
+    /// Sets the value of a [`Bool`](AnimationParameter::Bool) parameter.
+    pub fn set_bool(&mut self, name: impl Into<String>, value: bool) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Bool(value))
+    }
+
+    /// Sets a [`Trigger`](AnimationParameter::Trigger) parameter, which stays set until a
+    /// transition that checks it is taken.
+    pub fn set_trigger(&mut self, name: impl Into<String>) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Trigger(true))
+    }
+
+    /// Resets a [`Trigger`](AnimationParameter::Trigger) parameter.
+    pub fn reset_trigger(&mut self, name: impl Into<String>) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Trigger(false))
+    }
+
+    /// Puts the state machine back into its initial state on the next update, without a transition.
+    pub fn restart(&mut self) {
+        self.current_state = None;
+        self.time_in_state = 0.0;
+    }
+
+    /// Adds the default values of all parameters that haven't been set yet.
+    fn init_parameters(&mut self, state_machine: &AnimationStateMachine) {
+        for (name, default) in &state_machine.parameters {
+            if !self.parameters.contains_key(name) {
+                self.parameters.insert(name.clone(), *default);
+            }
+        }
+    }
+
+    /// Enters `state`, cross-fading to it over `duration`.
+    fn enter(
+        &mut self,
+        state_machine: &AnimationStateMachine,
+        state: AnimationStateIndex,
+        duration: Duration,
+        player: &mut AnimationPlayer,
+        transitions: &mut AnimationTransitions,
+    ) {
+        let Some(state_info) = state_machine.states.get(state) else {
+            return;
+        };
+        self.current_state = Some(state);
+        self.time_in_state = 0.0;
+        let animation = transitions
+            .play(player, state_info.node, duration)
+            .set_speed(state_info.speed);
+        if state_info.repeat {
+            animation.repeat();
+        }
+    }
+}
+
+/// A system that evaluates the transitions of every [`AnimationStateMachine`] and plays the
+/// nodes of the states they enter.
+pub fn advance_state_machines(
+    time: Res<Time>,
+    state_machines: Res<Assets<AnimationStateMachine>>,
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    animation_clips: Res<Assets<AnimationClip>>,
+    mut query: Query<(
+        &AnimationStateMachineHandle,
+        &AnimationGraphHandle,
+        &mut AnimationStateMachineInstance,
+        &mut AnimationTransitions,
+        &mut AnimationPlayer,
+    )>,
+) {
+    let delta_seconds = time.delta_secs();
+    for (handle, graph_handle, mut instance, mut transitions, mut player) in &mut query {
+        let Some(state_machine) = state_machines.get(&handle.0) else {
+            continue;
+        };
+        instance.init_parameters(state_machine);
+
+        let Some(current) = instance.current_state else {
+            instance.enter(
+                state_machine,
+                state_machine.initial_state,
+                Duration::ZERO,
+                &mut player,
+                &mut transitions,
+            );
+            continue;
+        };
+        let Some(state) = state_machine.states.get(current) else {
+            instance.restart();
+            continue;
+        };
+
+        instance.time_in_state += delta_seconds * state.speed.abs();
+        let clip_duration = animation_graphs
+            .get(&graph_handle.0)
+            .and_then(|graph| graph.get(state.node))
+            .and_then(|node| match node.node_type {
+                AnimationNodeType::Clip(ref clip) => animation_clips.get(clip),
+                _ => None,
+            })
+            .map(|clip| clip.duration)
+            .filter(|&duration| duration > 0.0);
+        let normalized_time = match clip_duration {
+            Some(duration) => instance.time_in_state / duration,
+            None => instance.time_in_state,
+        };
+
+        let Some(transition) =
+            state_machine.find_transition(current, normalized_time, &instance.parameters)
+        else {
+            continue;
+        };
+
+        // Consume the triggers this transition checked.
+        for condition in &transition.conditions {
+            if let TransitionCondition::Triggered(parameter) = condition {
+                instance.reset_trigger(parameter.clone());
+            }
+        }
+        instance.enter(
+            state_machine,
+            transition.to,
+            transition.duration,
+            &mut player,
+            &mut transitions,
+        );
+    }
+}
+
+/// A version of [`AnimationStateMachine`] suitable for serializing as an asset.
+///
+/// States are referred to by their names rather than their indices, which makes the files
+/// easier to author by hand.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedAnimationStateMachine {
+    /// Corresponds to the `states` field on [`AnimationStateMachine`].
+    pub states: Vec<AnimationState>,
+    /// The name of the initial state. Defaults to the first state.
+    #[serde(default)]
+    pub initial_state: Option<String>,
+    /// Corresponds to the `parameters` field on [`AnimationStateMachine`].
+    #[serde(default)]
+    pub parameters: HashMap<String, AnimationParameter>,
+    /// Corresponds to the `transitions` field on [`AnimationStateMachine`].
+    #[serde(default)]
+    pub transitions: Vec<SerializedAnimationStateTransition>,
+}
+
+/// A version of [`AnimationStateTransition`] suitable for serializing as part of a
+/// [`SerializedAnimationStateMachine`] asset.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedAnimationStateTransition {
+    /// The name of the state this transition leaves, or `None` for any state.
+    pub from: Option<String>,
+    /// The name of the state this transition enters.
+    pub to: String,
+    /// Corresponds to the `conditions` field on [`AnimationStateTransition`].
+    #[serde(default)]
+    pub conditions: Vec<TransitionCondition>,
+    /// Corresponds to the `exit_time` field on [`AnimationStateTransition`].
+    #[serde(default)]
+    pub exit_time: Option<f32>,
+    /// The cross-fade duration, in seconds.
+    #[serde(default)]
+    pub duration: f32,
+}
+
+impl TryFrom<AnimationStateMachine> for SerializedAnimationStateMachine {
+    type Error = AnimationStateMachineSaveError;
+
+    fn try_from(state_machine: AnimationStateMachine) -> Result<Self, Self::Error> {
+        let name = |index: AnimationStateIndex| {
+            state_machine
+                .states
+                .get(index)
+                .map(|state| state.name.clone())
+                .ok_or(AnimationStateMachineSaveError::InvalidState(index))
+        };
+        let mut transitions = Vec::with_capacity(state_machine.transitions.len());
+        for transition in &state_machine.transitions {
+            transitions.push(SerializedAnimationStateTransition {
+                from: transition.from.map(name).transpose()?,
+                to: name(transition.to)?,
+                conditions: transition.conditions.clone(),
+                exit_time: transition.exit_time,
+                duration: transition.duration.as_secs_f32(),
+            });
+        }
+        Ok(Self {
+            initial_state: state_machine
+                .states
+                .get(state_machine.initial_state)
+                .map(|state| state.name.clone()),
+            states: state_machine.states,
+            parameters: state_machine.parameters,
+            transitions,
+        })
+    }
+}
+
+impl TryFrom<SerializedAnimationStateMachine> for AnimationStateMachine {
+    type Error = AnimationStateMachineLoadError;
+
+    fn try_from(serialized: SerializedAnimationStateMachine) -> Result<Self, Self::Error> {
+        let mut state_machine = AnimationStateMachine {
+            states: serialized.states,
+            initial_state: 0,
+            parameters: serialized.parameters,
+            transitions: Vec::with_capacity(serialized.transitions.len()),
+        };
+        for (index, state) in state_machine.states.iter().enumerate() {
+            if state_machine.state_index(&state.name) != Some(index) {
+                return Err(AnimationStateMachineLoadError::DuplicateState(
+                    state.name.clone(),
+                ));
+            }
+        }
+        let resolve = |name: &str| {
+            state_machine
+                .state_index(name)
+                .ok_or_else(|| AnimationStateMachineLoadError::UnknownState(name.into()))
+        };
+
+        let initial_state = match serialized.initial_state {
+            Some(ref name) => resolve(name)?,
+            None if state_machine.states.is_empty() => {
+                return Err(AnimationStateMachineLoadError::NoStates);
+            }
+            None => 0,
+        };
+        let mut transitions = Vec::with_capacity(serialized.transitions.len());
+        for transition in serialized.transitions {
+            for condition in &transition.conditions {
+                let (TransitionCondition::Greater { parameter, .. }
+                | TransitionCondition::Less { parameter, .. }
+                | TransitionCondition::Equals { parameter, .. }
+                | TransitionCondition::NotEquals { parameter, .. }
+                | TransitionCondition::If(parameter)
+                | TransitionCondition::IfNot(parameter)
+                | TransitionCondition::Triggered(parameter)) = condition;
+                if !state_machine.parameters.contains_key(parameter) {
+                    return Err(AnimationStateMachineLoadError::UnknownParameter(
+                        parameter.clone(),
+                    ));
+                }
+            }
+            let duration = Some(transition.duration)
+                .filter(|duration| duration.is_finite())
+                .and_then(|duration| Duration::try_from_secs_f32(duration.max(0.0)).ok())
+                .ok_or(AnimationStateMachineLoadError::InvalidDuration(
+                    transition.duration,
+                ))?;
+            transitions.push(AnimationStateTransition {
+                from: transition.from.as_deref().map(resolve).transpose()?,
+                to: resolve(&transition.to)?,
+                conditions: transition.conditions,
+                exit_time: transition.exit_time,
+                duration,
+            });
+        }
+        state_machine.initial_state = initial_state;
+        state_machine.transitions = transitions;
+        Ok(state_machine)
+    }
+}
+
+/// An [`AssetLoader`] that can load [`AnimationStateMachine`]s as assets.
+///
+/// The canonical extension for [`AnimationStateMachine`]s is `.animstates.ron`. Plain
+/// `.animstates` is supported as well.
+#[derive(Default, TypePath)]
+pub struct AnimationStateMachineAssetLoader;
+
+/// Errors that can occur when serializing animation state machines to RON.
+#[derive(Error, Debug)]
+pub enum AnimationStateMachineSaveError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON serialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// A transition referred to a state index that doesn't exist.
+    #[error("The animation state machine has no state with index {0}")]
+    InvalidState(AnimationStateIndex),
+}
+
+/// Errors that can occur when deserializing animation state machines from RON.
+#[derive(Error, Debug)]
+pub enum AnimationStateMachineLoadError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON deserialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// An error occurred in RON deserialization, and the location of the error
+    /// is supplied.
+    #[error(transparent)]
+    SpannedRon(#[from] SpannedError),
+    /// The state machine has no states.
+    #[error("The animation state machine has no states")]
+    NoStates,
+    /// Two states have the same name.
+    #[error("The animation state machine has more than one state named `{0}`")]
+    DuplicateState(String),
+    /// A transition or the initial state referred to a state that doesn't exist.
+    #[error("The animation state machine has no state named `{0}`")]
+    UnknownState(String),
+    /// A transition condition referred to a parameter that doesn't exist.
+    #[error("The animation state machine has no parameter named `{0}`")]
+    UnknownParameter(String),
+    /// The duration of a transition is not finite, or too large.
+    #[error("The animation state machine has a transition with invalid duration {0}")]
+    InvalidDuration(f32),
+}
+
+impl AssetLoader for AnimationStateMachineAssetLoader {
+    type Asset = AnimationStateMachine;
+
+    type Settings = ();
+
+    type Error = AnimationStateMachineLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let serialized = SerializedAnimationStateMachine::deserialize(&mut deserializer)
+            .map_err(|err| deserializer.span_error(err))?;
+        serialized.try_into()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["animstates", "animstates.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_ecs::entity::Entity;
+
+    use super::*;
+
+    const LOCOMOTION: &str = r#"(
+        states: [
+            (name: "idle", node: 1),
+            (name: "run", node: 2, speed: 1.5),
+            (name: "jump", node: 3, repeat: false),
+        ],
+        parameters: {
+            "speed": Float(0.0),
+            "jump": Trigger(false),
+        },
+        transitions: [
+            (from: Some("idle"), to: "run", conditions: [Greater(parameter: "speed", value: 0.1)], duration: 0.25),
+            (from: Some("run"), to: "idle", conditions: [Less(parameter: "speed", value: 0.1)]),
+            (from: None, to: "jump", conditions: [Triggered("jump")]),
+            (from: Some("jump"), to: "idle", exit_time: Some(1.0)),
+        ],
+    )"#;
+
+    fn load(source: &str) -> Result<AnimationStateMachine, AnimationStateMachineLoadError> {
+        ron::de::from_str::<SerializedAnimationStateMachine>(source)
+            .map_err(|err| AnimationStateMachineLoadError::Ron(err.into()))?
+            .try_into()
+    }
+
+    #[test]
+    fn load_and_evaluate_transitions() {
+        let state_machine = load(LOCOMOTION).unwrap();
+        let idle = state_machine.state_index("idle").unwrap();
+        let run = state_machine.state_index("run").unwrap();
+        let jump = state_machine.state_index("jump").unwrap();
+        assert_eq!(state_machine.initial_state, idle);
+        assert_eq!(state_machine.states[run].speed, 1.5);
+        assert_eq!(
+            state_machine.transitions[0].duration,
+            Duration::from_millis(250)
+        );
+
+        let mut parameters = state_machine.parameters.clone();
+        assert!(state_machine
+            .find_transition(idle, 0.0, &parameters)
+            .is_none());
+
+        parameters.insert("speed".into(), AnimationParameter::Float(2.0));
+        assert_eq!(
+            state_machine
+                .find_transition(idle, 0.0, &parameters)
+                .map(|transition| transition.to),
+            Some(run)
+        );
+
+        // "Any state" transitions don't apply to their own target state.
+        parameters.insert("jump".into(), AnimationParameter::Trigger(true));
+        assert_eq!(
+            state_machine
+                .find_transition(run, 0.0, &parameters)
+                .map(|transition| transition.to),
+            Some(jump)
+        );
+        assert!(state_machine
+            .find_transition(jump, 0.5, &parameters)
+            .is_none());
+        assert_eq!(
+            state_machine
+                .find_transition(jump, 1.0, &parameters)
+                .map(|transition| transition.to),
+            Some(idle)
+        );
+    }
+
+    #[test]
+    fn save_round_trip() {
+        let state_machine = load(LOCOMOTION).unwrap();
+        let mut ron = String::new();
+        state_machine.save(&mut ron).unwrap();
+        let reloaded = load(&ron).unwrap();
+        assert_eq!(reloaded.states.len(), 3);
+        assert_eq!(reloaded.transitions.len(), 4);
+        assert_eq!(reloaded.transitions[3].exit_time, Some(1.0));
+    }
+
+    #[test]
+    fn load_errors() {
+        assert!(matches!(
+            load(r#"(states: [(name: "a", node: 0)], initial_state: Some("b"))"#),
+            Err(AnimationStateMachineLoadError::UnknownState(name)) if name == "b"
+        ));
+        assert!(matches!(
+            load(r#"(states: [(name: "a", node: 0), (name: "a", node: 1)])"#),
+            Err(AnimationStateMachineLoadError::DuplicateState(_))
+        ));
+        assert!(matches!(
+            load(
+                r#"(states: [(name: "a", node: 0)], transitions: [(from: None, to: "a", conditions: [If("grounded")])])"#
+            ),
+            Err(AnimationStateMachineLoadError::UnknownParameter(_))
+        ));
+        assert!(matches!(
+            load("(states: [])"),
+            Err(AnimationStateMachineLoadError::NoStates)
+        ));
+        for duration in ["inf", "NaN", "1e30"] {
+            assert!(matches!(
+                load(&format!(
+                    r#"(states: [(name: "a", node: 0)], transitions: [(from: None, to: "a", duration: {duration})])"#
+                )),
+                Err(AnimationStateMachineLoadError::InvalidDuration(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn save_invalid_transition() {
+        let mut state_machine = load(LOCOMOTION).unwrap();
+        state_machine.add_transition(AnimationStateTransition::new(None, 7));
+        let mut ron = String::new();
+        assert!(matches!(
+            state_machine.save(&mut ron),
+            Err(AnimationStateMachineSaveError::InvalidState(7))
+        ));
+    }
+
+    #[test]
+    fn advance_state_machine_transitions() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<Assets<AnimationStateMachine>>()
+            .init_resource::<Assets<AnimationGraph>>()
+            .init_resource::<Assets<AnimationClip>>()
+            .add_systems(Update, advance_state_machines);
+
+        let state_machine = load(LOCOMOTION).unwrap();
+        let idle = state_machine.state_index("idle").unwrap();
+        let run = state_machine.state_index("run").unwrap();
+        let jump = state_machine.state_index("jump").unwrap();
+        let nodes = state_machine
+            .states
+            .iter()
+            .map(|state| state.node)
+            .collect::<Vec<_>>();
+        let state_machine = app
+            .world_mut()
+            .resource_mut::<Assets<AnimationStateMachine>>()
+            .add(state_machine);
+        let graph = app
+            .world_mut()
+            .resource_mut::<Assets<AnimationGraph>>()
+            .add(AnimationGraph::new());
+        let entity = app
+            .world_mut()
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationGraphHandle(graph),
+                AnimationStateMachineHandle(state_machine),
+            ))
+            .id();
+
+        let state = |app: &mut App, entity: Entity| {
+            let entity = app.world().entity(entity);
+            let instance = entity.get::<AnimationStateMachineInstance>().unwrap();
+            let player = entity.get::<AnimationPlayer>().unwrap();
+            let state = instance.current_state().unwrap();
+            assert!(player.is_playing_animation(nodes[state]));
+            state
+        };
+
+        // The first update enters the initial state.
+        app.update();
+        assert_eq!(state(&mut app, entity), idle);
+        app.update();
+        assert_eq!(state(&mut app, entity), idle);
+
+        app.world_mut()
+            .get_mut::<AnimationStateMachineInstance>(entity)
+            .unwrap()
+            .set_float("speed", 1.0);
+        app.update();
+        assert_eq!(state(&mut app, entity), run);
+
+        // Triggers are consumed by the transition that checks them.
+        app.world_mut()
+            .get_mut::<AnimationStateMachineInstance>(entity)
+            .unwrap()
+            .set_trigger("jump");
+        app.update();
+        assert_eq!(state(&mut app, entity), jump);
+        let instance = app
+            .world()
+            .get::<AnimationStateMachineInstance>(entity)
+            .unwrap();
+        assert_eq!(
+            instance.parameter("jump"),
+            Some(AnimationParameter::Trigger(false))
+        );
+    }
+}
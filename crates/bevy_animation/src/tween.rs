@@ -0,0 +1,565 @@
+//! Lightweight tweens that animate a single property without an animation graph.
+//!
+//! A [`Tween`] drives one [`AnimatableProperty`] of another entity along a [`Curve`], typically
+//! an [`EasingCurve`] from a start value to an end value. Tweens live on their own entities and
+//! point at the entity they animate with a [`TweenTarget`] relationship, so any number of them
+//! can run on the same entity at once:
+//!
+//! ```
+//! # use core::time::Duration;
+//! # use bevy_animation::{animated_field, prelude::*};
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::{curve::EaseFunction, Vec3};
+//! # use bevy_transform::components::Transform;
+//! fn pop_in(mut commands: Commands, button: Single<Entity, With<Transform>>) {
+//!     commands.spawn((
+//!         TweenBuilder::new(
+//!             animated_field!(Transform::scale),
+//!             Vec3::ZERO,
+//!             Vec3::splat(1.2),
+//!             EaseFunction::CubicOut,
+//!             Duration::from_millis(150),
+//!         )
+//!         .then(Vec3::ONE, EaseFunction::QuadraticInOut, Duration::from_millis(100))
+//!         .build()
+//!         .with_delay(Duration::from_millis(50)),
+//!         TweenTarget(*button),
+//!     ));
+//! }
+//! ```
+//!
+//! When a tween reaches the end of its curve, a [`TweenCompleted`] event is triggered on the
+//! target entity and the tween entity is despawned, unless told otherwise with
+//! [`Tween::with_despawn_on_completion`].
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    time::Duration,
+};
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::EntityEvent,
+    query::Without,
+    reflect::ReflectComponent,
+    resource::IsResource,
+    system::{Commands, Query, Res},
+};
+use bevy_math::curve::{
+    ChainCurve, ConstantCurve, Curve, CurveExt, Ease, EaseFunction, EasingCurve, ForeverCurve,
+    Interval, LinearReparamCurve, PingPongCurve, RepeatCurve,
+};
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_time::Time;
+use tracing::warn;
+
+use crate::{
+    animatable::Animatable,
+    animation_curves::{
+        AnimatableCurve, AnimatableProperty, AnimationCompatibleCurve, AnimationCurveEvaluator,
+    },
+    graph::AnimationNodeIndex,
+    AnimationEntityMut, VariableCurve,
+};
+
+/// A component that animates a property of the entity named by its [`TweenTarget`] along a
+/// curve.
+///
+/// Build tweens that ease between values with a [`TweenBuilder`], or wrap any curve with
+/// [`Tween::new`].
+#[derive(Component)]
+pub struct Tween {
+    curve: VariableCurve,
+    evaluator: Box<dyn AnimationCurveEvaluator>,
+    elapsed: f32,
+    speed: f32,
+    paused: bool,
+    finished: bool,
+    despawn_on_completion: bool,
+}
+
+/// The entity that a [`Tween`] on this entity animates.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[reflect(Component, PartialEq, Debug, Clone)]
+#[relationship(relationship_target = Tweens)]
+pub struct TweenTarget(#[entities] pub Entity);
+
+/// The [`Tween`] entities animating this entity.
+///
+/// The tweens are despawned along with this entity.
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, Debug)]
+#[relationship_target(relationship = TweenTarget, linked_spawn)]
+pub struct Tweens(Vec<Entity>);
+
+/// Triggered on the target of a [`Tween`] when the tween reaches the end of its curve.
+///
+/// Tweens whose curve never ends, such as those built with [`TweenBuilder::forever`], never
+/// complete.
+#[derive(EntityEvent, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(PartialEq, Debug, Clone)]
+pub struct TweenCompleted {
+    /// The entity that the tween animated.
+    pub entity: Entity,
+    /// The tween entity that completed.
+    pub tween: Entity,
+}
+
+/// Builds a [`Tween`] out of eased segments, keeping track of the curve type so that the
+/// adaptors from [`bevy_math::curve`] can be used to repeat it.
+///
+/// Every segment starts at the value the previous one ended with.
+#[derive(Clone, Debug)]
+pub struct TweenBuilder<P, C> {
+    property: P,
+    curve: C,
+}
+
+impl Tween {
+    /// Creates a tween that animates `property` along `curve`.
+    ///
+    /// Time is measured from zero, so curves whose domain starts after zero hold their first
+    /// value until then. The tween completes when it reaches the end of the domain of `curve`.
+    pub fn new<P, C>(property: P, curve: C) -> Self
+    where
+        P: AnimatableProperty + Clone,
+        C: AnimationCompatibleCurve<P::Property>,
+    {
+        Self::from_curve(VariableCurve::new(AnimatableCurve::new(property, curve)))
+    }
+
+    fn from_curve(curve: VariableCurve) -> Self {
+        Self {
+            evaluator: curve.0.create_evaluator(),
+            curve,
+            elapsed: 0.0,
+            speed: 1.0,
+            paused: false,
+            finished: false,
+            despawn_on_completion: true,
+        }
+    }
+
+    /// Delays the start of the tween. The property isn't touched until the delay has passed.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.elapsed = -delay.as_secs_f32();
+        self
+    }
+
+    /// Sets whether the tween entity is despawned when the tween completes. Defaults to `true`.
+    pub fn with_despawn_on_completion(mut self, despawn_on_completion: bool) -> Self {
+        self.despawn_on_completion = despawn_on_completion;
+        self
+    }
+
+    /// Sets the speed at which the tween plays.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// The time since the tween started, in seconds. This is negative while the tween is
+    /// delayed.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// The speed at which the tween plays.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the speed at which the tween plays.
+    pub fn set_speed(&mut self, speed: f32) -> &mut Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Pauses the tween.
+    pub fn pause(&mut self) -> &mut Self {
+        self.paused = true;
+        self
+    }
+
+    /// Unpauses the tween.
+    pub fn resume(&mut self) -> &mut Self {
+        self.paused = false;
+        self
+    }
+
+    /// Returns whether the tween is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns whether the tween has reached the end of its curve.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Restarts the tween from the beginning, without any delay.
+    pub fn restart(&mut self) -> &mut Self {
+        self.elapsed = 0.0;
+        self.finished = false;
+        self
+    }
+}
+
+impl Clone for Tween {
+    fn clone(&self) -> Self {
+        Self {
+            curve: self.curve.clone(),
+            evaluator: self.curve.0.create_evaluator(),
+            elapsed: self.elapsed,
+            speed: self.speed,
+            paused: self.paused,
+            finished: self.finished,
+            despawn_on_completion: self.despawn_on_completion,
+        }
+    }
+}
+
+impl Debug for Tween {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tween")
+            .field("curve", &self.curve)
+            .field("elapsed", &self.elapsed)
+            .field("speed", &self.speed)
+            .field("paused", &self.paused)
+            .field("finished", &self.finished)
+            .field("despawn_on_completion", &self.despawn_on_completion)
+            .finish()
+    }
+}
+
+/// Creates a curve from `start` to `end` over `duration`, shaped by `ease`.
+///
+/// Zero durations are stretched to the smallest representable one, as curves can't have empty
+/// domains.
+fn segment<T: Ease + Clone>(
+    start: T,
+    end: T,
+    ease: EaseFunction,
+    duration: Duration,
+) -> LinearReparamCurve<T, EasingCurve<T>> {
+    EasingCurve::new(start, end, ease)
+        .reparametrize_linear(duration_interval(duration))
+        .expect("both domains are bounded")
+}
+
+fn duration_interval(duration: Duration) -> Interval {
+    Interval::new(0.0, duration.as_secs_f32().max(f32::EPSILON)).expect("the duration is positive")
+}
+
+impl<P, T> TweenBuilder<P, LinearReparamCurve<T, EasingCurve<T>>>
+where
+    P: AnimatableProperty<Property = T> + Clone,
+    T: Ease + Clone,
+{
+    /// Starts a tween that animates `property` from `start` to `end` over `duration`, shaped by
+    /// `ease`.
+    pub fn new(property: P, start: T, end: T, ease: EaseFunction, duration: Duration) -> Self {
+        Self {
+            property,
+            curve: segment(start, end, ease, duration),
+        }
+    }
+}
+
+impl<P, T, C> TweenBuilder<P, C>
+where
+    P: AnimatableProperty<Property = T> + Clone,
+    T: Animatable + Clone,
+    C: Curve<T>,
+{
+    /// The value the tween ends with so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the curve never ends.
+    fn end_value(&self) -> T {
+        let end = self.curve.domain().end();
+        assert!(end.is_finite(), "can't extend a tween that never ends");
+        self.curve.sample_clamped(end)
+    }
+
+    /// Continues the tween from its current end value to `end` over `duration`, shaped by
+    /// `ease`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after [`TweenBuilder::forever`].
+    pub fn then(
+        self,
+        end: T,
+        ease: EaseFunction,
+        duration: Duration,
+    ) -> TweenBuilder<P, ChainCurve<T, C, LinearReparamCurve<T, EasingCurve<T>>>>
+    where
+        T: Ease,
+    {
+        let start = self.end_value();
+        TweenBuilder {
+            property: self.property,
+            curve: self
+                .curve
+                .chain(segment(start, end, ease, duration))
+                .expect("both curves are bounded"),
+        }
+    }
+
+    /// Holds the current end value of the tween for `duration`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after [`TweenBuilder::forever`].
+    pub fn hold(self, duration: Duration) -> TweenBuilder<P, ChainCurve<T, C, ConstantCurve<T>>> {
+        let value = self.end_value();
+        TweenBuilder {
+            property: self.property,
+            curve: self
+                .curve
+                .chain(ConstantCurve::new(duration_interval(duration), value))
+                .expect("both curves are bounded"),
+        }
+    }
+
+    /// Plays the tween built so far `count` more times after the first, jumping back to the
+    /// start each time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after [`TweenBuilder::forever`].
+    pub fn repeat(self, count: usize) -> TweenBuilder<P, RepeatCurve<T, C>> {
+        TweenBuilder {
+            property: self.property,
+            curve: self.curve.repeat(count).expect("the curve is bounded"),
+        }
+    }
+
+    /// Plays the tween built so far forward and then backward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after [`TweenBuilder::forever`].
+    pub fn ping_pong(self) -> TweenBuilder<P, PingPongCurve<T, C>> {
+        TweenBuilder {
+            property: self.property,
+            curve: self.curve.ping_pong().expect("the curve is bounded"),
+        }
+    }
+
+    /// Repeats the tween built so far forever. The resulting tween never completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called twice.
+    pub fn forever(self) -> TweenBuilder<P, ForeverCurve<T, C>> {
+        TweenBuilder {
+            property: self.property,
+            curve: self.curve.forever().expect("the curve is bounded"),
+        }
+    }
+
+    /// Finishes building the tween.
+    pub fn build(self) -> Tween
+    where
+        C: AnimationCompatibleCurve<T>,
+    {
+        Tween::new(self.property, self.curve)
+    }
+}
+
+impl<P, T, C> From<TweenBuilder<P, C>> for Tween
+where
+    P: AnimatableProperty<Property = T> + Clone,
+    T: Animatable + Clone,
+    C: AnimationCompatibleCurve<T>,
+{
+    fn from(builder: TweenBuilder<P, C>) -> Self {
+        builder.build()
+    }
+}
+
+/// A system that advances all [`Tween`]s and applies them to their targets.
+pub fn advance_tweens(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut tweens: Query<(Entity, &mut Tween, &TweenTarget)>,
+    mut targets: Query<AnimationEntityMut, (Without<Tween>, Without<IsResource>)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut tween, &TweenTarget(target)) in &mut tweens {
+        if tween.paused || tween.finished {
+            continue;
+        }
+        tween.elapsed += delta * tween.speed;
+        if tween.elapsed < 0.0 {
+            continue;
+        }
+        let Ok(target_mut) = targets.get_mut(target) else {
+            continue;
+        };
+
+        let tween = &mut *tween;
+        if let Err(err) = tween
+            .curve
+            .0
+            .apply(
+                &mut *tween.evaluator,
+                tween.elapsed,
+                1.0,
+                AnimationNodeIndex::default(),
+            )
+            .and_then(|()| tween.evaluator.commit(target_mut))
+        {
+            warn!("Tween application failed: {:?}", err);
+        }
+
+        if tween.elapsed >= tween.curve.0.domain().end() {
+            tween.finished = true;
+            commands.trigger(TweenCompleted {
+                entity: target,
+                tween: entity,
+            });
+            if tween.despawn_on_completion {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        observer::On,
+        resource::Resource,
+        system::{ResMut, RunSystemOnce},
+        world::World,
+    };
+    use bevy_math::Vec3;
+    use bevy_transform::components::Transform;
+
+    use super::*;
+    use crate::{animated_field, animation_curves::AnimatedField};
+
+    #[derive(Resource, Default)]
+    struct Completed(Vec<Entity>);
+
+    fn step(world: &mut World, seconds: f32) {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        world.run_system_once(advance_tweens).unwrap();
+    }
+
+    fn translation(world: &World, entity: Entity) -> Vec3 {
+        world.get::<Transform>(entity).unwrap().translation
+    }
+
+    fn setup() -> World {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Completed>();
+        world.add_observer(
+            |completed: On<TweenCompleted>, mut log: ResMut<Completed>| {
+                log.0.push(completed.entity);
+            },
+        );
+        world
+    }
+
+    #[test]
+    fn ease_and_complete() {
+        let mut world = setup();
+        let target = world.spawn(Transform::default()).id();
+        let tween = world
+            .spawn((
+                TweenBuilder::new(
+                    animated_field!(Transform::translation),
+                    Vec3::ZERO,
+                    Vec3::X,
+                    EaseFunction::Linear,
+                    Duration::from_secs(1),
+                )
+                .build()
+                .with_delay(Duration::from_millis(500)),
+                TweenTarget(target),
+            ))
+            .id();
+
+        step(&mut world, 0.25);
+        assert_eq!(translation(&world, target), Vec3::ZERO);
+        step(&mut world, 0.75);
+        assert!(translation(&world, target).abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5));
+        assert!(world.resource::<Completed>().0.is_empty());
+
+        step(&mut world, 0.75);
+        assert_eq!(translation(&world, target), Vec3::X);
+        assert_eq!(world.resource::<Completed>().0, [target]);
+        assert!(world.get_entity(tween).is_err());
+    }
+
+    #[test]
+    fn sequence_and_ping_pong() {
+        let mut world = setup();
+        let target = world.spawn(Transform::default()).id();
+        world.spawn((
+            TweenBuilder::new(
+                animated_field!(Transform::translation),
+                Vec3::ZERO,
+                Vec3::X,
+                EaseFunction::Linear,
+                Duration::from_secs(1),
+            )
+            .hold(Duration::from_secs(1))
+            .then(Vec3::Y, EaseFunction::Linear, Duration::from_secs(1))
+            .ping_pong()
+            .build()
+            .with_despawn_on_completion(false),
+            TweenTarget(target),
+        ));
+
+        step(&mut world, 1.5);
+        assert!(translation(&world, target).abs_diff_eq(Vec3::X, 1e-5));
+        step(&mut world, 1.0);
+        assert!(translation(&world, target).abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), 1e-5));
+        step(&mut world, 1.0);
+        assert!(translation(&world, target).abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), 1e-5));
+        step(&mut world, 2.5);
+        assert!(translation(&world, target).abs_diff_eq(Vec3::ZERO, 1e-5));
+        assert_eq!(world.resource::<Completed>().0, [target]);
+
+        let (tween, _) = world
+            .query::<(&Tween, &TweenTarget)>()
+            .single(&world)
+            .unwrap();
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn tweens_despawn_with_target() {
+        let mut world = setup();
+        let target = world.spawn(Transform::default()).id();
+        let tween = world
+            .spawn((
+                TweenBuilder::new(
+                    animated_field!(Transform::scale),
+                    Vec3::ONE,
+                    Vec3::ZERO,
+                    EaseFunction::QuadraticIn,
+                    Duration::from_secs(1),
+                )
+                .forever()
+                .build(),
+                TweenTarget(target),
+            ))
+            .id();
+        step(&mut world, 10.5);
+        assert!(world.resource::<Completed>().0.is_empty());
+
+        world.despawn(target);
+        assert!(world.get_entity(tween).is_err());
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{App, Plugin};
+use crate::{App, Plugin, PreUpdate};
 
 use alloc::string::ToString;
+use bevy_ecs::world::AsyncWorldQueue;
 use bevy_platform::sync::Arc;
 use bevy_tasks::{AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool, TaskPoolBuilder};
 use core::fmt::Debug;
@@ -22,6 +23,10 @@ cfg_if::cfg_if! {
 }
 
 /// Setup of default task pools: [`AsyncComputeTaskPool`], [`ComputeTaskPool`], [`IoTaskPool`].
+///
+/// This also inserts an [`AsyncWorldQueue`] and applies it in [`PreUpdate`],
+/// so tasks spawned on these pools can access the world through an
+/// [`AsyncWorld`](bevy_ecs::world::AsyncWorld) handle.
 #[derive(Default)]
 pub struct TaskPoolPlugin {
     /// Options for the [`TaskPool`](bevy_tasks::TaskPool) created at application start.
@@ -29,12 +34,15 @@ pub struct TaskPoolPlugin {
 }
 
 impl Plugin for TaskPoolPlugin {
-    fn build(&self, _app: &mut App) {
+    fn build(&self, app: &mut App) {
         // Setup the default bevy task pools
         self.task_pool_options.create_default_pools();
 
+        app.init_resource::<AsyncWorldQueue>()
+            .add_systems(PreUpdate, AsyncWorldQueue::apply);
+
         #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
-        _app.add_systems(Last, tick_global_task_pools);
+        app.add_systems(Last, tick_global_task_pools);
     }
 }
 
@@ -1,5 +1,6 @@
 //! Specific distances from the camera in which entities are visible, also known
-//! as *hierarchical levels of detail* or *HLOD*s.
+//! as *hierarchical levels of detail* or *HLOD*s, and automatic switching
+//! between the levels of detail of a single mesh.
 
 use core::{
     hash::{Hash, Hasher},
@@ -7,34 +8,52 @@ use core::{
 };
 
 use bevy_app::{App, Plugin, PostUpdate};
+use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
 use bevy_ecs::{
+    change_detection::DetectChanges,
     component::Component,
     entity::{Entity, EntityHashMap},
+    message::MessageReader,
     query::{With, Without},
     reflect::ReflectComponent,
     resource::Resource,
     schedule::IntoScheduleConfigs as _,
-    system::{Local, Query, ResMut},
+    system::{Local, Query, Res, ResMut},
+    world::Ref,
 };
-use bevy_math::FloatOrd;
-use bevy_reflect::Reflect;
-use bevy_transform::components::GlobalTransform;
+use bevy_math::{FloatOrd, Mat4, Vec3A};
+use bevy_mesh::{mark_3d_meshes_as_changed_if_their_assets_changed, Mesh, Mesh3d};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_transform::{components::GlobalTransform, TransformSystems};
 use bevy_utils::Parallel;
 
 use super::{check_visibility_cpu_culling, VisibilitySystems};
-use crate::{camera::Camera, primitives::Aabb, visibility::NoCpuCulling};
+use crate::{
+    camera::Camera,
+    primitives::{Aabb, MeshAabb},
+    visibility::NoCpuCulling,
+    CameraUpdateSystems,
+};
 
 /// A plugin that enables [`VisibilityRange`]s, which allow entities to be
-/// hidden or shown based on distance to the camera.
+/// hidden or shown based on distance to the camera, and [`MeshLod`]s, which
+/// switch the mesh of an entity based on distance or screen size.
 pub struct VisibilityRangePlugin;
 
 impl Plugin for VisibilityRangePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<VisibleEntityRanges>().add_systems(
             PostUpdate,
-            check_visibility_ranges
-                .in_set(VisibilitySystems::CheckVisibility)
-                .before(check_visibility_cpu_culling),
+            (
+                check_visibility_ranges
+                    .in_set(VisibilitySystems::CheckVisibility)
+                    .before(check_visibility_cpu_culling),
+                select_mesh_lods
+                    .after(TransformSystems::Propagate)
+                    .after(CameraUpdateSystems)
+                    .before(VisibilitySystems::CalculateBounds)
+                    .before(mark_3d_meshes_as_changed_if_their_assets_changed),
+            ),
         );
     }
 }
@@ -294,3 +313,380 @@ pub fn check_visibility_ranges(
 
     visible_entity_ranges.entities.extend(par_local.drain());
 }
+
+/// Switches the [`Mesh3d`] of an entity between several levels of detail of
+/// the same mesh, based on how far it is from the camera or how large it
+/// appears on screen.
+///
+/// Unlike [`VisibilityRange`], which shows and hides separate entities, this
+/// component swaps the mesh handle of a single entity, so the levels share the
+/// same material, transform and other components. The levels are typically
+/// generated with `bevy_mesh::Mesh::generate_lods`.
+///
+/// The entity is measured with the bounding box of its most detailed level,
+/// which is kept in [`MeshLodBounds`], so that switching levels doesn't change
+/// the measurement. With several active cameras, the most detailed level
+/// needed by any of them is used.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, Clone, PartialEq, Debug)]
+#[require(MeshLodBounds)]
+pub struct MeshLod {
+    /// The levels of detail, from the most to the least detailed.
+    pub levels: Vec<MeshLodLevel>,
+
+    /// How the levels are chosen.
+    pub metric: MeshLodMetric,
+
+    /// How far past the threshold of a level the metric has to go before
+    /// switching to another level, as a fraction of the threshold.
+    ///
+    /// This keeps entities near a threshold from switching back and forth
+    /// between two levels every frame. Defaults to 0.1.
+    pub hysteresis: f32,
+}
+
+impl Default for MeshLod {
+    fn default() -> Self {
+        Self {
+            levels: Vec::new(),
+            metric: MeshLodMetric::default(),
+            hysteresis: 0.1,
+        }
+    }
+}
+
+/// The local bounding box a [`MeshLod`] measures its entity with.
+///
+/// This is the [`Aabb`] of the most detailed level of the [`MeshLod`]. It's
+/// computed once the mesh of that level has loaded, and again whenever the
+/// [`MeshLod`] or the mesh of that level changes.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Default, Reflect)]
+#[reflect(Component, Default, Clone, PartialEq, Debug)]
+pub struct MeshLodBounds(pub Option<Aabb>);
+
+/// A level of detail of a [`MeshLod`].
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[reflect(Clone, PartialEq, Debug)]
+pub struct MeshLodLevel {
+    /// The mesh to render at this level.
+    pub mesh: Handle<Mesh>,
+
+    /// The limit up to which this level is used, depending on the
+    /// [`MeshLodMetric`].
+    ///
+    /// For [`MeshLodMetric::Distance`], this level is used while the entity is
+    /// closer than this many world units to the camera. For
+    /// [`MeshLodMetric::ScreenSize`], this level is used while the entity
+    /// covers at least this fraction of the height of the viewport.
+    pub threshold: f32,
+}
+
+/// How a [`MeshLod`] chooses between its levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Reflect)]
+#[reflect(Default, Clone, PartialEq, Debug, Hash)]
+pub enum MeshLodMetric {
+    /// Levels are chosen by the distance from the camera to the center of the
+    /// [`MeshLodBounds`] of the entity, or to its origin until they are known.
+    #[default]
+    Distance,
+
+    /// Levels are chosen by the height of the bounding sphere of the
+    /// [`MeshLodBounds`] of the entity on screen, as a fraction of the height
+    /// of the viewport.
+    ///
+    /// Entities keep their current mesh until their bounds are known.
+    ScreenSize,
+}
+
+impl MeshLod {
+    /// Creates a [`MeshLod`] choosing between `levels` by distance, where each
+    /// level is a mesh and the distance up to which it's used.
+    pub fn distance(levels: impl IntoIterator<Item = (Handle<Mesh>, f32)>) -> Self {
+        Self::new(levels, MeshLodMetric::Distance)
+    }
+
+    /// Creates a [`MeshLod`] choosing between `levels` by screen size, where
+    /// each level is a mesh and the smallest screen size at which it's used.
+    pub fn screen_size(levels: impl IntoIterator<Item = (Handle<Mesh>, f32)>) -> Self {
+        Self::new(levels, MeshLodMetric::ScreenSize)
+    }
+
+    fn new(levels: impl IntoIterator<Item = (Handle<Mesh>, f32)>, metric: MeshLodMetric) -> Self {
+        Self {
+            levels: levels
+                .into_iter()
+                .map(|(mesh, threshold)| MeshLodLevel { mesh, threshold })
+                .collect(),
+            metric,
+            ..Self::default()
+        }
+    }
+
+    /// Returns this [`MeshLod`] with the given [`hysteresis`](Self::hysteresis).
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Returns the index of the level to use for the given value of the
+    /// [`metric`](Self::metric), or `None` if there are no levels.
+    ///
+    /// The least detailed level is used if no level's threshold is met.
+    pub fn select_level(&self, value: f32) -> Option<usize> {
+        let last = self.levels.len().checked_sub(1)?;
+        let position = self.levels.iter().position(|level| match self.metric {
+            MeshLodMetric::Distance => value < level.threshold,
+            MeshLodMetric::ScreenSize => value >= level.threshold,
+        });
+        Some(position.unwrap_or(last))
+    }
+
+    /// Returns the most and least detailed levels that may be used for the
+    /// given value of the [`metric`](Self::metric), taking the
+    /// [`hysteresis`](Self::hysteresis) into account, or `None` if there are
+    /// no levels.
+    ///
+    /// The current level should be kept if it's within this range.
+    pub fn select_level_range(&self, value: f32) -> Option<(usize, usize)> {
+        let margin = 1.0 + self.hysteresis.max(0.0);
+        let (finer, coarser) = match self.metric {
+            MeshLodMetric::Distance => (value / margin, value * margin),
+            MeshLodMetric::ScreenSize => (value * margin, value / margin),
+        };
+        Some((self.select_level(finer)?, self.select_level(coarser)?))
+    }
+}
+
+/// Returns the height on screen of a sphere with the given `radius` at
+/// `distance` from a camera, as a fraction of the height of the viewport.
+fn screen_size(radius: f32, distance: f32, clip_from_view: &Mat4) -> f32 {
+    // Perspective projections divide by the depth, orthographic ones don't.
+    let w = if clip_from_view.w_axis.w == 0.0 {
+        distance
+    } else {
+        1.0
+    };
+    radius * clip_from_view.y_axis.y / w
+}
+
+/// Swaps the [`Mesh3d`] of each entity with a [`MeshLod`] for the level of
+/// detail required by the active cameras.
+pub fn select_mesh_lods(
+    views: Query<(&Camera, &GlobalTransform)>,
+    meshes: Res<Assets<Mesh>>,
+    mut mesh_events: MessageReader<AssetEvent<Mesh>>,
+    mut lods: Query<(
+        Ref<MeshLod>,
+        &GlobalTransform,
+        &mut MeshLodBounds,
+        &mut Mesh3d,
+    )>,
+) {
+    let modified_meshes: Vec<AssetId<Mesh>> = mesh_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    let views: Vec<(Vec3A, Mat4)> = views
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .map(|(camera, transform)| (transform.translation_vec3a(), camera.clip_from_view()))
+        .collect();
+    if views.is_empty() {
+        return;
+    }
+
+    lods.par_iter_mut()
+        .for_each(|(lod, transform, mut bounds, mut mesh)| {
+            if lod.is_changed()
+                || bounds.0.is_none()
+                || lod
+                    .levels
+                    .first()
+                    .is_some_and(|level| modified_meshes.contains(&level.mesh.id()))
+            {
+                let aabb = lod
+                    .levels
+                    .first()
+                    .and_then(|level| meshes.get(&level.mesh))
+                    .and_then(MeshAabb::compute_aabb);
+                if bounds.0 != aabb {
+                    bounds.0 = aabb;
+                }
+            }
+
+            let (center, radius) = match bounds.0 {
+                Some(aabb) => (
+                    transform.affine().transform_point3a(aabb.center),
+                    Some(transform.radius_vec3a(aabb.half_extents)),
+                ),
+                None => (transform.translation_vec3a(), None),
+            };
+
+            // The most detailed level and the range of levels allowed by any view.
+            let Some((level, min, max)) = views
+                .iter()
+                .filter_map(|(view_position, clip_from_view)| {
+                    let distance = view_position.distance(center);
+                    let value = match lod.metric {
+                        MeshLodMetric::Distance => distance,
+                        MeshLodMetric::ScreenSize => screen_size(radius?, distance, clip_from_view),
+                    };
+                    let (min, max) = lod.select_level_range(value)?;
+                    Some((lod.select_level(value)?, min, max))
+                })
+                .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)))
+            else {
+                return;
+            };
+
+            let level = match lod.levels.iter().position(|level| level.mesh == mesh.0) {
+                Some(current) => current.clamp(min, max),
+                None => level,
+            };
+            let handle = &lod.levels[level].mesh;
+            if mesh.0 != *handle {
+                mesh.0 = handle.clone();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_asset::{uuid::Uuid, AssetEvent, Assets, Handle};
+    use bevy_ecs::entity::Entity;
+    use bevy_math::{primitives::Cuboid, Mat4, Vec3};
+    use bevy_mesh::{Mesh, Mesh3d};
+    use bevy_transform::components::GlobalTransform;
+
+    use super::{screen_size, select_mesh_lods, MeshLod, MeshLodBounds};
+    use crate::{camera::Camera, primitives::MeshAabb};
+
+    #[test]
+    fn select_level() {
+        let handles: [Handle<_>; 3] = [1, 2, 3].map(|id| Handle::from(Uuid::from_u128(id)));
+
+        let lod = MeshLod::distance([
+            (handles[0].clone(), 10.0),
+            (handles[1].clone(), 50.0),
+            (handles[2].clone(), 100.0),
+        ]);
+        assert_eq!(lod.select_level(0.0), Some(0));
+        assert_eq!(lod.select_level(10.0), Some(1));
+        assert_eq!(lod.select_level(99.0), Some(2));
+        assert_eq!(lod.select_level(1000.0), Some(2));
+
+        let lod = MeshLod::screen_size([
+            (handles[0].clone(), 0.5),
+            (handles[1].clone(), 0.1),
+            (handles[2].clone(), 0.0),
+        ]);
+        assert_eq!(lod.select_level(1.0), Some(0));
+        assert_eq!(lod.select_level(0.2), Some(1));
+        assert_eq!(lod.select_level(0.01), Some(2));
+
+        assert_eq!(MeshLod::default().select_level(1.0), None);
+
+        // Within 10% of a threshold, either of the levels around it may be used.
+        let lod = MeshLod::distance([
+            (handles[0].clone(), 10.0),
+            (handles[1].clone(), 50.0),
+            (handles[2].clone(), 100.0),
+        ]);
+        assert_eq!(lod.select_level_range(5.0), Some((0, 0)));
+        assert_eq!(lod.select_level_range(10.0), Some((0, 1)));
+        assert_eq!(lod.select_level_range(20.0), Some((1, 1)));
+        assert_eq!(
+            lod.with_hysteresis(0.0).select_level_range(10.0),
+            Some((1, 1))
+        );
+    }
+
+    #[test]
+    fn switch_mesh_lods() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .add_message::<AssetEvent<Mesh>>()
+            .add_systems(Update, select_mesh_lods);
+
+        let mut meshes = app.world_mut().resource_mut::<Assets<Mesh>>();
+        let levels = [2.0, 1.0, 0.5].map(|size| meshes.add(Cuboid::from_length(size)));
+        let bounds = meshes.get(&levels[0]).unwrap().compute_aabb();
+        let lod = MeshLod::distance([
+            (levels[0].clone(), 10.0),
+            (levels[1].clone(), 20.0),
+            (levels[2].clone(), f32::INFINITY),
+        ]);
+
+        app.world_mut()
+            .spawn((Camera::default(), GlobalTransform::default()));
+        let entity = app
+            .world_mut()
+            .spawn((lod.clone(), Mesh3d(levels[0].clone())))
+            .id();
+
+        // Moves `entity` to `distance` from the camera and returns the level it switched to.
+        let level_at = |app: &mut App, entity: Entity, distance: f32| {
+            app.world_mut()
+                .entity_mut(entity)
+                .insert(GlobalTransform::from_translation(Vec3::Z * -distance));
+            app.update();
+            let mesh = app.world().get::<Mesh3d>(entity).unwrap();
+            levels.iter().position(|level| *level == mesh.0)
+        };
+
+        assert_eq!(level_at(&mut app, entity, 5.0), Some(0));
+        // Going just past a threshold doesn't switch levels until the hysteresis is overcome, in
+        // either direction.
+        assert_eq!(level_at(&mut app, entity, 10.5), Some(0));
+        assert_eq!(level_at(&mut app, entity, 11.5), Some(1));
+        assert_eq!(level_at(&mut app, entity, 9.5), Some(1));
+        assert_eq!(level_at(&mut app, entity, 8.5), Some(0));
+        assert_eq!(level_at(&mut app, entity, 25.0), Some(2));
+
+        // The entity is still measured with the bounds of the most detailed level, rather than
+        // those of the mesh it currently shows.
+        assert!(bounds.is_some());
+        assert_eq!(
+            app.world().get::<MeshLodBounds>(entity),
+            Some(&MeshLodBounds(bounds))
+        );
+
+        // The bounds follow changes to the mesh of the most detailed level.
+        let mut meshes = app.world_mut().resource_mut::<Assets<Mesh>>();
+        meshes
+            .insert(&levels[0], Cuboid::from_length(4.0).into())
+            .unwrap();
+        let bounds = meshes.get(&levels[0]).unwrap().compute_aabb();
+        app.world_mut()
+            .write_message(AssetEvent::Modified { id: levels[0].id() });
+        app.update();
+        assert_eq!(
+            app.world().get::<MeshLodBounds>(entity),
+            Some(&MeshLodBounds(bounds))
+        );
+
+        // An entity showing a mesh that isn't one of the levels switches to the level for its
+        // distance.
+        let other = app.world_mut().spawn((lod, Mesh3d::default())).id();
+        assert_eq!(level_at(&mut app, other, 10.5), Some(1));
+    }
+
+    #[test]
+    fn sphere_screen_size() {
+        // With a vertical field of view of 90 degrees, a unit sphere 10 units
+        // away covers a tenth of the viewport.
+        let perspective =
+            Mat4::perspective_infinite_reverse_rh(core::f32::consts::FRAC_PI_2, 1.0, 0.1);
+        assert!((screen_size(1.0, 10.0, &perspective) - 0.1).abs() < 1e-5);
+
+        // An orthographic projection 20 units high ignores the distance.
+        let orthographic = Mat4::orthographic_rh(-10.0, 10.0, -10.0, 10.0, 0.0, 100.0);
+        assert!((screen_size(1.0, 10.0, &orthographic) - 0.1).abs() < 1e-5);
+        assert!((screen_size(1.0, 50.0, &orthographic) - 0.1).abs() < 1e-5);
+    }
+}
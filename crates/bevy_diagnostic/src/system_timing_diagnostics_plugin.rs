@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+use bevy_app::prelude::*;
+use bevy_ecs::{
+    prelude::*,
+    schedule::{SystemTimingKind, SystemTimings},
+};
+use bevy_platform::{collections::HashMap, time::Instant};
+
+use crate::{
+    Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore, DEFAULT_MAX_HISTORY_LENGTH,
+};
+
+/// Adds per-system and per-run-condition timing diagnostics to an App.
+///
+/// This inserts the [`SystemTimings`] resource, which makes the schedule executors measure
+/// every system and run condition they run. Once per frame, the measurements are summed up
+/// per system and recorded as diagnostics under the following paths:
+///
+/// - `system_timings/system/<system name>`: time spent running the system, in ms.
+/// - `system_timings/condition/<condition name>`: time spent evaluating the run condition, in ms.
+/// - `system_timings/thread/<system name>`: index of the thread the system last ran on.
+///   Threads are numbered in the order they were first seen.
+///
+/// Diagnostics are created the first time a system is seen, so they will show up in
+/// [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) output without registering them upfront.
+/// Use [`SystemTimingDiagnosticsPlugin::slowest_systems`] to find the systems with the highest
+/// smoothed run time.
+///
+/// Note that measuring every system has a small cost, so this plugin is best used while profiling.
+///
+/// This plugin is behind the `system_timing_plugin` feature, which also enables `bevy_ecs/debug`.
+/// Without it, every system and run condition has the same placeholder name, and their
+/// diagnostics would be merged into a single one.
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) to output diagnostics to the console.
+pub struct SystemTimingDiagnosticsPlugin {
+    /// The total number of values to keep for each system.
+    pub max_history_length: usize,
+}
+
+impl Default for SystemTimingDiagnosticsPlugin {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HISTORY_LENGTH)
+    }
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    /// Creates a new `SystemTimingDiagnosticsPlugin` with the specified `max_history_length`.
+    pub fn new(max_history_length: usize) -> Self {
+        Self { max_history_length }
+    }
+}
+
+impl Plugin for SystemTimingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DiagnosticsStore>()
+            .init_resource::<SystemTimings>()
+            .insert_resource(SystemTimingDiagnosticsSettings {
+                max_history_length: self.max_history_length,
+            })
+            .add_systems(Last, Self::diagnostic_system);
+    }
+}
+
+/// Settings copied from the [`SystemTimingDiagnosticsPlugin`] for use by its system.
+#[derive(Resource)]
+struct SystemTimingDiagnosticsSettings {
+    max_history_length: usize,
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    /// Root path of all system timing diagnostics.
+    pub const ROOT: &'static str = "system_timings";
+
+    /// Returns the path of the run time diagnostic for the system with the given name.
+    pub fn system_path(name: &str) -> DiagnosticPath {
+        DiagnosticPath::from_components([Self::ROOT, "system", name])
+    }
+
+    /// Returns the path of the run time diagnostic for the run condition with the given name.
+    pub fn condition_path(name: &str) -> DiagnosticPath {
+        DiagnosticPath::from_components([Self::ROOT, "condition", name])
+    }
+
+    /// Returns the path of the thread assignment diagnostic for the system with the given name.
+    pub fn thread_path(name: &str) -> DiagnosticPath {
+        DiagnosticPath::from_components([Self::ROOT, "thread", name])
+    }
+
+    /// Returns the system run time diagnostics, sorted from slowest to fastest by their smoothed value.
+    pub fn slowest_systems(store: &DiagnosticsStore) -> Vec<&Diagnostic> {
+        let mut systems: Vec<_> = store
+            .iter()
+            .filter(|diagnostic| {
+                let mut components = diagnostic.path().components();
+                components.next() == Some(Self::ROOT) && components.next() == Some("system")
+            })
+            .collect();
+        systems.sort_by(|a, b| {
+            let a = a.smoothed().unwrap_or_default();
+            let b = b.smoothed().unwrap_or_default();
+            b.total_cmp(&a)
+        });
+        systems
+    }
+
+    /// Turns the records collected in [`SystemTimings`] into diagnostic measurements.
+    fn diagnostic_system(
+        mut timings: ResMut<SystemTimings>,
+        mut store: ResMut<DiagnosticsStore>,
+        settings: Res<SystemTimingDiagnosticsSettings>,
+        #[cfg(feature = "std")] mut threads: Local<HashMap<std::thread::ThreadId, usize>>,
+    ) {
+        let mut totals = HashMap::<DiagnosticPath, f64>::default();
+        #[cfg(feature = "std")]
+        let mut thread_indices = HashMap::<DiagnosticPath, usize>::default();
+
+        for timing in timings.drain() {
+            let path = match timing.kind {
+                SystemTimingKind::System => Self::system_path(&timing.name),
+                SystemTimingKind::Condition => Self::condition_path(&timing.name),
+            };
+            *totals.entry(path).or_default() += timing.duration.as_secs_f64() * 1000.0;
+
+            #[cfg(feature = "std")]
+            if timing.kind == SystemTimingKind::System {
+                let next_index = threads.len();
+                let index = *threads.entry(timing.thread).or_insert(next_index);
+                thread_indices.insert(Self::thread_path(&timing.name), index);
+            }
+        }
+
+        let now = Instant::now();
+        let mut add_measurement = |path: DiagnosticPath, value: f64, suffix: &'static str| {
+            if store.get(&path).is_none() {
+                store.add(
+                    Diagnostic::new(path.clone())
+                        .with_suffix(suffix)
+                        .with_max_history_length(settings.max_history_length),
+                );
+            }
+            let diagnostic = store.get_mut(&path).unwrap();
+            if diagnostic.is_enabled {
+                diagnostic.add_measurement(DiagnosticMeasurement { time: now, value });
+            }
+        };
+
+        for (path, value) in totals {
+            add_measurement(path, value, "ms");
+        }
+        #[cfg(feature = "std")]
+        for (path, index) in thread_indices {
+            add_measurement(path, index as f64, "");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow_system() {
+        std::thread::sleep(core::time::Duration::from_millis(2));
+    }
+
+    #[test]
+    fn records_system_timings() {
+        let mut app = App::new();
+        app.add_plugins(SystemTimingDiagnosticsPlugin::default())
+            .add_systems(Update, slow_system.run_if(|| true));
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let slowest = SystemTimingDiagnosticsPlugin::slowest_systems(store);
+        assert!(!slowest.is_empty());
+        assert!(slowest[0].value().unwrap() >= 2.0);
+        assert!(slowest[0].path().as_str().ends_with("slow_system"));
+        assert!(store.iter().any(|diagnostic| {
+            diagnostic
+                .path()
+                .as_str()
+                .starts_with("system_timings/condition/")
+        }));
+        assert!(store.iter().any(|diagnostic| {
+            diagnostic
+                .path()
+                .as_str()
+                .starts_with("system_timings/thread/")
+        }));
+    }
+}
@@ -4,10 +4,11 @@ use crate::{
     component::ComponentId,
     entity::Entity,
     event::{EntityEvent, Event},
-    observer::{CachedObservers, TriggerContext},
+    observer::{CachedObserver, CachedObservers, TriggerContext},
     traversal::Traversal,
     world::DeferredWorld,
 };
+use alloc::vec::Vec;
 use bevy_ptr::PtrMut;
 use core::{fmt, marker::PhantomData};
 
@@ -101,7 +102,7 @@ impl GlobalTrigger {
         unsafe {
             world.as_unsafe_world_cell().increment_trigger_id();
         }
-        for (observer, runner) in observers.global_observers() {
+        for (observer, cached) in observers.global_observers() {
             // SAFETY:
             // - `observers` come from `world` and match the `event` type, enforced by the call to `trigger_internal`
             // - the passed in event pointer is an `Event`, enforced by the call to `trigger_internal`
@@ -109,7 +110,7 @@ impl GlobalTrigger {
             // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger_internal`
             // - this abides by the nuances defined in the `Trigger` safety docs
             unsafe {
-                (runner)(
+                (cached.runner)(
                     world.reborrow(),
                     *observer,
                     trigger_context,
@@ -165,6 +166,8 @@ unsafe impl<E: EntityEvent + for<'a> Event<Trigger<'a> = Self>> Trigger<E> for E
 /// Trigger observers watching for the given entity event.
 /// The `target_entity` should match the [`EntityEvent::event_target`] on `event` for logical correctness.
 ///
+/// "Global" observers and observers watching `target_entity` are run interleaved in order of their priority.
+///
 /// # Safety
 /// - `observers` must come from the `world` [`DeferredWorld`], and correspond to observers that match the `event` type
 /// - `event` must point to an [`Event`]
@@ -174,25 +177,74 @@ unsafe impl<E: EntityEvent + for<'a> Event<Trigger<'a> = Self>> Trigger<E> for E
 // Note: this is not an EntityTrigger method because we want to reuse this logic for the entity propagation trigger
 #[inline(never)]
 pub unsafe fn trigger_entity_internal(
+    world: DeferredWorld,
+    observers: &CachedObservers,
+    event: PtrMut,
+    trigger: PtrMut,
+    target_entity: Entity,
+    trigger_context: &TriggerContext,
+) {
+    // SAFETY: the caller upholds the safety requirements of `trigger_entity_internal`
+    unsafe {
+        trigger_entity_phase_internal(
+            world,
+            observers,
+            event,
+            trigger,
+            target_entity,
+            trigger_context,
+            PropagationPhase::Target,
+        );
+    }
+}
+
+/// Like [`trigger_entity_internal`], but only runs the observers that run during the given `phase`.
+///
+/// # Safety
+/// See [`trigger_entity_internal`].
+unsafe fn trigger_entity_phase_internal(
     mut world: DeferredWorld,
     observers: &CachedObservers,
     mut event: PtrMut,
     mut trigger: PtrMut,
     target_entity: Entity,
     trigger_context: &TriggerContext,
+    phase: PropagationPhase,
 ) {
     // SAFETY: there are no outstanding world references
     unsafe {
         world.as_unsafe_world_cell().increment_trigger_id();
     }
-    for (observer, runner) in observers.global_observers() {
+
+    // Both maps are sorted by priority, so merge them. Global observers run first on ties.
+    let mut global_observers = observers.global_observers().iter().peekable();
+    let mut entity_observers = observers
+        .entity_observers()
+        .get(&target_entity)
+        .into_iter()
+        .flat_map(|map| map.iter())
+        .peekable();
+    loop {
+        let next = match (global_observers.peek(), entity_observers.peek()) {
+            (Some((_, global)), Some((_, entity))) if entity.priority > global.priority => {
+                entity_observers.next()
+            }
+            (Some(_), _) => global_observers.next(),
+            (None, _) => entity_observers.next(),
+        };
+        let Some((observer, cached)) = next else {
+            break;
+        };
+        if !phase.runs(cached) {
+            continue;
+        }
         // SAFETY:
         // - `observers` come from `world` and match the `event` type, enforced by the call to `trigger_entity_internal`
         // - the passed in event pointer is an `Event`, enforced by the call to `trigger_entity_internal`
         // - `trigger` is a matching trigger type, enforced by the call to `trigger_entity_internal`
         // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger_entity_internal`
         unsafe {
-            (runner)(
+            (cached.runner)(
                 world.reborrow(),
                 *observer,
                 trigger_context,
@@ -201,23 +253,31 @@ pub unsafe fn trigger_entity_internal(
             );
         }
     }
+}
 
-    if let Some(map) = observers.entity_observers().get(&target_entity) {
-        for (observer, runner) in map {
-            // SAFETY:
-            // - `observers` come from `world` and match the `event` type, enforced by the call to `trigger_entity_internal`
-            // - the passed in event pointer is an `Event`, enforced by the call to `trigger_entity_internal`
-            // - `trigger` is a matching trigger type, enforced by the call to `trigger_entity_internal`
-            // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger_entity_internal`
-            unsafe {
-                (runner)(
-                    world.reborrow(),
-                    *observer,
-                    trigger_context,
-                    event.reborrow(),
-                    trigger.reborrow(),
-                );
-            }
+/// The phase a propagating [`EntityEvent`] is in, as tracked by [`PropagateEntityTrigger::phase`].
+///
+/// See the [`Observer`](crate::observer::Observer) docs on capture and bubble phases for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropagationPhase {
+    /// The event is walking the [`Traversal`] top-down towards its original target,
+    /// running only [capture observers](crate::observer::Observer::in_capture_phase).
+    Capture,
+    /// The event is running the observers of its original target.
+    #[default]
+    Target,
+    /// The event is propagating along the [`Traversal`] bottom-up,
+    /// running all observers except capture observers.
+    Bubble,
+}
+
+impl PropagationPhase {
+    /// Returns `true` if the `observer` runs during this phase.
+    fn runs(self, observer: &CachedObserver) -> bool {
+        match self {
+            PropagationPhase::Capture => observer.capture,
+            PropagationPhase::Target => true,
+            PropagationPhase::Bubble => !observer.capture,
         }
     }
 }
@@ -230,6 +290,10 @@ pub unsafe fn trigger_entity_internal(
 /// [`EntityEvent`] type.
 ///
 /// If `AUTO_PROPAGATE` is `true`, [`PropagateEntityTrigger::propagate`] will default to `true`.
+///
+/// If any [capture observers](crate::observer::Observer::in_capture_phase) exist for the event, the [`Traversal`] is first
+/// walked top-down, running only the capture observers, before the event reaches its original target.
+/// See [`PropagationPhase`] for details.
 pub struct PropagateEntityTrigger<const AUTO_PROPAGATE: bool, E: EntityEvent, T: Traversal<E>> {
     /// The original [`Entity`] the [`Event`] was _first_ triggered for.
     pub original_event_target: Entity,
@@ -238,6 +302,9 @@ pub struct PropagateEntityTrigger<const AUTO_PROPAGATE: bool, E: EntityEvent, T:
     /// The [`Traversal`] will stop on the current entity.
     pub propagate: bool,
 
+    /// The phase of propagation the event is currently in.
+    pub phase: PropagationPhase,
+
     _marker: PhantomData<(E, T)>,
 }
 
@@ -248,6 +315,7 @@ impl<const AUTO_PROPAGATE: bool, E: EntityEvent, T: Traversal<E>> Default
         Self {
             original_event_target: Entity::PLACEHOLDER,
             propagate: AUTO_PROPAGATE,
+            phase: PropagationPhase::default(),
             _marker: Default::default(),
         }
     }
@@ -260,6 +328,7 @@ impl<const AUTO_PROPAGATE: bool, E: EntityEvent, T: Traversal<E>> fmt::Debug
         f.debug_struct("PropagateEntityTrigger")
             .field("original_event_target", &self.original_event_target)
             .field("propagate", &self.propagate)
+            .field("phase", &self.phase)
             .field("_marker", &self._marker)
             .finish()
     }
@@ -282,6 +351,51 @@ unsafe impl<
     ) {
         let mut current_entity = event.event_target();
         self.original_event_target = current_entity;
+
+        if observers.has_capture_observers() {
+            // Collect the propagation path up front, so that it can be walked top-down.
+            let mut path = Vec::new();
+            while let Ok(entity) = world.get_entity(current_entity)
+                && let Ok(item) = entity.get_components::<T>()
+                && let Some(traverse_to) = T::traverse(item, event)
+            {
+                current_entity = traverse_to;
+                event.set_event_target(current_entity);
+                path.push(current_entity);
+            }
+
+            // The capture phase always runs unless it is stopped by an observer.
+            self.phase = PropagationPhase::Capture;
+            self.propagate = true;
+            for &entity in path.iter().rev() {
+                event.set_event_target(entity);
+                // SAFETY:
+                // - `observers` come from `world` and match the event type `E`, enforced by the call to `trigger`
+                // - the passed in event pointer comes from `event`, which is an `Event`
+                // - `trigger` is a matching trigger type, as it comes from `self`, which is the Trigger for `E`
+                // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger`
+                unsafe {
+                    trigger_entity_phase_internal(
+                        world.reborrow(),
+                        observers,
+                        event.into(),
+                        self.into(),
+                        entity,
+                        trigger_context,
+                        PropagationPhase::Capture,
+                    );
+                }
+                if !self.propagate {
+                    return;
+                }
+            }
+
+            current_entity = self.original_event_target;
+            event.set_event_target(current_entity);
+            self.propagate = AUTO_PROPAGATE;
+        }
+
+        self.phase = PropagationPhase::Target;
         // SAFETY:
         // - `observers` come from `world` and match the event type `E`, enforced by the call to `trigger`
         // - the passed in event pointer comes from `event`, which is an `Event`
@@ -298,6 +412,7 @@ unsafe impl<
             );
         }
 
+        self.phase = PropagationPhase::Bubble;
         loop {
             if !self.propagate {
                 return;
@@ -318,13 +433,14 @@ unsafe impl<
             // - `trigger` is a matching trigger type, as it comes from `self`, which is the Trigger for `E`
             // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger`
             unsafe {
-                trigger_entity_internal(
+                trigger_entity_phase_internal(
                     world.reborrow(),
                     observers,
                     event.into(),
                     self.into(),
                     current_entity,
                     trigger_context,
+                    PropagationPhase::Bubble,
                 );
             }
         }
@@ -478,14 +594,14 @@ impl<'a> EntityComponentsTrigger<'a> {
         // Trigger observers watching for a specific component
         for id in self.components {
             if let Some(component_observers) = observers.component_observers().get(id) {
-                for (observer, runner) in component_observers.global_observers() {
+                for (observer, cached) in component_observers.global_observers() {
                     // SAFETY:
                     // - `observers` come from `world` and match the `event` type, enforced by the call to `trigger_internal`
                     // - the passed in event pointer is an `Event`, enforced by the call to `trigger_internal`
                     // - `trigger` is a matching trigger type, enforced by the call to `trigger_internal`
                     // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger_internal`
                     unsafe {
-                        (runner)(
+                        (cached.runner)(
                             world.reborrow(),
                             *observer,
                             trigger_context,
@@ -499,14 +615,14 @@ impl<'a> EntityComponentsTrigger<'a> {
                     .entity_component_observers()
                     .get(&entity)
                 {
-                    for (observer, runner) in map {
+                    for (observer, cached) in map {
                         // SAFETY:
                         // - `observers` come from `world` and match the `event` type, enforced by the call to `trigger_internal`
                         // - the passed in event pointer is an `Event`, enforced by the call to `trigger_internal`
                         // - `trigger` is a matching trigger type, enforced by the call to `trigger_internal`
                         // - `trigger_context`'s event_key matches `E`, enforced by the call to `trigger_internal`
                         unsafe {
-                            (runner)(
+                            (cached.runner)(
                                 world.reborrow(),
                                 *observer,
                                 trigger_context,
@@ -7,12 +7,18 @@
 //!     - Lifecycle observers have their own fields to save lookups.
 //! - [`CachedObservers`] contains maps of [`ObserverRunner`]s, which are the actual functions that will be run when the observer is triggered.
 //!     - These are split by target type, in order to allow for different lookup strategies.
+//!     - Each [`ObserverMap`] sorts its observers into the order they should run in when it is first iterated after a change.
 //!     - [`CachedComponentObservers`] is one of these maps, which contains observers that are specifically targeted at a component.
 
-use bevy_platform::collections::HashMap;
+use alloc::{collections::BinaryHeap, vec::Vec};
+use bevy_platform::{collections::HashMap, sync::OnceLock};
+use core::cmp::Reverse;
 
 use crate::{
-    archetype::ArchetypeFlags, component::ComponentId, entity::EntityHashMap, event::EventKey,
+    archetype::ArchetypeFlags,
+    component::ComponentId,
+    entity::{Entity, EntityHashMap},
+    event::EventKey,
     observer::ObserverRunner,
 };
 
@@ -124,6 +130,8 @@ pub struct CachedObservers {
     pub(super) component_observers: HashMap<ComponentId, CachedComponentObservers>,
     /// Observers watching for triggers of events for a specific entity
     pub(super) entity_observers: EntityHashMap<ObserverMap>,
+    /// The number of registrations of observers that run during the capture phase
+    pub(super) capture_observers: usize,
 }
 
 impl CachedObservers {
@@ -142,10 +150,218 @@ impl CachedObservers {
     pub fn entity_observers(&self) -> &EntityHashMap<ObserverMap> {
         &self.entity_observers
     }
+
+    /// Returns `true` if any of these observers run during the capture phase of a propagating event.
+    ///
+    /// See [`Observer::in_capture_phase`](crate::observer::Observer::in_capture_phase).
+    pub fn has_capture_observers(&self) -> bool {
+        self.capture_observers > 0
+    }
+
+    /// Inserts `observer` into `map`, ordered after the observers in `after` and before the
+    /// observers in `before`, and updates the capture observer count.
+    pub(super) fn insert_observer(
+        capture_observers: &mut usize,
+        map: &mut ObserverMap,
+        entity: Entity,
+        observer: CachedObserver,
+        before: &[Entity],
+        after: &[Entity],
+    ) {
+        Self::remove_observer(capture_observers, map, entity);
+        map.insert(entity, observer, before, after);
+        if observer.capture {
+            *capture_observers += 1;
+        }
+    }
+
+    /// Removes the observer `entity` from `map`, and updates the capture observer count.
+    pub(super) fn remove_observer(
+        capture_observers: &mut usize,
+        map: &mut ObserverMap,
+        entity: Entity,
+    ) {
+        if let Some(removed) = map.remove(entity)
+            && removed.capture
+        {
+            *capture_observers -= 1;
+        }
+    }
+}
+
+/// The observers registered to an event for a particular target, in the order they run in.
+///
+/// Observers run after the observers they are ordered [after](crate::observer::Observer::after),
+/// and before the observers they are ordered [before](crate::observer::Observer::before).
+/// Otherwise, observers with a higher [priority](crate::observer::Observer::with_priority) run
+/// first, and observers with the same priority run in the order they were registered in.
+///
+/// Registering and unregistering observers is O(1). The run order is computed when the map is
+/// first iterated after a change.
+#[derive(Debug, Default, Clone)]
+pub struct ObserverMap {
+    observers: EntityHashMap<RegisteredObserver>,
+    registrations: u64,
+    sorted: OnceLock<Vec<(Entity, CachedObserver)>>,
+}
+
+#[derive(Debug, Clone)]
+struct RegisteredObserver {
+    observer: CachedObserver,
+    registration: u64,
+    before: Vec<Entity>,
+    after: Vec<Entity>,
+}
+
+impl ObserverMap {
+    /// Returns the number of observers in this map.
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+
+    /// Returns `true` if this map contains no observers.
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    /// Returns `true` if the observer `entity` is in this map.
+    pub fn contains_key(&self, entity: &Entity) -> bool {
+        self.observers.contains_key(entity)
+    }
+
+    /// Returns the [`CachedObserver`] of the observer `entity`, if it is in this map.
+    pub fn get(&self, entity: &Entity) -> Option<&CachedObserver> {
+        self.observers
+            .get(entity)
+            .map(|registered| &registered.observer)
+    }
+
+    /// Returns the observers in this map, in the order they run in.
+    pub fn iter(&self) -> core::slice::Iter<'_, (Entity, CachedObserver)> {
+        self.sorted.get_or_init(|| self.sort()).iter()
+    }
+
+    fn insert(
+        &mut self,
+        entity: Entity,
+        observer: CachedObserver,
+        before: &[Entity],
+        after: &[Entity],
+    ) {
+        self.observers.insert(
+            entity,
+            RegisteredObserver {
+                observer,
+                registration: self.registrations,
+                before: before.to_vec(),
+                after: after.to_vec(),
+            },
+        );
+        self.registrations += 1;
+        self.sorted = OnceLock::new();
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<CachedObserver> {
+        let removed = self.observers.remove(&entity)?;
+        self.sorted = OnceLock::new();
+        Some(removed.observer)
+    }
+
+    /// Returns the observers in the order they run in.
+    fn sort(&self) -> Vec<(Entity, CachedObserver)> {
+        // Higher priorities run first, observers with equal priority run in registration order.
+        let key = |registered: &RegisteredObserver| {
+            (
+                Reverse(registered.observer.priority),
+                registered.registration,
+            )
+        };
+        let mut sorted = self.observers.iter().collect::<Vec<_>>();
+        sorted.sort_unstable_by_key(|(_, registered)| key(registered));
+        if self
+            .observers
+            .values()
+            .all(|registered| registered.before.is_empty() && registered.after.is_empty())
+        {
+            return sorted
+                .into_iter()
+                .map(|(&entity, registered)| (entity, registered.observer))
+                .collect();
+        }
+
+        // Topologically sort the ordering constraints, picking the observer that would run first
+        // without them whenever there is a choice.
+        let mut successors = EntityHashMap::<Vec<Entity>>::default();
+        let mut predecessors = EntityHashMap::<usize>::default();
+        for (&entity, registered) in &self.observers {
+            let edges = registered
+                .after
+                .iter()
+                .map(|&other| (other, entity))
+                .chain(registered.before.iter().map(|&other| (entity, other)));
+            for (first, then) in edges {
+                if first != then
+                    && self.observers.contains_key(&first)
+                    && self.observers.contains_key(&then)
+                {
+                    successors.entry(first).or_default().push(then);
+                    *predecessors.entry(then).or_default() += 1;
+                }
+            }
+        }
+        let mut ready = sorted
+            .iter()
+            .filter(|(entity, _)| !predecessors.contains_key(*entity))
+            .map(|&(&entity, registered)| Reverse((key(registered), entity)))
+            .collect::<BinaryHeap<_>>();
+        let mut result = Vec::with_capacity(self.observers.len());
+        while result.len() < self.observers.len() {
+            let Some(Reverse((_, entity))) = ready.pop() else {
+                // The remaining observers form a cycle, so break it at the observer that would run
+                // first without ordering constraints.
+                let (&entity, registered) = *sorted
+                    .iter()
+                    .find(|(entity, _)| predecessors.contains_key(*entity))
+                    .unwrap();
+                predecessors.remove(&entity);
+                ready.push(Reverse((key(registered), entity)));
+                continue;
+            };
+            result.push((entity, self.observers[&entity].observer));
+            for &then in successors.get(&entity).into_iter().flatten() {
+                if let Some(count) = predecessors.get_mut(&then) {
+                    *count -= 1;
+                    if *count == 0 {
+                        predecessors.remove(&then);
+                        ready.push(Reverse((key(&self.observers[&then]), then)));
+                    }
+                }
+            }
+        }
+        result
+    }
 }
 
-/// Map between an observer entity and its [`ObserverRunner`]
-pub type ObserverMap = EntityHashMap<ObserverRunner>;
+impl<'a> IntoIterator for &'a ObserverMap {
+    type Item = &'a (Entity, CachedObserver);
+    type IntoIter = core::slice::Iter<'a, (Entity, CachedObserver)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An [`ObserverRunner`] stored in an [`ObserverMap`], along with how it is ordered.
+#[derive(Clone, Copy, Debug)]
+pub struct CachedObserver {
+    /// The function that runs the observer.
+    pub runner: ObserverRunner,
+    /// The priority of the observer. See [`Observer::with_priority`](crate::observer::Observer::with_priority).
+    pub priority: i32,
+    /// Whether the observer runs during the capture phase of propagating events, instead of the bubble phase.
+    /// See [`Observer::in_capture_phase`](crate::observer::Observer::in_capture_phase).
+    pub capture: bool,
+}
 
 /// Collection of [`ObserverRunner`] for [`Observer`](crate::observer::Observer) registered to a particular event targeted at a specific component.
 ///
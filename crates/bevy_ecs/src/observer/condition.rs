@@ -8,7 +8,9 @@ use core::marker::PhantomData;
 
 use crate::{
     bundle::Bundle,
+    entity::Entity,
     event::Event,
+    observer::Observer,
     schedule::{BoxedCondition, SystemCondition},
     system::{IntoObserverSystem, IntoSystem},
     world::{unsafe_world_cell::UnsafeWorldCell, World},
@@ -55,6 +57,10 @@ pub struct ObserverWithConditionMarker;
 pub struct ObserverWithCondition<E: Event, B: Bundle, M, S: IntoObserverSystem<E, B, M>> {
     pub(crate) system: S,
     pub(crate) conditions: Vec<BoxedCondition>,
+    pub(crate) priority: i32,
+    pub(crate) capture: bool,
+    pub(crate) before: Vec<Entity>,
+    pub(crate) after: Vec<Entity>,
     pub(crate) _marker: PhantomData<fn() -> (E, B, M)>,
 }
 
@@ -97,12 +103,53 @@ impl<E: Event, B: Bundle, M, S: IntoObserverSystem<E, B, M>> ObserverWithConditi
         self
     }
 
-    pub(crate) fn take_conditions(self) -> (S, Vec<ObserverCondition>) {
-        let conditions = self
+    /// Sets the priority of this observer. See [`Observer::with_priority`].
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Makes this observer run before the `observer` entity. See [`Observer::before`].
+    pub fn before(mut self, observer: Entity) -> Self {
+        self.before.push(observer);
+        self
+    }
+
+    /// Makes this observer run after the `observer` entity. See [`Observer::after`].
+    pub fn after(mut self, observer: Entity) -> Self {
+        self.after.push(observer);
+        self
+    }
+
+    /// Makes this observer run during the capture phase of propagating events.
+    /// See [`Observer::in_capture_phase`].
+    pub fn in_capture_phase(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    pub(crate) fn new(system: S) -> Self {
+        Self {
+            system,
+            conditions: Vec::new(),
+            priority: 0,
+            capture: false,
+            before: Vec::new(),
+            after: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn into_observer(self) -> Observer {
+        let mut observer = Observer::new(self.system).with_priority(self.priority);
+        observer.capture = self.capture;
+        observer.before = self.before;
+        observer.after = self.after;
+        observer.conditions = self
             .conditions
             .into_iter()
             .map(ObserverCondition::from_boxed)
             .collect();
-        (self.system, conditions)
+        observer
     }
 }
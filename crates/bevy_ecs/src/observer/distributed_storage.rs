@@ -11,8 +11,6 @@
 
 use core::any::Any;
 
-use core::marker::PhantomData;
-
 use crate::{
     component::{ComponentCloneBehavior, ComponentId, Mutable, StorageType},
     error::{ErrorContext, ErrorHandler},
@@ -150,9 +148,41 @@ use crate::prelude::ReflectComponent;
 /// To control the relative ordering of observer trigger commands sent from different systems,
 /// order the systems in the schedule relative to each other.
 ///
-/// Currently, Bevy does not provide [a way to specify the relative ordering of observers](https://github.com/bevyengine/bevy/issues/14890)
-/// watching for the same event. Their ordering is considered to be arbitrary. It is recommended to make no
-/// assumptions about their execution order.
+/// Observers watching for the same event run in order of their [priority](Observer::with_priority), highest first.
+/// Observers with the same priority run in the order they were registered in. When an [`EntityEvent`] is triggered,
+/// "global" observers and observers watching the target entity are interleaved by priority, with global observers
+/// running first on ties.
+///
+/// Observers can also be ordered [`before`](Observer::before) or [`after`](Observer::after) other observers watching the
+/// same event and targets, which takes precedence over their priority.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # let mut world = World::new();
+/// #[derive(Event)]
+/// struct Save;
+///
+/// world.add_observer(|_: On<Save>| println!("second"));
+/// world.spawn(Observer::new(|_: On<Save>| println!("first")).with_priority(10));
+/// let third = world.add_observer(|_: On<Save>| println!("third")).id();
+/// world.add_observer((|_: On<Save>| println!("fourth")).with_priority(20).after(third));
+/// world.trigger(Save);
+/// ```
+///
+/// ## Capture and bubble phases
+///
+/// Propagating [`EntityEvent`]s (see [`PropagateEntityTrigger`](crate::event::PropagateEntityTrigger)) are triggered in
+/// up to three phases, similar to DOM events:
+///
+/// - The **capture** phase walks the [`Traversal`](crate::traversal::Traversal) top-down, from the last entity the
+///   event would propagate to down to the parent of the original target, running only observers registered with
+///   [`Observer::in_capture_phase`].
+/// - The **target** phase runs all observers watching the original target.
+/// - The **bubble** phase walks the [`Traversal`](crate::traversal::Traversal) bottom-up as long as the event is
+///   propagating, running all observers that are not capture observers.
+///
+/// Calling [`On::propagate(false)`](On::propagate) during the capture phase stops the event before it reaches the target.
+/// The capture phase is skipped entirely if no capture observers exist for the event.
 ///
 /// Commands sent by observers are [currently not immediately applied](https://github.com/bevyengine/bevy/issues/19569).
 /// Instead, all queued observers will run, and then all of the commands from those observers will be applied.
@@ -213,6 +243,10 @@ pub struct Observer {
     pub(crate) despawned_watched_entities: u32,
     pub(crate) runner: ObserverRunner,
     pub(crate) conditions: Vec<ObserverCondition>,
+    pub(crate) priority: i32,
+    pub(crate) capture: bool,
+    pub(crate) before: Vec<Entity>,
+    pub(crate) after: Vec<Entity>,
 }
 
 impl Observer {
@@ -240,6 +274,10 @@ impl Observer {
             despawned_watched_entities: 0,
             last_trigger_id: 0,
             conditions: Vec::new(),
+            priority: 0,
+            capture: false,
+            before: Vec::new(),
+            after: Vec::new(),
         }
     }
 
@@ -278,6 +316,10 @@ impl Observer {
             despawned_watched_entities: 0,
             last_trigger_id: 0,
             conditions: Vec::new(),
+            priority: 0,
+            capture: false,
+            before: Vec::new(),
+            after: Vec::new(),
         }
     }
 
@@ -352,6 +394,57 @@ impl Observer {
         self
     }
 
+    /// Sets the priority of this observer. Defaults to `0`.
+    ///
+    /// Observers watching for the same event run in order of their priority, highest first.
+    /// Observers with the same priority run in the order they were registered in.
+    /// Note that if this is called _after_ an [`Observer`] is spawned, it will produce no effects.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Makes this observer run before the `observer` entity, if both watch the same event and targets.
+    ///
+    /// This takes precedence over the [priority](Observer::with_priority) of the observers.
+    /// Cycles of ordering constraints are broken at the observer that would run first without them.
+    /// Note that if this is called _after_ an [`Observer`] is spawned, it will produce no effects.
+    pub fn before(mut self, observer: Entity) -> Self {
+        self.before.push(observer);
+        self
+    }
+
+    /// Makes this observer run after the `observer` entity, if both watch the same event and targets.
+    ///
+    /// This takes precedence over the [priority](Observer::with_priority) of the observers.
+    /// Cycles of ordering constraints are broken at the observer that would run first without them.
+    /// Note that if this is called _after_ an [`Observer`] is spawned, it will produce no effects.
+    pub fn after(mut self, observer: Entity) -> Self {
+        self.after.push(observer);
+        self
+    }
+
+    /// Makes this observer run during the capture phase of propagating [`EntityEvent`]s,
+    /// before the event reaches its original target, instead of during the bubble phase.
+    ///
+    /// For events that don't propagate, capture observers run like any other observer.
+    /// See the [`Observer`] docs on capture and bubble phases for more information.
+    /// Note that if this is called _after_ an [`Observer`] is spawned, it will produce no effects.
+    pub fn in_capture_phase(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// Returns the priority of this observer. See [`Observer::with_priority`].
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns `true` if this observer runs during the capture phase. See [`Observer::in_capture_phase`].
+    pub fn is_capture(&self) -> bool {
+        self.capture
+    }
+
     /// Returns the [`ObserverDescriptor`] for this [`Observer`].
     pub fn descriptor(&self) -> &ObserverDescriptor {
         &self.descriptor
@@ -579,10 +672,7 @@ impl<E: Event, B: Bundle, M: 'static, S: IntoObserverSystem<E, B, M>>
     IntoObserver<ObserverWithConditionMarker> for ObserverWithCondition<E, B, M, S>
 {
     fn into_observer(self) -> Observer {
-        let (system, conditions) = self.take_conditions();
-        let mut observer = Observer::new(system);
-        observer.conditions = conditions;
-        observer
+        self.into_observer()
     }
 }
 
@@ -610,10 +700,7 @@ impl<E: EntityEvent, B: Bundle, M: 'static, S: IntoObserverSystem<E, B, M>>
     IntoEntityObserver<ObserverWithConditionMarker> for ObserverWithCondition<E, B, M, S>
 {
     fn into_observer_for_entity(self, entity: Entity) -> Observer {
-        let (system, conditions) = self.take_conditions();
-        let mut observer = Observer::new(system);
-        observer.conditions = conditions;
-        observer.with_entity(entity)
+        self.into_observer().with_entity(entity)
     }
 }
 
@@ -627,11 +714,28 @@ pub trait ObserverSystemExt<E: Event, B: Bundle, M>: IntoObserverSystem<E, B, M>
     where
         C: SystemCondition<CM>,
     {
-        ObserverWithCondition {
-            system: self,
-            conditions: alloc::vec![Box::new(IntoSystem::into_system(condition))],
-            _marker: PhantomData,
-        }
+        ObserverWithCondition::new(self).run_if(condition)
+    }
+
+    /// Sets the priority of this observer system. See [`Observer::with_priority`].
+    fn with_priority(self, priority: i32) -> ObserverWithCondition<E, B, M, Self> {
+        ObserverWithCondition::new(self).with_priority(priority)
+    }
+
+    /// Makes this observer system run before the `observer` entity. See [`Observer::before`].
+    fn before(self, observer: Entity) -> ObserverWithCondition<E, B, M, Self> {
+        ObserverWithCondition::new(self).before(observer)
+    }
+
+    /// Makes this observer system run after the `observer` entity. See [`Observer::after`].
+    fn after(self, observer: Entity) -> ObserverWithCondition<E, B, M, Self> {
+        ObserverWithCondition::new(self).after(observer)
+    }
+
+    /// Makes this observer system run during the capture phase of propagating events.
+    /// See [`Observer::in_capture_phase`].
+    fn in_capture_phase(self) -> ObserverWithCondition<E, B, M, Self> {
+        ObserverWithCondition::new(self).in_capture_phase()
     }
 }
 
@@ -125,19 +125,36 @@ impl World {
             (&*observer_state, &mut self.archetypes, &mut self.observers)
         };
         let descriptor = &observer_state.descriptor;
+        let cached_observer = CachedObserver {
+            runner: observer_state.runner,
+            priority: observer_state.priority,
+            capture: observer_state.capture,
+        };
 
         for &event_key in &descriptor.event_keys {
             let cache = observers.get_observers_mut(event_key);
 
             if descriptor.components.is_empty() && descriptor.entities.is_empty() {
-                cache
-                    .global_observers
-                    .insert(observer_entity, observer_state.runner);
+                CachedObservers::insert_observer(
+                    &mut cache.capture_observers,
+                    &mut cache.global_observers,
+                    observer_entity,
+                    cached_observer,
+                    &observer_state.before,
+                    &observer_state.after,
+                );
             } else if descriptor.components.is_empty() {
                 // Observer is not targeting any components so register it as an entity observer
                 for &watched_entity in &observer_state.descriptor.entities {
                     let map = cache.entity_observers.entry(watched_entity).or_default();
-                    map.insert(observer_entity, observer_state.runner);
+                    CachedObservers::insert_observer(
+                        &mut cache.capture_observers,
+                        map,
+                        observer_entity,
+                        cached_observer,
+                        &observer_state.before,
+                        &observer_state.after,
+                    );
                 }
             } else {
                 // Register observer for each watched component
@@ -154,9 +171,14 @@ impl World {
                             });
                     if descriptor.entities.is_empty() {
                         // Register for all triggers targeting the component
-                        observers
-                            .global_observers
-                            .insert(observer_entity, observer_state.runner);
+                        CachedObservers::insert_observer(
+                            &mut cache.capture_observers,
+                            &mut observers.global_observers,
+                            observer_entity,
+                            cached_observer,
+                            &observer_state.before,
+                            &observer_state.after,
+                        );
                     } else {
                         // Register for each watched entity
                         for &watched_entity in &descriptor.entities {
@@ -164,7 +186,14 @@ impl World {
                                 .entity_component_observers
                                 .entry(watched_entity)
                                 .or_default();
-                            map.insert(observer_entity, observer_state.runner);
+                            CachedObservers::insert_observer(
+                                &mut cache.capture_observers,
+                                map,
+                                observer_entity,
+                                cached_observer,
+                                &observer_state.before,
+                                &observer_state.after,
+                            );
                         }
                     }
                 }
@@ -180,14 +209,22 @@ impl World {
         for &event_key in &descriptor.event_keys {
             let cache = observers.get_observers_mut(event_key);
             if descriptor.components.is_empty() && descriptor.entities.is_empty() {
-                cache.global_observers.remove(&entity);
+                CachedObservers::remove_observer(
+                    &mut cache.capture_observers,
+                    &mut cache.global_observers,
+                    entity,
+                );
             } else if descriptor.components.is_empty() {
                 for watched_entity in &descriptor.entities {
                     // This check should be unnecessary since this observer hasn't been unregistered yet
                     let Some(observers) = cache.entity_observers.get_mut(watched_entity) else {
                         continue;
                     };
-                    observers.remove(&entity);
+                    CachedObservers::remove_observer(
+                        &mut cache.capture_observers,
+                        observers,
+                        entity,
+                    );
                     if observers.is_empty() {
                         cache.entity_observers.remove(watched_entity);
                     }
@@ -198,7 +235,11 @@ impl World {
                         continue;
                     };
                     if descriptor.entities.is_empty() {
-                        observers.global_observers.remove(&entity);
+                        CachedObservers::remove_observer(
+                            &mut cache.capture_observers,
+                            &mut observers.global_observers,
+                            entity,
+                        );
                     } else {
                         for watched_entity in &descriptor.entities {
                             let Some(map) =
@@ -206,7 +247,11 @@ impl World {
                             else {
                                 continue;
                             };
-                            map.remove(&entity);
+                            CachedObservers::remove_observer(
+                                &mut cache.capture_observers,
+                                map,
+                                entity,
+                            );
                             if map.is_empty() {
                                 observers.entity_component_observers.remove(watched_entity);
                             }
@@ -251,7 +296,7 @@ mod tests {
         archetype::{Archetype, ArchetypeId},
         change_detection::MaybeLocation,
         error::Result,
-        event::{EntityComponentsTrigger, Event, GlobalTrigger},
+        event::{EntityComponentsTrigger, Event, GlobalTrigger, PropagationPhase},
         hierarchy::ChildOf,
         observer::{Discard, Observer},
         prelude::*,
@@ -440,7 +485,7 @@ mod tests {
         world.add_observer(|_: On<Add, A>, mut res: ResMut<Order>| res.observed("add_2"));
 
         world.spawn(A).flush();
-        assert_eq!(vec!["add_2", "add_1"], world.resource::<Order>().0);
+        assert_eq!(vec!["add_1", "add_2"], world.resource::<Order>().0);
         // we have one A entity and two observers
         assert_eq!(world.query::<&A>().query(&world).count(), 1);
         assert_eq!(world.query::<&Observer>().query(&world).count(), 2);
@@ -816,6 +861,176 @@ mod tests {
         assert_eq!(vec!["child"], world.resource::<Order>().0);
     }
 
+    #[test]
+    fn observer_priority() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+
+        world.add_observer(|_: On<EventA>, mut res: ResMut<Order>| res.observed("default_1"));
+        let low = world
+            .add_observer(
+                (|_: On<EventA>, mut res: ResMut<Order>| res.observed("low")).with_priority(-1),
+            )
+            .id();
+        world.add_observer(
+            (|_: On<EventA>, mut res: ResMut<Order>| res.observed("high")).with_priority(10),
+        );
+        world.add_observer(|_: On<EventA>, mut res: ResMut<Order>| res.observed("default_2"));
+
+        world.trigger(EventA);
+        assert_eq!(
+            vec!["high", "default_1", "default_2", "low"],
+            world.resource::<Order>().0
+        );
+
+        world.resource_mut::<Order>().0.clear();
+        world.despawn(low);
+        world.add_observer(|_: On<EventA>, mut res: ResMut<Order>| res.observed("default_3"));
+        world.trigger(EventA);
+        assert_eq!(
+            vec!["high", "default_1", "default_2", "default_3"],
+            world.resource::<Order>().0
+        );
+    }
+
+    #[test]
+    fn observer_ordering_constraints() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+
+        let a = world
+            .add_observer(|_: On<EventA>, mut res: ResMut<Order>| res.observed("a"))
+            .id();
+        // Ordering constraints take precedence over priority.
+        let b = world
+            .add_observer(
+                (|_: On<EventA>, mut res: ResMut<Order>| res.observed("b"))
+                    .with_priority(10)
+                    .after(a),
+            )
+            .id();
+        // Constraints can refer to observers that are registered later.
+        let d = world.spawn_empty().id();
+        world.add_observer((|_: On<EventA>, mut res: ResMut<Order>| res.observed("c")).before(d));
+        world.entity_mut(d).insert(
+            Observer::new(|_: On<EventA>, mut res: ResMut<Order>| res.observed("d"))
+                .with_priority(5),
+        );
+
+        world.trigger(EventA);
+        assert_eq!(vec!["a", "b", "c", "d"], world.resource::<Order>().0);
+
+        // Cycles are broken at the observer that would run first without constraints.
+        world.resource_mut::<Order>().0.clear();
+        world.add_observer(
+            (|_: On<EventA>, mut res: ResMut<Order>| res.observed("e"))
+                .before(a)
+                .after(b),
+        );
+        world.trigger(EventA);
+        assert_eq!(vec!["c", "d", "b", "e", "a"], world.resource::<Order>().0);
+    }
+
+    #[test]
+    fn observer_priority_interleaves_global_and_entity() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+
+        let entity = world
+            .spawn_empty()
+            .observe(
+                (|_: On<EntityEventA>, mut res: ResMut<Order>| res.observed("entity_high"))
+                    .with_priority(1),
+            )
+            .observe(|_: On<EntityEventA>, mut res: ResMut<Order>| res.observed("entity"))
+            .id();
+        world.add_observer(|_: On<EntityEventA>, mut res: ResMut<Order>| res.observed("global"));
+
+        world.trigger(EntityEventA(entity));
+        assert_eq!(
+            vec!["entity_high", "global", "entity"],
+            world.resource::<Order>().0
+        );
+    }
+
+    #[test]
+    fn observer_propagating_capture() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+
+        let grandparent = world.spawn_empty().id();
+        let parent = world.spawn(ChildOf(grandparent)).id();
+        let child = world.spawn(ChildOf(parent)).id();
+
+        for (entity, capture, bubble) in [
+            (grandparent, "grandparent_capture", "grandparent"),
+            (parent, "parent_capture", "parent"),
+            (child, "child_capture", "child"),
+        ] {
+            world
+                .entity_mut(entity)
+                .observe(
+                    (move |event: On<EventPropagating>, mut res: ResMut<Order>| {
+                        assert_eq!(event.event_target(), entity);
+                        assert_eq!(event.original_event_target(), child);
+                        if entity != child {
+                            assert_eq!(event.propagation_phase(), PropagationPhase::Capture);
+                        }
+                        res.observed(capture);
+                    })
+                    .in_capture_phase(),
+                )
+                .observe(move |event: On<EventPropagating>, mut res: ResMut<Order>| {
+                    if entity != child {
+                        assert_eq!(event.propagation_phase(), PropagationPhase::Bubble);
+                    }
+                    res.observed(bubble);
+                });
+        }
+
+        world.trigger(EventPropagating(child));
+        assert_eq!(
+            vec![
+                "grandparent_capture",
+                "parent_capture",
+                "child_capture",
+                "child",
+                "parent",
+                "grandparent"
+            ],
+            world.resource::<Order>().0
+        );
+    }
+
+    #[test]
+    fn observer_propagating_capture_halt() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+
+        let parent = world
+            .spawn_empty()
+            .observe(
+                (|mut event: On<EventPropagating>, mut res: ResMut<Order>| {
+                    res.observed("parent_capture");
+                    event.propagate(false);
+                })
+                .in_capture_phase(),
+            )
+            .observe(|_: On<EventPropagating>, mut res: ResMut<Order>| {
+                res.observed("parent");
+            })
+            .id();
+        let child = world
+            .spawn(ChildOf(parent))
+            .observe(|_: On<EventPropagating>, mut res: ResMut<Order>| {
+                res.observed("child");
+            })
+            .id();
+
+        world.trigger(EventPropagating(child));
+        assert_eq!(vec!["parent_capture"], world.resource::<Order>().0);
+    }
+
     #[test]
     fn observer_propagating_join() {
         let mut world = World::new();
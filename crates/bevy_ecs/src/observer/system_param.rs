@@ -3,7 +3,7 @@
 use crate::{
     bundle::Bundle,
     change_detection::MaybeLocation,
-    event::{Event, EventKey, PropagateEntityTrigger},
+    event::{Event, EventKey, PropagateEntityTrigger, PropagationPhase},
     prelude::*,
     traversal::Traversal,
 };
@@ -151,12 +151,19 @@ impl<
     /// You can prevent an event from propagating further using `propagate(false)`. This will prevent the event from triggering on the next
     /// [`Entity`] in the [`Traversal`], but note that all remaining observers for the _current_ entity will still run.
     ///
+    /// During the capture phase, `propagate(false)` stops the event before it reaches its original target.
+    /// See [`Observer::in_capture_phase`](crate::observer::Observer::in_capture_phase).
     ///
     /// [`Traversal`]: crate::traversal::Traversal
     pub fn propagate(&mut self, should_propagate: bool) {
         self.trigger.propagate = should_propagate;
     }
 
+    /// Returns the [`PropagationPhase`] the event is currently in.
+    pub fn propagation_phase(&self) -> PropagationPhase {
+        self.trigger.phase
+    }
+
     /// Returns the value of the flag that controls event propagation. See [`propagate`] for more information.
     ///
     /// [`propagate`]: On::propagate
@@ -0,0 +1,1127 @@
+use crate::{
+    bundle::Bundle,
+    component::ComponentId,
+    entity::{Entity, EntityHashMap, SceneEntityMapper},
+    reflect::{clone_reflected, AppTypeRegistry, ReflectComponent},
+    relationship::RelationshipHookMode,
+    resource::Resource,
+    world::{EntityWorldMut, World},
+};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+use bevy_reflect::{PartialReflect, TypeRegistry};
+
+/// A command recorded into a [`CommandRecording`], in a form that can be serialized and replayed.
+///
+/// Components are stored as reflected values, so replaying them requires the component types to be
+/// registered with `#[reflect(Component)]` in the type registry of the target [`World`].
+#[derive(Debug)]
+pub enum RecordedCommand {
+    /// An empty entity was spawned.
+    Spawn {
+        /// The spawned entity.
+        entity: Entity,
+    },
+    /// An entity was despawned.
+    Despawn {
+        /// The despawned entity.
+        entity: Entity,
+    },
+    /// A component was inserted into an entity.
+    Insert {
+        /// The entity the component was inserted into.
+        entity: Entity,
+        /// The reflected value of the inserted component.
+        component: Box<dyn PartialReflect>,
+    },
+    /// A component was removed from an entity.
+    Remove {
+        /// The entity the component was removed from.
+        entity: Entity,
+        /// The type path of the removed component.
+        type_path: Cow<'static, str>,
+    },
+}
+
+impl RecordedCommand {
+    /// Returns the entity this command was applied to.
+    pub fn entity(&self) -> Entity {
+        match self {
+            RecordedCommand::Spawn { entity }
+            | RecordedCommand::Despawn { entity }
+            | RecordedCommand::Insert { entity, .. }
+            | RecordedCommand::Remove { entity, .. } => *entity,
+        }
+    }
+
+    /// Applies this command to `world`, using `type_registry` to look up reflected components.
+    ///
+    /// Entities are translated through `entity_map`. Entities that are not in the map yet
+    /// are spawned as empty entities in `world` and added to the map, except for
+    /// [`RecordedCommand::Despawn`], which is skipped for them.
+    ///
+    /// Returns [`CommandReplayError::MissingEntity`] if the entity an [`Insert`](RecordedCommand::Insert)
+    /// or [`Remove`](RecordedCommand::Remove) maps to has been despawned from `world`.
+    pub fn replay(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entity_map: &mut EntityHashMap<Entity>,
+    ) -> Result<(), CommandReplayError> {
+        let source = self.entity();
+        if let RecordedCommand::Despawn { .. } = self {
+            // Entities that were never replayed don't exist in `world`, so there is nothing to do.
+            if let Some(target) = entity_map.remove(&source)
+                && let Ok(entity) = world.get_entity_mut(target)
+            {
+                entity.despawn();
+            }
+            return Ok(());
+        }
+        let target = *entity_map
+            .entry(source)
+            .or_insert_with(|| world.spawn_empty().id());
+        if world.get_entity(target).is_err() {
+            return Err(CommandReplayError::MissingEntity { entity: source });
+        }
+
+        match self {
+            RecordedCommand::Spawn { .. } | RecordedCommand::Despawn { .. } => {}
+            RecordedCommand::Insert { component, .. } => {
+                let type_info = component.get_represented_type_info().ok_or_else(|| {
+                    CommandReplayError::NoRepresentedType {
+                        type_path: component.reflect_type_path().into(),
+                    }
+                })?;
+                let registration = type_registry.get(type_info.type_id()).ok_or_else(|| {
+                    CommandReplayError::UnregisteredType {
+                        type_path: type_info.type_path().into(),
+                    }
+                })?;
+                let reflect_component =
+                    registration.data::<ReflectComponent>().ok_or_else(|| {
+                        CommandReplayError::UnregisteredComponent {
+                            type_path: type_info.type_path().into(),
+                        }
+                    })?;
+                SceneEntityMapper::world_scope(entity_map, world, |world, mapper| {
+                    reflect_component.apply_or_insert_mapped(
+                        &mut world.entity_mut(target),
+                        component.as_partial_reflect(),
+                        type_registry,
+                        mapper,
+                        RelationshipHookMode::Run,
+                    );
+                });
+            }
+            RecordedCommand::Remove { type_path, .. } => {
+                let registration =
+                    type_registry.get_with_type_path(type_path).ok_or_else(|| {
+                        CommandReplayError::UnregisteredType {
+                            type_path: type_path.clone().into_owned(),
+                        }
+                    })?;
+                let reflect_component =
+                    registration.data::<ReflectComponent>().ok_or_else(|| {
+                        CommandReplayError::UnregisteredComponent {
+                            type_path: type_path.clone().into_owned(),
+                        }
+                    })?;
+                reflect_component.remove(&mut world.entity_mut(target));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error that occurs when replaying a [`RecordedCommand`] into a [`World`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CommandReplayError {
+    /// A recorded component does not represent any concrete type.
+    #[error("recorded component `{type_path}` does not represent any type")]
+    NoRepresentedType {
+        /// The type path of the recorded value.
+        type_path: String,
+    },
+    /// The type of a recorded component is not registered in the type registry.
+    #[error("`{type_path}` is not registered in the type registry")]
+    UnregisteredType {
+        /// The type path of the component.
+        type_path: String,
+    },
+    /// The type of a recorded component is registered, but not with `#[reflect(Component)]`.
+    #[error("`{type_path}` is not registered with `#[reflect(Component)]`")]
+    UnregisteredComponent {
+        /// The type path of the component.
+        type_path: String,
+    },
+    /// A recorded entity was mapped to an entity that no longer exists in the replayed world.
+    #[error("recorded entity {entity} no longer exists in the replayed world")]
+    MissingEntity {
+        /// The recorded entity.
+        entity: Entity,
+    },
+}
+
+/// A log of [`RecordedCommand`]s that can be replayed into another [`World`].
+///
+/// When this resource exists in a [`World`], the structural changes made by the built-in entity
+/// commands are appended to it, in the order they are applied:
+///
+/// - [`Commands::spawn`], [`Commands::spawn_empty`] and [`Commands::spawn_batch`] record a
+///   [`Spawn`](RecordedCommand::Spawn), followed by an [`Insert`](RecordedCommand::Insert) for
+///   each component of the bundle.
+/// - The `insert` family of [`EntityCommands`] (including [`EntityCommands::insert_by_id`]) and
+///   [`Commands::insert_batch`] and its variants record an [`Insert`](RecordedCommand::Insert) for
+///   each inserted component.
+/// - The `remove` family of [`EntityCommands`] (including [`EntityCommands::remove_with_requires`],
+///   [`EntityCommands::remove_by_id`], [`EntityCommands::clear`] and [`EntityCommands::retain`])
+///   records a [`Remove`](RecordedCommand::Remove) for each removed component.
+/// - [`EntityCommands::clone_components`], [`EntityCommands::clone_with_opt_in`] and
+///   [`EntityCommands::clone_with_opt_out`] record an [`Insert`](RecordedCommand::Insert) on the
+///   target for each cloned component. [`EntityCommands::move_components`] additionally records a
+///   [`Remove`](RecordedCommand::Remove) on the source for each moved component.
+/// - [`EntityCommands::despawn`] records a [`Despawn`](RecordedCommand::Despawn).
+///
+/// Only components registered with `#[reflect(Component)]` in the [`AppTypeRegistry`] of the world
+/// are recorded. Changes made directly to the [`World`], or by custom commands and hooks, aren't.
+///
+/// [`Commands::spawn`]: crate::system::Commands::spawn
+/// [`Commands::spawn_empty`]: crate::system::Commands::spawn_empty
+/// [`Commands::spawn_batch`]: crate::system::Commands::spawn_batch
+/// [`Commands::insert_batch`]: crate::system::Commands::insert_batch
+/// [`EntityCommands`]: crate::system::EntityCommands
+/// [`EntityCommands::insert_by_id`]: crate::system::EntityCommands::insert_by_id
+/// [`EntityCommands::remove_with_requires`]: crate::system::EntityCommands::remove_with_requires
+/// [`EntityCommands::remove_by_id`]: crate::system::EntityCommands::remove_by_id
+/// [`EntityCommands::clear`]: crate::system::EntityCommands::clear
+/// [`EntityCommands::retain`]: crate::system::EntityCommands::retain
+/// [`EntityCommands::clone_components`]: crate::system::EntityCommands::clone_components
+/// [`EntityCommands::clone_with_opt_in`]: crate::system::EntityCommands::clone_with_opt_in
+/// [`EntityCommands::clone_with_opt_out`]: crate::system::EntityCommands::clone_with_opt_out
+/// [`EntityCommands::move_components`]: crate::system::EntityCommands::move_components
+/// [`EntityCommands::despawn`]: crate::system::EntityCommands::despawn
+///
+/// Take the recording out with [`CommandRecording::take`] once per frame (or whenever a batch
+/// of changes should be sent), then [`replay`](CommandRecording::replay) it into another world,
+/// or serialize it with `CommandRecordingSerializer` when the `serialize` feature is enabled.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::entity::EntityHashMap;
+/// # use bevy_ecs::reflect::CommandRecording;
+/// # use bevy_reflect::Reflect;
+/// #[derive(Component, Reflect)]
+/// #[reflect(Component)]
+/// struct Health(u32);
+///
+/// let mut server = World::new();
+/// server.init_resource::<AppTypeRegistry>();
+/// server.resource::<AppTypeRegistry>().write().register::<Health>();
+/// server.init_resource::<CommandRecording>();
+/// server.commands().spawn(Health(100));
+/// server.flush();
+/// let recording = server.resource_mut::<CommandRecording>().take();
+///
+/// let mut client = World::new();
+/// client.init_resource::<AppTypeRegistry>();
+/// client.resource::<AppTypeRegistry>().write().register::<Health>();
+/// let mut entity_map = EntityHashMap::default();
+/// recording.replay(&mut client, &mut entity_map).unwrap();
+///
+/// assert_eq!(client.query::<&Health>().single(&client).unwrap().0, 100);
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct CommandRecording {
+    commands: Vec<RecordedCommand>,
+}
+
+impl CommandRecording {
+    /// Creates a recording from a list of commands.
+    pub fn from_commands(commands: Vec<RecordedCommand>) -> Self {
+        Self { commands }
+    }
+
+    /// Returns the recorded commands, in the order they were applied.
+    pub fn commands(&self) -> &[RecordedCommand] {
+        &self.commands
+    }
+
+    /// Returns the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns `true` if no commands have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Appends a command to the recording.
+    pub fn push(&mut self, command: RecordedCommand) {
+        self.commands.push(command);
+    }
+
+    /// Takes all recorded commands out of this recording, leaving it empty.
+    pub fn take(&mut self) -> CommandRecording {
+        core::mem::take(self)
+    }
+
+    /// Replays all recorded commands into `world`, using the reflection data in its [`AppTypeRegistry`].
+    ///
+    /// See [`RecordedCommand::replay`] for how entities are mapped.
+    ///
+    /// # Panics
+    ///
+    /// If [`AppTypeRegistry`] is not present in `world`.
+    pub fn replay(
+        &self,
+        world: &mut World,
+        entity_map: &mut EntityHashMap<Entity>,
+    ) -> Result<(), CommandReplayError> {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        self.replay_with_registry(world, &registry.read(), entity_map)
+    }
+
+    /// Same as [`replay`](CommandRecording::replay), but using `type_registry` instead of
+    /// [`AppTypeRegistry`].
+    ///
+    /// Stops at the first command that fails to replay.
+    pub fn replay_with_registry(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entity_map: &mut EntityHashMap<Entity>,
+    ) -> Result<(), CommandReplayError> {
+        for command in &self.commands {
+            command.replay(world, type_registry, entity_map)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the [`AppTypeRegistry`] of `world`, if it has a [`CommandRecording`] to record into.
+fn recording_registry(world: &World) -> Option<AppTypeRegistry> {
+    if !world.contains_resource::<CommandRecording>() {
+        return None;
+    }
+    world.get_resource::<AppTypeRegistry>().cloned()
+}
+
+/// Appends `commands` to the [`CommandRecording`] of the world of `entity`, if there is one.
+pub(crate) fn record(entity: &mut EntityWorldMut, commands: Vec<RecordedCommand>) {
+    if commands.is_empty() {
+        return;
+    }
+    entity.world_scope(|world| {
+        if let Some(mut recording) = world.get_resource_mut::<CommandRecording>() {
+            recording.commands.extend(commands);
+        }
+    });
+}
+
+/// Records a [`RecordedCommand::Spawn`] for `entity`, followed by a [`RecordedCommand::Insert`] for
+/// each component of `B`, if `world` has a [`CommandRecording`].
+pub(crate) fn record_spawn<B: Bundle>(world: &mut World, entity: Entity) {
+    if let Some(mut recording) = world.get_resource_mut::<CommandRecording>() {
+        recording.push(RecordedCommand::Spawn { entity });
+        if let Ok(mut entity) = world.get_entity_mut(entity) {
+            record_insert::<B>(&mut entity);
+        }
+    }
+}
+
+/// Records a [`RecordedCommand::Insert`] for each component of `B` on `entity`,
+/// if the world has a [`CommandRecording`].
+///
+/// Components that aren't registered with `#[reflect(Component)]` in the [`AppTypeRegistry`]
+/// can't be replayed, and are skipped.
+pub(crate) fn record_insert<B: Bundle>(entity: &mut EntityWorldMut) {
+    let ids: Vec<_> = B::get_component_ids(entity.world().components())
+        .flatten()
+        .collect();
+    record_insert_ids(entity, ids);
+}
+
+/// Records a [`RecordedCommand::Insert`] for each of the components `ids` on `entity`,
+/// if the world has a [`CommandRecording`].
+///
+/// Like in [`record_insert`], components that aren't registered are skipped.
+pub(crate) fn record_insert_ids(entity: &mut EntityWorldMut, ids: Vec<ComponentId>) {
+    if entity.is_despawned() {
+        return;
+    }
+    let Some(registry) = recording_registry(entity.world()) else {
+        return;
+    };
+    let commands = {
+        let registry = registry.read();
+        let world = entity.world();
+        let entity_ref = entity.as_readonly();
+        ids.into_iter()
+            .filter_map(|id| reflect_component(world, &registry, id))
+            .filter_map(|reflect_component| reflect_component.reflect(entity_ref))
+            .map(|component| RecordedCommand::Insert {
+                entity: entity_ref.id(),
+                component: clone_reflected(component.as_partial_reflect()),
+            })
+            .collect()
+    };
+    record(entity, commands);
+}
+
+/// Returns a [`RecordedCommand::Remove`] for each component of `B` on `entity`, to [`record`]
+/// once they are removed, if the world has a [`CommandRecording`].
+///
+/// Like in [`record_insert`], components that aren't registered are skipped.
+pub(crate) fn removals_to_record<B: Bundle>(entity: &EntityWorldMut) -> Vec<RecordedCommand> {
+    let world = entity.world();
+    let Some(registry) = recording_registry(world) else {
+        return Vec::new();
+    };
+    let registry = registry.read();
+    B::get_component_ids(world.components())
+        .flatten()
+        .filter(|&id| entity.contains_id(id))
+        .filter_map(|id| removal(world, &registry, entity.id(), id))
+        .collect()
+}
+
+/// Returns the components of `entity`, to pass to [`record_removed`] once some of them may have
+/// been removed, if the world has a [`CommandRecording`].
+pub(crate) fn components_to_record(entity: &EntityWorldMut) -> Vec<ComponentId> {
+    if entity.is_despawned() || recording_registry(entity.world()).is_none() {
+        return Vec::new();
+    }
+    entity.archetype().components().to_vec()
+}
+
+/// Records a [`RecordedCommand::Remove`] for each of the components `before` that `entity` no
+/// longer has, if the world has a [`CommandRecording`].
+///
+/// Like in [`record_insert`], components that aren't registered are skipped.
+pub(crate) fn record_removed(entity: &mut EntityWorldMut, before: Vec<ComponentId>) {
+    if before.is_empty() || entity.is_despawned() {
+        return;
+    }
+    let Some(registry) = recording_registry(entity.world()) else {
+        return;
+    };
+    let commands = {
+        let registry = registry.read();
+        before
+            .into_iter()
+            .filter(|&id| !entity.contains_id(id))
+            .filter_map(|id| removal(entity.world(), &registry, entity.id(), id))
+            .collect()
+    };
+    record(entity, commands);
+}
+
+/// Records a [`RecordedCommand::Insert`] for each of the components `source` had that `target`
+/// has after cloning them, if the world has a [`CommandRecording`].
+pub(crate) fn record_cloned(entity: &mut EntityWorldMut, target: Entity, source: Vec<ComponentId>) {
+    if source.is_empty() {
+        return;
+    }
+    entity.world_scope(|world| {
+        if let Ok(mut target) = world.get_entity_mut(target) {
+            let ids = source
+                .into_iter()
+                .filter(|&id| target.contains_id(id))
+                .collect();
+            record_insert_ids(&mut target, ids);
+        }
+    });
+}
+
+/// Returns a [`RecordedCommand::Remove`] of the component with the given `id` from `entity`,
+/// if the component is registered.
+fn removal(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    id: ComponentId,
+) -> Option<RecordedCommand> {
+    let type_id = world.components().get_info(id)?.type_id()?;
+    let registration = registry.get(type_id)?;
+    registration.data::<ReflectComponent>()?;
+    Some(RecordedCommand::Remove {
+        entity,
+        type_path: Cow::Borrowed(registration.type_info().type_path()),
+    })
+}
+
+/// Returns `true` if `world` has a [`CommandRecording`] to record into.
+pub(crate) fn is_recording(world: &World) -> bool {
+    world.contains_resource::<CommandRecording>()
+}
+
+/// Records a [`RecordedCommand::Despawn`] for `entity`, if the world has a [`CommandRecording`].
+pub(crate) fn record_despawn(mut entity: EntityWorldMut) -> EntityWorldMut {
+    if !entity.world().contains_resource::<CommandRecording>() {
+        return entity;
+    }
+    let id = entity.id();
+    entity.world_scope(|world| {
+        if let Some(mut recording) = world.get_resource_mut::<CommandRecording>() {
+            recording.push(RecordedCommand::Despawn { entity: id });
+        }
+    });
+    entity
+}
+
+/// Returns the [`ReflectComponent`] of the component with the given `id`, if it is registered.
+fn reflect_component<'a>(
+    world: &World,
+    registry: &'a TypeRegistry,
+    id: ComponentId,
+) -> Option<&'a ReflectComponent> {
+    let type_id = world.components().get_info(id)?.type_id()?;
+    registry.get_type_data::<ReflectComponent>(type_id)
+}
+
+#[cfg(feature = "serialize")]
+pub use serde_impls::{CommandRecordingDeserializer, CommandRecordingSerializer};
+
+#[cfg(feature = "serialize")]
+mod serde_impls {
+    use super::{CommandRecording, RecordedCommand};
+    use crate::entity::Entity;
+    use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+    use bevy_reflect::{
+        serde::{ReflectDeserializer, ReflectSerializer},
+        PartialReflect, TypeRegistry,
+    };
+    use core::fmt;
+    use serde::{
+        de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor},
+        ser::{SerializeSeq, SerializeStructVariant},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    const RECORDED_COMMAND: &str = "RecordedCommand";
+    const VARIANTS: &[&str] = &["Spawn", "Despawn", "Insert", "Remove"];
+    const INSERT_FIELDS: &[&str] = &["entity", "component"];
+    const REMOVE_FIELDS: &[&str] = &["entity", "type_path"];
+
+    /// Serializes a [`CommandRecording`], using `registry` to serialize the reflected components.
+    pub struct CommandRecordingSerializer<'a> {
+        /// The recording to serialize.
+        pub recording: &'a CommandRecording,
+        /// The type registry used to serialize the reflected components.
+        pub registry: &'a TypeRegistry,
+    }
+
+    impl<'a> CommandRecordingSerializer<'a> {
+        /// Creates a new [`CommandRecordingSerializer`].
+        pub fn new(recording: &'a CommandRecording, registry: &'a TypeRegistry) -> Self {
+            Self {
+                recording,
+                registry,
+            }
+        }
+    }
+
+    impl Serialize for CommandRecordingSerializer<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.recording.len()))?;
+            for command in self.recording.commands() {
+                seq.serialize_element(&RecordedCommandSerializer {
+                    command,
+                    registry: self.registry,
+                })?;
+            }
+            seq.end()
+        }
+    }
+
+    struct RecordedCommandSerializer<'a> {
+        command: &'a RecordedCommand,
+        registry: &'a TypeRegistry,
+    }
+
+    impl Serialize for RecordedCommandSerializer<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.command {
+                RecordedCommand::Spawn { entity } => {
+                    serializer.serialize_newtype_variant(RECORDED_COMMAND, 0, VARIANTS[0], entity)
+                }
+                RecordedCommand::Despawn { entity } => {
+                    serializer.serialize_newtype_variant(RECORDED_COMMAND, 1, VARIANTS[1], entity)
+                }
+                RecordedCommand::Insert { entity, component } => {
+                    let mut state =
+                        serializer.serialize_struct_variant(RECORDED_COMMAND, 2, VARIANTS[2], 2)?;
+                    state.serialize_field(INSERT_FIELDS[0], entity)?;
+                    state.serialize_field(
+                        INSERT_FIELDS[1],
+                        &ReflectSerializer::new(component.as_partial_reflect(), self.registry),
+                    )?;
+                    state.end()
+                }
+                RecordedCommand::Remove { entity, type_path } => {
+                    let mut state =
+                        serializer.serialize_struct_variant(RECORDED_COMMAND, 3, VARIANTS[3], 2)?;
+                    state.serialize_field(REMOVE_FIELDS[0], entity)?;
+                    state.serialize_field(REMOVE_FIELDS[1], type_path)?;
+                    state.end()
+                }
+            }
+        }
+    }
+
+    /// Deserializes a [`CommandRecording`], using `registry` to deserialize the reflected components.
+    pub struct CommandRecordingDeserializer<'a> {
+        /// The type registry used to deserialize the reflected components.
+        pub registry: &'a TypeRegistry,
+    }
+
+    impl<'a> CommandRecordingDeserializer<'a> {
+        /// Creates a new [`CommandRecordingDeserializer`].
+        pub fn new(registry: &'a TypeRegistry) -> Self {
+            Self { registry }
+        }
+    }
+
+    impl<'de> DeserializeSeed<'de> for CommandRecordingDeserializer<'_> {
+        type Value = CommandRecording;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for CommandRecordingDeserializer<'_> {
+        type Value = CommandRecording;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence of recorded commands")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut commands = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+            while let Some(command) = seq.next_element_seed(RecordedCommandDeserializer {
+                registry: self.registry,
+            })? {
+                commands.push(command);
+            }
+            Ok(CommandRecording::from_commands(commands))
+        }
+    }
+
+    #[derive(Deserialize)]
+    enum Variant {
+        Spawn,
+        Despawn,
+        Insert,
+        Remove,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(field_identifier, rename_all = "snake_case")]
+    enum Field {
+        Entity,
+        Component,
+        TypePath,
+    }
+
+    struct RecordedCommandDeserializer<'a> {
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'de> DeserializeSeed<'de> for RecordedCommandDeserializer<'_> {
+        type Value = RecordedCommand;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_enum(RECORDED_COMMAND, VARIANTS, self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for RecordedCommandDeserializer<'_> {
+        type Value = RecordedCommand;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a recorded command")
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+            let (variant, access) = data.variant::<Variant>()?;
+            match variant {
+                Variant::Spawn => Ok(RecordedCommand::Spawn {
+                    entity: access.newtype_variant()?,
+                }),
+                Variant::Despawn => Ok(RecordedCommand::Despawn {
+                    entity: access.newtype_variant()?,
+                }),
+                Variant::Insert => access.struct_variant(
+                    INSERT_FIELDS,
+                    FieldsVisitor {
+                        registry: self.registry,
+                        component: true,
+                    },
+                ),
+                Variant::Remove => access.struct_variant(
+                    REMOVE_FIELDS,
+                    FieldsVisitor {
+                        registry: self.registry,
+                        component: false,
+                    },
+                ),
+            }
+        }
+    }
+
+    /// Visits the fields of the `Insert` (if `component` is `true`) or `Remove` variants.
+    struct FieldsVisitor<'a> {
+        registry: &'a TypeRegistry,
+        component: bool,
+    }
+
+    impl FieldsVisitor<'_> {
+        fn build<E: Error>(
+            &self,
+            entity: Option<Entity>,
+            component: Option<Box<dyn PartialReflect>>,
+            type_path: Option<String>,
+        ) -> Result<RecordedCommand, E> {
+            let entity = entity.ok_or_else(|| E::missing_field(INSERT_FIELDS[0]))?;
+            if self.component {
+                Ok(RecordedCommand::Insert {
+                    entity,
+                    component: component.ok_or_else(|| E::missing_field(INSERT_FIELDS[1]))?,
+                })
+            } else {
+                Ok(RecordedCommand::Remove {
+                    entity,
+                    type_path: Cow::Owned(
+                        type_path.ok_or_else(|| E::missing_field(REMOVE_FIELDS[1]))?,
+                    ),
+                })
+            }
+        }
+    }
+
+    impl<'de> Visitor<'de> for FieldsVisitor<'_> {
+        type Value = RecordedCommand;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("recorded command fields")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let entity = seq.next_element()?;
+            if self.component {
+                let component = seq.next_element_seed(ReflectDeserializer::new(self.registry))?;
+                self.build(entity, component, None)
+            } else {
+                let type_path = seq.next_element()?;
+                self.build(entity, None, type_path)
+            }
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entity = None;
+            let mut component = None;
+            let mut type_path = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::Entity => entity = Some(map.next_value()?),
+                    Field::Component if self.component => {
+                        component =
+                            Some(map.next_value_seed(ReflectDeserializer::new(self.registry))?);
+                    }
+                    Field::TypePath if !self.component => type_path = Some(map.next_value()?),
+                    Field::Component => {
+                        return Err(Error::unknown_field("component", REMOVE_FIELDS))
+                    }
+                    Field::TypePath => {
+                        return Err(Error::unknown_field("type_path", INSERT_FIELDS))
+                    }
+                }
+            }
+            self.build(entity, component, type_path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandRecording, CommandReplayError, RecordedCommand};
+    use crate::{
+        component::Component,
+        entity::{Entity, EntityHashMap},
+        prelude::{AppTypeRegistry, ReflectComponent},
+        system::Commands,
+        world::World,
+    };
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+    use bevy_reflect::Reflect;
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Position(i32);
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Target(#[entities] Entity);
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Default)]
+    #[reflect(Component)]
+    struct Velocity(i32);
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    #[require(Velocity)]
+    struct Body;
+
+    #[derive(Component)]
+    struct Unregistered;
+
+    fn registered_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<Position>();
+            registry.register::<Target>();
+            registry.register::<Velocity>();
+            registry.register::<Body>();
+        }
+        world
+    }
+
+    fn server_world() -> World {
+        let mut world = registered_world();
+        world.init_resource::<CommandRecording>();
+        world
+    }
+
+    #[test]
+    fn records_in_application_order() {
+        let mut world = server_world();
+
+        let mut commands = world.commands();
+        let entity = commands.spawn((Position(1), Unregistered)).id();
+        commands
+            .entity(entity)
+            .remove::<(Position, Target)>()
+            .insert(Position(2))
+            .despawn();
+        world.flush();
+
+        let recording = world.resource_mut::<CommandRecording>().take();
+        assert!(matches!(
+            recording.commands(),
+            [
+                RecordedCommand::Spawn { .. },
+                RecordedCommand::Insert { .. },
+                RecordedCommand::Remove { .. },
+                RecordedCommand::Insert { .. },
+                RecordedCommand::Despawn { .. },
+            ]
+        ));
+        assert!(world.resource::<CommandRecording>().is_empty());
+        assert!(world.get_entity(entity).is_err());
+    }
+
+    /// Applies the commands queued by `queue` to `world`, and returns what was recorded, as
+    /// `(entity, "spawn" | "despawn" | "insert Type" | "remove Type")` pairs.
+    fn record_commands(
+        world: &mut World,
+        queue: impl FnOnce(&mut Commands),
+    ) -> Vec<(Entity, String)> {
+        world.resource_mut::<CommandRecording>().take();
+        queue(&mut world.commands());
+        world.flush();
+        let recording = world.resource_mut::<CommandRecording>().take();
+        recording
+            .commands()
+            .iter()
+            .map(|command| {
+                let description = match command {
+                    RecordedCommand::Spawn { .. } => "spawn".to_string(),
+                    RecordedCommand::Despawn { .. } => "despawn".to_string(),
+                    RecordedCommand::Insert { component, .. } => {
+                        format!("insert {}", component.reflect_short_type_path())
+                    }
+                    RecordedCommand::Remove { type_path, .. } => {
+                        format!("remove {}", type_path.rsplit("::").next().unwrap())
+                    }
+                };
+                (command.entity(), description)
+            })
+            .collect()
+    }
+
+    fn sorted(mut commands: Vec<(Entity, String)>) -> Vec<(Entity, String)> {
+        commands.sort();
+        commands
+    }
+
+    #[test]
+    fn records_remove_with_requires() {
+        let mut world = server_world();
+        let entity = world.spawn((Body, Position(1))).id();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(entity).remove_with_requires::<Body>();
+        });
+        assert_eq!(
+            sorted(recorded),
+            [
+                (entity, "remove Body".to_string()),
+                (entity, "remove Velocity".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_remove_by_id() {
+        let mut world = server_world();
+        let entity = world.spawn((Position(1), Velocity(2))).id();
+        let id = world.component_id::<Position>().unwrap();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(entity).remove_by_id(id);
+        });
+        assert_eq!(recorded, [(entity, "remove Position".to_string())]);
+    }
+
+    #[test]
+    fn records_clear() {
+        let mut world = server_world();
+        let entity = world.spawn((Position(1), Velocity(2), Unregistered)).id();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(entity).clear();
+        });
+        assert_eq!(
+            sorted(recorded),
+            [
+                (entity, "remove Position".to_string()),
+                (entity, "remove Velocity".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_retain() {
+        let mut world = server_world();
+        let entity = world.spawn((Position(1), Velocity(2), Unregistered)).id();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(entity).retain::<Position>();
+        });
+        assert_eq!(recorded, [(entity, "remove Velocity".to_string())]);
+    }
+
+    #[test]
+    fn records_insert_by_id() {
+        let mut world = server_world();
+        let entity = world.spawn_empty().id();
+        let id = world.register_component::<Position>();
+        let recorded = record_commands(&mut world, |commands| {
+            // SAFETY: `id` is the id of `Position` in this world.
+            unsafe {
+                commands.entity(entity).insert_by_id(id, Position(3));
+            }
+        });
+        assert_eq!(recorded, [(entity, "insert Position".to_string())]);
+    }
+
+    #[test]
+    fn records_insert_batch() {
+        let mut world = server_world();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let missing = world.spawn_empty().id();
+        world.despawn(missing);
+        let recorded = record_commands(&mut world, |commands| {
+            commands.insert_batch(vec![(a, Position(1)), (b, Position(2))]);
+            commands.try_insert_batch_if_new(vec![(missing, Velocity(3)), (a, Velocity(4))]);
+        });
+        assert_eq!(
+            recorded,
+            [
+                (a, "insert Position".to_string()),
+                (b, "insert Position".to_string()),
+                (a, "insert Velocity".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_spawn_batch() {
+        let mut world = server_world();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.spawn_batch([(Position(1), Unregistered), (Position(2), Unregistered)]);
+        });
+        let descriptions: Vec<_> = recorded
+            .iter()
+            .map(|(_, description)| description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            ["spawn", "insert Position", "spawn", "insert Position"]
+        );
+        assert_ne!(recorded[0].0, recorded[2].0);
+    }
+
+    #[test]
+    fn records_clone_and_move_components() {
+        let mut world = server_world();
+        let source = world.spawn((Position(1), Velocity(2))).id();
+        let target = world.spawn_empty().id();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(source).clone_components::<Position>(target);
+        });
+        assert_eq!(recorded, [(target, "insert Position".to_string())]);
+
+        let other = world.spawn_empty().id();
+        let recorded = record_commands(&mut world, |commands| {
+            commands.entity(source).move_components::<Velocity>(other);
+        });
+        assert_eq!(
+            recorded,
+            [
+                (other, "insert Velocity".to_string()),
+                (source, "remove Velocity".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_is_recorded_without_resource() {
+        let mut world = registered_world();
+        let entity = world.commands().spawn(Position(1)).id();
+        world.flush();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1)));
+        assert!(!world.contains_resource::<CommandRecording>());
+    }
+
+    #[test]
+    fn replay_maps_entities() {
+        let mut server = server_world();
+        let mut commands = server.commands();
+        let a = commands.spawn(Position(1)).id();
+        let b = commands.spawn_empty().insert(Target(a)).id();
+        server.flush();
+        let recording = server.resource_mut::<CommandRecording>().take();
+
+        let mut client = registered_world();
+        // Offset entity ids so that server and client ids don't line up.
+        client.spawn_empty();
+        let mut entity_map = EntityHashMap::default();
+        recording.replay(&mut client, &mut entity_map).unwrap();
+
+        let client_a = entity_map[&a];
+        let client_b = entity_map[&b];
+        assert_eq!(client.get::<Position>(client_a), Some(&Position(1)));
+        assert_eq!(client.get::<Target>(client_b), Some(&Target(client_a)));
+
+        let mut commands = server.commands();
+        commands.entity(a).remove::<Position>();
+        commands.entity(b).despawn();
+        server.flush();
+        let recording = server.resource_mut::<CommandRecording>().take();
+        recording.replay(&mut client, &mut entity_map).unwrap();
+
+        assert!(client.get::<Position>(client_a).is_none());
+        assert!(client.get_entity(client_b).is_err());
+        assert!(!entity_map.contains_key(&b));
+    }
+
+    #[test]
+    fn replay_missing_entities() {
+        let mut server = server_world();
+        let entity = server.spawn_empty().id();
+        let mut commands = server.commands();
+        commands.entity(entity).insert(Position(1));
+        commands.entity(entity).despawn();
+        server.flush();
+        let recording = server.resource_mut::<CommandRecording>().take();
+
+        // Despawning an entity that was never replayed does nothing.
+        let mut client = registered_world();
+        let mut entity_map = EntityHashMap::default();
+        let registry = client.resource::<AppTypeRegistry>().clone();
+        let spawned = client.entities().count_spawned();
+        recording.commands()[1]
+            .replay(&mut client, &registry.read(), &mut entity_map)
+            .unwrap();
+        assert!(entity_map.is_empty());
+        assert_eq!(client.entities().count_spawned(), spawned);
+
+        // Inserting into an entity that was despawned on the client is an error.
+        let client_entity = client.spawn_empty().id();
+        entity_map.insert(entity, client_entity);
+        client.despawn(client_entity);
+        assert_eq!(
+            recording.replay(&mut client, &mut entity_map),
+            Err(CommandReplayError::MissingEntity { entity })
+        );
+    }
+
+    #[test]
+    fn replay_unregistered_component() {
+        let mut server = server_world();
+        server.commands().spawn(Position(1));
+        server.flush();
+        let recording = server.resource_mut::<CommandRecording>().take();
+
+        let mut client = World::new();
+        client.init_resource::<AppTypeRegistry>();
+        let result = recording.replay(&mut client, &mut EntityHashMap::default());
+        assert!(matches!(
+            result,
+            Err(CommandReplayError::UnregisteredType { .. })
+        ));
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serialization_roundtrip() {
+        use super::{CommandRecordingDeserializer, CommandRecordingSerializer};
+        use bevy_reflect::TypePath;
+        use serde::de::DeserializeSeed;
+
+        let mut server = server_world();
+        let mut commands = server.commands();
+        let entity = commands.spawn_empty().insert(Position(7)).id();
+        commands.entity(entity).remove::<Position>();
+        commands.entity(entity).despawn();
+        server.flush();
+        let recording = server.resource_mut::<CommandRecording>().take();
+
+        let client = registered_world();
+        let registry = client.resource::<AppTypeRegistry>().read();
+        let serialized =
+            ron::to_string(&CommandRecordingSerializer::new(&recording, &registry)).unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&serialized).unwrap();
+        let deserialized = CommandRecordingDeserializer::new(&registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+
+        assert_eq!(deserialized.len(), 4);
+        let commands = deserialized.commands();
+        assert!(matches!(commands[0], RecordedCommand::Spawn { .. }));
+        assert!(matches!(commands[3], RecordedCommand::Despawn { .. }));
+        let RecordedCommand::Insert { component, .. } = &commands[1] else {
+            panic!("expected an insert command, got {:?}", commands[1]);
+        };
+        assert!(component
+            .reflect_partial_eq(&Position(7))
+            .unwrap_or_default());
+        let RecordedCommand::Remove { type_path, .. } = &commands[2] else {
+            panic!("expected a remove command, got {:?}", commands[2]);
+        };
+        assert_eq!(type_path, Position::type_path());
+    }
+}
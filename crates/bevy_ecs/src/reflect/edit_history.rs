@@ -0,0 +1,1062 @@
+use crate::{
+    change_detection::Mut,
+    component::{Component, Mutable},
+    entity::{Entity, EntityHashMap, EntityMapper},
+    hierarchy::{ChildOf, Children},
+    reflect::{clone_reflected, AppTypeRegistry, ReflectComponent},
+    relationship::{RelationshipAccessor, RelationshipHookMode},
+    resource::Resource,
+    system::{Commands, EntityCommands},
+    world::{EntityWorldMut, World},
+};
+use alloc::{borrow::Cow, boxed::Box, collections::VecDeque, string::String, vec::Vec};
+use bevy_reflect::{PartialReflect, Reflect, TypePath, TypeRegistry};
+
+/// A single reversible change to a [`World`], recorded into an [`EditHistory`].
+///
+/// Component and resource values are stored as reflected values, so undoing and redoing edits
+/// requires their types to be registered with `#[reflect(Component)]` or `#[reflect(Resource)]`
+/// in the [`AppTypeRegistry`].
+///
+/// Entities are stored as they were when the edit was recorded. When undoing or redoing respawns
+/// an entity, the [`EditHistory`] keeps track of the new entity, so older edits keep working.
+#[derive(Debug)]
+pub enum WorldEdit {
+    /// An empty entity was spawned.
+    Spawn {
+        /// The spawned entity.
+        entity: Entity,
+    },
+    /// An entity and its descendants were despawned.
+    Despawn {
+        /// Snapshots of the despawned entities, parents before their children.
+        entities: Vec<EntitySnapshot>,
+    },
+    /// A component was inserted, modified or removed.
+    Component {
+        /// The entity the component belongs to.
+        entity: Entity,
+        /// The type path of the component.
+        type_path: Cow<'static, str>,
+        /// The value before the edit, or `None` if the component didn't exist.
+        before: Option<Box<dyn PartialReflect>>,
+        /// The value after the edit, or `None` if the component was removed.
+        after: Option<Box<dyn PartialReflect>>,
+    },
+    /// A resource was inserted, modified or removed.
+    Resource {
+        /// The type path of the resource.
+        type_path: Cow<'static, str>,
+        /// The value before the edit, or `None` if the resource didn't exist.
+        before: Option<Box<dyn PartialReflect>>,
+        /// The value after the edit, or `None` if the resource was removed.
+        after: Option<Box<dyn PartialReflect>>,
+    },
+    /// The parent of an entity was changed.
+    Reparent {
+        /// The entity that was reparented.
+        entity: Entity,
+        /// The parent before the edit.
+        before: Option<Entity>,
+        /// The parent after the edit.
+        after: Option<Entity>,
+    },
+}
+
+/// The reflected components of an entity, taken right before it was despawned.
+#[derive(Debug)]
+pub struct EntitySnapshot {
+    /// The entity the snapshot was taken of.
+    pub entity: Entity,
+    /// The reflected components of the entity.
+    ///
+    /// Components whose types are not registered with `#[reflect(Component)]`, and
+    /// [`RelationshipTarget`](crate::relationship::RelationshipTarget)s, which are rebuilt from
+    /// their relationships, are not included.
+    pub components: Vec<Box<dyn PartialReflect>>,
+}
+
+impl EntitySnapshot {
+    /// Takes a snapshot of `entity` and all of its descendants, parents before their children.
+    pub fn hierarchy(world: &World, type_registry: &TypeRegistry, entity: Entity) -> Vec<Self> {
+        let mut snapshots = Vec::new();
+        let mut stack = alloc::vec![entity];
+        while let Some(entity) = stack.pop() {
+            let Ok(entity_ref) = world.get_entity(entity) else {
+                continue;
+            };
+            let components = entity_ref
+                .archetype()
+                .components()
+                .iter()
+                .filter_map(|&component_id| {
+                    let info = world.components().get_info(component_id)?;
+                    if let Some(RelationshipAccessor::RelationshipTarget { .. }) =
+                        info.relationship_accessor()
+                    {
+                        return None;
+                    }
+                    let reflect_component =
+                        type_registry.get_type_data::<ReflectComponent>(info.type_id()?)?;
+                    let value = reflect_component.reflect(entity_ref)?;
+                    Some(clone_reflected(value.as_partial_reflect()))
+                })
+                .collect();
+            snapshots.push(EntitySnapshot { entity, components });
+            if let Some(children) = entity_ref.get::<Children>() {
+                stack.extend(children.iter().rev());
+            }
+        }
+        snapshots
+    }
+}
+
+/// A group of [`WorldEdit`]s that are undone and redone together.
+#[derive(Debug, Default)]
+pub struct EditTransaction {
+    /// A description of the transaction, for display in an editor.
+    pub label: Option<Cow<'static, str>>,
+    /// The edits in this transaction, in the order they were applied.
+    pub edits: Vec<WorldEdit>,
+}
+
+/// An error that occurs when undoing or redoing a [`WorldEdit`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EditHistoryError {
+    /// The type of a recorded value is not registered in the type registry.
+    #[error("`{type_path}` is not registered in the type registry")]
+    UnregisteredType {
+        /// The type path of the value.
+        type_path: String,
+    },
+    /// The type of a recorded value is registered, but not with `#[reflect(Component)]`
+    /// or `#[reflect(Resource)]`.
+    #[error(
+        "`{type_path}` is not registered with `#[reflect(Component)]` or `#[reflect(Resource)]`"
+    )]
+    UnregisteredComponent {
+        /// The type path of the value.
+        type_path: String,
+    },
+}
+
+/// A transaction log of reversible changes to a [`World`], with undo and redo.
+///
+/// When this resource exists, the commands in [`UndoableCommandsExt`] and
+/// [`UndoableEntityCommandsExt`] record a [`WorldEdit`] for each change they make.
+/// Edits recorded between [`EditHistory::begin_transaction`] and [`EditHistory::end_transaction`]
+/// are grouped into a single [`EditTransaction`]; other edits get a transaction of their own.
+///
+/// Use [`EditHistory::undo`] and [`EditHistory::redo`] (or the matching commands) to move
+/// through the history. Recording a new edit clears everything that could be redone.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::reflect::{EditHistory, UndoableCommandsExt, UndoableEntityCommandsExt};
+/// # use bevy_reflect::Reflect;
+/// #[derive(Component, Reflect, PartialEq, Debug)]
+/// #[reflect(Component)]
+/// struct Position(i32);
+///
+/// let mut world = World::new();
+/// world.init_resource::<AppTypeRegistry>();
+/// world.resource::<AppTypeRegistry>().write().register::<Position>();
+/// world.init_resource::<EditHistory>();
+///
+/// let entity = world.spawn(Position(0)).id();
+/// world.commands().entity(entity).insert_undoable(Position(5));
+/// world.flush();
+/// assert_eq!(world.get::<Position>(entity), Some(&Position(5)));
+///
+/// EditHistory::undo(&mut world).unwrap();
+/// assert_eq!(world.get::<Position>(entity), Some(&Position(0)));
+///
+/// EditHistory::redo(&mut world).unwrap();
+/// assert_eq!(world.get::<Position>(entity), Some(&Position(5)));
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct EditHistory {
+    undo_stack: VecDeque<EditTransaction>,
+    redo_stack: Vec<EditTransaction>,
+    open: Option<EditTransaction>,
+    entity_map: EntityRemap,
+    limit: Option<usize>,
+}
+
+impl EditHistory {
+    /// Creates an [`EditHistory`] that only keeps the `limit` most recent transactions.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    /// Starts grouping recorded edits into a single transaction with the given `label`.
+    ///
+    /// If a transaction is already open, it is ended first.
+    pub fn begin_transaction(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.end_transaction();
+        self.open = Some(EditTransaction {
+            label: Some(label.into()),
+            edits: Vec::new(),
+        });
+    }
+
+    /// Ends the transaction started with [`EditHistory::begin_transaction`].
+    ///
+    /// Empty transactions are discarded.
+    pub fn end_transaction(&mut self) {
+        if let Some(transaction) = self.open.take()
+            && !transaction.edits.is_empty()
+        {
+            self.push_transaction(transaction);
+        }
+    }
+
+    /// Records an edit that has already been applied to the world.
+    ///
+    /// This clears the redo stack.
+    pub fn push(&mut self, edit: WorldEdit) {
+        self.redo_stack.clear();
+        match &mut self.open {
+            Some(transaction) => transaction.edits.push(edit),
+            None => self.push_transaction(EditTransaction {
+                label: None,
+                edits: alloc::vec![edit],
+            }),
+        }
+    }
+
+    fn push_transaction(&mut self, transaction: EditTransaction) {
+        self.redo_stack.clear();
+        self.undo_stack.push_back(transaction);
+        if let Some(limit) = self.limit {
+            while self.undo_stack.len() > limit {
+                self.undo_stack.pop_front();
+            }
+        }
+    }
+
+    /// Returns `true` if there is a transaction that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty() || self.open.as_ref().is_some_and(|t| !t.edits.is_empty())
+    }
+
+    /// Returns `true` if there is a transaction that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Returns the transactions that can be undone, oldest first.
+    pub fn undo_stack(&self) -> impl DoubleEndedIterator<Item = &EditTransaction> {
+        self.undo_stack.iter()
+    }
+
+    /// Returns the transactions that can be redone, the next one to redo last.
+    pub fn redo_stack(&self) -> impl DoubleEndedIterator<Item = &EditTransaction> {
+        self.redo_stack.iter()
+    }
+
+    /// Removes all recorded transactions.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.open = None;
+        self.entity_map.clear();
+    }
+
+    /// Returns the entity that currently stands for `entity`, which may have been respawned
+    /// by undoing or redoing edits.
+    pub fn resolve(&self, entity: Entity) -> Entity {
+        self.entity_map.resolve(entity)
+    }
+
+    /// Reverts the most recent transaction in the [`EditHistory`] of `world`.
+    ///
+    /// Ends any open transaction first. Returns `Ok(false)` if there was nothing to undo,
+    /// or if the world has no [`EditHistory`].
+    ///
+    /// If an edit can't be reverted, the edits of the transaction that were already reverted are
+    /// applied again, so that the world is left as it was, and the transaction stays on the undo
+    /// stack. If that fails as well, the world no longer matches any point of the history, so both
+    /// the undo and redo stacks are cleared.
+    ///
+    /// # Panics
+    ///
+    /// If [`AppTypeRegistry`] is not present in `world`.
+    pub fn undo(world: &mut World) -> Result<bool, EditHistoryError> {
+        world
+            .try_resource_scope(|world, mut history: Mut<EditHistory>| {
+                history.end_transaction();
+                let Some(transaction) = history.undo_stack.pop_back() else {
+                    return Ok(false);
+                };
+                let registry = world.resource::<AppTypeRegistry>().clone();
+                let registry = registry.read();
+                let entity_map = &mut history.entity_map;
+                let mut reverted = 0;
+                let result = transaction.edits.iter().rev().try_for_each(|edit| {
+                    edit.revert(world, &registry, entity_map)?;
+                    reverted += 1;
+                    Ok(())
+                });
+                let Err(error) = result else {
+                    history.redo_stack.push(transaction);
+                    return Ok(true);
+                };
+                let first_reverted = transaction.edits.len() - reverted;
+                let rolled_back = transaction.edits[first_reverted..]
+                    .iter()
+                    .try_for_each(|edit| edit.reapply(world, &registry, entity_map));
+                if rolled_back.is_ok() {
+                    history.undo_stack.push_back(transaction);
+                } else {
+                    history.undo_stack.clear();
+                    history.redo_stack.clear();
+                }
+                Err(error)
+            })
+            .unwrap_or(Ok(false))
+    }
+
+    /// Reapplies the most recently undone transaction in the [`EditHistory`] of `world`.
+    ///
+    /// Returns `Ok(false)` if there was nothing to redo, or if the world has no [`EditHistory`].
+    ///
+    /// Like in [`EditHistory::undo`], if an edit can't be reapplied, the edits of the transaction
+    /// that were already reapplied are reverted again and the transaction stays on the redo stack,
+    /// or both stacks are cleared if that fails as well.
+    ///
+    /// # Panics
+    ///
+    /// If [`AppTypeRegistry`] is not present in `world`.
+    pub fn redo(world: &mut World) -> Result<bool, EditHistoryError> {
+        world
+            .try_resource_scope(|world, mut history: Mut<EditHistory>| {
+                let Some(transaction) = history.redo_stack.pop() else {
+                    return Ok(false);
+                };
+                let registry = world.resource::<AppTypeRegistry>().clone();
+                let registry = registry.read();
+                let entity_map = &mut history.entity_map;
+                let mut reapplied = 0;
+                let result = transaction.edits.iter().try_for_each(|edit| {
+                    edit.reapply(world, &registry, entity_map)?;
+                    reapplied += 1;
+                    Ok(())
+                });
+                let Err(error) = result else {
+                    history.undo_stack.push_back(transaction);
+                    return Ok(true);
+                };
+                let rolled_back = transaction.edits[..reapplied]
+                    .iter()
+                    .rev()
+                    .try_for_each(|edit| edit.revert(world, &registry, entity_map));
+                if rolled_back.is_ok() {
+                    history.redo_stack.push(transaction);
+                } else {
+                    history.undo_stack.clear();
+                    history.redo_stack.clear();
+                }
+                Err(error)
+            })
+            .unwrap_or(Ok(false))
+    }
+}
+
+impl WorldEdit {
+    /// Reverts this edit.
+    fn revert(
+        &self,
+        world: &mut World,
+        registry: &TypeRegistry,
+        entity_map: &mut EntityRemap,
+    ) -> Result<(), EditHistoryError> {
+        match self {
+            WorldEdit::Spawn { entity } => despawn(world, entity_map, *entity),
+            WorldEdit::Despawn { entities } => {
+                return respawn(world, registry, entity_map, entities);
+            }
+            WorldEdit::Component {
+                entity,
+                type_path,
+                before,
+                ..
+            } => {
+                return set_component(world, registry, entity_map, *entity, type_path, before);
+            }
+            WorldEdit::Resource {
+                type_path, before, ..
+            } => return set_resource(world, registry, entity_map, type_path, before),
+            WorldEdit::Reparent { entity, before, .. } => {
+                set_parent(world, entity_map, *entity, *before);
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies this edit again after it was reverted.
+    fn reapply(
+        &self,
+        world: &mut World,
+        registry: &TypeRegistry,
+        entity_map: &mut EntityRemap,
+    ) -> Result<(), EditHistoryError> {
+        match self {
+            WorldEdit::Spawn { entity } => {
+                let new_entity = world.spawn_empty().id();
+                entity_map.insert(*entity, new_entity);
+            }
+            WorldEdit::Despawn { entities } => {
+                if let Some(root) = entities.first() {
+                    despawn(world, entity_map, root.entity);
+                }
+            }
+            WorldEdit::Component {
+                entity,
+                type_path,
+                after,
+                ..
+            } => {
+                return set_component(world, registry, entity_map, *entity, type_path, after);
+            }
+            WorldEdit::Resource {
+                type_path, after, ..
+            } => return set_resource(world, registry, entity_map, type_path, after),
+            WorldEdit::Reparent { entity, after, .. } => {
+                set_parent(world, entity_map, *entity, *after);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maps the entities recorded in an [`EditHistory`] to the entities that currently stand for
+/// them, and back.
+#[derive(Debug, Default)]
+struct EntityRemap {
+    current: EntityHashMap<Entity>,
+    original: EntityHashMap<Entity>,
+}
+
+impl EntityRemap {
+    fn resolve(&self, entity: Entity) -> Entity {
+        self.current.get(&entity).copied().unwrap_or(entity)
+    }
+
+    fn original(&self, entity: Entity) -> Entity {
+        self.original.get(&entity).copied().unwrap_or(entity)
+    }
+
+    fn insert(&mut self, original: Entity, current: Entity) {
+        if let Some(previous) = self.current.insert(original, current) {
+            self.original.remove(&previous);
+        }
+        self.original.insert(current, original);
+    }
+
+    fn clear(&mut self) {
+        self.current.clear();
+        self.original.clear();
+    }
+}
+
+impl EntityMapper for EntityRemap {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.resolve(source)
+    }
+
+    fn set_mapped(&mut self, source: Entity, target: Entity) {
+        self.insert(source, target);
+    }
+}
+
+fn reflect_component<'a>(
+    registry: &'a TypeRegistry,
+    type_path: &str,
+) -> Result<&'a ReflectComponent, EditHistoryError> {
+    registry
+        .get_with_type_path(type_path)
+        .ok_or_else(|| EditHistoryError::UnregisteredType {
+            type_path: type_path.into(),
+        })?
+        .data::<ReflectComponent>()
+        .ok_or_else(|| EditHistoryError::UnregisteredComponent {
+            type_path: type_path.into(),
+        })
+}
+
+fn despawn(world: &mut World, entity_map: &EntityRemap, entity: Entity) {
+    if let Ok(entity) = world.get_entity_mut(entity_map.resolve(entity)) {
+        entity.despawn();
+    }
+}
+
+fn respawn(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity_map: &mut EntityRemap,
+    snapshots: &[EntitySnapshot],
+) -> Result<(), EditHistoryError> {
+    // Spawn all entities first, so that components referencing each other can be mapped.
+    for snapshot in snapshots {
+        let new_entity = world.spawn_empty().id();
+        entity_map.insert(snapshot.entity, new_entity);
+    }
+    for snapshot in snapshots {
+        let entity = entity_map.resolve(snapshot.entity);
+        for component in &snapshot.components {
+            let type_path = component
+                .get_represented_type_info()
+                .map_or_else(|| component.reflect_type_path(), |info| info.type_path());
+            let reflect_component = reflect_component(registry, type_path)?;
+            reflect_component.apply_or_insert_mapped(
+                &mut world.entity_mut(entity),
+                component.as_partial_reflect(),
+                registry,
+                entity_map,
+                RelationshipHookMode::Run,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn set_component(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity_map: &mut EntityRemap,
+    entity: Entity,
+    type_path: &str,
+    value: &Option<Box<dyn PartialReflect>>,
+) -> Result<(), EditHistoryError> {
+    let reflect_component = reflect_component(registry, type_path)?;
+    let Ok(mut entity) = world.get_entity_mut(entity_map.resolve(entity)) else {
+        return Ok(());
+    };
+    match value {
+        Some(value) => reflect_component.apply_or_insert_mapped(
+            &mut entity,
+            value.as_partial_reflect(),
+            registry,
+            entity_map,
+            RelationshipHookMode::Run,
+        ),
+        None => reflect_component.remove(&mut entity),
+    }
+    Ok(())
+}
+
+fn set_resource(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity_map: &mut EntityRemap,
+    type_path: &str,
+    value: &Option<Box<dyn PartialReflect>>,
+) -> Result<(), EditHistoryError> {
+    let reflect_component = reflect_component(registry, type_path)?;
+    let component_id = reflect_component.register_component(world);
+    match value {
+        Some(value) => {
+            let entity = match world.resource_entities().get(component_id) {
+                Some(&entity) => entity,
+                None => world.spawn_empty().id(),
+            };
+            reflect_component.apply_or_insert_mapped(
+                &mut world.entity_mut(entity),
+                value.as_partial_reflect(),
+                registry,
+                entity_map,
+                RelationshipHookMode::Run,
+            );
+        }
+        None => {
+            world.remove_resource_by_id(component_id);
+        }
+    }
+    Ok(())
+}
+
+fn set_parent(world: &mut World, entity_map: &EntityRemap, entity: Entity, parent: Option<Entity>) {
+    let Ok(mut entity) = world.get_entity_mut(entity_map.resolve(entity)) else {
+        return;
+    };
+    match parent {
+        Some(parent) => {
+            entity.insert(ChildOf(entity_map.resolve(parent)));
+        }
+        None => {
+            entity.remove::<ChildOf>();
+        }
+    }
+}
+
+/// Records `edit` into the [`EditHistory`] of `world`, if there is one.
+fn record(world: &mut World, edit: impl FnOnce(&World) -> WorldEdit) {
+    if !world.contains_resource::<EditHistory>() {
+        return;
+    }
+    let edit = edit(world);
+    world.resource_mut::<EditHistory>().push(edit);
+}
+
+/// Returns the entity as it is known to the [`EditHistory`] of `world`.
+///
+/// Entities respawned by undo or redo are recorded under their original id,
+/// so that all edits to them can be undone together.
+fn original_entity(world: &World, entity: Entity) -> Entity {
+    world
+        .get_resource::<EditHistory>()
+        .map_or(entity, |history| history.entity_map.original(entity))
+}
+
+/// An extension trait for [`Commands`] to make changes that are recorded into an [`EditHistory`].
+pub trait UndoableCommandsExt {
+    /// Spawns a new empty entity like [`Commands::spawn_empty`], and records a [`WorldEdit::Spawn`].
+    fn spawn_undoable(&mut self) -> EntityCommands<'_>;
+
+    /// Inserts a resource like [`Commands::insert_resource`], and records a [`WorldEdit::Resource`].
+    fn insert_resource_undoable<R: Resource + Reflect + TypePath>(&mut self, resource: R);
+
+    /// Removes a resource like [`Commands::remove_resource`], and records a [`WorldEdit::Resource`].
+    fn remove_resource_undoable<R: Resource + Reflect + TypePath>(&mut self);
+
+    /// Calls [`EditHistory::begin_transaction`] when applied.
+    fn begin_edit(&mut self, label: impl Into<Cow<'static, str>>);
+
+    /// Calls [`EditHistory::end_transaction`] when applied.
+    fn end_edit(&mut self);
+
+    /// Calls [`EditHistory::undo`] when applied.
+    fn undo(&mut self);
+
+    /// Calls [`EditHistory::redo`] when applied.
+    fn redo(&mut self);
+}
+
+impl UndoableCommandsExt for Commands<'_, '_> {
+    fn spawn_undoable(&mut self) -> EntityCommands<'_> {
+        let mut entity_commands = self.spawn_empty();
+        let entity = entity_commands.id();
+        entity_commands.commands().queue(move |world: &mut World| {
+            record(world, |_| WorldEdit::Spawn { entity });
+        });
+        entity_commands
+    }
+
+    fn insert_resource_undoable<R: Resource + Reflect + TypePath>(&mut self, resource: R) {
+        self.queue(move |world: &mut World| {
+            record(world, |world| WorldEdit::Resource {
+                type_path: Cow::Borrowed(R::type_path()),
+                before: world
+                    .get_resource::<R>()
+                    .map(|before| clone_reflected(before)),
+                after: Some(clone_reflected(&resource)),
+            });
+            world.insert_resource(resource);
+        });
+    }
+
+    fn remove_resource_undoable<R: Resource + Reflect + TypePath>(&mut self) {
+        self.queue(move |world: &mut World| {
+            record(world, |world| WorldEdit::Resource {
+                type_path: Cow::Borrowed(R::type_path()),
+                before: world
+                    .get_resource::<R>()
+                    .map(|before| clone_reflected(before)),
+                after: None,
+            });
+            world.remove_resource::<R>();
+        });
+    }
+
+    fn begin_edit(&mut self, label: impl Into<Cow<'static, str>>) {
+        let label = label.into();
+        self.queue(move |world: &mut World| {
+            if let Some(mut history) = world.get_resource_mut::<EditHistory>() {
+                history.begin_transaction(label);
+            }
+        });
+    }
+
+    fn end_edit(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Some(mut history) = world.get_resource_mut::<EditHistory>() {
+                history.end_transaction();
+            }
+        });
+    }
+
+    fn undo(&mut self) {
+        self.queue(|world: &mut World| EditHistory::undo(world).map(|_| ()));
+    }
+
+    fn redo(&mut self) {
+        self.queue(|world: &mut World| EditHistory::redo(world).map(|_| ()));
+    }
+}
+
+/// An extension trait for [`EntityCommands`] to make changes that are recorded into an
+/// [`EditHistory`].
+///
+/// The changes are applied with the regular typed [`EntityWorldMut`] methods, and are only
+/// cloned into reflected values while an [`EditHistory`] resource exists.
+pub trait UndoableEntityCommandsExt {
+    /// Inserts a component like [`EntityCommands::insert`], and records a [`WorldEdit::Component`]
+    /// with the previous value of the component, if any.
+    fn insert_undoable<C: Component + Reflect + TypePath>(&mut self, component: C) -> &mut Self;
+
+    /// Modifies a component in place with `f`, and records a [`WorldEdit::Component`].
+    ///
+    /// Does nothing if the entity doesn't have the component.
+    fn modify_undoable<C: Component<Mutability = Mutable> + Reflect + TypePath>(
+        &mut self,
+        f: impl FnOnce(&mut C) + Send + 'static,
+    ) -> &mut Self;
+
+    /// Removes a component like [`EntityCommands::remove`], and records a [`WorldEdit::Component`].
+    fn remove_undoable<C: Component + Reflect + TypePath>(&mut self) -> &mut Self;
+
+    /// Sets the parent of the entity by inserting [`ChildOf`], or removes it if `parent` is `None`,
+    /// and records a [`WorldEdit::Reparent`].
+    fn set_parent_undoable(&mut self, parent: Option<Entity>) -> &mut Self;
+
+    /// Despawns the entity and its descendants like [`EntityCommands::despawn`],
+    /// and records a [`WorldEdit::Despawn`] with a snapshot of their reflected components.
+    ///
+    /// # Panics
+    ///
+    /// If [`AppTypeRegistry`] is not present in the world while an [`EditHistory`] exists.
+    fn despawn_undoable(&mut self);
+}
+
+impl UndoableEntityCommandsExt for EntityCommands<'_> {
+    fn insert_undoable<C: Component + Reflect + TypePath>(&mut self, component: C) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let id = entity.id();
+            let before = entity.get::<C>().map(|before| clone_reflected(before));
+            entity.world_scope(|world| {
+                record(world, |world| WorldEdit::Component {
+                    entity: original_entity(world, id),
+                    type_path: Cow::Borrowed(C::type_path()),
+                    before,
+                    after: Some(clone_reflected(&component)),
+                });
+            });
+            entity.insert(component);
+        })
+    }
+
+    fn modify_undoable<C: Component<Mutability = Mutable> + Reflect + TypePath>(
+        &mut self,
+        f: impl FnOnce(&mut C) + Send + 'static,
+    ) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let id = entity.id();
+            let Some(mut component) = entity.get_mut::<C>() else {
+                return;
+            };
+            let before = clone_reflected(component.as_partial_reflect());
+            f(&mut component);
+            let after = clone_reflected(component.as_partial_reflect());
+            entity.world_scope(|world| {
+                record(world, |world| WorldEdit::Component {
+                    entity: original_entity(world, id),
+                    type_path: Cow::Borrowed(C::type_path()),
+                    before: Some(before),
+                    after: Some(after),
+                });
+            });
+        })
+    }
+
+    fn remove_undoable<C: Component + Reflect + TypePath>(&mut self) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let id = entity.id();
+            let Some(before) = entity.get::<C>().map(|before| clone_reflected(before)) else {
+                return;
+            };
+            entity.world_scope(|world| {
+                record(world, |world| WorldEdit::Component {
+                    entity: original_entity(world, id),
+                    type_path: Cow::Borrowed(C::type_path()),
+                    before: Some(before),
+                    after: None,
+                });
+            });
+            entity.remove::<C>();
+        })
+    }
+
+    fn set_parent_undoable(&mut self, parent: Option<Entity>) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let id = entity.id();
+            let before = entity.get::<ChildOf>().map(ChildOf::parent);
+            entity.world_scope(|world| {
+                record(world, |world| WorldEdit::Reparent {
+                    entity: original_entity(world, id),
+                    before: before.map(|before| original_entity(world, before)),
+                    after: parent.map(|parent| original_entity(world, parent)),
+                });
+            });
+            match parent {
+                Some(parent) => entity.insert(ChildOf(parent)),
+                None => entity.remove::<ChildOf>(),
+            };
+        })
+    }
+
+    fn despawn_undoable(&mut self) {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let id = entity.id();
+            entity.world_scope(|world| {
+                record(world, |world| {
+                    let registry = world.resource::<AppTypeRegistry>().read();
+                    let mut entities = EntitySnapshot::hierarchy(world, &registry, id);
+                    for snapshot in &mut entities {
+                        snapshot.entity = original_entity(world, snapshot.entity);
+                    }
+                    WorldEdit::Despawn { entities }
+                });
+            });
+            entity.despawn();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EditHistory, UndoableCommandsExt, UndoableEntityCommandsExt, WorldEdit};
+    use crate::{
+        component::Component,
+        hierarchy::{ChildOf, Children},
+        prelude::{AppTypeRegistry, ReflectComponent, ReflectResource},
+        resource::Resource,
+        world::World,
+    };
+    use bevy_reflect::Reflect;
+
+    #[derive(Component, Reflect, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Position(i32);
+
+    #[derive(Component, Reflect, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Name(&'static str);
+
+    #[derive(Resource, Reflect, PartialEq, Debug)]
+    #[reflect(Resource)]
+    struct Gravity(f32);
+
+    fn editor_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<Position>();
+            registry.register::<Name>();
+            registry.register::<Gravity>();
+            registry.register::<ChildOf>();
+        }
+        world.init_resource::<EditHistory>();
+        world
+    }
+
+    #[test]
+    fn undo_redo_components() {
+        let mut world = editor_world();
+        let entity = world.spawn(Position(0)).id();
+
+        let mut commands = world.commands();
+        commands.entity(entity).insert_undoable(Position(1));
+        commands
+            .entity(entity)
+            .modify_undoable(|position: &mut Position| position.0 += 1);
+        commands.entity(entity).remove_undoable::<Position>();
+        world.flush();
+        assert_eq!(world.get::<Position>(entity), None);
+
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(2)));
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1)));
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(0)));
+        assert!(!EditHistory::undo(&mut world).unwrap());
+
+        EditHistory::redo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1)));
+
+        // Recording a new edit clears the redo stack.
+        world
+            .commands()
+            .entity(entity)
+            .insert_undoable(Position(10));
+        world.flush();
+        assert!(!world.resource::<EditHistory>().can_redo());
+    }
+
+    #[test]
+    fn transactions_group_edits() {
+        let mut world = editor_world();
+        let entity = world.spawn(Position(0)).id();
+
+        let mut commands = world.commands();
+        commands.begin_edit("Move and rename");
+        commands.entity(entity).insert_undoable(Position(3));
+        commands.entity(entity).insert_undoable(Name("moved"));
+        commands.end_edit();
+        world.flush();
+
+        let history = world.resource::<EditHistory>();
+        assert_eq!(history.undo_stack().count(), 1);
+        assert_eq!(
+            history.undo_stack().next().unwrap().label.as_deref(),
+            Some("Move and rename")
+        );
+
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(0)));
+        assert_eq!(world.get::<Name>(entity), None);
+    }
+
+    #[test]
+    fn failed_edits_are_rolled_back() {
+        let mut world = editor_world();
+        let entity = world.spawn(Position(0)).id();
+        let mut commands = world.commands();
+        commands.begin_edit("Move");
+        commands.entity(entity).insert_undoable(Position(1));
+        commands.entity(entity).insert_undoable(Name("moved"));
+        commands.entity(entity).insert_undoable(Position(2));
+        commands.end_edit();
+        world.flush();
+
+        // Without `Name` in the registry, the second edit fails both ways.
+        let full_registry = world.resource::<AppTypeRegistry>().clone();
+        let partial_registry = AppTypeRegistry::default();
+        partial_registry.write().register::<Position>();
+
+        world.insert_resource(partial_registry.clone());
+        assert!(EditHistory::undo(&mut world).is_err());
+        assert_eq!(world.get::<Position>(entity), Some(&Position(2)));
+        assert_eq!(world.get::<Name>(entity), Some(&Name("moved")));
+        let history = world.resource::<EditHistory>();
+        assert_eq!(history.undo_stack().count(), 1);
+        assert!(!history.can_redo());
+
+        world.insert_resource(full_registry);
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(0)));
+        assert_eq!(world.get::<Name>(entity), None);
+
+        world.insert_resource(partial_registry);
+        assert!(EditHistory::redo(&mut world).is_err());
+        assert_eq!(world.get::<Position>(entity), Some(&Position(0)));
+        assert_eq!(world.get::<Name>(entity), None);
+        let history = world.resource::<EditHistory>();
+        assert_eq!(history.redo_stack().count(), 1);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_redo_resources() {
+        let mut world = editor_world();
+        let mut commands = world.commands();
+        commands.insert_resource_undoable(Gravity(9.8));
+        commands.insert_resource_undoable(Gravity(1.6));
+        world.flush();
+
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get_resource::<Gravity>(), Some(&Gravity(9.8)));
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get_resource::<Gravity>(), None);
+        EditHistory::redo(&mut world).unwrap();
+        assert_eq!(world.get_resource::<Gravity>(), Some(&Gravity(9.8)));
+    }
+
+    #[test]
+    fn undo_redo_spawn_and_despawn_hierarchy() {
+        let mut world = editor_world();
+        let parent = world.spawn(Name("parent")).id();
+        let child = world.spawn((Name("child"), ChildOf(parent))).id();
+
+        world.commands().entity(parent).despawn_undoable();
+        world.flush();
+        assert!(world.get_entity(parent).is_err());
+        assert!(world.get_entity(child).is_err());
+        assert!(matches!(
+            world.resource::<EditHistory>().undo_stack().next().unwrap().edits[0],
+            WorldEdit::Despawn { ref entities } if entities.len() == 2
+        ));
+
+        EditHistory::undo(&mut world).unwrap();
+        let history = world.resource::<EditHistory>();
+        let (new_parent, new_child) = (history.resolve(parent), history.resolve(child));
+        assert_eq!(world.get::<Name>(new_parent), Some(&Name("parent")));
+        assert_eq!(world.get::<ChildOf>(new_child), Some(&ChildOf(new_parent)));
+        assert_eq!(world.get::<Children>(new_parent).map(|c| c.len()), Some(1));
+
+        // Edits to the respawned entity are recorded under the original id.
+        world
+            .commands()
+            .entity(new_parent)
+            .insert_undoable(Position(4));
+        world.flush();
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<Position>(new_parent), None);
+
+        let spawned = world.commands().spawn_undoable().id();
+        world.flush();
+        EditHistory::undo(&mut world).unwrap();
+        assert!(world.get_entity(spawned).is_err());
+        EditHistory::redo(&mut world).unwrap();
+        let respawned = world.resource::<EditHistory>().resolve(spawned);
+        assert!(world.get_entity(respawned).is_ok());
+
+        // Entities respawned more than once are still recorded under the original id.
+        EditHistory::undo(&mut world).unwrap();
+        EditHistory::redo(&mut world).unwrap();
+        let respawned = world.resource::<EditHistory>().resolve(spawned);
+        world
+            .commands()
+            .entity(respawned)
+            .insert_undoable(Position(1));
+        world.flush();
+        assert!(matches!(
+            world.resource::<EditHistory>().undo_stack().next_back().unwrap().edits[0],
+            WorldEdit::Component { entity, .. } if entity == spawned
+        ));
+    }
+
+    #[test]
+    fn undo_redo_reparent() {
+        let mut world = editor_world();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let child = world.spawn(ChildOf(a)).id();
+
+        world.commands().entity(child).set_parent_undoable(Some(b));
+        world.flush();
+        assert_eq!(world.get::<ChildOf>(child), Some(&ChildOf(b)));
+
+        EditHistory::undo(&mut world).unwrap();
+        assert_eq!(world.get::<ChildOf>(child), Some(&ChildOf(a)));
+        EditHistory::redo(&mut world).unwrap();
+        assert_eq!(world.get::<ChildOf>(child), Some(&ChildOf(b)));
+    }
+
+    #[test]
+    fn history_limit() {
+        let mut world = World::new();
+        world.insert_resource(EditHistory::with_limit(2));
+        let entity = world.spawn(Position(0)).id();
+        for i in 1..=3 {
+            world.commands().entity(entity).insert_undoable(Position(i));
+        }
+        world.flush();
+        assert_eq!(world.resource::<EditHistory>().undo_stack().count(), 2);
+    }
+}
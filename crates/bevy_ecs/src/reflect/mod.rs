@@ -12,7 +12,9 @@ use bevy_reflect::{
 };
 
 mod bundle;
+mod command_recording;
 mod component;
+mod edit_history;
 mod entity_commands;
 mod event;
 mod from_world;
@@ -22,7 +24,18 @@ mod resource;
 
 use bevy_utils::prelude::DebugName;
 pub use bundle::{ReflectBundle, ReflectBundleFns};
+pub(crate) use command_recording::{
+    components_to_record, is_recording, record, record_cloned, record_despawn, record_insert,
+    record_insert_ids, record_removed, record_spawn, removals_to_record,
+};
+pub use command_recording::{CommandRecording, CommandReplayError, RecordedCommand};
+#[cfg(feature = "serialize")]
+pub use command_recording::{CommandRecordingDeserializer, CommandRecordingSerializer};
 pub use component::{ReflectComponent, ReflectComponentFns};
+pub use edit_history::{
+    EditHistory, EditHistoryError, EditTransaction, EntitySnapshot, UndoableCommandsExt,
+    UndoableEntityCommandsExt, WorldEdit,
+};
 pub use entity_commands::ReflectCommandExt;
 pub use event::{ReflectEvent, ReflectEventFns};
 pub use from_world::{ReflectFromWorld, ReflectFromWorldFns};
@@ -162,3 +175,11 @@ pub fn from_reflect_with_fallback<T: Reflect + TypePath>(
     .downcast::<T>()
     .unwrap()
 }
+
+/// Clones a reflected value, falling back to a dynamic representation for types that can't be cloned.
+pub(crate) fn clone_reflected(value: &dyn PartialReflect) -> alloc::boxed::Box<dyn PartialReflect> {
+    value
+        .reflect_clone()
+        .map(PartialReflect::into_partial_reflect)
+        .unwrap_or_else(|_| value.to_dynamic())
+}
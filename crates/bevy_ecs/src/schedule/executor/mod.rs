@@ -1,12 +1,14 @@
 #[cfg(feature = "std")]
 mod multi_threaded;
 mod single_threaded;
+mod timings;
 
 use alloc::{boxed::Box, vec, vec::Vec};
 use bevy_utils::prelude::DebugName;
 use core::any::TypeId;
 
 pub use self::single_threaded::SingleThreadedExecutor;
+pub use self::timings::{SystemTiming, SystemTimingKind, SystemTimings};
 
 #[cfg(feature = "std")]
 pub use self::multi_threaded::{MainThreadExecutor, MultiThreadedExecutor};
@@ -317,7 +319,10 @@ mod __rust_begin_short_backtrace {
 mod tests {
     use crate::{
         prelude::{Component, In, IntoSystem, Resource, Schedule},
-        schedule::{MultiThreadedExecutor, SingleThreadedExecutor},
+        schedule::{
+            IntoScheduleConfigs, MultiThreadedExecutor, SingleThreadedExecutor, SystemTimingKind,
+            SystemTimings,
+        },
         system::{Populated, Res, ResMut, Single},
         world::World,
     };
@@ -565,6 +570,54 @@ mod tests {
         let counter = world.resource::<Counter>();
         assert_eq!(counter.0, 0);
     }
+
+    #[test]
+    fn system_timings_singlethreaded() {
+        let mut schedule = Schedule::default();
+        schedule.set_executor(SingleThreadedExecutor::new());
+        system_timings("SingleThreaded", schedule);
+    }
+
+    #[test]
+    fn system_timings_multithreaded() {
+        let mut schedule = Schedule::default();
+        schedule.set_executor(MultiThreadedExecutor::new());
+        system_timings("MultiThreaded", schedule);
+    }
+
+    fn system_timings(name: &str, mut schedule: Schedule) {
+        fn timed_system() {}
+        fn timed_condition() -> bool {
+            true
+        }
+        fn exclusive_system(_world: &mut World) {}
+
+        let mut world = World::new();
+        schedule.add_systems((timed_system.run_if(timed_condition), exclusive_system).chain());
+
+        // No measurements are taken unless the resource exists.
+        schedule.run(&mut world);
+        assert!(world.get_resource::<SystemTimings>().is_none());
+
+        world.init_resource::<SystemTimings>();
+        schedule.run(&mut world);
+        let timings = world.resource::<SystemTimings>().records();
+        let count =
+            |kind: SystemTimingKind| timings.iter().filter(|timing| timing.kind == kind).count();
+        assert_eq!(
+            count(SystemTimingKind::System),
+            2,
+            "Expected both systems to be timed with {name}"
+        );
+        assert_eq!(
+            count(SystemTimingKind::Condition),
+            1,
+            "Expected the condition to be timed with {name}"
+        );
+
+        assert_eq!(world.resource_mut::<SystemTimings>().drain().count(), 3);
+        assert!(world.resource::<SystemTimings>().records().is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use alloc::{boxed::Box, vec::Vec};
 use bevy_platform::cell::SyncUnsafeCell;
 use bevy_platform::sync::Arc;
+use bevy_platform::time::Instant;
 use bevy_tasks::{ComputeTaskPool, Scope, TaskPool, ThreadExecutor};
 use concurrent_queue::ConcurrentQueue;
 use core::{any::Any, panic::AssertUnwindSafe};
@@ -16,7 +17,8 @@ use crate::{
     error::{ErrorContext, ErrorHandler, Result},
     prelude::Resource,
     schedule::{
-        is_apply_deferred, ConditionWithAccess, SystemExecutor, SystemSchedule, SystemWithAccess,
+        is_apply_deferred, ConditionWithAccess, SystemExecutor, SystemSchedule, SystemTiming,
+        SystemTimingKind, SystemTimings, SystemWithAccess,
     },
     system::{RunSystemError, ScheduleSystem},
     world::{unsafe_world_cell::UnsafeWorldCell, World},
@@ -32,6 +34,8 @@ struct Environment<'env, 'sys> {
     systems: &'sys [SyncUnsafeCell<SystemWithAccess>],
     conditions: SyncUnsafeCell<Conditions<'sys>>,
     world_cell: UnsafeWorldCell<'env>,
+    /// Is `true` if systems and conditions should be timed into [`SystemTimings`].
+    record_timings: bool,
 }
 
 struct Conditions<'a> {
@@ -47,6 +51,7 @@ impl<'env, 'sys> Environment<'env, 'sys> {
         schedule: &'sys mut SystemSchedule,
         world: &'env mut World,
     ) -> Self {
+        let record_timings = world.contains_resource::<SystemTimings>();
         Environment {
             executor,
             systems: SyncUnsafeCell::from_mut(schedule.systems.as_mut_slice()).as_slice_of_cells(),
@@ -57,6 +62,7 @@ impl<'env, 'sys> Environment<'env, 'sys> {
                 systems_in_sets_with_conditions: &schedule.systems_in_sets_with_conditions,
             }),
             world_cell: world.as_unsafe_world_cell(),
+            record_timings,
         }
     }
 }
@@ -82,6 +88,8 @@ struct SystemTaskMetadata {
 /// The result of running a system that is sent across a channel.
 struct SystemResult {
     system_index: usize,
+    /// How long the system took to run, if timings are being recorded.
+    timing: Option<SystemTiming>,
 }
 
 /// Runs the schedule using a thread pool. Non-conflicting systems can run in parallel.
@@ -128,6 +136,8 @@ pub struct ExecutorState {
     completed_systems: FixedBitSet,
     /// Systems that have run but have not had their buffers applied.
     unapplied_systems: FixedBitSet,
+    /// Measurements taken during this run, flushed into [`SystemTimings`] at the end.
+    timings: Vec<SystemTiming>,
 }
 
 /// References to data required by the executor.
@@ -308,6 +318,13 @@ impl SystemExecutor for MultiThreadedExecutor {
             std::panic::resume_unwind(payload);
         }
 
+        if !state.timings.is_empty() {
+            if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+                timings.extend(&mut state.timings);
+            }
+            state.timings.clear();
+        }
+
         debug_assert!(state.ready_systems.is_clear());
         debug_assert!(state.running_systems.is_clear());
         state.evaluated_sets.clear();
@@ -326,12 +343,16 @@ impl<'scope, 'env: 'scope, 'sys> Context<'scope, 'env, 'sys> {
         system_index: usize,
         res: Result<(), Box<dyn Any + Send>>,
         system: &ScheduleSystem,
+        timing: Option<SystemTiming>,
     ) {
         // tell the executor that the system finished
         self.environment
             .executor
             .system_completion
-            .push(SystemResult { system_index })
+            .push(SystemResult {
+                system_index,
+                timing,
+            })
             .unwrap_or_else(|error| unreachable!("{}", error));
         if let Err(payload) = res {
             #[cfg(feature = "std")]
@@ -413,6 +434,7 @@ impl ExecutorState {
             skipped_systems: FixedBitSet::new(),
             completed_systems: FixedBitSet::new(),
             unapplied_systems: FixedBitSet::new(),
+            timings: Vec::new(),
         }
     }
 
@@ -504,6 +526,7 @@ impl ExecutorState {
                         conditions,
                         context.environment.world_cell,
                         context.error_handler,
+                        context.environment.record_timings,
                     )
                 } {
                     self.skip_system_and_signal_dependents(system_index);
@@ -588,6 +611,7 @@ impl ExecutorState {
         conditions: &mut Conditions,
         world: UnsafeWorldCell,
         error_handler: ErrorHandler,
+        record_timings: bool,
     ) -> bool {
         let mut should_run = !self.skipped_systems.contains(system_index);
 
@@ -607,6 +631,7 @@ impl ExecutorState {
                     error_handler,
                     system,
                     true,
+                    record_timings.then_some(&mut self.timings),
                 )
             };
 
@@ -630,6 +655,7 @@ impl ExecutorState {
                 error_handler,
                 system,
                 false,
+                record_timings.then_some(&mut self.timings),
             )
         };
 
@@ -656,6 +682,7 @@ impl ExecutorState {
         let system_meta = &self.system_task_metadata[system_index];
 
         let task = async move {
+            let start = context.environment.record_timings.then(Instant::now);
             let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
                 // SAFETY:
                 // - The caller ensures that we have permission to
@@ -678,7 +705,9 @@ impl ExecutorState {
                     }
                 };
             }));
-            context.system_completed(system_index, res, system);
+            let timing = start
+                .map(|start| SystemTiming::since(system.name(), SystemTimingKind::System, start));
+            context.system_completed(system_index, res, system, timing);
         };
 
         if system_meta.is_send {
@@ -706,7 +735,7 @@ impl ExecutorState {
                 // that no other systems currently have access to the world.
                 let world = unsafe { context.environment.world_cell.world_mut() };
                 let res = apply_deferred(&unapplied_systems, context.environment.systems, world);
-                context.system_completed(system_index, res, system);
+                context.system_completed(system_index, res, system, None);
             };
 
             context.scope.spawn_on_scope(task);
@@ -715,6 +744,7 @@ impl ExecutorState {
                 // SAFETY: `can_run` returned true for this system, which means
                 // that no other systems currently have access to the world.
                 let world = unsafe { context.environment.world_cell.world_mut() };
+                let start = context.environment.record_timings.then(Instant::now);
                 let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
                     if let Err(RunSystemError::Failed(err)) =
                         __rust_begin_short_backtrace::run(system, world)
@@ -728,7 +758,10 @@ impl ExecutorState {
                         );
                     }
                 }));
-                context.system_completed(system_index, res, system);
+                let timing = start.map(|start| {
+                    SystemTiming::since(system.name(), SystemTimingKind::System, start)
+                });
+                context.system_completed(system_index, res, system, timing);
             };
 
             context.scope.spawn_on_scope(task);
@@ -739,7 +772,14 @@ impl ExecutorState {
     }
 
     fn finish_system_and_handle_dependents(&mut self, result: SystemResult) {
-        let SystemResult { system_index, .. } = result;
+        let SystemResult {
+            system_index,
+            timing,
+        } = result;
+
+        if let Some(timing) = timing {
+            self.timings.push(timing);
+        }
 
         if self.system_task_metadata[system_index].is_exclusive {
             self.exclusive_running = false;
@@ -810,6 +850,7 @@ unsafe fn evaluate_and_fold_conditions(
     error_handler: ErrorHandler,
     for_system: &ScheduleSystem,
     on_set: bool,
+    mut timings: Option<&mut Vec<SystemTiming>>,
 ) -> bool {
     #[expect(
         clippy::unnecessary_fold,
@@ -818,24 +859,35 @@ unsafe fn evaluate_and_fold_conditions(
     conditions
         .iter_mut()
         .map(|ConditionWithAccess { condition, .. }| {
+            let start = timings.is_some().then(Instant::now);
             // SAFETY:
             // - The caller ensures that `world` has permission to read any data
             //   required by the condition.
-            unsafe { __rust_begin_short_backtrace::readonly_run_unsafe(&mut **condition, world) }
-                .unwrap_or_else(|err| {
-                    if let RunSystemError::Failed(err) = err {
-                        error_handler(
-                            err,
-                            ErrorContext::RunCondition {
-                                name: condition.name(),
-                                last_run: condition.get_last_run(),
-                                system: for_system.name(),
-                                on_set,
-                            },
-                        );
-                    };
-                    false
-                })
+            let result = unsafe {
+                __rust_begin_short_backtrace::readonly_run_unsafe(&mut **condition, world)
+            }
+            .unwrap_or_else(|err| {
+                if let RunSystemError::Failed(err) = err {
+                    error_handler(
+                        err,
+                        ErrorContext::RunCondition {
+                            name: condition.name(),
+                            last_run: condition.get_last_run(),
+                            system: for_system.name(),
+                            on_set,
+                        },
+                    );
+                };
+                false
+            });
+            if let (Some(timings), Some(start)) = (timings.as_deref_mut(), start) {
+                timings.push(SystemTiming::since(
+                    condition.name(),
+                    SystemTimingKind::Condition,
+                    start,
+                ));
+            }
+            result
         })
         .fold(true, |acc, res| acc && res)
 }
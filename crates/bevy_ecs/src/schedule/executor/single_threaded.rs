@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+use bevy_platform::time::Instant;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -11,7 +13,10 @@ use std::eprintln;
 
 use crate::{
     error::{ErrorContext, ErrorHandler},
-    schedule::{is_apply_deferred, ConditionWithAccess, SystemExecutor, SystemSchedule},
+    schedule::{
+        is_apply_deferred, ConditionWithAccess, SystemExecutor, SystemSchedule, SystemTiming,
+        SystemTimingKind, SystemTimings,
+    },
     system::{RunSystemError, ScheduleSystem},
     world::World,
 };
@@ -35,6 +40,8 @@ pub struct SingleThreadedExecutor {
     unapplied_systems: FixedBitSet,
     /// Setting when true applies deferred system buffers after all systems have run
     apply_final_deferred: bool,
+    /// Measurements taken during this run, flushed into [`SystemTimings`] at the end.
+    timings: Vec<SystemTiming>,
 }
 
 impl SystemExecutor for SingleThreadedExecutor {
@@ -68,6 +75,8 @@ impl SystemExecutor for SingleThreadedExecutor {
             .map(|r| r.last_changed())
             .unwrap_or_default();
 
+        let record_timings = world.contains_resource::<SystemTimings>();
+
         for system_index in 0..schedule.systems.len() {
             let system = &mut schedule.systems[system_index].system;
 
@@ -89,6 +98,7 @@ impl SystemExecutor for SingleThreadedExecutor {
                     error_handler,
                     system,
                     true,
+                    record_timings.then_some(&mut self.timings),
                 );
 
                 if !set_conditions_met {
@@ -107,6 +117,7 @@ impl SystemExecutor for SingleThreadedExecutor {
                 error_handler,
                 system,
                 false,
+                record_timings.then_some(&mut self.timings),
             );
 
             should_run &= system_conditions_met;
@@ -131,6 +142,7 @@ impl SystemExecutor for SingleThreadedExecutor {
                 continue;
             }
 
+            let start = record_timings.then(Instant::now);
             let f = AssertUnwindSafe(|| {
                 if let Err(RunSystemError::Failed(err)) =
                     __rust_begin_short_backtrace::run_without_applying_deferred(system, world)
@@ -159,12 +171,26 @@ impl SystemExecutor for SingleThreadedExecutor {
                 (f)();
             }
 
+            if let Some(start) = start {
+                self.timings.push(SystemTiming::since(
+                    system.name(),
+                    SystemTimingKind::System,
+                    start,
+                ));
+            }
+
             self.unapplied_systems.insert(system_index);
         }
 
         if self.apply_final_deferred {
             self.apply_deferred(schedule, world);
         }
+        if !self.timings.is_empty() {
+            if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+                timings.extend(&mut self.timings);
+            }
+            self.timings.clear();
+        }
         self.evaluated_sets.clear();
         self.completed_systems.clear();
     }
@@ -184,6 +210,7 @@ impl SingleThreadedExecutor {
             completed_systems: FixedBitSet::new(),
             unapplied_systems: FixedBitSet::new(),
             apply_final_deferred: true,
+            timings: Vec::new(),
         }
     }
 
@@ -203,6 +230,7 @@ fn evaluate_and_fold_conditions(
     error_handler: ErrorHandler,
     for_system: &ScheduleSystem,
     on_set: bool,
+    mut timings: Option<&mut Vec<SystemTiming>>,
 ) -> bool {
     #[cfg(feature = "hotpatching")]
     let hotpatch_tick = world
@@ -221,8 +249,9 @@ fn evaluate_and_fold_conditions(
             if hotpatch_tick.is_newer_than(condition.get_last_run(), world.change_tick()) {
                 condition.refresh_hotpatch();
             }
-            __rust_begin_short_backtrace::readonly_run(&mut **condition, world).unwrap_or_else(
-                |err| {
+            let start = timings.is_some().then(Instant::now);
+            let result = __rust_begin_short_backtrace::readonly_run(&mut **condition, world)
+                .unwrap_or_else(|err| {
                     if let RunSystemError::Failed(err) = err {
                         error_handler(
                             err,
@@ -235,8 +264,15 @@ fn evaluate_and_fold_conditions(
                         );
                     };
                     false
-                },
-            )
+                });
+            if let (Some(timings), Some(start)) = (timings.as_deref_mut(), start) {
+                timings.push(SystemTiming::since(
+                    condition.name(),
+                    SystemTimingKind::Condition,
+                    start,
+                ));
+            }
+            result
         })
         .fold(true, |acc, res| acc && res)
 }
@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+use bevy_platform::time::Instant;
+use bevy_utils::prelude::DebugName;
+use core::time::Duration;
+
+use crate::resource::Resource;
+
+/// Opt-in storage for the run durations of systems and run conditions.
+///
+/// When this resource exists in a [`World`](crate::world::World), the [`SingleThreadedExecutor`]
+/// and [`MultiThreadedExecutor`] measure how long each system and each run condition took to run,
+/// and append a [`SystemTiming`] for each of them once the schedule has finished running.
+/// Without this resource, no measurements are taken.
+///
+/// Records accumulate across schedule runs until they are taken out with [`SystemTimings::drain`].
+/// `bevy_diagnostic`'s `SystemTimingDiagnosticsPlugin` does this once per frame and
+/// turns the records into diagnostics.
+///
+/// [`SingleThreadedExecutor`]: crate::schedule::SingleThreadedExecutor
+/// [`MultiThreadedExecutor`]: crate::schedule::MultiThreadedExecutor
+#[derive(Resource, Debug, Default)]
+pub struct SystemTimings {
+    records: Vec<SystemTiming>,
+}
+
+impl SystemTimings {
+    /// Returns the records collected since the last call to [`SystemTimings::drain`].
+    pub fn records(&self) -> &[SystemTiming] {
+        &self.records
+    }
+
+    /// Removes and returns all collected records.
+    pub fn drain(&mut self) -> impl Iterator<Item = SystemTiming> + '_ {
+        self.records.drain(..)
+    }
+
+    /// Appends the records collected by an executor.
+    pub(crate) fn extend(&mut self, records: &mut Vec<SystemTiming>) {
+        self.records.append(records);
+    }
+}
+
+/// What kind of schedule node a [`SystemTiming`] was measured for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemTimingKind {
+    /// A system.
+    System,
+    /// A run condition attached to a system or a system set.
+    Condition,
+}
+
+/// A single measurement of a system or run condition, recorded into [`SystemTimings`].
+#[derive(Debug, Clone)]
+pub struct SystemTiming {
+    /// The name of the system or run condition.
+    pub name: DebugName,
+    /// Whether this measurement is for a system or a run condition.
+    pub kind: SystemTimingKind,
+    /// How long the system or run condition took to run.
+    pub duration: Duration,
+    /// The thread the system or run condition ran on.
+    #[cfg(feature = "std")]
+    pub thread: std::thread::ThreadId,
+}
+
+impl SystemTiming {
+    /// Creates a new [`SystemTiming`] measured from `start` until now, on the current thread.
+    pub(crate) fn since(name: DebugName, kind: SystemTimingKind, start: Instant) -> Self {
+        Self {
+            name,
+            kind,
+            duration: start.elapsed(),
+            #[cfg(feature = "std")]
+            thread: std::thread::current().id(),
+        }
+    }
+}
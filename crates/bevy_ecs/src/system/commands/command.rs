@@ -133,6 +133,15 @@ where
 {
     let caller = MaybeLocation::caller();
     move |world: &mut World| {
+        #[cfg(feature = "bevy_reflect")]
+        if crate::reflect::is_recording(world) {
+            let entities: alloc::vec::Vec<Entity> =
+                SpawnBatchIter::new(world, bundles_iter.into_iter(), caller).collect();
+            for entity in entities {
+                crate::reflect::record_spawn::<I::Item>(world, entity);
+            }
+            return;
+        }
         SpawnBatchIter::new(world, bundles_iter.into_iter(), caller);
     }
 }
@@ -151,6 +160,21 @@ where
 {
     let caller = MaybeLocation::caller();
     move |world: &mut World| -> Result {
+        #[cfg(feature = "bevy_reflect")]
+        if crate::reflect::is_recording(world) {
+            let mut entities = alloc::vec::Vec::new();
+            let batch = batch
+                .into_iter()
+                .inspect(|(entity, _)| entities.push(*entity));
+            let result = world.try_insert_batch_with_caller(batch, insert_mode, caller);
+            for entity in entities {
+                if let Ok(mut entity) = world.get_entity_mut(entity) {
+                    crate::reflect::record_insert::<B>(&mut entity);
+                }
+            }
+            result?;
+            return Ok(());
+        }
         world.try_insert_batch_with_caller(batch, insert_mode, caller)?;
         Ok(())
     }
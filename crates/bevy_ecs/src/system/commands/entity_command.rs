@@ -129,11 +129,13 @@ where
 
 /// An [`EntityCommand`] that adds the components in a [`Bundle`] to an entity.
 #[track_caller]
-pub fn insert(bundle: impl Bundle, mode: InsertMode) -> impl EntityCommand {
+pub fn insert<B: Bundle>(bundle: B, mode: InsertMode) -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
         move_as_ptr!(bundle);
         entity.insert_with_caller(bundle, mode, caller, RelationshipHookMode::Run);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_insert::<B>(&mut entity);
     }
 }
 
@@ -163,6 +165,8 @@ pub unsafe fn insert_by_id<T: Send + 'static>(
                 RelationshipHookMode::Run,
             );
         });
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_insert_ids(&mut entity, alloc::vec![component_id]);
     }
 }
 
@@ -180,6 +184,8 @@ pub fn insert_from_world<T: Component + FromWorld>(mode: InsertMode) -> impl Ent
             let value = entity.world_scope(|world| T::from_world(world));
             move_as_ptr!(value);
             entity.insert_with_caller(value, mode, caller, RelationshipHookMode::Run);
+            #[cfg(feature = "bevy_reflect")]
+            crate::reflect::record_insert::<T>(&mut entity);
         }
     }
 }
@@ -201,6 +207,8 @@ where
             let bundle = component_fn();
             move_as_ptr!(bundle);
             entity.insert_with_caller(bundle, mode, caller, RelationshipHookMode::Run);
+            #[cfg(feature = "bevy_reflect")]
+            crate::reflect::record_insert::<T>(&mut entity);
         }
     }
 }
@@ -210,7 +218,11 @@ where
 pub fn remove<T: Bundle>() -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let removals = crate::reflect::removals_to_record::<T>(&entity);
         entity.remove_with_caller::<T>(caller);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record(&mut entity, removals);
     }
 }
 
@@ -220,7 +232,11 @@ pub fn remove<T: Bundle>() -> impl EntityCommand {
 pub fn remove_with_requires<T: Bundle>() -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.remove_with_requires_with_caller::<T>(caller);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_removed(&mut entity, components);
     }
 }
 
@@ -229,7 +245,11 @@ pub fn remove_with_requires<T: Bundle>() -> impl EntityCommand {
 pub fn remove_by_id(component_id: ComponentId) -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.remove_by_id_with_caller(component_id, caller);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_removed(&mut entity, components);
     }
 }
 
@@ -238,7 +258,11 @@ pub fn remove_by_id(component_id: ComponentId) -> impl EntityCommand {
 pub fn clear() -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.clear_with_caller(caller);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_removed(&mut entity, components);
     }
 }
 
@@ -248,7 +272,11 @@ pub fn clear() -> impl EntityCommand {
 pub fn retain<T: Bundle>() -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.retain_with_caller::<T>(caller);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_removed(&mut entity, components);
     }
 }
 
@@ -264,6 +292,8 @@ pub fn retain<T: Bundle>() -> impl EntityCommand {
 pub fn despawn() -> impl EntityCommand {
     let caller = MaybeLocation::caller();
     move |entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let entity = crate::reflect::record_despawn(entity);
         entity.despawn_with_caller(caller);
     }
 }
@@ -297,7 +327,11 @@ pub fn clone_with_opt_out(
     config: impl FnOnce(&mut EntityClonerBuilder<OptOut>) + Send + Sync + 'static,
 ) -> impl EntityCommand {
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.clone_with_opt_out(target, config);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_cloned(&mut entity, target, components);
     }
 }
 
@@ -314,7 +348,11 @@ pub fn clone_with_opt_in(
     config: impl FnOnce(&mut EntityClonerBuilder<OptIn>) + Send + Sync + 'static,
 ) -> impl EntityCommand {
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.clone_with_opt_in(target, config);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_cloned(&mut entity, target, components);
     }
 }
 
@@ -322,7 +360,11 @@ pub fn clone_with_opt_in(
 /// and inserts them into another entity.
 pub fn clone_components<B: Bundle>(target: Entity) -> impl EntityCommand {
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.clone_components::<B>(target);
+        #[cfg(feature = "bevy_reflect")]
+        crate::reflect::record_cloned(&mut entity, target, components);
     }
 }
 
@@ -342,7 +384,14 @@ pub fn clone_components<B: Bundle>(target: Entity) -> impl EntityCommand {
 /// [`Custom`]: crate::component::ComponentCloneBehavior::Custom
 pub fn move_components<B: Bundle>(target: Entity) -> impl EntityCommand {
     move |mut entity: EntityWorldMut| {
+        #[cfg(feature = "bevy_reflect")]
+        let components = crate::reflect::components_to_record(&entity);
         entity.move_components::<B>(target);
+        #[cfg(feature = "bevy_reflect")]
+        {
+            crate::reflect::record_cloned(&mut entity, target, components.clone());
+            crate::reflect::record_removed(&mut entity, components);
+        }
     }
 }
 
@@ -346,7 +346,12 @@ impl<'w, 's> Commands<'w, 's> {
         let entity = self.allocator.alloc();
         let caller = MaybeLocation::caller();
         self.queue(move |world: &mut World| {
-            world.spawn_empty_at_with_caller(entity, caller).map(|_| ())
+            let result = world.spawn_empty_at_with_caller(entity, caller).map(|_| ());
+            #[cfg(feature = "bevy_reflect")]
+            if result.is_ok() {
+                crate::reflect::record_spawn::<()>(world, entity);
+            }
+            result
         });
         self.entity(entity)
     }
@@ -399,9 +404,14 @@ impl<'w, 's> Commands<'w, 's> {
         let caller = MaybeLocation::caller();
         self.queue(move |world: &mut World| {
             move_as_ptr!(bundle);
-            world
+            let result = world
                 .spawn_at_with_caller(entity, bundle, caller)
-                .map(|_| ())
+                .map(|_| ());
+            #[cfg(feature = "bevy_reflect")]
+            if result.is_ok() {
+                crate::reflect::record_spawn::<T>(world, entity);
+            }
+            result
         });
         self.entity(entity)
     }
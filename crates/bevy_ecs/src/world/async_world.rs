@@ -0,0 +1,307 @@
+//! Provides [`AsyncWorld`], a handle that lets futures access a [`World`] at sync points.
+
+use alloc::boxed::Box;
+use bevy_platform::sync::{Arc, Mutex, Weak};
+use concurrent_queue::ConcurrentQueue;
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    resource::Resource,
+    system::{IntoSystem, RunSystemError, RunSystemOnce},
+    world::World,
+};
+
+type WorldCallback = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Owns the queue of closures sent to a [`World`] through [`AsyncWorld`] handles.
+///
+/// Closures are run with `&mut World` each time [`AsyncWorldQueue::apply`] is called.
+/// When this resource (or the world holding it) is dropped, all closures still in the queue are
+/// dropped too, and the futures waiting on them resolve to [`AsyncWorldError::Closed`].
+///
+/// `bevy_app`'s `TaskPoolPlugin` inserts this resource and applies the queue once per frame.
+#[derive(Resource)]
+pub struct AsyncWorldQueue {
+    queue: Arc<ConcurrentQueue<WorldCallback>>,
+}
+
+impl Default for AsyncWorldQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncWorldQueue {
+    /// Creates a new, empty [`AsyncWorldQueue`].
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(ConcurrentQueue::unbounded()),
+        }
+    }
+
+    /// Returns a new [`AsyncWorld`] handle that sends closures to this queue.
+    pub fn handle(&self) -> AsyncWorld {
+        AsyncWorld {
+            queue: Arc::downgrade(&self.queue),
+        }
+    }
+
+    /// Runs all closures queued on the [`AsyncWorldQueue`] resource of `world`.
+    ///
+    /// Closures queued while this runs are deferred to the next call.
+    /// Does nothing if the resource doesn't exist.
+    pub fn apply(world: &mut World) {
+        let Some(queue) = world.get_resource::<AsyncWorldQueue>() else {
+            return;
+        };
+        let queue = queue.queue.clone();
+        for _ in 0..queue.len() {
+            let Ok(callback) = queue.pop() else {
+                break;
+            };
+            callback(world);
+        }
+    }
+}
+
+/// A cloneable handle that lets futures running on a task pool access a [`World`].
+///
+/// Closures sent through this handle are queued up on the world's [`AsyncWorldQueue`] and run with
+/// `&mut World` the next time the queue is applied. Awaiting the returned future suspends the
+/// task until the closure has run, and then resumes it with the closure's return value.
+///
+/// Handles are created with [`AsyncWorldQueue::handle`], and don't keep the queue alive.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::world::AsyncWorldQueue;
+/// # use bevy_tasks::AsyncComputeTaskPool;
+/// #[derive(Resource)]
+/// struct LoadingProgress(f32);
+///
+/// fn start_loading(queue: Res<AsyncWorldQueue>) {
+///     let async_world = queue.handle();
+///     AsyncComputeTaskPool::get_or_init(Default::default)
+///         .spawn(async move {
+///             // ... do some expensive work ...
+///             let _ = async_world
+///                 .run(|world| world.insert_resource(LoadingProgress(0.5)))
+///                 .await;
+///             // ... do some more work ...
+///             let _ = async_world
+///                 .run(|world| world.insert_resource(LoadingProgress(1.0)))
+///                 .await;
+///         })
+///         .detach();
+/// }
+/// # bevy_ecs::system::assert_is_system(start_loading);
+/// ```
+#[derive(Clone)]
+pub struct AsyncWorld {
+    queue: Weak<ConcurrentQueue<WorldCallback>>,
+}
+
+impl AsyncWorld {
+    /// Queues `f` to run with `&mut World` at the next sync point,
+    /// and returns a future that resolves to its return value.
+    ///
+    /// The future resolves to [`AsyncWorldError::Closed`] if the [`AsyncWorldQueue`]
+    /// is dropped before the closure gets to run.
+    pub fn run<R, F>(&self, f: F) -> impl Future<Output = Result<R, AsyncWorldError>> + use<R, F>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut World) -> R + Send + 'static,
+    {
+        let (sender, receiver) = oneshot();
+        if let Some(queue) = self.queue.upgrade() {
+            let callback: WorldCallback = Box::new(move |world| sender.send(f(world)));
+            // The queue is unbounded and never closed, so this can't fail.
+            let _ = queue.push(callback);
+        }
+        // Otherwise `sender` is dropped here, and the receiver resolves to `Closed`.
+        receiver
+    }
+
+    /// Queues `system` to run once at the next sync point,
+    /// and returns a future that resolves to its output.
+    ///
+    /// See [`RunSystemOnce::run_system_once`](crate::system::RunSystemOnce::run_system_once) for the
+    /// caveats of running systems this way.
+    pub fn run_system<O, M, S>(
+        &self,
+        system: S,
+    ) -> impl Future<Output = Result<O, AsyncWorldError>> + use<O, M, S>
+    where
+        O: Send + 'static,
+        S: IntoSystem<(), O, M> + Send + 'static,
+    {
+        let result = self.run(move |world| world.run_system_once(system));
+        async move { result.await?.map_err(AsyncWorldError::RunSystem) }
+    }
+
+    /// Returns `true` if the [`AsyncWorldQueue`] this handle sends to has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.queue.strong_count() == 0
+    }
+}
+
+/// An error returned when awaiting a closure or system queued through an [`AsyncWorld`].
+#[derive(thiserror::Error, Debug)]
+pub enum AsyncWorldError {
+    /// The [`AsyncWorldQueue`] was dropped before the queued closure could run.
+    #[error("The world was dropped before the queued closure could run")]
+    Closed,
+    /// The queued system failed to run.
+    #[error("The queued system failed to run: {0}")]
+    RunSystem(RunSystemError),
+}
+
+/// Creates a single-use channel used to send a closure's output back to the awaiting future.
+fn oneshot<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let state = Arc::new(Mutex::new(OneshotState {
+        value: None,
+        closed: false,
+        waker: None,
+    }));
+    (
+        OneshotSender {
+            state: state.clone(),
+        },
+        OneshotReceiver { state },
+    )
+}
+
+struct OneshotState<T> {
+    value: Option<T>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+struct OneshotSender<T> {
+    state: Arc<Mutex<OneshotState<T>>>,
+}
+
+impl<T> OneshotSender<T> {
+    fn send(self, value: T) {
+        self.state.lock().unwrap().value = Some(value);
+        // Waking happens when `self` is dropped.
+    }
+}
+
+impl<T> Drop for OneshotSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct OneshotReceiver<T> {
+    state: Arc<Mutex<OneshotState<T>>>,
+}
+
+impl<T> Future for OneshotReceiver<T> {
+    type Output = Result<T, AsyncWorldError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            Poll::Ready(Ok(value))
+        } else if state.closed {
+            Poll::Ready(Err(AsyncWorldError::Closed))
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncWorldError, AsyncWorldQueue};
+    use crate::{resource::Resource, system::ResMut, world::World};
+    use bevy_tasks::block_on;
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    fn setup() -> World {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.init_resource::<AsyncWorldQueue>();
+        world
+    }
+
+    #[test]
+    fn run_resolves_after_apply() {
+        let mut world = setup();
+        let async_world = world.resource::<AsyncWorldQueue>().handle();
+
+        let future = async_world.run(|world| {
+            world.resource_mut::<Counter>().0 += 1;
+            world.resource::<Counter>().0
+        });
+        assert_eq!(world.resource::<Counter>().0, 0);
+
+        AsyncWorldQueue::apply(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+        assert_eq!(block_on(future).unwrap(), 1);
+    }
+
+    #[test]
+    fn run_system_resolves_after_apply() {
+        let mut world = setup();
+        let async_world = world.resource::<AsyncWorldQueue>().handle();
+
+        let future = async_world.run_system(|mut counter: ResMut<Counter>| {
+            counter.0 += 2;
+            counter.0
+        });
+        AsyncWorldQueue::apply(&mut world);
+        assert_eq!(block_on(future).unwrap(), 2);
+
+        let future = async_world.run_system(|_: ResMut<Counter>| {});
+        world.remove_resource::<Counter>();
+        AsyncWorldQueue::apply(&mut world);
+        assert!(matches!(
+            block_on(future),
+            Err(AsyncWorldError::RunSystem(_))
+        ));
+    }
+
+    #[test]
+    fn closures_queued_during_apply_are_deferred() {
+        let mut world = setup();
+        let async_world = world.resource::<AsyncWorldQueue>().handle();
+
+        let inner = async_world.clone();
+        let _outer = async_world.run(move |_| {
+            drop(inner.run(|world| world.resource_mut::<Counter>().0 += 1));
+        });
+        AsyncWorldQueue::apply(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 0);
+        AsyncWorldQueue::apply(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+    }
+
+    #[test]
+    fn dropping_world_closes_pending_futures() {
+        let world = setup();
+        let async_world = world.resource::<AsyncWorldQueue>().handle();
+        let pending = async_world.run(|_| ());
+        drop(world);
+
+        assert!(async_world.is_closed());
+        assert!(matches!(block_on(pending), Err(AsyncWorldError::Closed)));
+        assert!(matches!(
+            block_on(async_world.run(|_| ())),
+            Err(AsyncWorldError::Closed)
+        ));
+    }
+}
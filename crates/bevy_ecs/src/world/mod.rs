@@ -5,6 +5,7 @@
 
 //! Defines the [`World`] and APIs for accessing it directly.
 
+mod async_world;
 pub(crate) mod command_queue;
 mod deferred_world;
 mod entity_access;
@@ -22,6 +23,7 @@ pub use crate::{
     change_detection::{Mut, Ref, CHECK_TICK_THRESHOLD},
     world::command_queue::CommandQueue,
 };
+pub use async_world::{AsyncWorld, AsyncWorldError, AsyncWorldQueue};
 pub use bevy_ecs_macros::FromWorld;
 pub use deferred_world::DeferredWorld;
 pub use entity_access::{
@@ -0,0 +1,211 @@
+//! Exporting of [`AnimationClip`]s as glTF animations.
+
+use core::any::TypeId;
+
+use bevy_animation::{
+    animation_curves::EvaluatorId, graph::AnimationNodeIndex, AnimationClip, AnimationEntityMut,
+    AnimationTargetId,
+};
+use bevy_asset::AssetId;
+use bevy_ecs::world::World;
+use bevy_transform::components::Transform;
+use serde_json::json;
+
+use super::{GltfExportAssets, GltfExportError, GltfExportMaterial, GltfExporter, FLOAT};
+
+/// The glTF paths of the animated fields of [`Transform`], in field order.
+const TRANSFORM_PATHS: [(&str, &str); 3] = [
+    ("translation", "VEC3"),
+    ("rotation", "VEC4"),
+    ("scale", "VEC3"),
+];
+
+impl<A: GltfExportAssets, M: GltfExportMaterial> GltfExporter<'_, A, M> {
+    /// Adds an animation playing `clip` on the nodes added so far, and returns its index.
+    ///
+    /// Only the curves animating the [`Transform`] of exported entities with an
+    /// [`AnimationTargetId`] are exported. They are sampled at
+    /// [`GltfExportSettings::animation_sample_rate`](super::GltfExportSettings::animation_sample_rate),
+    /// and `None` is returned if there are none.
+    pub fn add_animation(
+        &mut self,
+        clip: AssetId<AnimationClip>,
+        name: Option<&str>,
+    ) -> Result<Option<usize>, GltfExportError> {
+        let assets = self.assets;
+        let clip = assets
+            .get(clip)
+            .ok_or(GltfExportError::MissingAsset(clip.untyped()))?;
+
+        let duration = clip.duration().max(0.0);
+        let rate = self.settings.animation_sample_rate.max(f32::EPSILON);
+        let count = (duration * rate).ceil() as usize + 1;
+        let times: Vec<f32> = (0..count)
+            .map(|i| (i as f32 / rate).min(duration))
+            .collect();
+
+        // Curves are sampled through their evaluators, writing to a scratch entity.
+        let mut world = World::new();
+        let scratch = world.spawn(Transform::IDENTITY).id();
+        let mut query = world.query::<AnimationEntityMut>();
+
+        let mut time_accessor = None;
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        let mut targets: Vec<(&AnimationTargetId, _)> = clip.curves().iter().collect();
+        targets.sort_by_key(|(target, _)| target.0);
+        for (target, curves) in targets {
+            let Some(&node) = self.animation_targets.get(target) else {
+                continue;
+            };
+            for curve in curves {
+                let EvaluatorId::ComponentField(field) = curve.0.evaluator_id() else {
+                    continue;
+                };
+                let (type_id, field) = **field;
+                if type_id != TypeId::of::<Transform>() || field >= TRANSFORM_PATHS.len() {
+                    continue;
+                }
+
+                let mut evaluator = curve.0.create_evaluator();
+                let mut bytes = Vec::new();
+                for &time in &times {
+                    curve
+                        .0
+                        .apply(&mut *evaluator, time, 1.0, AnimationNodeIndex::new(0))
+                        .map_err(GltfExportError::Animation)?;
+                    let entity = query.get_mut(&mut world, scratch).unwrap();
+                    evaluator
+                        .commit(entity)
+                        .map_err(GltfExportError::Animation)?;
+                    let transform = world.get::<Transform>(scratch).unwrap();
+                    let values = match field {
+                        0 => transform.translation.to_array().to_vec(),
+                        1 => transform.rotation.to_array().to_vec(),
+                        _ => transform.scale.to_array().to_vec(),
+                    };
+                    bytes.extend(values.into_iter().flat_map(f32::to_le_bytes));
+                }
+
+                // Keyframe times must have bounds, and are shared by all the samplers.
+                let input = *time_accessor.get_or_insert_with(|| {
+                    let bytes: Vec<u8> = times.iter().copied().flat_map(f32::to_le_bytes).collect();
+                    self.add_accessor(
+                        &bytes,
+                        times.len(),
+                        FLOAT,
+                        "SCALAR",
+                        None,
+                        Some((json!([0.0]), json!([duration]))),
+                    )
+                });
+                let (path, ty) = TRANSFORM_PATHS[field];
+                let output = self.add_accessor(&bytes, times.len(), FLOAT, ty, None, None);
+                samplers.push(json!({
+                    "input": input,
+                    "output": output,
+                    "interpolation": "LINEAR",
+                }));
+                channels.push(json!({
+                    "sampler": samplers.len() - 1,
+                    "target": { "node": node, "path": path },
+                }));
+            }
+        }
+
+        if channels.is_empty() {
+            return Ok(None);
+        }
+        let mut animation = json!({ "samplers": samplers, "channels": channels });
+        if let Some(name) = name {
+            animation["name"] = json!(name);
+        }
+        self.animations.push(animation);
+        Ok(Some(self.animations.len() - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_animation::{
+        animated_field,
+        animation_curves::{AnimatableCurve, AnimatableKeyframeCurve, AnimatedField},
+        AnimationClip, AnimationTargetId,
+    };
+    use bevy_asset::Assets;
+    use bevy_ecs::{name::Name, world::World};
+    use bevy_math::Vec3;
+    use bevy_transform::components::Transform;
+
+    use crate::{GltfExportSettings, GltfExporter, GltfMaterial};
+
+    #[derive(bevy_asset::Asset, bevy_reflect::TypePath)]
+    struct TestMaterial;
+
+    #[derive(bevy_ecs::component::Component)]
+    struct TestMaterial3d(bevy_asset::Handle<TestMaterial>);
+
+    impl crate::GltfExportMaterial for TestMaterial {
+        type Component = TestMaterial3d;
+
+        fn material_id(component: &TestMaterial3d) -> bevy_asset::AssetId<Self> {
+            component.0.id()
+        }
+
+        fn to_gltf_material(&self) -> GltfMaterial {
+            GltfMaterial::default()
+        }
+    }
+
+    #[test]
+    fn export_animation() {
+        let mut world = World::new();
+        let name = Name::new("Cube");
+        let target = AnimationTargetId::from_name(&name);
+        world.spawn((name, Transform::default(), target));
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                animated_field!(Transform::translation),
+                AnimatableKeyframeCurve::new([(0.0, Vec3::ZERO), (1.0, Vec3::new(2.0, 0.0, 0.0))])
+                    .unwrap(),
+            ),
+        );
+        let mut clips = Assets::<AnimationClip>::default();
+        let clip = clips.add(clip);
+        world.insert_resource(clips);
+
+        let mut exporter = GltfExporter::<_, TestMaterial>::new(
+            &world,
+            GltfExportSettings {
+                animation_sample_rate: 4.0,
+                ..Default::default()
+            },
+        );
+        exporter.add_scene(&world).unwrap();
+        assert_eq!(
+            exporter.add_animation(clip.id(), Some("Slide")).unwrap(),
+            Some(0)
+        );
+        let glb = exporter.finish().unwrap();
+
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        let blob = gltf.blob.as_deref();
+        let animation = gltf.animations().next().unwrap();
+        assert_eq!(animation.name(), Some("Slide"));
+        let channel = animation.channels().next().unwrap();
+        assert_eq!(channel.target().node().name(), Some("Cube"));
+        let reader = channel.reader(|_| blob);
+        let times: Vec<f32> = reader.read_inputs().unwrap().collect();
+        assert_eq!(times, [0.0, 0.25, 0.5, 0.75, 1.0]);
+        let Some(gltf::animation::util::ReadOutputs::Translations(translations)) =
+            reader.read_outputs()
+        else {
+            panic!("expected translations");
+        };
+        let translations: Vec<[f32; 3]> = translations.collect();
+        assert_eq!(translations[2], [1.0, 0.0, 0.0]);
+        assert_eq!(translations[4], [2.0, 0.0, 0.0]);
+    }
+}
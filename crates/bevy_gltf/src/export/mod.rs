@@ -0,0 +1,1196 @@
+//! Exporting of Bevy scenes, meshes and materials as glTF files.
+
+#[cfg(feature = "bevy_animation")]
+mod animation;
+
+use core::marker::PhantomData;
+use std::{io::Cursor, path::PathBuf};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bevy_asset::{
+    io::Writer,
+    saver::{AssetSaver, SavedAsset},
+    Asset, AssetId, AssetPath, Assets, AsyncWriteExt, Handle, UntypedAssetId,
+};
+use bevy_color::ColorToComponents;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    hierarchy::{ChildOf, Children},
+    name::Name,
+    world::{EntityRef, World},
+};
+use bevy_image::Image;
+use bevy_material::AlphaMode;
+use bevy_math::Mat4;
+use bevy_mesh::{
+    skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+    Indices, Mesh, Mesh3d, MeshAccessError, PrimitiveTopology, UvChannel, VertexAttributeValues,
+};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::TypePath;
+use bevy_scene::Scene;
+use bevy_transform::components::Transform;
+use image::{ExtendedColorType, ImageFormat};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+use tracing::warn;
+use wgpu_types::TextureFormat;
+
+use crate::{GltfLoader, GltfLoaderSettings, GltfMaterial};
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// The characters that are percent-encoded in the URIs of textures.
+const URI: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'.')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'~');
+
+/// A material that can be exported to glTF by a [`GltfExporter`] or a [`GltfSaver`].
+///
+/// `bevy_pbr` implements this for `StandardMaterial`.
+pub trait GltfExportMaterial: Asset + Sized {
+    /// The component assigning the material to mesh entities.
+    type Component: Component;
+
+    /// Returns the material assigned by `component`.
+    fn material_id(component: &Self::Component) -> AssetId<Self>;
+
+    /// Converts the material to a [`GltfMaterial`], which is then written to the glTF file.
+    fn to_gltf_material(&self) -> GltfMaterial;
+}
+
+/// The assets that meshes, materials, skins and animations are read from when exporting glTF
+/// files.
+///
+/// This is implemented for [`World`], which reads them from its [`Assets`] resources, and for
+/// [`SavedAsset`], which reads them from its labeled assets.
+pub trait GltfExportAssets {
+    /// Returns the asset with the given id, if it exists.
+    fn get<A: Asset>(&self, id: AssetId<A>) -> Option<&A>;
+}
+
+impl GltfExportAssets for World {
+    fn get<A: Asset>(&self, id: AssetId<A>) -> Option<&A> {
+        self.get_resource::<Assets<A>>()?.get(id)
+    }
+}
+
+impl<T: Asset> GltfExportAssets for SavedAsset<'_, '_, T> {
+    fn get<A: Asset>(&self, id: AssetId<A>) -> Option<&A> {
+        self.get_labeled_by_id(id).map(|asset| asset.get())
+    }
+}
+
+/// The container format of exported glTF files.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GltfExportFormat {
+    /// A binary `.glb` file, containing the JSON document and the binary buffer.
+    #[default]
+    Glb,
+    /// A `.gltf` JSON file, with the binary buffer embedded as a base64 data URI.
+    Gltf,
+}
+
+/// Settings for exporting glTF files with a [`GltfExporter`] or a [`GltfSaver`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GltfExportSettings {
+    /// The container format of the file.
+    pub format: GltfExportFormat,
+    /// The number of samples per second of exported animations.
+    ///
+    /// Animation curves can't be converted to glTF keyframes in general, so they are sampled and
+    /// written with linear interpolation.
+    pub animation_sample_rate: f32,
+}
+
+impl Default for GltfExportSettings {
+    fn default() -> Self {
+        Self {
+            format: GltfExportFormat::default(),
+            animation_sample_rate: 30.0,
+        }
+    }
+}
+
+/// An error that occurs when exporting a glTF file.
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum GltfExportError {
+    /// I/O Error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An asset referenced by the exported entities isn't available.
+    #[error("asset {0:?} is missing")]
+    MissingAsset(UntypedAssetId),
+    /// A joint of a skinned mesh isn't part of the exported entities.
+    #[error("joint {0} of a skinned mesh isn't exported")]
+    MissingJoint(Entity),
+    /// The data of a mesh can't be accessed.
+    #[error("mesh access error: {0}")]
+    MeshAccess(#[from] MeshAccessError),
+    /// An animation curve can't be sampled.
+    #[cfg(feature = "bevy_animation")]
+    #[error("failed to sample animation: {0:?}")]
+    Animation(bevy_animation::AnimationEvaluationError),
+    /// The JSON document can't be serialized.
+    #[error("failed to serialize glTF document: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Builds a glTF file from entities and the assets they use.
+///
+/// Entities are exported as nodes, with their [`Name`] and [`Transform`]. Their [`Mesh3d`] and
+/// material are exported as a mesh with a single primitive, and their [`SkinnedMesh`] as a skin.
+/// Materials are converted with [`GltfExportMaterial`]; textures are referenced by the path of
+/// their asset, relative to the path given to [`GltfExporter::with_path`]. Textures that aren't
+/// loaded from a file of their own are embedded as PNG images.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_gltf::{GltfExportMaterial, GltfExportSettings, GltfExporter, GltfMaterial};
+/// # use bevy_asset::{Asset, AssetId, Handle};
+/// # use bevy_reflect::TypePath;
+/// # #[derive(Asset, TypePath)]
+/// # struct MyMaterial;
+/// # #[derive(Component)]
+/// # struct MyMaterial3d(Handle<MyMaterial>);
+/// # impl GltfExportMaterial for MyMaterial {
+/// #     type Component = MyMaterial3d;
+/// #     fn material_id(component: &MyMaterial3d) -> AssetId<Self> { component.0.id() }
+/// #     fn to_gltf_material(&self) -> GltfMaterial { GltfMaterial::default() }
+/// # }
+/// #[derive(Component)]
+/// struct Level;
+///
+/// fn export_level(world: &mut World) {
+///     let level: Vec<Entity> = world
+///         .query_filtered::<Entity, With<Level>>()
+///         .iter(world)
+///         .collect();
+///     let mut exporter =
+///         GltfExporter::<_, MyMaterial>::new(world, GltfExportSettings::default());
+///     exporter.add_entities(world, level).unwrap();
+///     let glb: Vec<u8> = exporter.finish().unwrap();
+/// }
+/// ```
+pub struct GltfExporter<'a, A: GltfExportAssets, M: GltfExportMaterial> {
+    assets: &'a A,
+    settings: GltfExportSettings,
+    path: Option<AssetPath<'static>>,
+    scenes: Vec<Value>,
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    textures: Vec<Value>,
+    images: Vec<Value>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    accessors: Vec<Value>,
+    buffer_views: Vec<Value>,
+    buffer: Vec<u8>,
+    extensions_used: Vec<&'static str>,
+    mesh_indices: HashMap<(AssetId<Mesh>, Option<AssetId<M>>), usize>,
+    material_indices: HashMap<AssetId<M>, usize>,
+    texture_indices: HashMap<AssetId<Image>, Option<usize>>,
+    skin_indices: HashMap<(AssetId<SkinnedMeshInverseBindposes>, Vec<usize>), usize>,
+    #[cfg(feature = "bevy_animation")]
+    animation_targets: HashMap<bevy_animation::AnimationTargetId, usize>,
+}
+
+impl<'a, A: GltfExportAssets, M: GltfExportMaterial> GltfExporter<'a, A, M> {
+    /// Creates an exporter reading assets from `assets`.
+    pub fn new(assets: &'a A, settings: GltfExportSettings) -> Self {
+        Self {
+            assets,
+            settings,
+            path: None,
+            scenes: Vec::new(),
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            accessors: Vec::new(),
+            buffer_views: Vec::new(),
+            buffer: Vec::new(),
+            extensions_used: Vec::new(),
+            mesh_indices: HashMap::default(),
+            material_indices: HashMap::default(),
+            texture_indices: HashMap::default(),
+            skin_indices: HashMap::default(),
+            #[cfg(feature = "bevy_animation")]
+            animation_targets: HashMap::default(),
+        }
+    }
+
+    /// Sets the asset path the file will be saved to, which the paths of textures are made
+    /// relative to.
+    pub fn with_path(mut self, path: impl Into<AssetPath<'static>>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Adds a scene containing all the root entities of `world` with a [`Transform`], and returns
+    /// its index.
+    pub fn add_scene(&mut self, world: &World) -> Result<usize, GltfExportError> {
+        let roots: Vec<Entity> = world
+            .iter_entities()
+            .filter(|entity| entity.contains::<Transform>() && !entity.contains::<ChildOf>())
+            .map(|entity| entity.id())
+            .collect();
+        self.add_entities(world, roots)
+    }
+
+    /// Adds a scene containing the `roots` entities of `world` and their descendants with a
+    /// [`Transform`], and returns its index.
+    ///
+    /// The transforms of the roots are exported as is, so they are relative to their parents in
+    /// `world`, if any.
+    pub fn add_entities(
+        &mut self,
+        world: &World,
+        roots: impl IntoIterator<Item = Entity>,
+    ) -> Result<usize, GltfExportError> {
+        let mut entity_nodes = HashMap::default();
+        let mut skinned_meshes = Vec::new();
+        let mut nodes = Vec::new();
+        for root in roots {
+            if let Ok(root) = world.get_entity(root) {
+                nodes.push(self.add_node(world, root, &mut entity_nodes, &mut skinned_meshes)?);
+            }
+        }
+
+        // Skins are added once all the nodes their joints can refer to exist.
+        for (node, skinned_mesh) in skinned_meshes {
+            let skin = self.add_skin(&skinned_mesh, &entity_nodes)?;
+            self.nodes[node]["skin"] = json!(skin);
+        }
+
+        self.scenes.push(json!({ "nodes": nodes }));
+        Ok(self.scenes.len() - 1)
+    }
+
+    /// Returns the glTF file.
+    pub fn finish(self) -> Result<Vec<u8>, GltfExportError> {
+        let mut root = Map::new();
+        root.insert(
+            "asset".into(),
+            json!({ "version": "2.0", "generator": "Bevy" }),
+        );
+        if !self.scenes.is_empty() {
+            root.insert("scene".into(), json!(0));
+        }
+        for (name, values) in [
+            ("scenes", self.scenes),
+            ("nodes", self.nodes),
+            ("meshes", self.meshes),
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("images", self.images),
+            ("skins", self.skins),
+            ("animations", self.animations),
+            ("accessors", self.accessors),
+            ("bufferViews", self.buffer_views),
+        ] {
+            if !values.is_empty() {
+                root.insert(name.into(), Value::Array(values));
+            }
+        }
+        if !self.extensions_used.is_empty() {
+            root.insert("extensionsUsed".into(), json!(self.extensions_used));
+        }
+
+        let mut buffer = self.buffer;
+        if !buffer.is_empty() {
+            let uri = match self.settings.format {
+                GltfExportFormat::Glb => None,
+                GltfExportFormat::Gltf => Some(format!(
+                    "data:application/octet-stream;base64,{}",
+                    BASE64_STANDARD.encode(&buffer)
+                )),
+            };
+            let mut description = json!({ "byteLength": buffer.len() });
+            if let Some(uri) = uri {
+                description["uri"] = json!(uri);
+            }
+            root.insert("buffers".into(), json!([description]));
+        }
+
+        let root = Value::Object(root);
+        match self.settings.format {
+            GltfExportFormat::Gltf => Ok(serde_json::to_vec_pretty(&root)?),
+            GltfExportFormat::Glb => {
+                let mut document = serde_json::to_vec(&root)?;
+                // Chunks are aligned to 4 bytes, with spaces for JSON and zeros for binary data.
+                document.resize(document.len().next_multiple_of(4), b' ');
+                buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+                let mut length = 12 + 8 + document.len();
+                if !buffer.is_empty() {
+                    length += 8 + buffer.len();
+                }
+                let mut glb = Vec::with_capacity(length);
+                glb.extend_from_slice(b"glTF");
+                glb.extend_from_slice(&2u32.to_le_bytes());
+                glb.extend_from_slice(&(length as u32).to_le_bytes());
+                glb.extend_from_slice(&(document.len() as u32).to_le_bytes());
+                glb.extend_from_slice(b"JSON");
+                glb.extend_from_slice(&document);
+                if !buffer.is_empty() {
+                    glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+                    glb.extend_from_slice(b"BIN\0");
+                    glb.extend_from_slice(&buffer);
+                }
+                Ok(glb)
+            }
+        }
+    }
+
+    fn add_node(
+        &mut self,
+        world: &World,
+        entity: EntityRef,
+        entity_nodes: &mut HashMap<Entity, usize>,
+        skinned_meshes: &mut Vec<(usize, SkinnedMesh)>,
+    ) -> Result<usize, GltfExportError> {
+        let index = self.nodes.len();
+        self.nodes.push(Value::Null);
+        entity_nodes.insert(entity.id(), index);
+
+        let mut node = Map::new();
+        if let Some(name) = entity.get::<Name>() {
+            node.insert("name".into(), json!(name.as_str()));
+        }
+        let transform = entity.get::<Transform>().copied().unwrap_or_default();
+        if transform.translation != Transform::IDENTITY.translation {
+            node.insert(
+                "translation".into(),
+                json!(transform.translation.to_array()),
+            );
+        }
+        if transform.rotation != Transform::IDENTITY.rotation {
+            node.insert("rotation".into(), json!(transform.rotation.to_array()));
+        }
+        if transform.scale != Transform::IDENTITY.scale {
+            node.insert("scale".into(), json!(transform.scale.to_array()));
+        }
+        if let Some(mesh) = entity.get::<Mesh3d>() {
+            let material = entity.get::<M::Component>().map(M::material_id);
+            if let Some(mesh) = self.add_mesh(mesh.id(), material)? {
+                node.insert("mesh".into(), json!(mesh));
+                // glTF only allows skins on nodes with a mesh.
+                if let Some(skinned_mesh) = entity.get::<SkinnedMesh>() {
+                    skinned_meshes.push((index, skinned_mesh.clone()));
+                }
+            }
+        }
+        #[cfg(feature = "bevy_animation")]
+        if let Some(&target) = entity.get::<bevy_animation::AnimationTargetId>() {
+            self.animation_targets.insert(target, index);
+        }
+
+        let mut children = Vec::new();
+        for child in entity.get::<Children>().into_iter().flatten() {
+            if let Ok(child) = world.get_entity(*child)
+                && child.contains::<Transform>()
+            {
+                children.push(self.add_node(world, child, entity_nodes, skinned_meshes)?);
+            }
+        }
+        if !children.is_empty() {
+            node.insert("children".into(), json!(children));
+        }
+
+        self.nodes[index] = Value::Object(node);
+        Ok(index)
+    }
+
+    /// Returns the index of the glTF mesh for `id` with `material`, or `None` if the mesh has no
+    /// vertices, as glTF doesn't allow empty accessors.
+    fn add_mesh(
+        &mut self,
+        id: AssetId<Mesh>,
+        material: Option<AssetId<M>>,
+    ) -> Result<Option<usize>, GltfExportError> {
+        if let Some(&index) = self.mesh_indices.get(&(id, material)) {
+            return Ok(Some(index));
+        }
+        let mesh = self
+            .assets
+            .get(id)
+            .ok_or(GltfExportError::MissingAsset(id.untyped()))?;
+        if mesh
+            .try_attribute_option(Mesh::ATTRIBUTE_POSITION)?
+            .is_none_or(VertexAttributeValues::is_empty)
+        {
+            warn!("Skipping mesh {id:?}, which has no vertices");
+            return Ok(None);
+        }
+
+        let mut attributes = Map::new();
+        for (name, attribute) in [
+            ("POSITION", Mesh::ATTRIBUTE_POSITION),
+            ("NORMAL", Mesh::ATTRIBUTE_NORMAL),
+            ("TANGENT", Mesh::ATTRIBUTE_TANGENT),
+            ("TEXCOORD_0", Mesh::ATTRIBUTE_UV_0),
+            ("TEXCOORD_1", Mesh::ATTRIBUTE_UV_1),
+            ("COLOR_0", Mesh::ATTRIBUTE_COLOR),
+            ("JOINTS_0", Mesh::ATTRIBUTE_JOINT_INDEX),
+            ("WEIGHTS_0", Mesh::ATTRIBUTE_JOINT_WEIGHT),
+        ] {
+            let Some(values) = mesh.try_attribute_option(attribute)? else {
+                continue;
+            };
+            let (component_type, ty) = match values {
+                VertexAttributeValues::Float32x2(_) => (FLOAT, "VEC2"),
+                VertexAttributeValues::Float32x3(_) => (FLOAT, "VEC3"),
+                VertexAttributeValues::Float32x4(_) => (FLOAT, "VEC4"),
+                VertexAttributeValues::Uint16x4(_) => (UNSIGNED_SHORT, "VEC4"),
+                _ => {
+                    warn!("Skipping the {name} attribute of mesh {id:?}, whose format isn't supported by glTF");
+                    continue;
+                }
+            };
+            // Positions must have bounds.
+            let bounds = values
+                .as_float3()
+                .filter(|_| name == "POSITION")
+                .map(|positions| {
+                    let (min, max) = positions.iter().fold(
+                        ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+                        |(min, max), position| {
+                            (
+                                core::array::from_fn(|i| min[i].min(position[i])),
+                                core::array::from_fn(|i| max[i].max(position[i])),
+                            )
+                        },
+                    );
+                    (json!(min), json!(max))
+                });
+            let accessor = self.add_accessor(
+                values.get_bytes(),
+                values.len(),
+                component_type,
+                ty,
+                Some(ARRAY_BUFFER),
+                bounds,
+            );
+            attributes.insert(name.into(), json!(accessor));
+        }
+
+        let mode = match mesh.primitive_topology() {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList => 1,
+            PrimitiveTopology::LineStrip => 3,
+            PrimitiveTopology::TriangleList => 4,
+            PrimitiveTopology::TriangleStrip => 5,
+        };
+        let mut primitive = json!({ "attributes": attributes, "mode": mode });
+        if let Some(indices) = mesh.try_indices_option()? {
+            let (bytes, component_type): (Vec<u8>, _) = match indices {
+                Indices::U16(indices) => (
+                    indices
+                        .iter()
+                        .flat_map(|index| index.to_le_bytes())
+                        .collect(),
+                    UNSIGNED_SHORT,
+                ),
+                Indices::U32(indices) => (
+                    indices
+                        .iter()
+                        .flat_map(|index| index.to_le_bytes())
+                        .collect(),
+                    UNSIGNED_INT,
+                ),
+            };
+            primitive["indices"] = json!(self.add_accessor(
+                &bytes,
+                indices.len(),
+                component_type,
+                "SCALAR",
+                Some(ELEMENT_ARRAY_BUFFER),
+                None,
+            ));
+        }
+        if let Some(material) = material {
+            primitive["material"] = json!(self.add_material(material)?);
+        }
+
+        self.meshes.push(json!({ "primitives": [primitive] }));
+        let index = self.meshes.len() - 1;
+        self.mesh_indices.insert((id, material), index);
+        Ok(Some(index))
+    }
+
+    fn add_material(&mut self, id: AssetId<M>) -> Result<usize, GltfExportError> {
+        if let Some(&index) = self.material_indices.get(&id) {
+            return Ok(index);
+        }
+        let material = self
+            .assets
+            .get(id)
+            .ok_or(GltfExportError::MissingAsset(id.untyped()))?
+            .to_gltf_material();
+
+        let mut pbr = json!({
+            "baseColorFactor": material.base_color.to_linear().to_f32_array(),
+            "metallicFactor": material.metallic,
+            "roughnessFactor": material.perceptual_roughness,
+        });
+        if let Some(texture) =
+            self.texture_info(&material.base_color_texture, &material.base_color_channel)
+        {
+            pbr["baseColorTexture"] = texture;
+        }
+        if let Some(texture) = self.texture_info(
+            &material.metallic_roughness_texture,
+            &material.metallic_roughness_channel,
+        ) {
+            pbr["metallicRoughnessTexture"] = texture;
+        }
+        let mut json = json!({
+            "pbrMetallicRoughness": pbr,
+            "doubleSided": material.double_sided,
+        });
+        if let Some(texture) =
+            self.texture_info(&material.normal_map_texture, &material.normal_map_channel)
+        {
+            json["normalTexture"] = texture;
+        }
+        if let Some(texture) =
+            self.texture_info(&material.occlusion_texture, &material.occlusion_channel)
+        {
+            json["occlusionTexture"] = texture;
+        }
+        if let Some(texture) =
+            self.texture_info(&material.emissive_texture, &material.emissive_channel)
+        {
+            json["emissiveTexture"] = texture;
+        }
+        match material.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask(cutoff) => {
+                json["alphaMode"] = json!("MASK");
+                json["alphaCutoff"] = json!(cutoff);
+            }
+            _ => json["alphaMode"] = json!("BLEND"),
+        }
+
+        let mut extensions = Map::new();
+        // Emissive factors are limited to 1, so brighter colors are scaled by a strength.
+        let emissive = material.emissive.to_vec3();
+        let strength = emissive.max_element();
+        if strength > 1.0 {
+            json["emissiveFactor"] = json!((emissive / strength).to_array());
+            extensions.insert(
+                "KHR_materials_emissive_strength".into(),
+                json!({ "emissiveStrength": strength }),
+            );
+        } else if strength > 0.0 {
+            json["emissiveFactor"] = json!(emissive.to_array());
+        }
+        if material.unlit {
+            extensions.insert("KHR_materials_unlit".into(), json!({}));
+        }
+        if material.ior != 1.5 {
+            extensions.insert("KHR_materials_ior".into(), json!({ "ior": material.ior }));
+        }
+        if material.specular_transmission > 0.0 {
+            extensions.insert(
+                "KHR_materials_transmission".into(),
+                json!({ "transmissionFactor": material.specular_transmission }),
+            );
+        }
+        if material.thickness > 0.0 {
+            let mut volume = json!({
+                "thicknessFactor": material.thickness,
+                "attenuationColor": material.attenuation_color.to_linear().to_vec3().to_array(),
+            });
+            if material.attenuation_distance.is_finite() {
+                volume["attenuationDistance"] = json!(material.attenuation_distance);
+            }
+            extensions.insert("KHR_materials_volume".into(), volume);
+        }
+        for extension in [
+            "KHR_materials_emissive_strength",
+            "KHR_materials_unlit",
+            "KHR_materials_ior",
+            "KHR_materials_transmission",
+            "KHR_materials_volume",
+        ] {
+            if extensions.contains_key(extension) && !self.extensions_used.contains(&extension) {
+                self.extensions_used.push(extension);
+            }
+        }
+        if !extensions.is_empty() {
+            json["extensions"] = Value::Object(extensions);
+        }
+
+        self.materials.push(json);
+        let index = self.materials.len() - 1;
+        self.material_indices.insert(id, index);
+        Ok(index)
+    }
+
+    /// Returns the texture info referring to `image`, if it can be exported.
+    fn texture_info(
+        &mut self,
+        image: &Option<Handle<Image>>,
+        channel: &UvChannel,
+    ) -> Option<Value> {
+        let image = image.as_ref()?;
+        let index = match self.texture_indices.get(&image.id()) {
+            Some(&index) => index,
+            None => {
+                let source = match image.path().filter(|path| path.label().is_none()) {
+                    Some(path) => Some(json!({ "uri": self.texture_uri(path) })),
+                    None => self.embed_image(image.id()),
+                };
+                let index = source.map(|source| {
+                    self.images.push(source);
+                    self.textures
+                        .push(json!({ "source": self.images.len() - 1 }));
+                    self.textures.len() - 1
+                });
+                self.texture_indices.insert(image.id(), index);
+                index
+            }
+        }?;
+        let mut info = json!({ "index": index });
+        if *channel == UvChannel::Uv1 {
+            info["texCoord"] = json!(1);
+        }
+        Some(info)
+    }
+
+    /// Encodes `id` as a PNG embedded in the file, and returns the JSON of the glTF image.
+    ///
+    /// GLB files store the PNG in the binary buffer, and glTF files in a data URI.
+    fn embed_image(&mut self, id: AssetId<Image>) -> Option<Value> {
+        let Some(png) = self.assets.get(id).and_then(encode_png) else {
+            warn!("Skipping texture {:?}, which can't be encoded as PNG", id);
+            return None;
+        };
+        match self.settings.format {
+            GltfExportFormat::Glb => {
+                self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+                self.buffer_views.push(json!({
+                    "buffer": 0,
+                    "byteOffset": self.buffer.len(),
+                    "byteLength": png.len(),
+                }));
+                self.buffer.extend_from_slice(&png);
+                Some(json!({
+                    "bufferView": self.buffer_views.len() - 1,
+                    "mimeType": "image/png",
+                }))
+            }
+            GltfExportFormat::Gltf => Some(json!({
+                "uri": format!("data:image/png;base64,{}", BASE64_STANDARD.encode(&png)),
+            })),
+        }
+    }
+
+    /// Returns the URI of a texture, relative to the directory of the exported file.
+    fn texture_uri(&self, path: &AssetPath) -> String {
+        let target = path.path();
+        let directory = self
+            .path
+            .as_ref()
+            .filter(|base| base.source() == path.source())
+            .and_then(|base| base.path().parent());
+        let relative = match directory {
+            Some(directory) => {
+                let common = directory
+                    .components()
+                    .zip(target.components())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let mut relative = PathBuf::new();
+                for _ in directory.components().skip(common) {
+                    relative.push("..");
+                }
+                relative.extend(target.components().skip(common));
+                relative
+            }
+            None => target.to_path_buf(),
+        };
+        // URIs always use forward slashes.
+        let relative = relative
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        utf8_percent_encode(&relative, URI).to_string()
+    }
+
+    fn add_skin(
+        &mut self,
+        skinned_mesh: &SkinnedMesh,
+        entity_nodes: &HashMap<Entity, usize>,
+    ) -> Result<usize, GltfExportError> {
+        let joints = skinned_mesh
+            .joints
+            .iter()
+            .map(|joint| {
+                entity_nodes
+                    .get(joint)
+                    .copied()
+                    .ok_or(GltfExportError::MissingJoint(*joint))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let id = skinned_mesh.inverse_bindposes.id();
+        if let Some(&index) = self.skin_indices.get(&(id, joints.clone())) {
+            return Ok(index);
+        }
+        let inverse_bindposes = self
+            .assets
+            .get(id)
+            .ok_or(GltfExportError::MissingAsset(id.untyped()))?;
+
+        let bytes: Vec<u8> = inverse_bindposes
+            .iter()
+            .flat_map(Mat4::to_cols_array)
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let accessor =
+            self.add_accessor(&bytes, inverse_bindposes.len(), FLOAT, "MAT4", None, None);
+        self.skins.push(json!({
+            "inverseBindMatrices": accessor,
+            "joints": joints,
+        }));
+        let index = self.skins.len() - 1;
+        self.skin_indices.insert((id, joints), index);
+        Ok(index)
+    }
+
+    /// Appends `bytes` to the buffer in a view of its own, and returns the index of an accessor
+    /// to it.
+    fn add_accessor(
+        &mut self,
+        bytes: &[u8],
+        count: usize,
+        component_type: u32,
+        ty: &str,
+        target: Option<u32>,
+        bounds: Option<(Value, Value)>,
+    ) -> usize {
+        // Accessors must be aligned to the size of their components.
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.push(view);
+
+        let mut accessor = json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": ty,
+        });
+        if let Some((min, max)) = bounds {
+            accessor["min"] = min;
+            accessor["max"] = max;
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+/// Encodes the first layer of `image` as a PNG, if its format allows it.
+fn encode_png(image: &Image) -> Option<Vec<u8>> {
+    let data = image.data.as_ref()?;
+    let (width, height) = (image.width(), image.height());
+    let color_type = match image.texture_descriptor.format {
+        TextureFormat::R8Unorm => ExtendedColorType::L8,
+        TextureFormat::Rg8Unorm => ExtendedColorType::La8,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => ExtendedColorType::Rgba8,
+        _ => {
+            let mut png = Vec::new();
+            image
+                .clone()
+                .try_into_dynamic()
+                .ok()?
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .ok()?;
+            return Some(png);
+        }
+    };
+    let length = width as usize * height as usize * color_type.bits_per_pixel() as usize / 8;
+    let mut png = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut png),
+        data.get(..length)?,
+        width,
+        height,
+        color_type,
+        ImageFormat::Png,
+    )
+    .ok()?;
+    Some(png)
+}
+
+/// Saves a [`Scene`] as a glTF file.
+///
+/// The scene is exported with [`GltfExporter::add_scene`], reading meshes, materials, skins and
+/// animation clips from its labeled assets. With the `bevy_animation` feature, all the labeled
+/// `AnimationClip`s are exported as animations.
+#[derive(TypePath)]
+pub struct GltfSaver<M: GltfExportMaterial> {
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M: GltfExportMaterial> Default for GltfSaver<M> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: GltfExportMaterial> AssetSaver for GltfSaver<M> {
+    type Asset = Scene;
+    type Settings = GltfExportSettings;
+    type OutputLoader = GltfLoader;
+    type Error = GltfExportError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, '_, Scene>,
+        settings: &Self::Settings,
+        asset_path: AssetPath<'_>,
+    ) -> Result<GltfLoaderSettings, Self::Error> {
+        let bytes = {
+            let mut exporter = GltfExporter::<_, M>::new(&asset, settings.clone())
+                .with_path(asset_path.into_owned());
+            exporter.add_scene(&asset.world)?;
+
+            #[cfg(feature = "bevy_animation")]
+            {
+                let mut labels: Vec<_> = asset.iter_labels().collect();
+                labels.sort();
+                for label in labels {
+                    if let Some(clip) = asset.get_handle::<bevy_animation::AnimationClip>(label) {
+                        exporter.add_animation(clip.id(), Some(label))?;
+                    }
+                }
+            }
+
+            exporter.finish()?
+        };
+        writer.write_all(&bytes).await?;
+        Ok(GltfLoaderSettings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use bevy_asset::{Asset, AssetId, AssetPath, Assets, Handle, RenderAssetUsages};
+    use bevy_color::{Color, LinearRgba};
+    use bevy_ecs::{component::Component, name::Name, world::World};
+    use bevy_image::Image;
+    use bevy_material::AlphaMode;
+    use bevy_math::{primitives::Cuboid, Mat4, Quat, Vec3};
+    use bevy_mesh::{
+        skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+        Mesh, Mesh3d, MeshBuilder, Meshable, PrimitiveTopology, VertexAttributeValues,
+    };
+    use bevy_reflect::TypePath;
+    use bevy_transform::components::Transform;
+    use image::ImageFormat;
+    use wgpu_types::{Extent3d, TextureDimension, TextureFormat};
+
+    use super::{GltfExportFormat, GltfExportMaterial, GltfExportSettings, GltfExporter};
+    use crate::GltfMaterial;
+
+    #[derive(Asset, TypePath, Clone)]
+    struct TestMaterial(GltfMaterial);
+
+    #[derive(Component)]
+    struct TestMaterial3d(Handle<TestMaterial>);
+
+    impl GltfExportMaterial for TestMaterial {
+        type Component = TestMaterial3d;
+
+        fn material_id(component: &TestMaterial3d) -> AssetId<Self> {
+            component.0.id()
+        }
+
+        fn to_gltf_material(&self) -> GltfMaterial {
+            self.0.clone()
+        }
+    }
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<TestMaterial>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<Assets<SkinnedMeshInverseBindposes>>();
+        world
+    }
+
+    #[test]
+    fn export_scene() {
+        let mut world = test_world();
+        let cube = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default().mesh().build());
+        let material = world
+            .resource_mut::<Assets<TestMaterial>>()
+            .add(TestMaterial(GltfMaterial {
+                base_color: Color::linear_rgb(1.0, 0.0, 0.0),
+                emissive: LinearRgba::rgb(4.0, 2.0, 0.0),
+                alpha_mode: AlphaMode::Mask(0.25),
+                ..Default::default()
+            }));
+        world
+            .spawn((
+                Name::new("Root"),
+                Transform::from_xyz(1.0, 2.0, 3.0),
+                Mesh3d(cube.clone()),
+                TestMaterial3d(material.clone()),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Name::new("Child"),
+                    Transform::from_rotation(Quat::from_rotation_y(1.0)),
+                    Mesh3d(cube),
+                    TestMaterial3d(material),
+                ));
+            });
+
+        for format in [GltfExportFormat::Glb, GltfExportFormat::Gltf] {
+            let mut exporter = GltfExporter::<_, TestMaterial>::new(
+                &world,
+                GltfExportSettings {
+                    format,
+                    ..Default::default()
+                },
+            );
+            exporter.add_scene(&world).unwrap();
+            let bytes = exporter.finish().unwrap();
+
+            let gltf = gltf::Gltf::from_slice(&bytes).unwrap();
+            let scene = gltf.default_scene().unwrap();
+            let root = scene.nodes().next().unwrap();
+            assert_eq!(root.name(), Some("Root"));
+            assert_eq!(root.transform().decomposed().0, [1.0, 2.0, 3.0]);
+            let child = root.children().next().unwrap();
+            assert_eq!(child.name(), Some("Child"));
+
+            // The mesh and material are shared.
+            assert_eq!(gltf.meshes().len(), 1);
+            assert_eq!(gltf.materials().len(), 1);
+            let primitive = root.mesh().unwrap().primitives().next().unwrap();
+            assert_eq!(primitive.mode(), gltf::mesh::Mode::Triangles);
+            assert_eq!(primitive.indices().unwrap().count(), 36);
+            assert_eq!(
+                primitive.get(&gltf::Semantic::Positions).unwrap().count(),
+                24
+            );
+            assert!(primitive.get(&gltf::Semantic::TexCoords(0)).is_some());
+
+            let material = primitive.material();
+            assert_eq!(
+                material.pbr_metallic_roughness().base_color_factor(),
+                [1.0, 0.0, 0.0, 1.0]
+            );
+            assert_eq!(material.alpha_mode(), gltf::material::AlphaMode::Mask);
+            assert_eq!(material.alpha_cutoff(), Some(0.25));
+            assert_eq!(material.emissive_factor(), [1.0, 0.5, 0.0]);
+            assert_eq!(material.emissive_strength(), Some(4.0));
+        }
+    }
+
+    #[test]
+    fn skip_empty_meshes() {
+        let mut world = test_world();
+        let empty = world.resource_mut::<Assets<Mesh>>().add(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new()),
+        );
+        world.spawn((Name::new("Empty"), Transform::default(), Mesh3d(empty)));
+
+        let mut exporter = GltfExporter::<_, TestMaterial>::new(&world, Default::default());
+        exporter.add_scene(&world).unwrap();
+        let gltf = gltf::Gltf::from_slice(&exporter.finish().unwrap()).unwrap();
+
+        // The node is kept, but without a mesh.
+        let node = gltf.nodes().next().unwrap();
+        assert_eq!(node.name(), Some("Empty"));
+        assert!(node.mesh().is_none());
+        assert_eq!(gltf.meshes().len(), 0);
+        assert_eq!(gltf.accessors().len(), 0);
+    }
+
+    #[test]
+    fn texture_uri() {
+        let world = test_world();
+        let exporter = GltfExporter::<_, TestMaterial>::new(&world, Default::default())
+            .with_path("levels/generated/level 1.glb");
+        assert_eq!(
+            exporter.texture_uri(&AssetPath::from("levels/textures/brick wall.png")),
+            "../textures/brick%20wall.png"
+        );
+        assert_eq!(
+            exporter.texture_uri(&AssetPath::from("levels/generated/floor.png")),
+            "floor.png"
+        );
+        assert_eq!(
+            exporter.texture_uri(&AssetPath::from("other://floor.png")),
+            "floor.png"
+        );
+    }
+
+    #[test]
+    fn embed_textures() {
+        let mut world = test_world();
+        let cube = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default().mesh().build());
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let mut image = |pixel: &[u8], format| {
+            images.add(Image::new_fill(
+                Extent3d {
+                    width: 2,
+                    height: 2,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                pixel,
+                format,
+                RenderAssetUsages::default(),
+            ))
+        };
+        let base_color = image(&[255, 0, 0, 255], TextureFormat::Rgba8UnormSrgb);
+        let normal_map = image(&[128, 128, 255, 255], TextureFormat::Rgba8Unorm);
+        let material = world
+            .resource_mut::<Assets<TestMaterial>>()
+            .add(TestMaterial(GltfMaterial {
+                base_color_texture: Some(base_color),
+                normal_map_texture: Some(normal_map),
+                ..Default::default()
+            }));
+        world.spawn((Transform::default(), Mesh3d(cube), TestMaterial3d(material)));
+
+        for format in [GltfExportFormat::Glb, GltfExportFormat::Gltf] {
+            let mut exporter = GltfExporter::<_, TestMaterial>::new(
+                &world,
+                GltfExportSettings {
+                    format,
+                    ..Default::default()
+                },
+            );
+            exporter.add_scene(&world).unwrap();
+            let gltf = gltf::Gltf::from_slice(&exporter.finish().unwrap()).unwrap();
+            assert_eq!(gltf.images().len(), 2);
+
+            let pixels: Vec<_> = gltf
+                .images()
+                .map(|image| {
+                    let png = match image.source() {
+                        gltf::image::Source::View { view, mime_type } => {
+                            assert_eq!(format, GltfExportFormat::Glb);
+                            assert_eq!(mime_type, "image/png");
+                            let blob = gltf.blob.as_deref().unwrap();
+                            blob[view.offset()..view.offset() + view.length()].to_vec()
+                        }
+                        gltf::image::Source::Uri { uri, .. } => {
+                            assert_eq!(format, GltfExportFormat::Gltf);
+                            let data = uri.strip_prefix("data:image/png;base64,").unwrap();
+                            BASE64_STANDARD.decode(data).unwrap()
+                        }
+                    };
+                    let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+                        .unwrap()
+                        .into_rgba8();
+                    assert_eq!(image.dimensions(), (2, 2));
+                    image.get_pixel(1, 1).0
+                })
+                .collect();
+            assert_eq!(pixels, [[255, 0, 0, 255], [128, 128, 255, 255]]);
+        }
+    }
+
+    #[test]
+    fn export_skin() {
+        let mut world = test_world();
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3]; 3])
+            .with_inserted_attribute(
+                Mesh::ATTRIBUTE_JOINT_INDEX,
+                VertexAttributeValues::Uint16x4(vec![[0, 1, 0, 0]; 3]),
+            )
+            .with_inserted_attribute(
+                Mesh::ATTRIBUTE_JOINT_WEIGHT,
+                vec![[0.5f32, 0.5, 0.0, 0.0]; 3],
+            ),
+        );
+        let inverse_bindposes = world
+            .resource_mut::<Assets<SkinnedMeshInverseBindposes>>()
+            .add(SkinnedMeshInverseBindposes::from(vec![
+                Mat4::IDENTITY,
+                Mat4::from_translation(-Vec3::Y),
+            ]));
+        let root = world.spawn(Transform::default()).id();
+        let hips = world
+            .spawn((Transform::default(), bevy_ecs::hierarchy::ChildOf(root)))
+            .id();
+        let spine = world
+            .spawn((
+                Transform::from_xyz(0.0, 1.0, 0.0),
+                bevy_ecs::hierarchy::ChildOf(hips),
+            ))
+            .id();
+        world.spawn((
+            Transform::default(),
+            bevy_ecs::hierarchy::ChildOf(root),
+            Mesh3d(mesh),
+            SkinnedMesh {
+                inverse_bindposes,
+                joints: vec![hips, spine],
+            },
+        ));
+
+        let mut exporter = GltfExporter::<_, TestMaterial>::new(&world, Default::default());
+        exporter.add_entities(&world, [root]).unwrap();
+        let gltf = gltf::Gltf::from_slice(&exporter.finish().unwrap()).unwrap();
+
+        let skin = gltf.skins().next().unwrap();
+        let joints: Vec<_> = skin.joints().map(|joint| joint.index()).collect();
+        assert_eq!(joints, [1, 2]);
+        assert_eq!(skin.inverse_bind_matrices().unwrap().count(), 2);
+        let skinned = gltf.nodes().find(|node| node.skin().is_some()).unwrap();
+        let primitive = skinned.mesh().unwrap().primitives().next().unwrap();
+        assert!(primitive.get(&gltf::Semantic::Joints(0)).is_some());
+        assert!(primitive.get(&gltf::Semantic::Weights(0)).is_some());
+    }
+}
@@ -126,9 +126,16 @@
 //! \**`KHR_texture_transform` is only supported on `base_color_texture`, see [#15310](https://github.com/bevyengine/bevy/issues/15310).
 //!
 //! See the [glTF Extension Registry](https://github.com/KhronosGroup/glTF/blob/main/extensions/README.md) for more information on extensions.
+//!
+//! # Exporting
+//!
+//! Scenes and entity hierarchies can be exported as glTF files with a [`GltfExporter`], or saved
+//! as assets with a [`GltfSaver`]. Materials are exported through [`GltfExportMaterial`], which
+//! `bevy_pbr` implements for `StandardMaterial`.
 
 mod assets;
 pub mod convert_coordinates;
+mod export;
 mod label;
 mod loader;
 mod material;
@@ -160,7 +167,7 @@ pub mod prelude {
 
 use crate::{convert_coordinates::GltfConvertCoordinates, extensions::GltfExtensionHandlers};
 
-pub use {assets::*, label::GltfAssetLabel, loader::*, material::GltfMaterial};
+pub use {assets::*, export::*, label::GltfAssetLabel, loader::*, material::GltfMaterial};
 
 /// Re-exports for GLTF
 pub mod gltf {
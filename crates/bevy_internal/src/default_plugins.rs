@@ -69,6 +69,8 @@ plugin_group! {
         bevy_gltf:::GltfPlugin,
         #[cfg(feature = "bevy_pbr")]
         bevy_pbr:::PbrPlugin,
+        #[cfg(feature = "bevy_mesh_formats")]
+        bevy_mesh_formats:::MeshFormatsPlugin,
         #[cfg(feature = "bevy_audio")]
         bevy_audio:::AudioPlugin,
         #[cfg(feature = "bevy_gilrs")]
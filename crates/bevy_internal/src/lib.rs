@@ -63,6 +63,8 @@ pub use bevy_material as material;
 pub use bevy_math as math;
 #[cfg(feature = "bevy_mesh")]
 pub use bevy_mesh as mesh;
+#[cfg(feature = "bevy_mesh_formats")]
+pub use bevy_mesh_formats as mesh_formats;
 #[cfg(feature = "bevy_pbr")]
 pub use bevy_pbr as pbr;
 #[cfg(feature = "bevy_picking")]
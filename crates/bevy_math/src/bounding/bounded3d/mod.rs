@@ -582,7 +582,7 @@ impl BoundingSphere {
         } else {
             // The point is outside the sphere.
             // Find the closest point on the surface of the sphere.
-            let dir_to_point = point / ops::sqrt(distance_squared);
+            let dir_to_point = (point - self.center) / ops::sqrt(distance_squared);
             self.center + radius * dir_to_point
         }
     }
@@ -812,6 +812,9 @@ mod bounding_sphere_tests {
             sphere.closest_point(Vec3::new(0.25, 0.1, 0.3)),
             Vec3A::new(0.25, 0.1, 0.3)
         );
+
+        let sphere = BoundingSphere::new(Vec3::new(2.0, 0.0, 0.0), 1.0);
+        assert_eq!(sphere.closest_point(Vec3::X * 10.0), Vec3A::X * 3.0);
     }
 
     #[test]
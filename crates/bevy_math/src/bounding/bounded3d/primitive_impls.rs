@@ -11,7 +11,7 @@ use crate::{
 };
 
 #[cfg(feature = "alloc")]
-use crate::primitives::Polyline3d;
+use crate::primitives::{ConvexPolyhedron, Polyline3d};
 
 use super::{Aabb3d, Bounded3d, BoundingSphere};
 
@@ -97,6 +97,17 @@ impl Bounded3d for Polyline3d {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl Bounded3d for ConvexPolyhedron {
+    fn aabb_3d(&self, isometry: impl Into<Isometry3d>) -> Aabb3d {
+        Aabb3d::from_point_cloud(isometry, self.vertices().iter().copied())
+    }
+
+    fn bounding_sphere(&self, isometry: impl Into<Isometry3d>) -> BoundingSphere {
+        BoundingSphere::from_point_cloud(isometry, self.vertices())
+    }
+}
+
 impl Bounded3d for Cuboid {
     fn aabb_3d(&self, isometry: impl Into<Isometry3d>) -> Aabb3d {
         let isometry = isometry.into();
@@ -372,8 +383,8 @@ mod tests {
     use crate::{
         bounding::Bounded3d,
         primitives::{
-            Capsule3d, Cone, ConicalFrustum, Cuboid, Cylinder, InfinitePlane3d, Line3d, Polyline3d,
-            Segment3d, Sphere, Torus, Triangle3d,
+            Capsule3d, Cone, ConicalFrustum, ConvexPolyhedron, Cuboid, Cylinder, InfinitePlane3d,
+            Line3d, Polyline3d, Segment3d, Sphere, Torus, Triangle3d,
         },
         Dir3,
     };
@@ -459,6 +470,28 @@ mod tests {
         assert_eq!(bounding_sphere.radius(), ops::hypot(1.0, 0.5));
     }
 
+    #[test]
+    fn convex_polyhedron() {
+        let polyhedron = ConvexPolyhedron::convex_hull([
+            Vec3::ONE,
+            Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::NEG_ONE,
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(0.5, 0.0, -0.5),
+        ])
+        .unwrap();
+        let translation = Vec3::new(2.0, 1.0, 0.0);
+
+        let aabb = polyhedron.aabb_3d(translation);
+        assert_eq!(aabb.min, Vec3A::new(1.0, 0.0, -1.0));
+        assert_eq!(aabb.max, Vec3A::new(3.0, 2.0, 1.0));
+
+        // The point inside the hull doesn't affect the bounding sphere.
+        let bounding_sphere = polyhedron.bounding_sphere(translation);
+        assert_eq!(bounding_sphere.center, Vec3A::new(2.0, 1.0, -0.5));
+        assert_eq!(bounding_sphere.radius(), ops::sqrt(4.25));
+    }
+
     #[test]
     fn polyline() {
         let polyline = Polyline3d::new([
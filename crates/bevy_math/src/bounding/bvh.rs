@@ -0,0 +1,437 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{Aabb2d, Aabb3d, BoundingCircle, BoundingSphere, BoundingVolume, RayCast2d, RayCast3d};
+
+/// A [`BoundingVolume`] that can be used to build a [`Bvh`].
+pub trait BvhVolume: BoundingVolume + Clone {
+    /// The number of axes along which volumes can be partitioned.
+    const AXES: usize;
+
+    /// Returns the coordinate of the center of the volume along the given `axis`.
+    fn center_along(&self, axis: usize) -> f32;
+}
+
+impl BvhVolume for Aabb2d {
+    const AXES: usize = 2;
+
+    #[inline]
+    fn center_along(&self, axis: usize) -> f32 {
+        (self.min[axis] + self.max[axis]) / 2.
+    }
+}
+
+impl BvhVolume for Aabb3d {
+    const AXES: usize = 3;
+
+    #[inline]
+    fn center_along(&self, axis: usize) -> f32 {
+        (self.min[axis] + self.max[axis]) / 2.
+    }
+}
+
+impl BvhVolume for BoundingCircle {
+    const AXES: usize = 2;
+
+    #[inline]
+    fn center_along(&self, axis: usize) -> f32 {
+        self.center[axis]
+    }
+}
+
+impl BvhVolume for BoundingSphere {
+    const AXES: usize = 3;
+
+    #[inline]
+    fn center_along(&self, axis: usize) -> f32 {
+        self.center[axis]
+    }
+}
+
+/// A node of a [`Bvh`].
+#[derive(Clone, Debug)]
+struct BvhNode<V> {
+    /// A volume containing every primitive below this node.
+    volume: V,
+    /// For leaves, the index of the first primitive of the leaf.
+    /// Otherwise, the index of the first of the two child nodes.
+    start: u32,
+    /// The number of primitives in a leaf, or zero for interior nodes.
+    count: u32,
+}
+
+/// A bounding volume hierarchy, a binary tree of [bounding volumes](BoundingVolume) used to speed
+/// up spatial queries against a large number of primitives.
+///
+/// Each primitive is identified by its index in the list of volumes the hierarchy was built from.
+/// The hierarchy is immutable; if primitives move, a new one has to be built.
+///
+/// ```
+/// # use bevy_math::{bounding::{Aabb3d, Bvh, IntersectsVolume, RayCast3d}, Dir3, Vec3};
+/// let volumes: Vec<Aabb3d> = (0..100)
+///     .map(|i| Aabb3d::new(Vec3::new(i as f32 * 2.0, 0.0, 0.0), Vec3::splat(0.5)))
+///     .collect();
+/// let bvh = Bvh::new(volumes.iter().copied());
+///
+/// // Find all of the volumes overlapping a region.
+/// let region = Aabb3d::new(Vec3::new(10.0, 0.0, 0.0), Vec3::splat(2.0));
+/// let mut overlapping: Vec<usize> = bvh.query(|volume| volume.intersects(&region)).collect();
+/// overlapping.sort();
+/// assert_eq!(overlapping, [4, 5, 6]);
+///
+/// // Find the closest volume hit by a ray.
+/// let ray = RayCast3d::new(Vec3::new(-10.0, 0.0, 0.0), Dir3::X, f32::MAX);
+/// let hit = bvh.cast_ray(&ray, |index, _| ray.aabb_intersection_at(&volumes[index]));
+/// assert_eq!(hit, Some((0, 9.5)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Bvh<V> {
+    nodes: Vec<BvhNode<V>>,
+    /// The volumes of the primitives, ordered so that each leaf refers to a contiguous range.
+    volumes: Vec<V>,
+    /// The original index of each primitive in `volumes`.
+    indices: Vec<usize>,
+}
+
+impl<V> Default for Bvh<V> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            volumes: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+}
+
+impl<V: BvhVolume> Bvh<V> {
+    /// The maximum number of primitives stored in a single leaf of the hierarchy.
+    pub const MAX_LEAF_SIZE: usize = 4;
+
+    /// Builds a hierarchy over the given bounding volumes.
+    ///
+    /// Primitives are recursively split in half along the axis in which their centers are spread
+    /// out the most, which produces a balanced tree in O(n * log n).
+    pub fn new(volumes: impl IntoIterator<Item = V>) -> Self {
+        let mut primitives: Vec<(usize, V)> = volumes.into_iter().enumerate().collect();
+        let mut nodes = Vec::new();
+        if !primitives.is_empty() {
+            nodes.reserve(2 * primitives.len().div_ceil(Self::MAX_LEAF_SIZE));
+            nodes.push(BvhNode {
+                volume: primitives[0].1.clone(),
+                start: 0,
+                count: 0,
+            });
+            Self::build(&mut nodes, 0, &mut primitives, 0);
+        }
+        let (indices, volumes) = primitives.into_iter().unzip();
+        Self {
+            nodes,
+            volumes,
+            indices,
+        }
+    }
+
+    fn build(
+        nodes: &mut Vec<BvhNode<V>>,
+        node: usize,
+        primitives: &mut [(usize, V)],
+        offset: usize,
+    ) {
+        let volume = primitives[1..]
+            .iter()
+            .fold(primitives[0].1.clone(), |volume, (_, other)| {
+                volume.merge(other)
+            });
+
+        if primitives.len() <= Self::MAX_LEAF_SIZE {
+            nodes[node] = BvhNode {
+                volume,
+                start: offset as u32,
+                count: primitives.len() as u32,
+            };
+            return;
+        }
+
+        // Split along the axis with the largest spread of centers.
+        let spread = |axis: usize| {
+            let (min, max) = primitives
+                .iter()
+                .map(|(_, volume)| volume.center_along(axis))
+                .fold((f32::MAX, f32::MIN), |(min, max), center| {
+                    (min.min(center), max.max(center))
+                });
+            max - min
+        };
+        let axis = (0..V::AXES)
+            .max_by(|&a, &b| spread(a).total_cmp(&spread(b)))
+            .unwrap_or(0);
+        let mid = primitives.len() / 2;
+        primitives.select_nth_unstable_by(mid, |(_, a), (_, b)| {
+            a.center_along(axis).total_cmp(&b.center_along(axis))
+        });
+
+        let children = nodes.len();
+        for _ in 0..2 {
+            nodes.push(BvhNode {
+                volume: volume.clone(),
+                start: 0,
+                count: 0,
+            });
+        }
+        nodes[node] = BvhNode {
+            volume,
+            start: children as u32,
+            count: 0,
+        };
+        let (left, right) = primitives.split_at_mut(mid);
+        Self::build(nodes, children, left, offset);
+        Self::build(nodes, children + 1, right, offset + mid);
+    }
+
+    /// Returns the number of primitives in the hierarchy.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Returns `true` if the hierarchy contains no primitives.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.volumes.is_empty()
+    }
+
+    /// Returns a volume containing every primitive in the hierarchy, or `None` if it is empty.
+    #[inline]
+    pub fn volume(&self) -> Option<&V> {
+        self.nodes.first().map(|root| &root.volume)
+    }
+
+    /// Returns an iterator over the indices of the primitives whose volumes satisfy `predicate`.
+    ///
+    /// The predicate is also used to skip entire subtrees, so it must hold for any volume that
+    /// contains a volume it holds for. Intersection tests like [`IntersectsVolume`] satisfy this.
+    ///
+    /// [`IntersectsVolume`]: super::IntersectsVolume
+    pub fn query<F: FnMut(&V) -> bool>(&self, predicate: F) -> BvhQuery<'_, V, F> {
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        BvhQuery {
+            bvh: self,
+            predicate,
+            stack,
+            leaf: 0..0,
+        }
+    }
+
+    /// Finds the closest primitive along a ray, visiting nodes in order of distance.
+    ///
+    /// `entry` returns the distance at which the ray enters a volume, if it does at all.
+    fn cast(
+        &self,
+        max: f32,
+        entry: impl Fn(&V) -> Option<f32>,
+        mut intersect: impl FnMut(usize, f32) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let mut closest = None;
+        let mut max = max;
+        let mut stack = Vec::new();
+        if let Some(distance) = self.volume().and_then(&entry) {
+            stack.push((0, distance));
+        }
+        while let Some((node, distance)) = stack.pop() {
+            if distance > max {
+                continue;
+            }
+            let node = &self.nodes[node];
+            let start = node.start as usize;
+            if node.count > 0 {
+                for primitive in start..start + node.count as usize {
+                    if entry(&self.volumes[primitive]).is_none_or(|distance| distance > max) {
+                        continue;
+                    }
+                    let index = self.indices[primitive];
+                    if let Some(distance) = intersect(index, max)
+                        && distance <= max
+                    {
+                        max = distance;
+                        closest = Some((index, distance));
+                    }
+                }
+            } else {
+                // Push the farther child first so that the closer one is visited first.
+                let [near, far] =
+                    [start, start + 1].map(|child| (child, entry(&self.nodes[child].volume)));
+                let (near, far) = match (near.1, far.1) {
+                    (Some(a), Some(b)) if b < a => (far, near),
+                    _ => (near, far),
+                };
+                for (child, distance) in [far, near] {
+                    if let Some(distance) = distance {
+                        stack.push((child, distance));
+                    }
+                }
+            }
+        }
+        closest
+    }
+}
+
+impl Bvh<Aabb2d> {
+    /// Finds the closest primitive hit by the given `ray`.
+    ///
+    /// Primitives are visited roughly in order of distance, skipping those whose volumes the ray
+    /// doesn't enter before the closest hit found so far. For each of them, `intersect` is called
+    /// with the index of the primitive and the distance of the closest hit so far, and should
+    /// return the distance at which the ray hits the primitive, if it does.
+    ///
+    /// Returns the index of the closest primitive and the distance at which it was hit.
+    pub fn cast_ray(
+        &self,
+        ray: &RayCast2d,
+        intersect: impl FnMut(usize, f32) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        self.cast(ray.max, |aabb| ray.aabb_intersection_at(aabb), intersect)
+    }
+}
+
+impl Bvh<Aabb3d> {
+    /// Finds the closest primitive hit by the given `ray`.
+    ///
+    /// Primitives are visited roughly in order of distance, skipping those whose volumes the ray
+    /// doesn't enter before the closest hit found so far. For each of them, `intersect` is called
+    /// with the index of the primitive and the distance of the closest hit so far, and should
+    /// return the distance at which the ray hits the primitive, if it does.
+    ///
+    /// Returns the index of the closest primitive and the distance at which it was hit.
+    pub fn cast_ray(
+        &self,
+        ray: &RayCast3d,
+        intersect: impl FnMut(usize, f32) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        self.cast(ray.max, |aabb| ray.aabb_intersection_at(aabb), intersect)
+    }
+}
+
+/// An iterator over the primitives of a [`Bvh`] matching a predicate.
+///
+/// Created by [`Bvh::query`].
+pub struct BvhQuery<'a, V, F> {
+    bvh: &'a Bvh<V>,
+    predicate: F,
+    stack: Vec<u32>,
+    leaf: Range<usize>,
+}
+
+impl<V, F: FnMut(&V) -> bool> Iterator for BvhQuery<'_, V, F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for primitive in self.leaf.by_ref() {
+                if (self.predicate)(&self.bvh.volumes[primitive]) {
+                    return Some(self.bvh.indices[primitive]);
+                }
+            }
+
+            let node = &self.bvh.nodes[self.stack.pop()? as usize];
+            if !(self.predicate)(&node.volume) {
+                continue;
+            }
+            if node.count > 0 {
+                self.leaf = node.start as usize..(node.start + node.count) as usize;
+            } else {
+                self.stack.push(node.start + 1);
+                self.stack.push(node.start);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::Bvh;
+    use crate::{
+        bounding::{Aabb2d, Aabb3d, BoundingVolume, IntersectsVolume, RayCast3d},
+        Dir3, Vec2, Vec3,
+    };
+
+    fn grid() -> Vec<Aabb3d> {
+        (0..1000)
+            .map(|i| {
+                let center = Vec3::new((i % 10) as f32, (i / 10 % 10) as f32, (i / 100) as f32);
+                Aabb3d::new(center * 2.0, Vec3::splat(0.5))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bvh_query() {
+        let volumes = grid();
+        let bvh = Bvh::new(volumes.iter().copied());
+        assert_eq!(bvh.len(), 1000);
+        assert_eq!(
+            bvh.volume(),
+            Some(&Aabb3d::new(Vec3::splat(9.0), Vec3::splat(9.5)))
+        );
+
+        let region = Aabb3d::new(Vec3::new(4.0, 6.0, 3.0), Vec3::splat(2.4));
+        let mut found: Vec<usize> = bvh.query(|volume| volume.intersects(&region)).collect();
+        found.sort_unstable();
+        let expected: Vec<usize> = (0..volumes.len())
+            .filter(|&i| volumes[i].intersects(&region))
+            .collect();
+        assert_eq!(found.len(), 18);
+        assert_eq!(found, expected);
+
+        assert!(Bvh::<Aabb2d>::new([]).query(|_| true).next().is_none());
+    }
+
+    #[test]
+    fn bvh_cast_ray() {
+        let volumes = grid();
+        let bvh = Bvh::new(volumes.iter().copied());
+
+        // A diagonal ray through the grid hits the boxes along the diagonal.
+        let ray = RayCast3d::new(Vec3::splat(-5.0), Dir3::new(Vec3::ONE).unwrap(), f32::MAX);
+        let mut tested = 0;
+        let hit = bvh.cast_ray(&ray, |index, _| {
+            tested += 1;
+            ray.aabb_intersection_at(&volumes[index])
+        });
+        assert_eq!(hit.map(|(index, _)| index), Some(0));
+        // Only the boxes close to the start of the ray should have been tested.
+        assert!(tested < 20);
+
+        // Primitives that the callback rejects are skipped.
+        let hit = bvh.cast_ray(&ray, |index, _| {
+            (index != 0)
+                .then(|| ray.aabb_intersection_at(&volumes[index]))
+                .flatten()
+        });
+        assert_eq!(hit.map(|(index, _)| index), Some(111));
+
+        // Rays that miss everything don't hit anything.
+        let ray = RayCast3d::new(Vec3::splat(-5.0), Dir3::NEG_X, f32::MAX);
+        assert!(bvh.cast_ray(&ray, |_, distance| Some(distance)).is_none());
+    }
+
+    #[test]
+    fn bvh_2d() {
+        let volumes: Vec<Aabb2d> = (0..50)
+            .map(|i| Aabb2d::new(Vec2::new(i as f32, 0.0), Vec2::splat(0.25)))
+            .collect();
+        let bvh = Bvh::new(volumes.iter().copied());
+        let region = Aabb2d::new(Vec2::new(10.0, 0.0), Vec2::splat(1.0));
+        let mut found: Vec<usize> = bvh.query(|volume| volume.intersects(&region)).collect();
+        found.sort_unstable();
+        assert_eq!(found, [9, 10, 11]);
+        assert_eq!(
+            bvh.volume().map(BoundingVolume::center),
+            Some(Vec2::new(24.5, 0.0))
+        );
+    }
+}
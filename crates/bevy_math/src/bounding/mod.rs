@@ -111,6 +111,15 @@ pub use bounded2d::*;
 mod bounded3d;
 pub use bounded3d::*;
 
+#[cfg(feature = "alloc")]
+mod bvh;
+#[cfg(feature = "alloc")]
+pub use bvh::*;
+#[cfg(feature = "alloc")]
+mod spatial;
+#[cfg(feature = "alloc")]
+pub use spatial::*;
+
 mod raycast2d;
 pub use raycast2d::*;
 mod raycast3d;
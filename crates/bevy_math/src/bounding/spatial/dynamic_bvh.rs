@@ -0,0 +1,597 @@
+use alloc::vec::Vec;
+
+use super::{SpatialVolume, VolumeCast};
+use crate::ops;
+
+/// The index used for missing nodes.
+const NULL: u32 = u32::MAX;
+
+/// A handle to a volume stored in a [`DynamicBvh`].
+///
+/// Handles of removed volumes may be reused for volumes inserted later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DynamicBvhId(u32);
+
+#[derive(Clone, Debug)]
+enum NodeKind<T> {
+    Leaf(T),
+    Branch([u32; 2]),
+    /// An unused node, with the index of the next unused node.
+    Free(u32),
+}
+
+#[derive(Clone, Debug)]
+struct Node<V, T> {
+    volume: V,
+    parent: u32,
+    /// The height of the subtree below this node, zero for leaves.
+    height: u32,
+    kind: NodeKind<T>,
+}
+
+/// A dynamic AABB tree: a [bounding volume hierarchy](super::super::Bvh) that supports
+/// inserting, updating and removing volumes, each associated with some data of type `T`.
+///
+/// New volumes are inserted next to the sibling that increases the surface area of the tree the
+/// least, and the tree is rebalanced with rotations, so that queries stay fast as volumes move.
+///
+/// ```
+/// # use bevy_math::{bounding::{BoundingSphere, DynamicBvh, RayCast3d}, Dir3, Vec3};
+/// let mut bvh = DynamicBvh::new();
+/// let a = bvh.insert(BoundingSphere::new(Vec3::new(0.0, 0.0, 5.0), 1.0), "a");
+/// let b = bvh.insert(BoundingSphere::new(Vec3::new(0.0, 0.0, 10.0), 1.0), "b");
+///
+/// // Find the volumes overlapping a region.
+/// let region = BoundingSphere::new(Vec3::new(0.0, 1.0, 5.0), 1.0);
+/// assert_eq!(bvh.overlapping(&region).map(|(id, ..)| id).collect::<Vec<_>>(), [a]);
+///
+/// // Cast a ray, ignoring the volume with the data "a".
+/// let ray = RayCast3d::new(Vec3::ZERO, Dir3::Z, f32::MAX);
+/// assert_eq!(bvh.cast(&ray, |_, data| *data != "a"), Some((b, 9.0)));
+///
+/// // Move a volume.
+/// bvh.update(b, BoundingSphere::new(Vec3::new(0.0, 0.0, 2.0), 1.0));
+/// assert_eq!(bvh.nearest(Vec3::ZERO.into(), |_, _| true), Some((b, 1.0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynamicBvh<V, T> {
+    nodes: Vec<Node<V, T>>,
+    root: u32,
+    free: u32,
+    len: usize,
+}
+
+impl<V, T> Default for DynamicBvh<V, T> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: NULL,
+            free: NULL,
+            len: 0,
+        }
+    }
+}
+
+impl<V: SpatialVolume, T> DynamicBvh<V, T> {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of volumes in the tree.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree contains no volumes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every volume from the tree.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns a volume containing every volume in the tree, or `None` if it is empty.
+    pub fn volume(&self) -> Option<&V> {
+        self.nodes.get(self.root as usize).map(|root| &root.volume)
+    }
+
+    /// Inserts a volume with the given data, returning a handle to it.
+    pub fn insert(&mut self, volume: V, data: T) -> DynamicBvhId {
+        let leaf = self.allocate(Node {
+            volume,
+            parent: NULL,
+            height: 0,
+            kind: NodeKind::Leaf(data),
+        });
+        self.attach(leaf);
+        self.len += 1;
+        DynamicBvhId(leaf)
+    }
+
+    /// Moves the volume with the given handle, returning `false` if it doesn't exist.
+    pub fn update(&mut self, id: DynamicBvhId, volume: V) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        self.detach(id.0);
+        self.nodes[id.0 as usize].volume = volume;
+        self.attach(id.0);
+        true
+    }
+
+    /// Removes the volume with the given handle, returning its data if it exists.
+    pub fn remove(&mut self, id: DynamicBvhId) -> Option<T> {
+        self.get(id)?;
+        self.detach(id.0);
+        self.len -= 1;
+        match self.deallocate(id.0) {
+            NodeKind::Leaf(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns the volume with the given handle and its data, if it exists.
+    pub fn get(&self, id: DynamicBvhId) -> Option<(&V, &T)> {
+        match self.nodes.get(id.0 as usize) {
+            Some(Node {
+                volume,
+                kind: NodeKind::Leaf(data),
+                ..
+            }) => Some((volume, data)),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the data of the volume with the given handle, if it exists.
+    pub fn get_mut(&mut self, id: DynamicBvhId) -> Option<&mut T> {
+        match self.nodes.get_mut(id.0 as usize) {
+            Some(Node {
+                kind: NodeKind::Leaf(data),
+                ..
+            }) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over every volume in the tree and its data, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (DynamicBvhId, &V, &T)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| match &node.kind {
+                NodeKind::Leaf(data) => Some((DynamicBvhId(index as u32), &node.volume, data)),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator over the volumes that satisfy `predicate` and their data.
+    ///
+    /// The predicate is also used to skip entire subtrees, so it must hold for any volume that
+    /// contains a volume it holds for. Intersection tests like
+    /// [`IntersectsVolume`](super::super::IntersectsVolume) satisfy this.
+    pub fn query<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&V) -> bool + 'a,
+    ) -> impl Iterator<Item = (DynamicBvhId, &'a V, &'a T)> + 'a {
+        let mut stack = Vec::new();
+        if self.root != NULL {
+            stack.push(self.root);
+        }
+        core::iter::from_fn(move || {
+            while let Some(index) = stack.pop() {
+                let node = &self.nodes[index as usize];
+                if !predicate(&node.volume) {
+                    continue;
+                }
+                match &node.kind {
+                    NodeKind::Leaf(data) => return Some((DynamicBvhId(index), &node.volume, data)),
+                    NodeKind::Branch(children) => stack.extend(children),
+                    NodeKind::Free(_) => {}
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns an iterator over the volumes that intersect `volume` and their data.
+    pub fn overlapping<'a>(
+        &'a self,
+        volume: &'a V,
+    ) -> impl Iterator<Item = (DynamicBvhId, &'a V, &'a T)> + 'a {
+        self.query(move |other| volume.intersects(other))
+    }
+
+    /// Returns the closest volume hit by `cast` for which `filter` returns `true`, and the distance
+    /// along the cast at which it was hit.
+    pub fn cast<C: VolumeCast<V>>(
+        &self,
+        cast: &C,
+        mut filter: impl FnMut(DynamicBvhId, &T) -> bool,
+    ) -> Option<(DynamicBvhId, f32)> {
+        let mut closest = None;
+        let mut max = f32::INFINITY;
+        let mut stack = Vec::new();
+        if let Some(distance) = self.volume().and_then(|volume| cast.cast_distance(volume)) {
+            stack.push((self.root, distance));
+        }
+        while let Some((index, distance)) = stack.pop() {
+            if distance >= max {
+                continue;
+            }
+            match &self.nodes[index as usize].kind {
+                NodeKind::Leaf(data) => {
+                    if filter(DynamicBvhId(index), data) {
+                        max = distance;
+                        closest = Some((DynamicBvhId(index), distance));
+                    }
+                }
+                NodeKind::Branch(children) => {
+                    self.push_ordered(&mut stack, *children, |volume| cast.cast_distance(volume));
+                }
+                NodeKind::Free(_) => {}
+            }
+        }
+        closest
+    }
+
+    /// Returns the volume closest to `point` for which `filter` returns `true`, and its distance
+    /// from the point. The distance is zero if the point is inside of the volume.
+    pub fn nearest(
+        &self,
+        point: V::Translation,
+        mut filter: impl FnMut(DynamicBvhId, &T) -> bool,
+    ) -> Option<(DynamicBvhId, f32)> {
+        let mut closest = None;
+        let mut max = f32::INFINITY;
+        let mut stack = Vec::new();
+        if let Some(volume) = self.volume() {
+            stack.push((self.root, volume.distance_squared_to_point(point)));
+        }
+        while let Some((index, distance)) = stack.pop() {
+            if distance >= max {
+                continue;
+            }
+            match &self.nodes[index as usize].kind {
+                NodeKind::Leaf(data) => {
+                    if filter(DynamicBvhId(index), data) {
+                        max = distance;
+                        closest = Some((DynamicBvhId(index), distance));
+                    }
+                }
+                NodeKind::Branch(children) => {
+                    self.push_ordered(&mut stack, *children, |volume| {
+                        Some(volume.distance_squared_to_point(point))
+                    });
+                }
+                NodeKind::Free(_) => {}
+            }
+        }
+        closest.map(|(id, distance_squared)| (id, ops::sqrt(distance_squared)))
+    }
+
+    /// Pushes the children that `distance` returns a value for onto the stack, farthest first so
+    /// that the closest one is visited first.
+    fn push_ordered(
+        &self,
+        stack: &mut Vec<(u32, f32)>,
+        children: [u32; 2],
+        distance: impl Fn(&V) -> Option<f32>,
+    ) {
+        let [a, b] = children.map(|child| (child, distance(&self.nodes[child as usize].volume)));
+        let (near, far) = match (a.1, b.1) {
+            (Some(a_distance), Some(b_distance)) if b_distance < a_distance => (b, a),
+            _ => (a, b),
+        };
+        for (child, distance) in [far, near] {
+            if let Some(distance) = distance {
+                stack.push((child, distance));
+            }
+        }
+    }
+
+    fn allocate(&mut self, node: Node<V, T>) -> u32 {
+        if self.free == NULL {
+            self.nodes.push(node);
+            return (self.nodes.len() - 1) as u32;
+        }
+        let index = self.free;
+        if let NodeKind::Free(next) = self.nodes[index as usize].kind {
+            self.free = next;
+        }
+        self.nodes[index as usize] = node;
+        index
+    }
+
+    fn deallocate(&mut self, index: u32) -> NodeKind<T> {
+        let kind = core::mem::replace(
+            &mut self.nodes[index as usize].kind,
+            NodeKind::Free(self.free),
+        );
+        self.free = index;
+        kind
+    }
+
+    fn children(&self, index: u32) -> [u32; 2] {
+        match self.nodes[index as usize].kind {
+            NodeKind::Branch(children) => children,
+            _ => [NULL; 2],
+        }
+    }
+
+    fn set_children(&mut self, index: u32, children: [u32; 2]) {
+        self.nodes[index as usize].kind = NodeKind::Branch(children);
+        for child in children {
+            self.nodes[child as usize].parent = index;
+        }
+    }
+
+    /// Replaces the child `old` of `parent` with `new`, or makes `new` the root.
+    fn replace_child(&mut self, parent: u32, old: u32, new: u32) {
+        if parent == NULL {
+            self.root = new;
+        } else {
+            let children = self
+                .children(parent)
+                .map(|child| if child == old { new } else { child });
+            self.nodes[parent as usize].kind = NodeKind::Branch(children);
+        }
+        self.nodes[new as usize].parent = parent;
+    }
+
+    /// Recomputes the volume and height of a branch from its children.
+    fn refit(&mut self, index: u32) {
+        let [a, b] = self
+            .children(index)
+            .map(|child| &self.nodes[child as usize]);
+        let volume = a.volume.merge(&b.volume);
+        let height = 1 + a.height.max(b.height);
+        let node = &mut self.nodes[index as usize];
+        node.volume = volume;
+        node.height = height;
+    }
+
+    /// Inserts a detached leaf into the tree.
+    fn attach(&mut self, leaf: u32) {
+        if self.root == NULL {
+            self.root = leaf;
+            self.nodes[leaf as usize].parent = NULL;
+            return;
+        }
+
+        // Descend towards the sibling that increases the surface area of the tree the least.
+        let volume = self.nodes[leaf as usize].volume.clone();
+        let mut sibling = self.root;
+        while let NodeKind::Branch(children) = self.nodes[sibling as usize].kind {
+            let node = &self.nodes[sibling as usize];
+            let area = node.volume.visible_area();
+            let combined_area = node.volume.merge(&volume).visible_area();
+            // The cost of making the leaf a sibling of this node.
+            let cost = 2. * combined_area;
+            // The minimum cost of pushing the leaf further down the tree.
+            let inheritance_cost = 2. * (combined_area - area);
+            let [cost_a, cost_b] = children.map(|child| {
+                let child = &self.nodes[child as usize];
+                let merged_area = child.volume.merge(&volume).visible_area();
+                match child.kind {
+                    NodeKind::Leaf(_) => merged_area + inheritance_cost,
+                    _ => merged_area - child.volume.visible_area() + inheritance_cost,
+                }
+            });
+            if cost < cost_a && cost < cost_b {
+                break;
+            }
+            sibling = if cost_a < cost_b {
+                children[0]
+            } else {
+                children[1]
+            };
+        }
+
+        // Create a new parent for the sibling and the leaf.
+        let old_parent = self.nodes[sibling as usize].parent;
+        let parent = self.allocate(Node {
+            volume: volume.merge(&self.nodes[sibling as usize].volume),
+            parent: NULL,
+            height: 0,
+            kind: NodeKind::Branch([NULL; 2]),
+        });
+        self.replace_child(old_parent, sibling, parent);
+        self.set_children(parent, [sibling, leaf]);
+        self.refit_ancestors(parent);
+    }
+
+    /// Removes a leaf from the tree without deallocating it.
+    fn detach(&mut self, leaf: u32) {
+        if leaf == self.root {
+            self.root = NULL;
+            return;
+        }
+        let parent = self.nodes[leaf as usize].parent;
+        let grandparent = self.nodes[parent as usize].parent;
+        let [a, b] = self.children(parent);
+        let sibling = if a == leaf { b } else { a };
+        self.replace_child(grandparent, parent, sibling);
+        self.deallocate(parent);
+        if grandparent != NULL {
+            self.refit_ancestors(grandparent);
+        }
+    }
+
+    /// Rebalances and refits every branch from `index` up to the root.
+    fn refit_ancestors(&mut self, mut index: u32) {
+        while index != NULL {
+            index = self.balance(index);
+            self.refit(index);
+            index = self.nodes[index as usize].parent;
+        }
+    }
+
+    /// Rotates the taller child of the branch at `index` up if the heights of its children
+    /// differ by more than one, returning the index of the branch now in its place.
+    fn balance(&mut self, index: u32) -> u32 {
+        let [a, b] = self.children(index);
+        let [height_a, height_b] = [a, b].map(|child| self.nodes[child as usize].height as i64);
+        if height_a - height_b > 1 {
+            self.rotate_up(index, 0)
+        } else if height_b - height_a > 1 {
+            self.rotate_up(index, 1)
+        } else {
+            index
+        }
+    }
+
+    /// Rotates the child in the given `slot` of the branch at `index` up to take its place.
+    fn rotate_up(&mut self, index: u32, slot: usize) -> u32 {
+        let mut children = self.children(index);
+        let child = children[slot];
+        let [f, g] = self.children(child);
+
+        self.replace_child(self.nodes[index as usize].parent, index, child);
+
+        // The taller grandchild stays below the rotated child, the other one moves down a level.
+        let (keep, moved) = if self.nodes[f as usize].height > self.nodes[g as usize].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+        children[slot] = moved;
+        self.set_children(index, children);
+        self.refit(index);
+        self.set_children(child, [index, keep]);
+        self.refit(child);
+        child
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{DynamicBvh, DynamicBvhId, NodeKind, NULL};
+    use crate::{
+        bounding::{Aabb2d, Aabb3d, AabbCast3d, BoundingVolume, IntersectsVolume, RayCast2d},
+        ops, Dir2, Dir3, Vec2, Vec3, Vec3A,
+    };
+
+    /// Checks that the tree is consistent and balanced.
+    fn validate<T>(bvh: &DynamicBvh<Aabb3d, T>) {
+        let mut leaves = 0;
+        let mut stack = Vec::from_iter((bvh.root != NULL).then_some(bvh.root));
+        while let Some(index) = stack.pop() {
+            let node = &bvh.nodes[index as usize];
+            match node.kind {
+                NodeKind::Leaf(_) => leaves += 1,
+                NodeKind::Branch(children) => {
+                    let [a, b] = children.map(|child| &bvh.nodes[child as usize]);
+                    assert_eq!(a.parent, index);
+                    assert_eq!(b.parent, index);
+                    assert!(node.volume.contains(&a.volume) && node.volume.contains(&b.volume));
+                    assert_eq!(node.height, 1 + a.height.max(b.height));
+                    assert!(a.height.abs_diff(b.height) <= 1);
+                    stack.extend(children);
+                }
+                NodeKind::Free(_) => panic!("free node in the tree"),
+            }
+        }
+        assert_eq!(leaves, bvh.len());
+    }
+
+    #[test]
+    fn insert_update_remove() {
+        let mut bvh = DynamicBvh::new();
+        // Inserting volumes in order would produce a degenerate tree without rebalancing.
+        let ids: Vec<DynamicBvhId> = (0..200)
+            .map(|i| {
+                bvh.insert(
+                    Aabb3d::new(Vec3::new(i as f32, 0.0, 0.0), Vec3::splat(0.25)),
+                    i,
+                )
+            })
+            .collect();
+        validate(&bvh);
+        assert!(bvh.nodes[bvh.root as usize].height <= 10);
+
+        for (i, &id) in ids.iter().enumerate().step_by(3) {
+            assert_eq!(bvh.remove(id), Some(i));
+        }
+        assert_eq!(bvh.remove(ids[0]), None);
+        validate(&bvh);
+        assert_eq!(bvh.len(), 133);
+
+        for &id in ids.iter().skip(1).step_by(3) {
+            let (volume, _) = bvh.get(id).unwrap();
+            let moved = Aabb3d::new(
+                volume.center() + Vec3A::new(0.0, 10.0, 0.0),
+                Vec3::splat(0.25),
+            );
+            assert!(bvh.update(id, moved));
+        }
+        validate(&bvh);
+
+        // Removed slots are reused.
+        let id = bvh.insert(Aabb3d::new(Vec3::ZERO, Vec3::ONE), 1000);
+        assert_eq!(bvh.get(id).map(|(_, data)| *data), Some(1000));
+        assert_eq!(bvh.iter().count(), 134);
+        validate(&bvh);
+    }
+
+    #[test]
+    fn queries() {
+        let mut bvh = DynamicBvh::new();
+        let volumes: Vec<Aabb3d> = (0..100)
+            .map(|i| {
+                let center = Vec3::new((i % 10) as f32, (i / 10) as f32, 0.0) * 3.0;
+                Aabb3d::new(center, Vec3::splat(1.0))
+            })
+            .collect();
+        let ids: Vec<DynamicBvhId> = volumes
+            .iter()
+            .map(|&volume| bvh.insert(volume, ()))
+            .collect();
+
+        let region = Aabb3d::new(Vec3::new(6.0, 6.0, 0.0), Vec3::splat(2.5));
+        let mut found: Vec<DynamicBvhId> = bvh.overlapping(&region).map(|(id, ..)| id).collect();
+        found.sort_unstable();
+        let expected: Vec<DynamicBvhId> = (0..100)
+            .filter(|&i| volumes[i].intersects(&region))
+            .map(|i| ids[i])
+            .collect();
+        assert_eq!(found.len(), 9);
+        assert_eq!(found, expected);
+
+        let cast = AabbCast3d::new(
+            Aabb3d::new(Vec3::ZERO, Vec3::splat(0.5)),
+            Vec3::new(-10.0, 3.0, 0.0),
+            Dir3::X,
+            f32::MAX,
+        );
+        assert_eq!(bvh.cast(&cast, |_, _| true), Some((ids[10], 8.5)));
+        assert_eq!(
+            bvh.cast(&cast, |id, _| id != ids[10]),
+            Some((ids[11], 11.5))
+        );
+
+        let point = Vec3::new(13.4, 4.0, 0.0).into();
+        let (id, distance) = bvh.nearest(point, |_, _| true).unwrap();
+        assert_eq!(id, ids[14]);
+        assert!(ops::abs(distance - 0.4) < 1e-6);
+    }
+
+    #[test]
+    fn queries_2d() {
+        let mut bvh = DynamicBvh::new();
+        let a = bvh.insert(Aabb2d::new(Vec2::new(5.0, 0.0), Vec2::splat(1.0)), 'a');
+        bvh.insert(Aabb2d::new(Vec2::new(10.0, 0.0), Vec2::splat(1.0)), 'b');
+        let ray = RayCast2d::new(Vec2::ZERO, Dir2::X, f32::MAX);
+        assert_eq!(bvh.cast(&ray, |_, _| true), Some((a, 4.0)));
+        assert_eq!(
+            bvh.nearest(Vec2::new(5.0, 3.0), |_, _| true),
+            Some((a, 2.0))
+        );
+    }
+}
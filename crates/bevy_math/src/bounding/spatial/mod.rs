@@ -0,0 +1,246 @@
+//! Dynamic spatial query structures over bounding volumes.
+//!
+//! - [`DynamicBvh`] is a dynamic AABB tree, which adapts to volumes of any size and distribution.
+//! - [`SpatialHash`] is a sparse uniform grid, which is simple and fast when volumes have similar
+//!   sizes, but needs a cell size that matches them.
+//!
+//! Both of them support inserting, updating and removing volumes, overlap queries, casts with any
+//! [`VolumeCast`] and nearest-neighbor queries.
+
+mod dynamic_bvh;
+mod spatial_hash;
+
+pub use dynamic_bvh::*;
+pub use spatial_hash::*;
+
+use super::{
+    Aabb2d, Aabb3d, AabbCast2d, AabbCast3d, BoundingCircle, BoundingCircleCast, BoundingSphere,
+    BoundingSphereCast, BoundingVolume, BvhVolume, IntersectsVolume, RayCast2d, RayCast3d,
+};
+use crate::{Dir3A, Vec2, Vec3A};
+
+/// A [`BoundingVolume`] that can be stored in [`DynamicBvh`] and [`SpatialHash`].
+pub trait SpatialVolume: BvhVolume + IntersectsVolume<Self> {
+    /// Returns the minimum and maximum corners of an axis-aligned box containing the volume.
+    ///
+    /// For 2D volumes, the Z coordinates are zero.
+    fn extents(&self) -> (Vec3A, Vec3A);
+
+    /// Returns the squared distance from `point` to the closest point of the volume, or zero if
+    /// the point is inside of it.
+    fn distance_squared_to_point(&self, point: Self::Translation) -> f32;
+
+    /// Returns `point` as a 3D point. For 2D volumes, the Z coordinate is zero.
+    fn point_3d(point: Self::Translation) -> Vec3A;
+}
+
+impl SpatialVolume for Aabb2d {
+    #[inline]
+    fn extents(&self) -> (Vec3A, Vec3A) {
+        (self.min.extend(0.).into(), self.max.extend(0.).into())
+    }
+
+    #[inline]
+    fn distance_squared_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    #[inline]
+    fn point_3d(point: Vec2) -> Vec3A {
+        point.extend(0.).into()
+    }
+}
+
+impl SpatialVolume for BoundingCircle {
+    #[inline]
+    fn extents(&self) -> (Vec3A, Vec3A) {
+        let aabb = self.aabb_2d();
+        (aabb.min.extend(0.).into(), aabb.max.extend(0.).into())
+    }
+
+    #[inline]
+    fn distance_squared_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    #[inline]
+    fn point_3d(point: Vec2) -> Vec3A {
+        point.extend(0.).into()
+    }
+}
+
+impl SpatialVolume for Aabb3d {
+    #[inline]
+    fn extents(&self) -> (Vec3A, Vec3A) {
+        (self.min, self.max)
+    }
+
+    #[inline]
+    fn distance_squared_to_point(&self, point: Vec3A) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    #[inline]
+    fn point_3d(point: Vec3A) -> Vec3A {
+        point
+    }
+}
+
+impl SpatialVolume for BoundingSphere {
+    #[inline]
+    fn extents(&self) -> (Vec3A, Vec3A) {
+        let aabb = self.aabb_3d();
+        (aabb.min, aabb.max)
+    }
+
+    #[inline]
+    fn distance_squared_to_point(&self, point: Vec3A) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    #[inline]
+    fn point_3d(point: Vec3A) -> Vec3A {
+        point
+    }
+}
+
+/// A ray or shape cast that can be performed against a [`SpatialVolume`] of type `V`.
+pub trait VolumeCast<V: BoundingVolume> {
+    /// Returns the distance along the cast at which it first hits `volume`, if it does.
+    fn cast_distance(&self, volume: &V) -> Option<f32>;
+
+    /// Returns the ray along which the cast moves. 2D rays lie in the XY plane.
+    fn ray(&self) -> RayCast3d;
+
+    /// Returns the minimum and maximum corners of an axis-aligned box containing the cast shape,
+    /// relative to the origin of the [ray](VolumeCast::ray).
+    fn shape_extents(&self) -> (Vec3A, Vec3A);
+}
+
+fn ray_2d_to_3d(ray: &RayCast2d) -> RayCast3d {
+    RayCast3d::new(
+        ray.ray.origin.extend(0.),
+        Dir3A::new_unchecked(ray.ray.direction.extend(0.).into()),
+        ray.max,
+    )
+}
+
+impl VolumeCast<Aabb2d> for RayCast2d {
+    #[inline]
+    fn cast_distance(&self, volume: &Aabb2d) -> Option<f32> {
+        self.aabb_intersection_at(volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        ray_2d_to_3d(self)
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        (Vec3A::ZERO, Vec3A::ZERO)
+    }
+}
+
+impl VolumeCast<BoundingCircle> for RayCast2d {
+    #[inline]
+    fn cast_distance(&self, volume: &BoundingCircle) -> Option<f32> {
+        self.circle_intersection_at(volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        ray_2d_to_3d(self)
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        (Vec3A::ZERO, Vec3A::ZERO)
+    }
+}
+
+impl VolumeCast<Aabb2d> for AabbCast2d {
+    #[inline]
+    fn cast_distance(&self, volume: &Aabb2d) -> Option<f32> {
+        self.aabb_collision_at(*volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        ray_2d_to_3d(&self.ray)
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        self.aabb.extents()
+    }
+}
+
+impl VolumeCast<BoundingCircle> for BoundingCircleCast {
+    #[inline]
+    fn cast_distance(&self, volume: &BoundingCircle) -> Option<f32> {
+        self.circle_collision_at(*volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        ray_2d_to_3d(&self.ray)
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        self.circle.extents()
+    }
+}
+
+impl VolumeCast<Aabb3d> for RayCast3d {
+    #[inline]
+    fn cast_distance(&self, volume: &Aabb3d) -> Option<f32> {
+        self.aabb_intersection_at(volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        self.clone()
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        (Vec3A::ZERO, Vec3A::ZERO)
+    }
+}
+
+impl VolumeCast<BoundingSphere> for RayCast3d {
+    #[inline]
+    fn cast_distance(&self, volume: &BoundingSphere) -> Option<f32> {
+        self.sphere_intersection_at(volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        self.clone()
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        (Vec3A::ZERO, Vec3A::ZERO)
+    }
+}
+
+impl VolumeCast<Aabb3d> for AabbCast3d {
+    #[inline]
+    fn cast_distance(&self, volume: &Aabb3d) -> Option<f32> {
+        self.aabb_collision_at(*volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        self.ray.clone()
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        self.aabb.extents()
+    }
+}
+
+impl VolumeCast<BoundingSphere> for BoundingSphereCast {
+    #[inline]
+    fn cast_distance(&self, volume: &BoundingSphere) -> Option<f32> {
+        self.sphere_collision_at(*volume)
+    }
+
+    fn ray(&self) -> RayCast3d {
+        self.ray.clone()
+    }
+
+    fn shape_extents(&self) -> (Vec3A, Vec3A) {
+        self.sphere.extents()
+    }
+}
@@ -0,0 +1,532 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+use super::{SpatialVolume, VolumeCast};
+use crate::{
+    bounding::Aabb3d,
+    ops::{self, FloatPow},
+    IVec3, Vec3A,
+};
+
+/// A handle to a volume stored in a [`SpatialHash`].
+///
+/// Handles of removed volumes may be reused for volumes inserted later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpatialHashId(u32);
+
+/// A sparse uniform grid of cubic cells, each storing the volumes overlapping it together with some
+/// data of type `T`.
+///
+/// Queries only have to look at the cells overlapping the queried region, which makes spatial
+/// hashes fast when the volumes have sizes similar to the cell size. Volumes much larger than the
+/// cell size are stored in many cells, which makes them slow to insert and update, and a cell size
+/// much larger than the volumes makes each cell contain many of them. A [`DynamicBvh`] doesn't
+/// have these problems.
+///
+/// For 2D volumes, the grid lies in the XY plane.
+///
+/// [`DynamicBvh`]: super::DynamicBvh
+///
+/// ```
+/// # use bevy_math::{bounding::{BoundingCircle, RayCast2d, SpatialHash}, Dir2, Vec2};
+/// let mut grid = SpatialHash::new(4.0);
+/// let a = grid.insert(BoundingCircle::new(Vec2::new(5.0, 0.0), 1.0), "a");
+/// let b = grid.insert(BoundingCircle::new(Vec2::new(10.0, 0.0), 1.0), "b");
+///
+/// // Find the volumes overlapping a region.
+/// let region = BoundingCircle::new(Vec2::new(5.0, 1.0), 1.0);
+/// assert_eq!(grid.overlapping(&region).map(|(id, ..)| id).collect::<Vec<_>>(), [a]);
+///
+/// // Cast a ray, ignoring the volume with the data "a".
+/// let ray = RayCast2d::new(Vec2::ZERO, Dir2::X, f32::MAX);
+/// assert_eq!(grid.cast(&ray, |_, data| *data != "a"), Some((b, 9.0)));
+///
+/// // Move a volume.
+/// grid.update(b, BoundingCircle::new(Vec2::new(0.0, 2.0), 1.0));
+/// assert_eq!(grid.nearest(Vec2::ZERO, |_, _| true), Some((b, 1.0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpatialHash<V, T> {
+    cell_size: f32,
+    cells: BTreeMap<[i32; 3], Vec<u32>>,
+    entries: Vec<Option<(V, T)>>,
+    free: Vec<u32>,
+    /// The range of cells that have ever been occupied, used to bound searches.
+    bounds: Option<([i32; 3], [i32; 3])>,
+}
+
+impl<V: SpatialVolume, T> SpatialHash<V, T> {
+    /// Creates an empty spatial hash with cells of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive and finite.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(
+            cell_size > 0. && cell_size.is_finite(),
+            "the cell size of a spatial hash must be positive and finite"
+        );
+        Self {
+            cell_size,
+            cells: BTreeMap::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+            bounds: None,
+        }
+    }
+
+    /// Returns the size of the cells of the grid.
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the number of volumes in the spatial hash.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    /// Returns `true` if the spatial hash contains no volumes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every volume from the spatial hash.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.free.clear();
+        self.bounds = None;
+    }
+
+    /// Inserts a volume with the given data, returning a handle to it.
+    pub fn insert(&mut self, volume: V, data: T) -> SpatialHashId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.entries.push(None);
+                (self.entries.len() - 1) as u32
+            }
+        };
+        self.add_to_cells(index, &volume);
+        self.entries[index as usize] = Some((volume, data));
+        SpatialHashId(index)
+    }
+
+    /// Moves the volume with the given handle, returning `false` if it doesn't exist.
+    pub fn update(&mut self, id: SpatialHashId, volume: V) -> bool {
+        let Some((old_volume, _)) = self.entries.get(id.0 as usize).and_then(Option::as_ref) else {
+            return false;
+        };
+        let old_cells = self.volume_cells(old_volume);
+        if old_cells != self.volume_cells(&volume) {
+            let old_volume = old_volume.clone();
+            self.remove_from_cells(id.0, &old_volume);
+            self.add_to_cells(id.0, &volume);
+        }
+        if let Some((old_volume, _)) = &mut self.entries[id.0 as usize] {
+            *old_volume = volume;
+        }
+        true
+    }
+
+    /// Removes the volume with the given handle, returning its data if it exists.
+    pub fn remove(&mut self, id: SpatialHashId) -> Option<T> {
+        let (volume, data) = self.entries.get_mut(id.0 as usize)?.take()?;
+        self.remove_from_cells(id.0, &volume);
+        self.free.push(id.0);
+        Some(data)
+    }
+
+    /// Returns the volume with the given handle and its data, if it exists.
+    pub fn get(&self, id: SpatialHashId) -> Option<(&V, &T)> {
+        let (volume, data) = self.entries.get(id.0 as usize)?.as_ref()?;
+        Some((volume, data))
+    }
+
+    /// Returns a mutable reference to the data of the volume with the given handle, if it exists.
+    pub fn get_mut(&mut self, id: SpatialHashId) -> Option<&mut T> {
+        let (_, data) = self.entries.get_mut(id.0 as usize)?.as_mut()?;
+        Some(data)
+    }
+
+    /// Returns an iterator over every volume in the spatial hash and its data, in no particular
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (SpatialHashId, &V, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let (volume, data) = entry.as_ref()?;
+                Some((SpatialHashId(index as u32), volume, data))
+            })
+    }
+
+    /// Returns an iterator over the volumes that intersect `volume` and their data.
+    pub fn overlapping<'a>(
+        &'a self,
+        volume: &'a V,
+    ) -> impl Iterator<Item = (SpatialHashId, &'a V, &'a T)> + 'a {
+        let (min, max) = self.volume_cells(volume);
+        let mut candidates = Vec::new();
+        self.for_each_cell_in(min, max, |_, entries| candidates.extend(entries));
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.into_iter().filter_map(move |index| {
+            let (other, data) = self.entries[index as usize].as_ref()?;
+            volume
+                .intersects(other)
+                .then_some((SpatialHashId(index), other, data))
+        })
+    }
+
+    /// Returns the closest volume hit by `cast` for which `filter` returns `true`, and the distance
+    /// along the cast at which it was hit.
+    ///
+    /// The cells along the cast are visited in order, so this stops early once a hit is found.
+    pub fn cast<C: VolumeCast<V>>(
+        &self,
+        cast: &C,
+        mut filter: impl FnMut(SpatialHashId, &T) -> bool,
+    ) -> Option<(SpatialHashId, f32)> {
+        let (bounds_min, bounds_max) = self.bounds?;
+        let ray = cast.ray();
+        let (shape_min, shape_max) = cast.shape_extents();
+        let cell_size = self.cell_size;
+
+        // The cast can only hit a volume while the ray is in this region.
+        let region = Aabb3d {
+            min: IVec3::from(bounds_min).as_vec3a() * cell_size - shape_max,
+            max: (IVec3::from(bounds_max) + 1).as_vec3a() * cell_size - shape_min,
+        };
+        let (region_min, region_max) = self.cell_range(region.min, region.max);
+        let mut distance = ray.aabb_intersection_at(&region)?;
+
+        // Walk through the cells along the ray with a 3D DDA.
+        let origin = ray.origin.to_array();
+        let direction = Vec3A::from(ray.direction).to_array();
+        let mut cell = self.cell(ray.origin + ray.direction * distance).to_array();
+        let mut step = [0; 3];
+        let mut next = [f32::INFINITY; 3];
+        let mut delta = [f32::INFINITY; 3];
+        for axis in 0..3 {
+            if direction[axis] != 0. {
+                step[axis] = if direction[axis] > 0. { 1 } else { -1 };
+                let boundary = (cell[axis] + step[axis].max(0)) as f32 * cell_size;
+                next[axis] = (boundary - origin[axis]) / direction[axis];
+                delta[axis] = cell_size / ops::abs(direction[axis]);
+            }
+        }
+
+        let mut closest = None;
+        let mut max = f32::INFINITY;
+        let mut visited = BTreeSet::new();
+        while distance <= ray.max && distance < max {
+            // Stop once the ray has left the region for good.
+            if (0..3).any(|axis| {
+                (cell[axis] < region_min[axis] && step[axis] <= 0)
+                    || (cell[axis] > region_max[axis] && step[axis] >= 0)
+            }) {
+                break;
+            }
+
+            // The cells the cast shape overlaps while the ray is in this cell.
+            let cell_min = IVec3::from(cell).as_vec3a() * cell_size;
+            let (min, max_cell) =
+                self.cell_range(cell_min + shape_min, cell_min + cell_size + shape_max);
+            self.for_each_cell_in(min, max_cell, |_, entries| {
+                for &index in entries {
+                    if !visited.insert(index) {
+                        continue;
+                    }
+                    let Some((volume, data)) = &self.entries[index as usize] else {
+                        continue;
+                    };
+                    if let Some(hit) = cast.cast_distance(volume)
+                        && hit < max
+                        && filter(SpatialHashId(index), data)
+                    {
+                        max = hit;
+                        closest = Some((SpatialHashId(index), hit));
+                    }
+                }
+            });
+
+            let axis = (0..3)
+                .min_by(|&a, &b| next[a].total_cmp(&next[b]))
+                .unwrap_or(0);
+            distance = next[axis];
+            cell[axis] += step[axis];
+            next[axis] += delta[axis];
+        }
+        closest
+    }
+
+    /// Returns the volume closest to `point` for which `filter` returns `true`, and its distance
+    /// from the point. The distance is zero if the point is inside of the volume.
+    ///
+    /// The cells around the point are visited in order of distance, so this stops early once a
+    /// close volume is found.
+    pub fn nearest(
+        &self,
+        point: V::Translation,
+        mut filter: impl FnMut(SpatialHashId, &T) -> bool,
+    ) -> Option<(SpatialHashId, f32)> {
+        let (bounds_min, bounds_max) = self.bounds?;
+        let center = self.cell(V::point_3d(point)).to_array();
+
+        // Search rings of cells around the center, up to the farthest occupied cell.
+        let axes = V::AXES.min(3);
+        let ring_distance = |cell: [i32; 3]| {
+            (0..axes)
+                .map(|axis| (cell[axis] - center[axis]).unsigned_abs())
+                .max()
+                .unwrap_or(0)
+        };
+        let clamped =
+            core::array::from_fn(|axis| center[axis].clamp(bounds_min[axis], bounds_max[axis]));
+        let first_ring = ring_distance(clamped);
+        let last_ring = (0..axes)
+            .map(|axis| {
+                (center[axis] - bounds_min[axis])
+                    .unsigned_abs()
+                    .max((bounds_max[axis] - center[axis]).unsigned_abs())
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut closest = None;
+        let mut max = f32::INFINITY;
+        let mut visited = BTreeSet::new();
+        for ring in first_ring..=last_ring {
+            // Volumes that haven't been visited yet are at least this far away.
+            if ring > 0 && ((ring - 1) as f32 * self.cell_size).squared() >= max {
+                break;
+            }
+
+            let ring_offset = ring.min(i32::MAX as u32) as i32;
+            let min = core::array::from_fn(|axis| {
+                if axis < axes {
+                    center[axis].saturating_sub(ring_offset)
+                } else {
+                    center[axis]
+                }
+            });
+            let max_cell = core::array::from_fn(|axis| {
+                if axis < axes {
+                    center[axis].saturating_add(ring_offset)
+                } else {
+                    center[axis]
+                }
+            });
+            self.for_each_cell_in(min, max_cell, |cell, entries| {
+                if ring_distance(cell) != ring {
+                    return;
+                }
+                for &index in entries {
+                    if !visited.insert(index) {
+                        continue;
+                    }
+                    let Some((volume, data)) = &self.entries[index as usize] else {
+                        continue;
+                    };
+                    let distance = volume.distance_squared_to_point(point);
+                    if distance < max && filter(SpatialHashId(index), data) {
+                        max = distance;
+                        closest = Some((SpatialHashId(index), distance));
+                    }
+                }
+            });
+        }
+        closest.map(|(id, distance_squared)| (id, ops::sqrt(distance_squared)))
+    }
+
+    /// Returns the cell containing `point`.
+    fn cell(&self, point: Vec3A) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Returns the range of cells overlapping the box between `min` and `max`.
+    fn cell_range(&self, min: Vec3A, max: Vec3A) -> ([i32; 3], [i32; 3]) {
+        (self.cell(min).to_array(), self.cell(max).to_array())
+    }
+
+    fn volume_cells(&self, volume: &V) -> ([i32; 3], [i32; 3]) {
+        let (min, max) = volume.extents();
+        self.cell_range(min, max)
+    }
+
+    /// Calls `f` for every occupied cell between `min` and `max`.
+    fn for_each_cell_in(&self, min: [i32; 3], max: [i32; 3], mut f: impl FnMut([i32; 3], &[u32])) {
+        let Some((bounds_min, bounds_max)) = self.bounds else {
+            return;
+        };
+        let min: [i32; 3] = core::array::from_fn(|axis| min[axis].max(bounds_min[axis]));
+        let max: [i32; 3] = core::array::from_fn(|axis| max[axis].min(bounds_max[axis]));
+        if (0..3).any(|axis| min[axis] > max[axis]) {
+            return;
+        }
+
+        // Looking up every cell in a large range is slower than going through the occupied ones.
+        let cell_count = (0..3)
+            .map(|axis| (max[axis] as i64 - min[axis] as i64 + 1) as u64)
+            .fold(1u64, u64::saturating_mul);
+        if cell_count > self.cells.len() as u64 {
+            for (&cell, entries) in &self.cells {
+                if (0..3).all(|axis| (min[axis]..=max[axis]).contains(&cell[axis])) {
+                    f(cell, entries);
+                }
+            }
+            return;
+        }
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    if let Some(entries) = self.cells.get(&[x, y, z]) {
+                        f([x, y, z], entries);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_to_cells(&mut self, index: u32, volume: &V) {
+        let (min, max) = self.volume_cells(volume);
+        self.bounds = Some(match self.bounds {
+            Some((bounds_min, bounds_max)) => (
+                core::array::from_fn(|axis| bounds_min[axis].min(min[axis])),
+                core::array::from_fn(|axis| bounds_max[axis].max(max[axis])),
+            ),
+            None => (min, max),
+        });
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    self.cells.entry([x, y, z]).or_default().push(index);
+                }
+            }
+        }
+    }
+
+    fn remove_from_cells(&mut self, index: u32, volume: &V) {
+        let (min, max) = self.volume_cells(volume);
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    let Some(entries) = self.cells.get_mut(&[x, y, z]) else {
+                        continue;
+                    };
+                    entries.retain(|&entry| entry != index);
+                    if entries.is_empty() {
+                        self.cells.remove(&[x, y, z]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{SpatialHash, SpatialHashId};
+    use crate::{
+        bounding::{
+            Aabb3d, BoundingSphere, BoundingSphereCast, DynamicBvh, IntersectsVolume, RayCast3d,
+        },
+        ops, Dir3, Vec3, Vec3A,
+    };
+
+    fn spheres() -> Vec<BoundingSphere> {
+        (0..300)
+            .map(|i| {
+                let i = i as f32;
+                let center = Vec3::new(ops::sin(i * 1.3) * 20.0, ops::cos(i * 0.7) * 20.0, i * 0.1);
+                BoundingSphere::new(center, 0.5 + (i % 3.0) * 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn insert_update_remove() {
+        let mut grid = SpatialHash::new(1.0);
+        let a = grid.insert(Aabb3d::new(Vec3::ZERO, Vec3::splat(1.5)), 'a');
+        let b = grid.insert(Aabb3d::new(Vec3::splat(10.0), Vec3::splat(0.2)), 'b');
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.cells.len(), 72);
+
+        assert!(grid.update(b, Aabb3d::new(Vec3::splat(-10.0), Vec3::splat(0.2))));
+        assert_eq!(grid.cells.len(), 72);
+        assert_eq!(grid.remove(a), Some('a'));
+        assert_eq!(grid.remove(a), None);
+        assert!(!grid.update(a, Aabb3d::new(Vec3::ZERO, Vec3::ONE)));
+        assert_eq!(grid.cells.len(), 8);
+        assert_eq!(grid.get(b).map(|(_, data)| *data), Some('b'));
+
+        // Removed slots are reused.
+        assert_eq!(grid.insert(Aabb3d::new(Vec3::ZERO, Vec3::ONE), 'c'), a);
+        assert_eq!(grid.iter().count(), 2);
+    }
+
+    #[test]
+    fn matches_dynamic_bvh() {
+        let spheres = spheres();
+        let mut grid = SpatialHash::new(2.0);
+        let mut bvh = DynamicBvh::new();
+        for (i, sphere) in spheres.iter().enumerate() {
+            grid.insert(*sphere, i);
+            bvh.insert(*sphere, i);
+        }
+
+        let region = BoundingSphere::new(Vec3::new(3.0, -4.0, 10.0), 6.0);
+        let mut found: Vec<usize> = grid.overlapping(&region).map(|(_, _, &i)| i).collect();
+        found.sort_unstable();
+        let expected: Vec<usize> = (0..spheres.len())
+            .filter(|&i| spheres[i].intersects(&region))
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+
+        let data = |id: Option<(SpatialHashId, f32)>| id.map(|(id, _)| *grid.get(id).unwrap().1);
+        // Aim rays at some of the spheres, through the others.
+        for (origin, target) in [
+            (Vec3::new(-30.0, 0.0, 15.0), 37),
+            (Vec3::new(0.0, 0.0, 15.0), 150),
+            (Vec3::new(25.0, 25.0, -5.0), 260),
+        ] {
+            let direction = Dir3::new(Vec3::from(spheres[target].center) - origin).unwrap();
+            let ray = RayCast3d::new(origin, direction, f32::MAX);
+            let expected = bvh
+                .cast(&ray, |_, _| true)
+                .map(|(id, _)| *bvh.get(id).unwrap().1);
+            assert!(expected.is_some());
+            assert_eq!(data(grid.cast(&ray, |_, _| true)), expected);
+
+            let cast = BoundingSphereCast::new(
+                BoundingSphere::new(Vec3::ZERO, 1.5),
+                origin,
+                direction,
+                f32::MAX,
+            );
+            let expected = bvh
+                .cast(&cast, |_, _| true)
+                .map(|(id, _)| *bvh.get(id).unwrap().1);
+            assert_eq!(data(grid.cast(&cast, |_, _| true)), expected);
+
+            let point = Vec3A::from(origin + direction * 5.0);
+            let (id, distance) = grid.nearest(point, |_, &i| i % 2 == 0).unwrap();
+            let (expected, expected_distance) = bvh.nearest(point, |_, &i| i % 2 == 0).unwrap();
+            assert_eq!(grid.get(id).unwrap().1, bvh.get(expected).unwrap().1);
+            assert_eq!(distance, expected_distance);
+        }
+
+        // Rays that miss every volume don't hit anything.
+        let ray = RayCast3d::new(Vec3::new(0.0, 0.0, -5.0), Dir3::NEG_Z, f32::MAX);
+        assert!(grid.cast(&ray, |_, _| true).is_none());
+    }
+}
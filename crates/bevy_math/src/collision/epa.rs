@@ -0,0 +1,289 @@
+//! The Expanding Polytope Algorithm for the penetration depth of two overlapping convex shapes.
+
+use alloc::vec::Vec;
+
+use super::gjk::{GjkVector, Simplex, SupportPoint};
+use crate::{ops, Vec2, Vec3};
+
+/// The maximum number of vertices [`epa_2d`] and [`epa_3d`] add to the polytope. Polytopes
+/// converge in a handful of iterations, but curved shapes converge asymptotically.
+const MAX_ITERATIONS: usize = 128;
+
+/// The expansion stops once it can't get farther from the origin than this fraction of the size
+/// of the initial polytope.
+const RELATIVE_TOLERANCE: f32 = 1e-5;
+
+/// The smallest translation separating two overlapping shapes.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Penetration<V> {
+    /// The direction in which `B` needs to move to separate the shapes.
+    pub normal: V,
+    /// The distance `B` needs to move to separate the shapes.
+    pub depth: f32,
+    /// The point of `A` deepest inside of `B`.
+    pub point_a: V,
+    /// The point of `B` deepest inside of `A`.
+    pub point_b: V,
+}
+
+/// Finds the point on the boundary of the Minkowski difference `A - B` of two overlapping 2D
+/// shapes that is closest to the origin, starting from a simplex that contains the origin.
+///
+/// If the Minkowski difference has no area, returns a normal of it instead, or zero if it's a
+/// single point.
+pub(super) fn epa_2d(
+    support: impl Fn(Vec2) -> SupportPoint<Vec2>,
+    simplex: &Simplex<Vec2>,
+) -> Result<Penetration<Vec2>, Vec2> {
+    let mut polygon: Vec<SupportPoint<Vec2>> = simplex.vertices().to_vec();
+    let scale = polygon
+        .iter()
+        .map(|vertex| vertex.point.length())
+        .fold(0.0, f32::max);
+
+    // Grow the simplex into a triangle by adding the vertices farthest from it.
+    if let [vertex] = polygon[..] {
+        polygon.push(
+            farthest(
+                &support,
+                [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y],
+                |point| point.distance(vertex.point),
+            )
+            .ok_or(Vec2::ZERO)?,
+        );
+    }
+    if let [a, b] = polygon[..] {
+        let normal = (b.point - a.point).perp().normalize_or_zero();
+        polygon.push(
+            farthest(&support, [normal, -normal], |point| {
+                ops::abs(normal.dot(point - a.point))
+            })
+            .ok_or(normal)?,
+        );
+    }
+    if (polygon[1].point - polygon[0].point).perp_dot(polygon[2].point - polygon[0].point) < 0.0 {
+        polygon.swap(1, 2);
+    }
+
+    let scale = polygon
+        .iter()
+        .map(|vertex| vertex.point.length())
+        .fold(scale, f32::max);
+    let tolerance = RELATIVE_TOLERANCE * scale;
+    let mut iteration = 0;
+    loop {
+        // The edge closest to the origin, with its outward normal. The polygon is wound
+        // counterclockwise, so the outward normal points to the right of each edge.
+        let (index, normal, distance) = (0..polygon.len())
+            .filter_map(|i| {
+                let a = polygon[i].point;
+                let b = polygon[(i + 1) % polygon.len()].point;
+                let normal = (a - b).perp().try_normalize()?;
+                Some((i, normal, normal.dot(a)))
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+            .ok_or(Vec2::ZERO)?;
+
+        let vertex = support(normal);
+        if vertex.point.dot(normal) - distance <= tolerance || iteration == MAX_ITERATIONS {
+            let a = polygon[index];
+            let b = polygon[(index + 1) % polygon.len()];
+            let edge = b.point - a.point;
+            let t =
+                ((normal * distance - a.point).dot(edge) / edge.length_squared()).clamp(0.0, 1.0);
+            return Ok(Penetration {
+                normal,
+                depth: distance.max(0.0),
+                point_a: a.a.lerp(b.a, t),
+                point_b: a.b.lerp(b.b, t),
+            });
+        }
+        polygon.insert(index + 1, vertex);
+        iteration += 1;
+    }
+}
+
+/// A triangular face of the polytope expanded by [`epa_3d`], with its outward plane.
+struct EpaFace {
+    vertices: [usize; 3],
+    normal: Vec3,
+    distance: f32,
+}
+
+impl EpaFace {
+    fn new(points: &[SupportPoint<Vec3>], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|index| points[index].point);
+        match (b - a).cross(c - a).try_normalize() {
+            Some(normal) => Self {
+                vertices,
+                normal,
+                distance: normal.dot(a),
+            },
+            // Degenerate faces are never visible and never the closest face.
+            None => Self {
+                vertices,
+                normal: Vec3::ZERO,
+                distance: f32::INFINITY,
+            },
+        }
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Finds the point on the boundary of the Minkowski difference `A - B` of two overlapping 3D
+/// shapes that is closest to the origin, starting from a simplex that contains the origin.
+///
+/// If the Minkowski difference has no volume, returns a normal of it instead, or zero if it's a
+/// single point.
+pub(super) fn epa_3d(
+    support: impl Fn(Vec3) -> SupportPoint<Vec3>,
+    simplex: &Simplex<Vec3>,
+) -> Result<Penetration<Vec3>, Vec3> {
+    let mut points: Vec<SupportPoint<Vec3>> = simplex.vertices().to_vec();
+
+    // Grow the simplex into a tetrahedron by adding the vertices farthest from it.
+    if let [vertex] = points[..] {
+        let directions = [
+            Vec3::X,
+            Vec3::NEG_X,
+            Vec3::Y,
+            Vec3::NEG_Y,
+            Vec3::Z,
+            Vec3::NEG_Z,
+        ];
+        points.push(
+            farthest(&support, directions, |point| point.distance(vertex.point))
+                .ok_or(Vec3::ZERO)?,
+        );
+    }
+    if let [a, b] = points[..] {
+        let axis = (b.point - a.point).normalize_or_zero();
+        let u = axis.any_orthonormal_vector();
+        let v = axis.cross(u);
+        points.push(
+            farthest(&support, [u, -u, v, -v], |point| {
+                (point - a.point).cross(axis).length()
+            })
+            .ok_or(u)?,
+        );
+    }
+    if let [a, b, c] = points[..] {
+        let normal = (b.point - a.point)
+            .cross(c.point - a.point)
+            .normalize_or_zero();
+        points.push(
+            farthest(&support, [normal, -normal], |point| {
+                ops::abs(normal.dot(point - a.point))
+            })
+            .ok_or(normal)?,
+        );
+    }
+
+    let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|index| points[index].point);
+    let initial = if (p1 - p0).cross(p2 - p0).dot(p3 - p0) > 0.0 {
+        [[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]]
+    } else {
+        [[0, 1, 2], [0, 3, 1], [1, 3, 2], [2, 3, 0]]
+    };
+    let mut faces: Vec<EpaFace> = initial
+        .into_iter()
+        .map(|vertices| EpaFace::new(&points, vertices))
+        .collect();
+
+    let scale = points
+        .iter()
+        .map(|vertex| vertex.point.length())
+        .fold(0.0, f32::max);
+    let tolerance = RELATIVE_TOLERANCE * scale;
+    let mut horizon = Vec::new();
+    let mut iteration = 0;
+    loop {
+        let closest = faces
+            .iter()
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .filter(|face| face.distance.is_finite())
+            .ok_or(Vec3::ZERO)?;
+        let normal = closest.normal;
+
+        let vertex = support(normal);
+        if vertex.point.dot(normal) - closest.distance <= tolerance || iteration == MAX_ITERATIONS {
+            let [a, b, c] = closest.vertices.map(|index| points[index]);
+            let [u, v, w] = barycentric(normal * closest.distance, [a.point, b.point, c.point]);
+            return Ok(Penetration {
+                normal,
+                depth: closest.distance.max(0.0),
+                point_a: a.a * u + b.a * v + c.a * w,
+                point_b: a.b * u + b.b * v + c.b * w,
+            });
+        }
+
+        // Replace the faces visible from the new vertex with a fan of faces connecting it to the
+        // horizon, which consists of the edges of visible faces whose neighboring face isn't
+        // visible.
+        let is_visible = |face: &EpaFace| face.normal.dot(vertex.point) - face.distance > 0.0;
+        let visible_edges: Vec<(usize, usize)> = faces
+            .iter()
+            .filter(|face| is_visible(face))
+            .flat_map(EpaFace::edges)
+            .collect();
+        horizon.clear();
+        horizon.extend(
+            visible_edges
+                .iter()
+                .filter(|&&(a, b)| !visible_edges.contains(&(b, a))),
+        );
+
+        let index = points.len();
+        points.push(vertex);
+        faces.retain(|face| !is_visible(face));
+        faces.extend(
+            horizon
+                .iter()
+                .map(|&(a, b)| EpaFace::new(&points, [a, b, index])),
+        );
+        iteration += 1;
+    }
+}
+
+/// Returns the support point in whichever of the `directions` maximizes `distance`, or `None` if
+/// none of them is meaningfully far from the current simplex.
+fn farthest<V: GjkVector, const N: usize>(
+    support: impl Fn(V) -> SupportPoint<V>,
+    directions: [V; N],
+    distance: impl Fn(V) -> f32,
+) -> Option<SupportPoint<V>> {
+    let vertices = directions.map(support);
+    let scale = vertices
+        .iter()
+        .map(|vertex| ops::sqrt(vertex.point.dot(vertex.point)))
+        .fold(0.0, f32::max);
+    let (vertex, distance) = vertices
+        .into_iter()
+        .map(|vertex| (vertex, distance(vertex.point)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    (distance > RELATIVE_TOLERANCE * scale).then_some(vertex)
+}
+
+/// Computes the barycentric coordinates of the projection of `point` onto the plane of a
+/// triangle.
+fn barycentric(point: Vec3, [a, b, c]: [Vec3; 3]) -> [f32; 3] {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d00 = ab.dot(ab);
+    let d01 = ab.dot(ac);
+    let d11 = ac.dot(ac);
+    let d20 = ap.dot(ab);
+    let d21 = ap.dot(ac);
+    let denominator = d00 * d11 - d01 * d01;
+    if denominator <= 0.0 {
+        return [1.0, 0.0, 0.0];
+    }
+    let v = (d11 * d20 - d01 * d21) / denominator;
+    let w = (d00 * d21 - d01 * d20) / denominator;
+    [1.0 - v - w, v, w]
+}
@@ -0,0 +1,378 @@
+//! The Gilbert-Johnson-Keerthi algorithm for the distance between two convex shapes.
+
+#![cfg_attr(
+    not(feature = "alloc"),
+    expect(
+        dead_code,
+        reason = "the witness points are only used by the contact queries, which require `alloc`"
+    )
+)]
+
+use core::{
+    array,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use crate::{ops, Vec2, Vec3};
+
+/// The maximum number of iterations of [`gjk`]. Polytopes converge in a handful of iterations,
+/// but curved shapes converge asymptotically.
+const MAX_ITERATIONS: usize = 64;
+
+/// [`gjk`] stops once the distance can't shrink by more than this fraction.
+const RELATIVE_TOLERANCE: f32 = 1e-5;
+
+/// Shapes are overlapping if the squared distance between them is smaller than this fraction of
+/// the squared size of their Minkowski difference.
+const OVERLAP_TOLERANCE: f32 = 1e-10;
+
+/// Barycentric weights down to this negative value are accepted when reducing a simplex, to
+/// account for rounding errors.
+const WEIGHT_TOLERANCE: f32 = 1e-6;
+
+/// A sub-simplex is considered degenerate if a pivot of its Gram matrix is smaller than this
+/// fraction of its largest diagonal element.
+const SINGULAR_TOLERANCE: f32 = 1e-6;
+
+/// A vector type the GJK and EPA algorithms can work with.
+pub(super) trait GjkVector:
+    Copy
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<f32, Output = Self>
+    + Neg<Output = Self>
+{
+    /// The number of dimensions of the vector.
+    const DIM: usize;
+
+    /// The zero vector.
+    const ZERO: Self;
+
+    /// The unit vector along the Y axis, used when any direction will do.
+    const Y: Self;
+
+    fn dot(self, rhs: Self) -> f32;
+
+    fn normalize_or_zero(self) -> Self;
+}
+
+impl GjkVector for Vec2 {
+    const DIM: usize = 2;
+    const ZERO: Self = Vec2::ZERO;
+    const Y: Self = Vec2::Y;
+
+    #[inline]
+    fn dot(self, rhs: Self) -> f32 {
+        Vec2::dot(self, rhs)
+    }
+
+    #[inline]
+    fn normalize_or_zero(self) -> Self {
+        Vec2::normalize_or_zero(self)
+    }
+}
+
+impl GjkVector for Vec3 {
+    const DIM: usize = 3;
+    const ZERO: Self = Vec3::ZERO;
+    const Y: Self = Vec3::Y;
+
+    #[inline]
+    fn dot(self, rhs: Self) -> f32 {
+        Vec3::dot(self, rhs)
+    }
+
+    #[inline]
+    fn normalize_or_zero(self) -> Self {
+        Vec3::normalize_or_zero(self)
+    }
+}
+
+/// A point of the Minkowski difference `A - B` of two shapes, together with the points of `A`
+/// and `B` it was computed from.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SupportPoint<V> {
+    pub point: V,
+    pub a: V,
+    pub b: V,
+}
+
+impl<V: GjkVector> SupportPoint<V> {
+    #[inline]
+    pub fn new(a: V, b: V) -> Self {
+        Self { point: a - b, a, b }
+    }
+}
+
+/// A simplex of up to four [`SupportPoint`]s, with the barycentric weights of the point of the
+/// simplex closest to the origin.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Simplex<V> {
+    vertices: [SupportPoint<V>; 4],
+    weights: [f32; 4],
+    len: usize,
+}
+
+impl<V: GjkVector> Simplex<V> {
+    fn new(vertex: SupportPoint<V>) -> Self {
+        Self {
+            vertices: [vertex; 4],
+            weights: [1.0, 0.0, 0.0, 0.0],
+            len: 1,
+        }
+    }
+
+    /// Returns the vertices of the simplex.
+    #[inline]
+    pub fn vertices(&self) -> &[SupportPoint<V>] {
+        &self.vertices[..self.len]
+    }
+
+    /// Returns the point of the simplex closest to the origin.
+    pub fn closest_point(&self) -> V {
+        self.weighted_sum(|vertex| vertex.point)
+    }
+
+    /// Returns the points of `A` and `B` whose difference is the
+    /// [closest point](Self::closest_point) of the simplex.
+    pub fn witnesses(&self) -> (V, V) {
+        (
+            self.weighted_sum(|vertex| vertex.a),
+            self.weighted_sum(|vertex| vertex.b),
+        )
+    }
+
+    fn weighted_sum(&self, f: impl Fn(&SupportPoint<V>) -> V) -> V {
+        self.vertices()
+            .iter()
+            .zip(self.weights)
+            .fold(V::ZERO, |sum, (vertex, weight)| sum + f(vertex) * weight)
+    }
+
+    /// Reduces the simplex to the smallest sub-simplex containing the point closest to the origin,
+    /// and updates the weights of that point.
+    fn reduce(&mut self) {
+        // The closest point lies in the relative interior of one of the sub-simplices, where its
+        // weights are all positive. Every other candidate is at least as far from the origin.
+        let mut best = (f32::INFINITY, [1.0, 0.0, 0.0, 0.0]);
+        for mask in 1..(1u8 << self.len) {
+            let Some(weights) = self.affine_weights(mask) else {
+                continue;
+            };
+            if weights.iter().any(|&weight| weight < -WEIGHT_TOLERANCE) {
+                continue;
+            }
+            let point =
+                (0..self.len).fold(V::ZERO, |sum, i| sum + self.vertices[i].point * weights[i]);
+            let distance_squared = point.dot(point);
+            if distance_squared < best.0 {
+                best = (distance_squared, weights);
+            }
+        }
+
+        let weights = best.1;
+        let total: f32 = weights.iter().filter(|&&weight| weight > 0.0).sum();
+        let vertices = self.vertices;
+        let len = self.len;
+        self.len = 0;
+        for (vertex, weight) in vertices.into_iter().zip(weights).take(len) {
+            if weight > 0.0 {
+                self.vertices[self.len] = vertex;
+                self.weights[self.len] = weight / total;
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Computes the barycentric weights of the point closest to the origin on the affine hull of
+    /// the vertices in `mask`.
+    ///
+    /// Returns `None` if the vertices are affinely dependent.
+    fn affine_weights(&self, mask: u8) -> Option<[f32; 4]> {
+        let mut indices = [0; 4];
+        let mut count = 0;
+        for i in 0..self.len {
+            if mask & (1 << i) != 0 {
+                indices[count] = i;
+                count += 1;
+            }
+        }
+
+        // Minimize |origin + sum(mu_i * edge_i)| by solving the normal equations.
+        let origin = self.vertices[indices[0]].point;
+        let n = count - 1;
+        let edges: [V; 3] = array::from_fn(|i| {
+            if i < n {
+                self.vertices[indices[i + 1]].point - origin
+            } else {
+                V::ZERO
+            }
+        });
+        let mut matrix = [[0.0; 4]; 3];
+        for i in 0..n {
+            for j in 0..n {
+                matrix[i][j] = edges[i].dot(edges[j]);
+            }
+            matrix[i][3] = -origin.dot(edges[i]);
+        }
+        let mu = solve(&mut matrix, n)?;
+
+        let mut weights = [0.0; 4];
+        weights[indices[0]] = 1.0 - mu.iter().sum::<f32>();
+        for i in 0..n {
+            weights[indices[i + 1]] = mu[i];
+        }
+        Some(weights)
+    }
+
+    fn push(&mut self, vertex: SupportPoint<V>) {
+        self.vertices[self.len] = vertex;
+        self.weights[self.len] = 0.0;
+        self.len += 1;
+    }
+}
+
+/// Solves the linear system given by the first `n` rows of an augmented matrix, whose last column
+/// is the right-hand side, using Gauss-Jordan elimination.
+///
+/// Returns `None` if the system is singular.
+fn solve(matrix: &mut [[f32; 4]; 3], n: usize) -> Option<[f32; 3]> {
+    let scale = (0..n).map(|i| matrix[i][i]).fold(0.0, f32::max);
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| ops::abs(matrix[a][column]).total_cmp(&ops::abs(matrix[b][column])))?;
+        if ops::abs(matrix[pivot][column]) <= SINGULAR_TOLERANCE * scale {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        for row in 0..n {
+            if row != column {
+                let pivot_row = matrix[column];
+                let factor = matrix[row][column] / pivot_row[column];
+                for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(column) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+    Some(array::from_fn(|i| {
+        if i < n {
+            matrix[i][3] / matrix[i][i]
+        } else {
+            0.0
+        }
+    }))
+}
+
+/// The result of [`gjk`].
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Gjk<V> {
+    /// The simplex containing the point of the Minkowski difference closest to the origin.
+    pub simplex: Simplex<V>,
+    /// Whether the Minkowski difference contains the origin, meaning that the shapes overlap.
+    pub overlapping: bool,
+}
+
+/// Finds the point of the Minkowski difference `A - B` of two convex shapes closest to the origin.
+///
+/// `support` returns the support points of `A` and `B` in the given direction and the opposite
+/// direction respectively. The first support point is taken in `initial_direction`.
+pub(super) fn gjk<V: GjkVector>(
+    support: impl Fn(V) -> SupportPoint<V>,
+    initial_direction: V,
+) -> Gjk<V> {
+    let first = support(initial_direction);
+    let mut simplex = Simplex::new(first);
+    let mut scale = first.point.dot(first.point);
+    let mut closest = first.point;
+
+    for _ in 0..MAX_ITERATIONS {
+        let distance_squared = closest.dot(closest);
+        if distance_squared <= OVERLAP_TOLERANCE * scale {
+            return Gjk {
+                simplex,
+                overlapping: true,
+            };
+        }
+
+        let vertex = support(-closest);
+        scale = scale.max(vertex.point.dot(vertex.point));
+        // Stop if the new vertex doesn't get meaningfully closer to the origin than the current
+        // closest point, in which case it's within the tolerance of the real distance.
+        let converged =
+            distance_squared - closest.dot(vertex.point) <= RELATIVE_TOLERANCE * distance_squared;
+        if converged
+            || simplex
+                .vertices()
+                .iter()
+                .any(|other| other.point == vertex.point)
+        {
+            break;
+        }
+
+        simplex.push(vertex);
+        simplex.reduce();
+        if simplex.len > V::DIM {
+            return Gjk {
+                simplex,
+                overlapping: true,
+            };
+        }
+
+        let next = simplex.closest_point();
+        let stalled = next.dot(next) >= distance_squared;
+        closest = next;
+        if stalled {
+            break;
+        }
+    }
+
+    Gjk {
+        simplex,
+        overlapping: closest.dot(closest) <= OVERLAP_TOLERANCE * scale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::{gjk, SupportPoint};
+    use crate::{Vec2, Vec3};
+
+    #[test]
+    fn simplex_reduction() {
+        // Unit squares centered at the origin and at (3, 0.5).
+        let square = |direction: Vec2, center: Vec2| center + direction.signum() * 0.5;
+        let support = |offset: Vec2| {
+            move |direction: Vec2| {
+                SupportPoint::new(square(direction, Vec2::ZERO), square(-direction, offset))
+            }
+        };
+
+        let result = gjk(support(Vec2::new(3.0, 0.5)), Vec2::X);
+        assert!(!result.overlapping);
+        let closest = result.simplex.closest_point();
+        assert_relative_eq!(closest, Vec2::new(-2.0, 0.0), epsilon = 1e-5);
+        let (a, b) = result.simplex.witnesses();
+        assert_relative_eq!(a.x, 0.5, epsilon = 1e-5);
+        assert_relative_eq!(b.x, 2.5, epsilon = 1e-5);
+
+        let result = gjk(support(Vec2::new(0.5, 0.5)), Vec2::X);
+        assert!(result.overlapping);
+
+        // Unit cubes touching diagonally at a single edge are not separated.
+        let cube = |direction: Vec3, center: Vec3| center + direction.signum() * 0.5;
+        let result = gjk(
+            |direction: Vec3| {
+                SupportPoint::new(
+                    cube(direction, Vec3::ZERO),
+                    cube(-direction, Vec3::new(1.0, 1.0, 0.0)),
+                )
+            },
+            Vec3::X,
+        );
+        assert!(result.simplex.closest_point().length() < 1e-5);
+    }
+}
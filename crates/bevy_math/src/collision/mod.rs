@@ -0,0 +1,554 @@
+//! Exact intersection, distance and contact queries between convex shapes.
+//!
+//! Any shape implementing [`SupportMap2d`] or [`SupportMap3d`] can be tested against any other
+//! shape of the same dimension, each placed in the world with an isometry:
+//! - [`intersects_2d`]/[`intersects_3d`] check whether two shapes intersect.
+//! - [`contact_2d`]/[`contact_3d`] compute the signed distance between two shapes, together with
+//!   their closest points if they are separated or the penetration depth and direction if they
+//!   overlap.
+//!
+//! The queries use the Gilbert-Johnson-Keerthi (GJK) algorithm to find the distance between
+//! separated shapes, and the Expanding Polytope Algorithm (EPA) to find the penetration depth of
+//! overlapping shapes. This is enough for simple collision checks, but doesn't replace a physics
+//! engine: there is no broad phase, and only a single contact point is computed.
+//!
+//! ```
+//! # use bevy_math::{collision::contact_2d, prelude::*};
+//! let circle = Circle::new(1.0);
+//! let rectangle = Rectangle::new(2.0, 2.0);
+//!
+//! let contact = contact_2d(&circle, Vec2::ZERO, &rectangle, Vec2::new(2.5, 0.0)).unwrap();
+//! assert!((contact.distance - 0.5).abs() < 1e-4);
+//! assert_eq!(contact.normal, Dir2::X);
+//!
+//! let contact = contact_2d(&circle, Vec2::ZERO, &rectangle, Vec2::new(1.5, 0.0)).unwrap();
+//! assert!((contact.penetration_depth() - 0.5).abs() < 1e-4);
+//! ```
+
+#[cfg(feature = "alloc")]
+mod epa;
+mod gjk;
+mod support;
+
+use gjk::{gjk, GjkVector, SupportPoint};
+
+use crate::{Dir2, Dir3, Isometry2d, Isometry3d, Vec2, Vec3};
+
+#[cfg(feature = "alloc")]
+use crate::ops;
+#[cfg(feature = "alloc")]
+use epa::{epa_2d, epa_3d, Penetration};
+#[cfg(feature = "alloc")]
+use gjk::Simplex;
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::Reflect;
+
+/// A convex 2D shape that can be used in [intersection and contact queries](self).
+///
+/// The shape is described as the set of points within [`rounding_radius`] of a convex core,
+/// which is given by its support function [`core_support`]. Describing rounded shapes like
+/// circles and capsules by a point or segment and a radius makes queries involving them both
+/// faster and more precise.
+///
+/// [`rounding_radius`]: Self::rounding_radius
+/// [`core_support`]: Self::core_support
+pub trait SupportMap2d {
+    /// Returns the point of the core of the shape that is farthest along `direction`, in the local
+    /// space of the shape.
+    ///
+    /// `direction` doesn't need to be normalized.
+    fn core_support(&self, direction: Vec2) -> Vec2;
+
+    /// Returns the radius by which the core of the shape is rounded.
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        0.0
+    }
+
+    /// Returns the point of the shape that is farthest along `direction`, in the local space of
+    /// the shape.
+    ///
+    /// `direction` doesn't need to be normalized.
+    #[inline]
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.core_support(direction) + direction.normalize_or_zero() * self.rounding_radius()
+    }
+}
+
+/// A convex 3D shape that can be used in [intersection and contact queries](self).
+///
+/// The shape is described as the set of points within [`rounding_radius`] of a convex core,
+/// which is given by its support function [`core_support`]. Describing rounded shapes like
+/// spheres and capsules by a point or segment and a radius makes queries involving them both
+/// faster and more precise.
+///
+/// [`rounding_radius`]: Self::rounding_radius
+/// [`core_support`]: Self::core_support
+pub trait SupportMap3d {
+    /// Returns the point of the core of the shape that is farthest along `direction`, in the local
+    /// space of the shape.
+    ///
+    /// `direction` doesn't need to be normalized.
+    fn core_support(&self, direction: Vec3) -> Vec3;
+
+    /// Returns the radius by which the core of the shape is rounded.
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        0.0
+    }
+
+    /// Returns the point of the shape that is farthest along `direction`, in the local space of
+    /// the shape.
+    ///
+    /// `direction` doesn't need to be normalized.
+    #[inline]
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.core_support(direction) + direction.normalize_or_zero() * self.rounding_radius()
+    }
+}
+
+/// The closest points or the deepest penetration between two 2D shapes, computed by
+/// [`contact_2d`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+pub struct Contact2d {
+    /// The point of the first shape closest to the second shape, or deepest inside of it if the
+    /// shapes overlap.
+    pub point_a: Vec2,
+    /// The point of the second shape closest to the first shape, or deepest inside of it if the
+    /// shapes overlap.
+    pub point_b: Vec2,
+    /// The direction from the first shape towards the second shape.
+    ///
+    /// Moving the second shape by `-distance` along this direction makes the shapes touch.
+    pub normal: Dir2,
+    /// The signed distance between the shapes, which is negative if they overlap.
+    pub distance: f32,
+}
+
+impl Contact2d {
+    /// Returns how deep the shapes penetrate each other, or zero if they are separated.
+    #[inline]
+    pub fn penetration_depth(&self) -> f32 {
+        (-self.distance).max(0.0)
+    }
+}
+
+/// The closest points or the deepest penetration between two 3D shapes, computed by
+/// [`contact_3d`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+pub struct Contact3d {
+    /// The point of the first shape closest to the second shape, or deepest inside of it if the
+    /// shapes overlap.
+    pub point_a: Vec3,
+    /// The point of the second shape closest to the first shape, or deepest inside of it if the
+    /// shapes overlap.
+    pub point_b: Vec3,
+    /// The direction from the first shape towards the second shape.
+    ///
+    /// Moving the second shape by `-distance` along this direction makes the shapes touch.
+    pub normal: Dir3,
+    /// The signed distance between the shapes, which is negative if they overlap.
+    pub distance: f32,
+}
+
+impl Contact3d {
+    /// Returns how deep the shapes penetrate each other, or zero if they are separated.
+    #[inline]
+    pub fn penetration_depth(&self) -> f32 {
+        (-self.distance).max(0.0)
+    }
+}
+
+/// Returns the support function of the Minkowski difference of the cores of two 2D shapes, in
+/// world space.
+fn core_support_2d<'a>(
+    shape_a: &'a (impl SupportMap2d + ?Sized),
+    isometry_a: Isometry2d,
+    shape_b: &'a (impl SupportMap2d + ?Sized),
+    isometry_b: Isometry2d,
+) -> impl Fn(Vec2) -> SupportPoint<Vec2> + 'a {
+    move |direction| {
+        SupportPoint::new(
+            isometry_a
+                .transform_point(shape_a.core_support(isometry_a.rotation.inverse() * direction)),
+            isometry_b
+                .transform_point(shape_b.core_support(isometry_b.rotation.inverse() * -direction)),
+        )
+    }
+}
+
+/// Returns the support function of the Minkowski difference of the cores of two 3D shapes, in
+/// world space.
+fn core_support_3d<'a>(
+    shape_a: &'a (impl SupportMap3d + ?Sized),
+    isometry_a: Isometry3d,
+    shape_b: &'a (impl SupportMap3d + ?Sized),
+    isometry_b: Isometry3d,
+) -> impl Fn(Vec3) -> SupportPoint<Vec3> + 'a {
+    move |direction| {
+        SupportPoint::new(
+            isometry_a
+                .transform_point(shape_a.core_support(isometry_a.rotation.inverse() * direction))
+                .into(),
+            isometry_b
+                .transform_point(shape_b.core_support(isometry_b.rotation.inverse() * -direction))
+                .into(),
+        )
+    }
+}
+
+/// Checks whether two 2D shapes, transformed by the given isometries, intersect.
+///
+/// Shapes that are just touching are considered to intersect.
+pub fn intersects_2d(
+    shape_a: &(impl SupportMap2d + ?Sized),
+    isometry_a: impl Into<Isometry2d>,
+    shape_b: &(impl SupportMap2d + ?Sized),
+    isometry_b: impl Into<Isometry2d>,
+) -> bool {
+    let (isometry_a, isometry_b) = (isometry_a.into(), isometry_b.into());
+    intersects(
+        core_support_2d(shape_a, isometry_a, shape_b, isometry_b),
+        shape_a.rounding_radius() + shape_b.rounding_radius(),
+        isometry_b.translation - isometry_a.translation,
+    )
+}
+
+/// Checks whether two 3D shapes, transformed by the given isometries, intersect.
+///
+/// Shapes that are just touching are considered to intersect.
+pub fn intersects_3d(
+    shape_a: &(impl SupportMap3d + ?Sized),
+    isometry_a: impl Into<Isometry3d>,
+    shape_b: &(impl SupportMap3d + ?Sized),
+    isometry_b: impl Into<Isometry3d>,
+) -> bool {
+    let (isometry_a, isometry_b) = (isometry_a.into(), isometry_b.into());
+    intersects(
+        core_support_3d(shape_a, isometry_a, shape_b, isometry_b),
+        shape_a.rounding_radius() + shape_b.rounding_radius(),
+        (isometry_b.translation - isometry_a.translation).into(),
+    )
+}
+
+fn intersects<V: GjkVector>(
+    core_support: impl Fn(V) -> SupportPoint<V>,
+    radius: f32,
+    initial_direction: V,
+) -> bool {
+    let result = gjk(core_support, initial_direction);
+    let closest = result.simplex.closest_point();
+    result.overlapping || closest.dot(closest) <= radius * radius
+}
+
+/// Computes the signed distance between two 2D shapes transformed by the given isometries, along
+/// with their closest points if they are separated or their deepest points if they overlap.
+///
+/// Returns `None` if the shapes or isometries aren't finite.
+#[cfg(feature = "alloc")]
+pub fn contact_2d(
+    shape_a: &(impl SupportMap2d + ?Sized),
+    isometry_a: impl Into<Isometry2d>,
+    shape_b: &(impl SupportMap2d + ?Sized),
+    isometry_b: impl Into<Isometry2d>,
+) -> Option<Contact2d> {
+    let (isometry_a, isometry_b) = (isometry_a.into(), isometry_b.into());
+    let (point_a, point_b, normal, distance) = contact(
+        core_support_2d(shape_a, isometry_a, shape_b, isometry_b),
+        [shape_a.rounding_radius(), shape_b.rounding_radius()],
+        isometry_b.translation - isometry_a.translation,
+        |support, simplex| epa_2d(support, simplex),
+    );
+    Some(Contact2d {
+        point_a,
+        point_b,
+        normal: Dir2::new(normal).ok()?,
+        distance,
+    })
+}
+
+/// Computes the signed distance between two 3D shapes transformed by the given isometries, along
+/// with their closest points if they are separated or their deepest points if they overlap.
+///
+/// Returns `None` if the shapes or isometries aren't finite.
+#[cfg(feature = "alloc")]
+pub fn contact_3d(
+    shape_a: &(impl SupportMap3d + ?Sized),
+    isometry_a: impl Into<Isometry3d>,
+    shape_b: &(impl SupportMap3d + ?Sized),
+    isometry_b: impl Into<Isometry3d>,
+) -> Option<Contact3d> {
+    let (isometry_a, isometry_b) = (isometry_a.into(), isometry_b.into());
+    let (point_a, point_b, normal, distance) = contact(
+        core_support_3d(shape_a, isometry_a, shape_b, isometry_b),
+        [shape_a.rounding_radius(), shape_b.rounding_radius()],
+        (isometry_b.translation - isometry_a.translation).into(),
+        |support, simplex| epa_3d(support, simplex),
+    );
+    Some(Contact3d {
+        point_a,
+        point_b,
+        normal: Dir3::new(normal).ok()?,
+        distance,
+    })
+}
+
+/// Computes the closest or deepest points of two shapes, the normal pointing from the first shape
+/// towards the second one, and the signed distance between them.
+#[cfg(feature = "alloc")]
+fn contact<V: GjkVector>(
+    core_support: impl Fn(V) -> SupportPoint<V>,
+    [radius_a, radius_b]: [f32; 2],
+    center_offset: V,
+    epa: impl FnOnce(&dyn Fn(V) -> SupportPoint<V>, &Simplex<V>) -> Result<Penetration<V>, V>,
+) -> (V, V, V, f32) {
+    // The rounded shapes are offset from their cores by their radii, so only the cores need to be
+    // tested against each other.
+    let cores = gjk(&core_support, center_offset);
+    let (point_a, point_b, normal, distance) = if !cores.overlapping {
+        let (point_a, point_b) = cores.simplex.witnesses();
+        let offset = point_b - point_a;
+        let distance = ops::sqrt(offset.dot(offset));
+        (point_a, point_b, offset * distance.recip(), distance)
+    } else {
+        match epa(&core_support, &cores.simplex) {
+            Ok(penetration) => (
+                penetration.point_a,
+                penetration.point_b,
+                penetration.normal,
+                -penetration.depth,
+            ),
+            // The cores are flat, so they can be separated by moving them apart by any distance
+            // along a normal of their Minkowski difference.
+            Err(normal) => {
+                let (point_a, point_b) = cores.simplex.witnesses();
+                let normal = if normal != V::ZERO {
+                    normal
+                } else {
+                    let offset = center_offset.normalize_or_zero();
+                    if offset != V::ZERO {
+                        offset
+                    } else {
+                        V::Y
+                    }
+                };
+                // Separate the shapes in the direction of their centers if possible.
+                let normal = if normal.dot(center_offset) < 0.0 {
+                    -normal
+                } else {
+                    normal
+                };
+                (point_a, point_b, normal, 0.0)
+            }
+        }
+    };
+
+    (
+        point_a + normal * radius_a,
+        point_b - normal * radius_b,
+        normal,
+        distance - radius_a - radius_b,
+    )
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::{
+        primitives::{
+            Capsule2d, Capsule3d, Circle, ConvexPolygon, Cuboid, Cylinder, Rectangle, Sphere,
+            Triangle2d, Triangle3d,
+        },
+        EulerRot, Quat, Rot2,
+    };
+
+    #[test]
+    fn circles() {
+        let a = Circle::new(1.0);
+        let b = Circle::new(0.5);
+
+        let contact = contact_2d(&a, Vec2::ZERO, &b, Vec2::new(0.0, 3.0)).unwrap();
+        assert_relative_eq!(contact.distance, 1.5);
+        assert_eq!(contact.normal, Dir2::Y);
+        assert_relative_eq!(contact.point_a, Vec2::new(0.0, 1.0));
+        assert_relative_eq!(contact.point_b, Vec2::new(0.0, 2.5));
+        assert!(!intersects_2d(&a, Vec2::ZERO, &b, Vec2::new(0.0, 3.0)));
+
+        let contact = contact_2d(&a, Vec2::ZERO, &b, Vec2::new(1.0, 0.0)).unwrap();
+        assert_relative_eq!(contact.distance, -0.5);
+        assert_relative_eq!(contact.penetration_depth(), 0.5);
+        assert_eq!(contact.normal, Dir2::X);
+        assert!(intersects_2d(&a, Vec2::ZERO, &b, Vec2::new(1.0, 0.0)));
+
+        // Concentric circles need to be separated by the sum of their radii in any direction.
+        let contact = contact_2d(&a, Vec2::ZERO, &b, Vec2::ZERO).unwrap();
+        assert_relative_eq!(contact.distance, -1.5, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn polygons() {
+        let square = Rectangle::new(2.0, 2.0);
+
+        // Overlapping squares are separated along the axis of least penetration.
+        let contact = contact_2d(&square, Vec2::ZERO, &square, Vec2::new(1.5, 0.2)).unwrap();
+        assert_relative_eq!(contact.distance, -0.5, epsilon = 1e-5);
+        assert_relative_eq!(contact.normal.as_vec2(), Vec2::X, epsilon = 1e-5);
+
+        // A corner of the rotated square points towards the circle.
+        let rotated = Isometry2d::from_rotation(Rot2::degrees(45.0));
+        let circle = Circle::new(0.5);
+        let contact = contact_2d(&square, rotated, &circle, Vec2::new(3.0, 0.0)).unwrap();
+        assert_relative_eq!(
+            contact.distance,
+            2.5 - core::f32::consts::SQRT_2,
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            contact.point_a,
+            Vec2::new(core::f32::consts::SQRT_2, 0.0),
+            epsilon = 1e-5
+        );
+
+        let triangle = Triangle2d::new(
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+        );
+        let hexagon = ConvexPolygon::convex_hull((0..6).map(|i| {
+            let (sin, cos) = ops::sin_cos(i as f32 * core::f32::consts::FRAC_PI_3);
+            Vec2::new(cos, sin)
+        }))
+        .unwrap();
+        let contact = contact_2d(&triangle, Vec2::ZERO, &hexagon, Vec2::new(0.0, -1.5)).unwrap();
+        assert_relative_eq!(contact.distance, 1.5 - ops::sqrt(0.75), epsilon = 1e-5);
+        assert_relative_eq!(contact.normal.as_vec2(), Vec2::NEG_Y, epsilon = 1e-5);
+
+        let capsule = Capsule2d::new(0.5, 2.0);
+        let isometry = Isometry2d::new(Vec2::new(0.0, 0.5), Rot2::degrees(90.0));
+        assert!(intersects_2d(&triangle, Vec2::ZERO, &capsule, isometry));
+        let contact = contact_2d(&triangle, Vec2::ZERO, &capsule, isometry).unwrap();
+        assert_relative_eq!(contact.distance, -1.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn cuboids() {
+        let cube = Cuboid::new(2.0, 2.0, 2.0);
+
+        let isometry = Isometry3d::from_translation(Vec3::new(0.2, 1.7, -0.1));
+        let contact = contact_3d(&cube, Vec3::ZERO, &cube, isometry).unwrap();
+        assert_relative_eq!(contact.distance, -0.3, epsilon = 1e-5);
+        assert_relative_eq!(contact.normal.as_vec3(), Vec3::Y, epsilon = 1e-5);
+
+        // The edge of the rotated cube is closest to the other cube.
+        let isometry = Isometry3d::new(
+            Vec3::new(4.0, 0.0, 0.0),
+            Quat::from_rotation_y(45.0_f32.to_radians()),
+        );
+        let contact = contact_3d(&cube, Vec3::ZERO, &cube, isometry).unwrap();
+        assert_relative_eq!(
+            contact.distance,
+            3.0 - core::f32::consts::SQRT_2,
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            contact.point_b.x,
+            4.0 - core::f32::consts::SQRT_2,
+            epsilon = 1e-5
+        );
+        assert!(!intersects_3d(&cube, Vec3::ZERO, &cube, isometry));
+    }
+
+    #[test]
+    fn rounded_shapes() {
+        let sphere = Sphere::new(1.0);
+        let capsule = Capsule3d::new(0.5, 2.0);
+        let cylinder = Cylinder::new(1.0, 2.0);
+
+        // A capsule lying on its side above a sphere.
+        let isometry = Isometry3d::new(
+            Vec3::new(0.0, 2.0, 0.0),
+            Quat::from_rotation_z(90.0_f32.to_radians()),
+        );
+        let contact = contact_3d(&sphere, Vec3::ZERO, &capsule, isometry).unwrap();
+        assert_relative_eq!(contact.distance, 0.5, epsilon = 1e-5);
+        assert_relative_eq!(contact.point_a, Vec3::Y, epsilon = 1e-5);
+        assert_relative_eq!(contact.point_b, Vec3::new(0.0, 1.5, 0.0), epsilon = 1e-5);
+
+        // A capsule standing inside a cylinder.
+        let contact =
+            contact_3d(&cylinder, Vec3::ZERO, &capsule, Vec3::new(0.8, 0.0, 0.0)).unwrap();
+        assert_relative_eq!(contact.distance, -0.7, epsilon = 1e-3);
+        assert_relative_eq!(contact.normal.as_vec3(), Vec3::X, epsilon = 1e-2);
+
+        // Concentric spheres need to be separated by the sum of their radii in any direction.
+        let contact = contact_3d(&sphere, Vec3::ZERO, &sphere, Vec3::ZERO).unwrap();
+        assert_relative_eq!(contact.distance, -2.0, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn sphere_around_cuboid() {
+        let cuboid = Cuboid::new(2.0, 1.0, 3.0);
+        let sphere = Sphere::new(0.25);
+        let isometry = Isometry3d::new(
+            Vec3::new(1.0, -2.0, 0.5),
+            Quat::from_euler(EulerRot::XYZ, 0.3, -1.1, 0.7),
+        );
+
+        // Compare against the exact distance for spheres on a grid both inside and outside of the
+        // cuboid.
+        for i in 0..343 {
+            let offset = Vec3::new((i % 7) as f32, (i / 7 % 7) as f32, (i / 49) as f32) / 2.0 - 1.5;
+            let local = offset * Vec3::new(1.3, 0.8, 1.1) + Vec3::splat(0.01);
+            let outside = (local.abs() - cuboid.half_size).max(Vec3::ZERO).length();
+            let inside = (cuboid.half_size - local.abs()).min_element().max(0.0);
+            let expected = outside - inside - sphere.radius;
+
+            let center = Vec3::from(isometry.transform_point(local));
+            let contact = contact_3d(&cuboid, isometry, &sphere, center).unwrap();
+            assert_relative_eq!(contact.distance, expected, epsilon = 1e-4);
+            assert_eq!(
+                intersects_3d(&cuboid, isometry, &sphere, center),
+                expected <= 0.0
+            );
+            // Moving the sphere out of the cuboid along the normal makes them touch.
+            let center = center - contact.normal * contact.distance;
+            let contact = contact_3d(&cuboid, isometry, &sphere, center).unwrap();
+            assert_relative_eq!(contact.distance, 0.0, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn flat_shapes() {
+        let triangle = Triangle3d::new(Vec3::ZERO, Vec3::X, Vec3::Z);
+        let sphere = Sphere::new(0.5);
+
+        let contact =
+            contact_3d(&triangle, Vec3::ZERO, &sphere, Vec3::new(0.25, 1.0, 0.25)).unwrap();
+        assert_relative_eq!(contact.distance, 0.5, epsilon = 1e-5);
+        assert_relative_eq!(contact.point_a, Vec3::new(0.25, 0.0, 0.25), epsilon = 1e-5);
+
+        let contact =
+            contact_3d(&triangle, Vec3::ZERO, &sphere, Vec3::new(0.25, 0.25, 0.25)).unwrap();
+        assert_relative_eq!(contact.distance, -0.25, epsilon = 1e-3);
+        assert_relative_eq!(contact.normal.as_vec3(), Vec3::Y, epsilon = 1e-2);
+
+        // Coplanar triangles overlap without penetrating.
+        let other = Isometry3d::from_translation(Vec3::new(0.2, 0.0, 0.2));
+        assert!(intersects_3d(&triangle, Vec3::ZERO, &triangle, other));
+        let contact = contact_3d(&triangle, Vec3::ZERO, &triangle, other).unwrap();
+        assert_relative_eq!(contact.distance, 0.0, epsilon = 1e-5);
+    }
+}
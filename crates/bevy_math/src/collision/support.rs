@@ -0,0 +1,225 @@
+//! [`SupportMap2d`] and [`SupportMap3d`] implementations for primitives.
+
+use super::{SupportMap2d, SupportMap3d};
+use crate::{
+    ops,
+    primitives::{
+        Capsule2d, Capsule3d, Circle, Cone, ConicalFrustum, Cuboid, Cylinder, Ellipse, Rectangle,
+        RegularPolygon, Rhombus, Segment2d, Segment3d, Sphere, Tetrahedron, Triangle2d, Triangle3d,
+    },
+    Vec2, Vec3,
+};
+
+#[cfg(feature = "alloc")]
+use crate::primitives::{ConvexPolygon, ConvexPolyhedron};
+
+/// Returns the point of `points` that is farthest along `direction`.
+#[inline]
+fn farthest_2d(points: impl IntoIterator<Item = Vec2>, direction: Vec2) -> Vec2 {
+    points
+        .into_iter()
+        .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+        .unwrap_or(Vec2::ZERO)
+}
+
+/// Returns the point of `points` that is farthest along `direction`.
+#[inline]
+fn farthest_3d(points: impl IntoIterator<Item = Vec3>, direction: Vec3) -> Vec3 {
+    points
+        .into_iter()
+        .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+        .unwrap_or(Vec3::ZERO)
+}
+
+impl SupportMap2d for Circle {
+    #[inline]
+    fn core_support(&self, _direction: Vec2) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl SupportMap2d for Ellipse {
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        // The normal of the ellipse at (x, y) is proportional to (x / a^2, y / b^2).
+        let scaled = self.half_size * self.half_size * direction;
+        let length_squared = scaled.dot(direction);
+        if length_squared > 0.0 {
+            scaled / ops::sqrt(length_squared)
+        } else {
+            Vec2::ZERO
+        }
+    }
+}
+
+impl SupportMap2d for Rectangle {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        Vec2::select(direction.cmpge(Vec2::ZERO), self.half_size, -self.half_size)
+    }
+}
+
+impl SupportMap2d for Rhombus {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        let [x, y] = self.half_diagonals.to_array();
+        farthest_2d(
+            [
+                Vec2::new(x, 0.0),
+                Vec2::new(-x, 0.0),
+                Vec2::new(0.0, y),
+                Vec2::new(0.0, -y),
+            ],
+            direction,
+        )
+    }
+}
+
+impl SupportMap2d for RegularPolygon {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        farthest_2d(self.vertices(0.0), direction)
+    }
+}
+
+impl SupportMap2d for Segment2d {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        farthest_2d(self.vertices, direction)
+    }
+}
+
+impl SupportMap2d for Triangle2d {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        farthest_2d(self.vertices, direction)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SupportMap2d for ConvexPolygon {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        farthest_2d(self.vertices().iter().copied(), direction)
+    }
+}
+
+impl SupportMap2d for Capsule2d {
+    #[inline]
+    fn core_support(&self, direction: Vec2) -> Vec2 {
+        Vec2::new(0.0, ops::copysign(self.half_length, direction.y))
+    }
+
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl SupportMap3d for Sphere {
+    #[inline]
+    fn core_support(&self, _direction: Vec3) -> Vec3 {
+        Vec3::ZERO
+    }
+
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl SupportMap3d for Cuboid {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        Vec3::select(direction.cmpge(Vec3::ZERO), self.half_size, -self.half_size)
+    }
+}
+
+impl SupportMap3d for Cylinder {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        let radial = Vec2::new(direction.x, direction.z).normalize_or_zero() * self.radius;
+        Vec3::new(
+            radial.x,
+            ops::copysign(self.half_height, direction.y),
+            radial.y,
+        )
+    }
+}
+
+impl SupportMap3d for Capsule3d {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        Vec3::new(0.0, ops::copysign(self.half_length, direction.y), 0.0)
+    }
+
+    #[inline]
+    fn rounding_radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl SupportMap3d for Cone {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        let half_height = self.height / 2.0;
+        let radial = Vec2::new(direction.x, direction.z).normalize_or_zero() * self.radius;
+        farthest_3d(
+            [
+                Vec3::new(0.0, half_height, 0.0),
+                Vec3::new(radial.x, -half_height, radial.y),
+            ],
+            direction,
+        )
+    }
+}
+
+impl SupportMap3d for ConicalFrustum {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        let half_height = self.height / 2.0;
+        let radial = Vec2::new(direction.x, direction.z).normalize_or_zero();
+        let top = radial * self.radius_top;
+        let bottom = radial * self.radius_bottom;
+        farthest_3d(
+            [
+                Vec3::new(top.x, half_height, top.y),
+                Vec3::new(bottom.x, -half_height, bottom.y),
+            ],
+            direction,
+        )
+    }
+}
+
+impl SupportMap3d for Segment3d {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        farthest_3d(self.vertices, direction)
+    }
+}
+
+impl SupportMap3d for Triangle3d {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        farthest_3d(self.vertices, direction)
+    }
+}
+
+impl SupportMap3d for Tetrahedron {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        farthest_3d(self.vertices, direction)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SupportMap3d for ConvexPolyhedron {
+    #[inline]
+    fn core_support(&self, direction: Vec3) -> Vec3 {
+        farthest_3d(self.vertices().iter().copied(), direction)
+    }
+}
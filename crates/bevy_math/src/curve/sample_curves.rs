@@ -349,6 +349,11 @@ impl<T> UnevenSampleAutoCurve<T> {
         })
     }
 
+    /// The timed samples underlying this [`UnevenSampleAutoCurve`].
+    pub fn core(&self) -> &UnevenCore<T> {
+        &self.core
+    }
+
     /// This [`UnevenSampleAutoCurve`], but with the sample times moved by the map `f`.
     /// In principle, when `f` is monotone, this is equivalent to [`CurveExt::reparametrize`],
     /// but the function inputs to each are inverses of one another.
@@ -0,0 +1,250 @@
+//! Convex hull algorithms backing [`ConvexPolygon::convex_hull`] and
+//! [`ConvexPolyhedron::convex_hull`].
+//!
+//! [`ConvexPolygon::convex_hull`]: super::ConvexPolygon::convex_hull
+//! [`ConvexPolyhedron::convex_hull`]: super::ConvexPolyhedron::convex_hull
+
+use alloc::vec::Vec;
+
+use crate::{ops, Vec2, Vec3};
+
+/// Computes the convex hull of `points` using Andrew's monotone chain algorithm.
+///
+/// Returns the vertices of the hull in counterclockwise order, starting with the vertex with the
+/// smallest X coordinate. Points lying on an edge of the hull and non-finite points are
+/// discarded. If all of the points are collinear, fewer than three vertices are returned.
+///
+/// This function will run in O(n * log n).
+pub(crate) fn convex_hull_2d(points: impl IntoIterator<Item = Vec2>) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = points.into_iter().filter(|p| p.is_finite()).collect();
+    points.sort_unstable_by(|a, b| a.x.total_cmp(&b.x).then_with(|| a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // A point is kept only if the chain turns left at it.
+    let turns_left = |hull: &[Vec2], p: Vec2| {
+        let [.., a, b] = hull else {
+            return true;
+        };
+        (*b - *a).perp_dot(p - *b) > 0.0
+    };
+
+    let mut hull = Vec::with_capacity(points.len() + 1);
+    // The lower hull, from left to right.
+    for &p in &points {
+        while hull.len() >= 2 && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The upper hull, from right to left.
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The last point is the first point of the lower hull.
+    hull.pop();
+    hull
+}
+
+/// A triangular face of a hull under construction, with its outward plane.
+struct HullFace {
+    vertices: [usize; 3],
+    normal: Vec3,
+    offset: f32,
+}
+
+impl HullFace {
+    fn new(points: &[Vec3], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|index| points[index]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        Self {
+            vertices,
+            normal,
+            offset: normal.dot(a),
+        }
+    }
+
+    fn distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.offset
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Computes the convex hull of `points` by incrementally adding points to an initial
+/// tetrahedron.
+///
+/// Returns the vertices of the hull together with its triangular faces, as indices into the
+/// returned vertices wound counterclockwise when seen from outside the hull. Non-finite points are
+/// discarded, and so are points lying on the surface of the hull in most cases.
+///
+/// Returns `None` if fewer than four of the points are finite or if they are all coplanar.
+///
+/// This function will run in O(n * f), where f is the number of faces of the hull.
+pub(crate) fn convex_hull_3d(
+    points: impl IntoIterator<Item = Vec3>,
+) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
+    let points: Vec<Vec3> = points.into_iter().filter(|p| p.is_finite()).collect();
+    let (min, max) = points
+        .iter()
+        .fold((Vec3::MAX, Vec3::MIN), |(min, max), &p| {
+            (min.min(p), max.max(p))
+        });
+    // Points closer than this to a face are considered to lie on it.
+    let epsilon = (max - min).max_element() * 1e-5;
+    if points.len() < 4 || epsilon <= 0.0 {
+        return None;
+    }
+
+    // Build the initial tetrahedron from extreme points.
+    let farthest = |distance: &dyn Fn(Vec3) -> f32| {
+        (0..points.len())
+            .map(|index| (index, distance(points[index])))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|&(_, distance)| distance > epsilon)
+            .map(|(index, _)| index)
+    };
+    let i0 = (0..points.len()).min_by(|&a, &b| points[a].x.total_cmp(&points[b].x))?;
+    let p0 = points[i0];
+    let i1 = farthest(&|p| p.distance(p0))?;
+    let axis = (points[i1] - p0).normalize();
+    let i2 = farthest(&|p| (p - p0).cross(axis).length())?;
+    let normal = axis.cross(points[i2] - p0).normalize();
+    let i3 = farthest(&|p| ops::abs(normal.dot(p - p0)))?;
+
+    let initial = if normal.dot(points[i3] - p0) > 0.0 {
+        [[i0, i2, i1], [i0, i1, i3], [i1, i2, i3], [i2, i0, i3]]
+    } else {
+        [[i0, i1, i2], [i0, i3, i1], [i1, i3, i2], [i2, i3, i0]]
+    };
+    let mut faces: Vec<HullFace> = initial
+        .into_iter()
+        .map(|vertices| HullFace::new(&points, vertices))
+        .collect();
+
+    // Adding the farthest points first makes it less likely for points that end up on the surface
+    // of the hull to be added as vertices before the hull grows past them.
+    let center = (min + max) / 2.0;
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_unstable_by(|&a, &b| {
+        let distance = |index: usize| points[index].distance_squared(center);
+        distance(b).total_cmp(&distance(a))
+    });
+
+    let mut horizon = Vec::new();
+    for index in order {
+        let point = points[index];
+        if faces.iter().all(|face| face.distance(point) <= epsilon) {
+            continue;
+        }
+
+        // The horizon consists of the edges of visible faces whose neighboring face isn't visible.
+        let visible_edges: Vec<(usize, usize)> = faces
+            .iter()
+            .filter(|face| face.distance(point) > epsilon)
+            .flat_map(HullFace::edges)
+            .collect();
+        horizon.clear();
+        horizon.extend(
+            visible_edges
+                .iter()
+                .filter(|&&(a, b)| !visible_edges.contains(&(b, a))),
+        );
+
+        faces.retain(|face| face.distance(point) <= epsilon);
+        faces.extend(
+            horizon
+                .iter()
+                .map(|&(a, b)| HullFace::new(&points, [a, b, index])),
+        );
+    }
+
+    // Only keep the points that ended up on the hull.
+    let mut remap = Vec::from_iter(core::iter::repeat_n(u32::MAX, points.len()));
+    let mut vertices = Vec::new();
+    let faces = faces
+        .iter()
+        .map(|face| {
+            face.vertices.map(|index| {
+                if remap[index] == u32::MAX {
+                    remap[index] = vertices.len() as u32;
+                    vertices.push(points[index]);
+                }
+                remap[index]
+            })
+        })
+        .collect();
+    Some((vertices, faces))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convex_hull_2d, convex_hull_3d};
+    use crate::{Vec2, Vec3};
+
+    #[test]
+    fn hull_2d() {
+        let points = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(0.5, 1.5),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(f32::NAN, 5.0),
+        ];
+        assert_eq!(
+            convex_hull_2d(points),
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0),
+            ]
+        );
+
+        // Collinear points don't have a hull with a nonzero area.
+        let points = [Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0), Vec2::ONE];
+        assert_eq!(convex_hull_2d(points).len(), 2);
+    }
+
+    #[test]
+    fn hull_3d() {
+        // The corners of a cube, with points on its faces and inside of it.
+        let corners = (0..8).map(|i| {
+            Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            )
+        });
+        let inner = (0..27)
+            .map(|i| Vec3::new((i % 3) as f32, (i / 3 % 3) as f32, (i / 9) as f32) - Vec3::ONE);
+        let (vertices, faces) = convex_hull_3d(inner.chain(corners)).unwrap();
+
+        assert_eq!(vertices.len(), 8);
+        assert!(vertices.iter().all(|v| v.abs() == Vec3::ONE));
+        assert_eq!(faces.len(), 12);
+        for face in &faces {
+            let [a, b, c] = face.map(|index| vertices[index as usize]);
+            let normal = (b - a).cross(c - a);
+            // Every face points away from the center of the cube.
+            assert!(normal.dot(a) > 0.0);
+        }
+
+        // Coplanar points don't have a hull with a nonzero volume.
+        let points = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::ONE.with_z(0.0), Vec3::X];
+        assert!(convex_hull_3d(points).is_none());
+    }
+}
@@ -11,7 +11,10 @@ use crate::{
 };
 
 #[cfg(feature = "alloc")]
-use super::polygon::is_polygon_simple;
+use super::{
+    convex_hull::convex_hull_2d,
+    polygon::{is_polygon_simple, triangulate_polygon},
+};
 
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
@@ -1930,6 +1933,31 @@ impl Polygon {
     pub fn is_simple(&self) -> bool {
         is_polygon_simple(&self.vertices)
     }
+
+    /// Triangulates the polygon using ear clipping.
+    ///
+    /// Returns the triangles as triples of indices into [`Polygon::vertices`], wound
+    /// counterclockwise regardless of the winding order of the polygon.
+    ///
+    /// The polygon may be concave, but should be [simple](Polygon::is_simple).
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        triangulate_polygon(&self.vertices, &[])
+    }
+
+    /// Triangulates the region inside this polygon and outside all of the `holes` using ear
+    /// clipping.
+    ///
+    /// Returns the triangles as triples of indices into the vertices of this polygon followed by
+    /// the vertices of each hole in order, wound counterclockwise regardless of the winding order
+    /// of the polygon and the holes.
+    ///
+    /// The polygon and the holes should be [simple](Polygon::is_simple), the holes should lie
+    /// inside the polygon, and the holes should not overlap each other. Holes outside the polygon
+    /// are ignored.
+    pub fn triangulate_with_holes(&self, holes: &[Polygon]) -> Vec<[usize; 3]> {
+        let holes: Vec<&[Vec2]> = holes.iter().map(|hole| hole.vertices.as_slice()).collect();
+        triangulate_polygon(&self.vertices, &holes)
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -2012,6 +2040,17 @@ impl ConvexPolygon {
         }
     }
 
+    /// Create the smallest [`ConvexPolygon`] containing all of the given `points`.
+    ///
+    /// The vertices of the hull are in counterclockwise order. Points lying on an edge of the
+    /// hull and non-finite points are discarded.
+    ///
+    /// Returns `None` if there are fewer than three points or if they are all collinear.
+    pub fn convex_hull(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        let vertices = convex_hull_2d(points);
+        (vertices.len() >= 3).then_some(Self { vertices })
+    }
+
     /// Get the vertices of this polygon
     #[inline]
     pub fn vertices(&self) -> &[Vec2] {
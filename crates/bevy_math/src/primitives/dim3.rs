@@ -12,6 +12,8 @@ use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use bevy_reflect::{ReflectDeserialize, ReflectSerialize};
 use glam::Quat;
 
+#[cfg(feature = "alloc")]
+use super::convex_hull::convex_hull_3d;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
@@ -1533,6 +1535,109 @@ impl Measured3d for Tetrahedron {
     }
 }
 
+/// A convex polyhedron with triangular faces.
+///
+/// Coplanar faces are not merged, so for example a cube is described by twelve triangles.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ConvexPolyhedron {
+    /// The vertices of the [`ConvexPolyhedron`].
+    vertices: Vec<Vec3>,
+    /// The faces of the [`ConvexPolyhedron`], as indices into its vertices.
+    faces: Vec<[u32; 3]>,
+}
+
+#[cfg(feature = "alloc")]
+impl Primitive3d for ConvexPolyhedron {}
+
+#[cfg(feature = "alloc")]
+impl ConvexPolyhedron {
+    /// Create the smallest [`ConvexPolyhedron`] containing all of the given `points`, for example
+    /// the positions of a mesh.
+    ///
+    /// Non-finite points are discarded, and so are points lying on the surface of the hull in
+    /// most cases.
+    ///
+    /// Returns `None` if there are fewer than four points or if they are all coplanar.
+    pub fn convex_hull(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let (vertices, faces) = convex_hull_3d(points)?;
+        Some(Self { vertices, faces })
+    }
+
+    /// Create a [`ConvexPolyhedron`] from its `vertices` and triangular `faces`, without checks.
+    ///
+    /// Use this version only if you know that the `faces` make up a closed convex surface, with
+    /// each face wound counterclockwise when seen from outside the polyhedron.
+    #[inline]
+    pub fn new_unchecked(
+        vertices: impl IntoIterator<Item = Vec3>,
+        faces: impl IntoIterator<Item = [u32; 3]>,
+    ) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+            faces: faces.into_iter().collect(),
+        }
+    }
+
+    /// Get the vertices of this polyhedron.
+    #[inline]
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    /// Get the triangular faces of this polyhedron, as indices into [`Self::vertices`] wound
+    /// counterclockwise when seen from outside the polyhedron.
+    #[inline]
+    pub fn faces(&self) -> &[[u32; 3]] {
+        &self.faces
+    }
+
+    /// Iterate over the faces of this polyhedron as triangles.
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle3d> + '_ {
+        self.faces.iter().map(|face| {
+            let [a, b, c] = face.map(|index| self.vertices[index as usize]);
+            Triangle3d::new(a, b, c)
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Measured3d for ConvexPolyhedron {
+    /// Get the surface area of the polyhedron.
+    #[inline]
+    fn area(&self) -> f32 {
+        self.triangles().map(|triangle| triangle.area()).sum()
+    }
+
+    /// Get the volume of the polyhedron.
+    #[inline]
+    fn volume(&self) -> f32 {
+        // Sum the signed volumes of the tetrahedra formed by each face and the first vertex.
+        let Some(&origin) = self.vertices.first() else {
+            return 0.0;
+        };
+        let volume: f32 = self
+            .triangles()
+            .map(
+                |Triangle3d {
+                     vertices: [a, b, c],
+                 }| (a - origin).dot((b - origin).cross(c - origin)),
+            )
+            .sum();
+        volume / 6.0
+    }
+}
+
 /// A 3D shape representing an extruded 2D `base_shape`.
 ///
 /// Extruding a shape effectively "thickens" a 2D shapes,
@@ -1582,6 +1687,24 @@ mod tests {
     use crate::{InvalidDirectionError, Quat};
     use approx::assert_relative_eq;
 
+    #[test]
+    fn convex_polyhedron_math() {
+        let cube = ConvexPolyhedron::convex_hull(
+            [-1.0, 1.0]
+                .into_iter()
+                .flat_map(|x| [-1.0, 1.0].map(|y| Vec2::new(x, y)))
+                .flat_map(|xy| [-1.0, 1.0].map(|z| xy.extend(z))),
+        )
+        .unwrap();
+        assert_eq!(cube.vertices().len(), 8);
+        assert_eq!(cube.faces().len(), 12);
+        assert_relative_eq!(cube.area(), 24.0);
+        assert_relative_eq!(cube.volume(), 8.0);
+
+        let coplanar = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(1.0, 1.0, 0.0)];
+        assert!(ConvexPolyhedron::convex_hull(coplanar).is_none());
+    }
+
     #[test]
     fn direction_creation() {
         assert_eq!(Dir3::new(Vec3::X * 12.5), Ok(Dir3::X));
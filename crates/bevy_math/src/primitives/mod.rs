@@ -8,6 +8,8 @@ mod dim3;
 pub use dim3::*;
 mod inset;
 pub use inset::*;
+#[cfg(feature = "alloc")]
+mod convex_hull;
 mod half_space;
 mod polygon;
 pub use half_space::*;
@@ -5,7 +5,7 @@ use {
     core::cmp::Ordering,
 };
 
-use crate::Vec2;
+use crate::{ops, Vec2};
 
 #[derive(Debug, Clone, Copy)]
 #[cfg(feature = "alloc")]
@@ -321,9 +321,253 @@ pub fn is_polygon_simple(vertices: &[Vec2]) -> bool {
     true
 }
 
+/// Triangulates the polygon bounded by `outer` with the given `holes` cut out of it, using ear
+/// clipping.
+///
+/// Returns triangles as triples of indices into the concatenation of `outer` and all of the
+/// `holes`, in order. The triangles are wound counterclockwise regardless of the winding order of
+/// the input.
+///
+/// Each hole is connected to the outer boundary by a bridge edge before clipping, so the holes
+/// must lie inside `outer` and must not overlap each other. Holes that aren't inside `outer` are
+/// ignored. For polygons that aren't simple the result is a best effort and may contain
+/// overlapping triangles.
+///
+/// This function will run in O(n²) for typical inputs and O(n³) in the worst case.
+#[cfg(feature = "alloc")]
+pub(crate) fn triangulate_polygon(outer: &[Vec2], holes: &[&[Vec2]]) -> Vec<[usize; 3]> {
+    if outer.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut points = outer.to_vec();
+    let mut hole_rings = Vec::with_capacity(holes.len());
+    for hole in holes {
+        let ring: Vec<usize> = (points.len()..points.len() + hole.len()).collect();
+        points.extend_from_slice(hole);
+        if ring.len() >= 3 {
+            hole_rings.push(oriented_ring(ring, &points, false));
+        }
+    }
+
+    let mut ring = oriented_ring((0..outer.len()).collect(), &points, true);
+
+    // Bridging holes from right to left guarantees that the bridge of a hole never crosses a hole
+    // that hasn't been bridged yet.
+    let max_x = |ring: &[usize]| {
+        ring.iter()
+            .map(|&index| points[index].x)
+            .fold(f32::NEG_INFINITY, f32::max)
+    };
+    hole_rings.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+    for hole in &hole_rings {
+        bridge_hole(&mut ring, hole, &points);
+    }
+
+    clip_ears(&ring, &points)
+}
+
+/// Returns the signed area of the polygon described by `ring`, which is positive if the polygon is
+/// wound counterclockwise.
+#[cfg(feature = "alloc")]
+fn signed_area(ring: &[usize], points: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, &index) in ring.iter().enumerate() {
+        let next = ring[(i + 1) % ring.len()];
+        area += points[index].perp_dot(points[next]);
+    }
+    area / 2.0
+}
+
+/// Reverses `ring` if needed so that it's wound counterclockwise if `ccw` is true, and clockwise
+/// otherwise.
+#[cfg(feature = "alloc")]
+fn oriented_ring(mut ring: Vec<usize>, points: &[Vec2], ccw: bool) -> Vec<usize> {
+    if (signed_area(&ring, points) > 0.0) != ccw {
+        ring.reverse();
+    }
+    ring
+}
+
+/// Tests whether `q` lies inside or on the boundary of the counterclockwise triangle `abc`.
+#[cfg(feature = "alloc")]
+fn triangle_contains(a: Vec2, b: Vec2, c: Vec2, q: Vec2) -> bool {
+    point_side(a, b, q) >= 0.0 && point_side(b, c, q) >= 0.0 && point_side(c, a, q) >= 0.0
+}
+
+/// Connects the clockwise `hole` to the counterclockwise `ring` with a pair of coincident edges,
+/// turning both into a single ring.
+///
+/// The bridge goes from the rightmost vertex of the hole to a vertex of the ring that is visible
+/// from it, as described in David Eberly's "Triangulation by Ear Clipping".
+#[cfg(feature = "alloc")]
+fn bridge_hole(ring: &mut Vec<usize>, hole: &[usize], points: &[Vec2]) {
+    let Some(hole_start) =
+        (0..hole.len()).max_by(|&a, &b| xy_order(points[hole[a]], points[hole[b]]))
+    else {
+        return;
+    };
+    let m = points[hole[hole_start]];
+
+    // Cast a ray from `m` towards +X and find the closest edge of the ring that it hits. As the ring
+    // is wound counterclockwise, that edge goes up if `m` is inside the ring, and down otherwise.
+    // The ray is treated as if it were infinitesimally above `m`, so that it never grazes a vertex:
+    // edges cover the half-open range of heights from their lower end, and edges sharing the hit
+    // point are ordered by where they cross just above it.
+    let mut closest: Option<(f32, f32, usize, bool)> = None;
+    for i in 0..ring.len() {
+        let j = (i + 1) % ring.len();
+        let (a, b) = (points[ring[i]], points[ring[j]]);
+        if a.y.min(b.y) > m.y || a.y.max(b.y) <= m.y {
+            continue;
+        }
+        let inverse_slope = (b.x - a.x) / (b.y - a.y);
+        let x = a.x + (m.y - a.y) * inverse_slope;
+        if x < m.x
+            || closest.is_some_and(|(closest_x, closest_slope, ..)| {
+                closest_x < x || (closest_x == x && closest_slope <= inverse_slope)
+            })
+        {
+            continue;
+        }
+        let candidate = if a.y == m.y || b.x <= a.x { i } else { j };
+        closest = Some((x, inverse_slope, candidate, b.y > a.y));
+    }
+    let Some((x, _, mut bridge, true)) = closest else {
+        // The hole isn't inside the ring.
+        return;
+    };
+
+    // The candidate vertex may be hidden behind other vertices of the ring. If so, the visible
+    // vertex is the one inside the triangle formed by `m`, the hit point and the candidate which
+    // makes the smallest angle with the ray.
+    let hit = Vec2::new(x, m.y);
+    let p = points[ring[bridge]];
+    if p != hit {
+        let (a, b, c) = if p.y < m.y { (m, p, hit) } else { (m, hit, p) };
+        let mut best_tangent = f32::INFINITY;
+        let mut best_distance = f32::INFINITY;
+        for (position, &index) in ring.iter().enumerate() {
+            let q = points[index];
+            if q == p || q == m || !triangle_contains(a, b, c, q) {
+                continue;
+            }
+            let delta = q - m;
+            let tangent = ops::abs(delta.y) / delta.x;
+            let distance = delta.length_squared();
+            if tangent < best_tangent || (tangent == best_tangent && distance < best_distance) {
+                best_tangent = tangent;
+                best_distance = distance;
+                bridge = position;
+            }
+        }
+    }
+
+    let mut bridged = Vec::with_capacity(ring.len() + hole.len() + 2);
+    bridged.extend_from_slice(&ring[..=bridge]);
+    bridged.extend_from_slice(&hole[hole_start..]);
+    bridged.extend_from_slice(&hole[..=hole_start]);
+    bridged.extend_from_slice(&ring[bridge..]);
+    *ring = bridged;
+}
+
+/// Triangulates the counterclockwise `ring` by repeatedly clipping ears off of it.
+#[cfg(feature = "alloc")]
+fn clip_ears(ring: &[usize], points: &[Vec2]) -> Vec<[usize; 3]> {
+    let len = ring.len();
+    let mut prev: Vec<usize> = (0..len).map(|i| (i + len - 1) % len).collect();
+    let mut next: Vec<usize> = (0..len).map(|i| (i + 1) % len).collect();
+    let mut triangles = Vec::with_capacity(len.saturating_sub(2));
+
+    let mut remaining = len;
+    let mut current = 0;
+    // The number of vertices visited since the last one was clipped.
+    let mut stalled = 0;
+    while remaining > 3 {
+        let (before, after) = (prev[current], next[current]);
+        let (a, b, c) = (
+            points[ring[before]],
+            points[ring[current]],
+            points[ring[after]],
+        );
+        let side = point_side(a, b, c);
+
+        let clip = if side == 0.0 {
+            // Degenerate vertices can be removed without changing the covered area.
+            true
+        } else if stalled < remaining {
+            side > 0.0 && is_ear(ring, points, &next, before, current, after)
+        } else if stalled < 2 * remaining {
+            // A full pass didn't find any ear, which only happens when the polygon isn't simple.
+            // Clip convex vertices regardless of what they overlap to guarantee progress.
+            side > 0.0
+        } else {
+            true
+        };
+
+        if clip {
+            if side != 0.0 {
+                triangles.push([ring[before], ring[current], ring[after]]);
+            }
+            next[before] = after;
+            prev[after] = before;
+            remaining -= 1;
+            stalled = 0;
+        } else {
+            stalled += 1;
+        }
+        current = after;
+    }
+
+    let (before, after) = (prev[current], next[current]);
+    if point_side(
+        points[ring[before]],
+        points[ring[current]],
+        points[ring[after]],
+    ) != 0.0
+    {
+        triangles.push([ring[before], ring[current], ring[after]]);
+    }
+    triangles
+}
+
+/// Tests whether the convex vertex `current` of the ring is an ear, which is the case if no other
+/// vertex of the ring lies inside the triangle it forms with its neighbors.
+#[cfg(feature = "alloc")]
+fn is_ear(
+    ring: &[usize],
+    points: &[Vec2],
+    next: &[usize],
+    before: usize,
+    current: usize,
+    after: usize,
+) -> bool {
+    let (a, b, c) = (
+        points[ring[before]],
+        points[ring[current]],
+        points[ring[after]],
+    );
+    let mut position = next[after];
+    while position != before {
+        let q = points[ring[position]];
+        // Bridges duplicate vertices, so vertices coinciding with a corner must be skipped.
+        if q != a && q != b && q != c && triangle_contains(a, b, c, q) {
+            return false;
+        }
+        position = next[position];
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{primitives::polygon::is_polygon_simple, Vec2};
+    use alloc::vec::Vec;
+
+    use crate::{
+        ops,
+        primitives::polygon::{is_polygon_simple, triangulate_polygon},
+        Vec2,
+    };
 
     #[test]
     fn complex_polygon() {
@@ -372,4 +616,91 @@ mod tests {
         let verts = [];
         assert!(is_polygon_simple(&verts));
     }
+
+    /// Returns the total area of `triangles`, asserting that they're all counterclockwise.
+    fn triangulated_area(vertices: &[Vec2], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let area = (vertices[b] - vertices[a]).perp_dot(vertices[c] - vertices[a]) / 2.0;
+                assert!(area > 0.0, "triangle {a}, {b}, {c} isn't counterclockwise");
+                area
+            })
+            .sum()
+    }
+
+    #[test]
+    fn triangulate_concave_polygon() {
+        // An L shape, wound clockwise.
+        let verts = [
+            Vec2::ZERO,
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+        let triangles = triangulate_polygon(&verts, &[]);
+        assert_eq!(triangles.len(), 4);
+        assert!(ops::abs(triangulated_area(&verts, &triangles) - 3.0) < 1e-6);
+
+        // Fewer than three vertices can't be triangulated.
+        assert!(triangulate_polygon(&verts[..2], &[]).is_empty());
+    }
+
+    #[test]
+    fn triangulate_polygon_with_holes() {
+        let outer = [
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let left_hole = [
+            Vec2::new(0.5, 1.0),
+            Vec2::new(1.5, 1.0),
+            Vec2::new(1.5, 3.0),
+            Vec2::new(0.5, 3.0),
+        ];
+        // Wound clockwise, unlike the other hole.
+        let right_hole = [
+            Vec2::new(2.5, 1.0),
+            Vec2::new(2.5, 3.0),
+            Vec2::new(3.5, 3.0),
+            Vec2::new(3.5, 1.0),
+        ];
+        let triangles = triangulate_polygon(&outer, &[&left_hole, &right_hole]);
+
+        let verts: Vec<Vec2> = [outer, left_hole, right_hole].concat();
+        assert!(ops::abs(triangulated_area(&verts, &triangles) - 12.0) < 1e-5);
+
+        // Holes outside of the polygon are ignored, including ones level with its vertices.
+        let outside_holes = [
+            [
+                Vec2::new(-3.0, 1.0),
+                Vec2::new(-2.0, 1.0),
+                Vec2::new(-2.0, 2.0),
+                Vec2::new(-3.0, 2.0),
+            ],
+            [
+                Vec2::new(-3.0, 3.0),
+                Vec2::new(-2.0, 3.0),
+                Vec2::new(-2.0, 4.0),
+                Vec2::new(-3.0, 4.0),
+            ],
+            [
+                Vec2::new(-3.0, -1.0),
+                Vec2::new(-2.0, -1.0),
+                Vec2::new(-2.0, 0.0),
+                Vec2::new(-3.0, 0.0),
+            ],
+        ];
+        for hole in &outside_holes {
+            let triangles = triangulate_polygon(&outer, &[hole]);
+            assert_eq!(triangles.len(), 2);
+            assert!(triangles.iter().flatten().all(|&index| index < outer.len()));
+            let verts: Vec<Vec2> = [&outer[..], hole].concat();
+            assert!(ops::abs(triangulated_area(&verts, &triangles) - 16.0) < 1e-5);
+        }
+    }
 }
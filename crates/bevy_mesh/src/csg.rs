@@ -0,0 +1,860 @@
+//! Plane slicing and constructive solid geometry.
+//!
+//! [`Mesh::sliced`] cuts a mesh in two capped halves along a plane, and [`Mesh::union`],
+//! [`Mesh::difference`] and [`Mesh::intersection`] combine two closed meshes.
+//!
+//! All of them work on binary space partitioning trees of the triangles of the meshes: the
+//! triangles of each mesh are split by the planes of the other one, and the pieces inside or
+//! outside of it are kept. The floating point attributes of split triangles, such as UVs, normals
+//! and colors, are interpolated.
+
+use core::mem;
+
+use bevy_math::{primitives::InfinitePlane3d, Isometry3d, Vec3};
+use bevy_platform::collections::{hash_map::Entry, HashMap};
+use thiserror::Error;
+
+use crate::{
+    Indices, Mesh, MeshAccessError, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues,
+    VertexFormat,
+};
+
+/// The distance under which points are considered to lie on a plane.
+const EPSILON: f32 = 1e-5;
+
+/// An error that can occur when slicing or combining a [`Mesh`].
+#[derive(Error, Debug, Clone)]
+pub enum MeshCsgError {
+    #[error("Only triangle lists are supported, but the mesh uses {0:?}.")]
+    UnsupportedTopology(PrimitiveTopology),
+    #[error("The mesh has no `Float32x3` positions.")]
+    MissingPositions,
+    #[error("Meshes with morph targets aren't supported.")]
+    MorphTargets,
+    #[error("Mesh access error: {0}")]
+    MeshAccessError(#[from] MeshAccessError),
+}
+
+impl Mesh {
+    /// Cuts this mesh along a plane, and returns the halves in front of and behind it, in the
+    /// direction of its normal.
+    ///
+    /// The plane is `plane` transformed by `isometry`. The cut is capped with faces on the plane,
+    /// whose UVs are a planar projection in world units, so that textures line up across the
+    /// cut. Caps are only reliable for closed meshes.
+    ///
+    /// Returns an error if the topology isn't [`PrimitiveTopology::TriangleList`], if the mesh
+    /// has no positions or has morph targets, or if the mesh data has been extracted to
+    /// `RenderWorld`.
+    pub fn sliced(
+        &self,
+        plane: InfinitePlane3d,
+        isometry: impl Into<Isometry3d>,
+    ) -> Result<(Mesh, Mesh), MeshCsgError> {
+        let isometry = isometry.into();
+        let normal = isometry.rotation * *plane.normal;
+        let origin = Vec3::from(isometry.translation);
+
+        let layout = Layout::of(self)?;
+        let mut vertices = Vertices::new(layout.stride);
+        let polygons = vertices.add_mesh(self, &layout)?;
+
+        // Each half is the intersection of the mesh with a box standing in for a half-space.
+        let extent = vertices
+            .positions
+            .iter()
+            .map(|position| position.distance(origin))
+            .fold(0.0, f32::max)
+            + 1.0;
+        let u = normal.any_orthonormal_vector();
+        let v = normal.cross(u);
+        let cap = Cap { origin, u, v };
+        let [front, back] = [(u, v, normal), (u, -v, -normal)].map(|(u, v, normal)| {
+            let half_space = vertices.add_box(&layout, &cap, [u, v, normal], extent);
+            let mesh = Bsp::new(polygons.clone(), &mut vertices);
+            let half_space = Bsp::new(half_space, &mut vertices);
+            let polygons = intersection(mesh, half_space, &mut vertices);
+            vertices.to_mesh(polygons, &layout, self.asset_usage)
+        });
+        Ok((front, back))
+    }
+
+    /// Returns the union of this mesh and `other`, the space inside of either of them.
+    ///
+    /// Both meshes must be closed. Only the floating point attributes present on both meshes
+    /// are kept.
+    ///
+    /// Returns an error if the topology of either mesh isn't [`PrimitiveTopology::TriangleList`],
+    /// if either has no positions or has morph targets, or if the mesh data has been extracted to
+    /// `RenderWorld`.
+    pub fn union(&self, other: &Mesh) -> Result<Mesh, MeshCsgError> {
+        self.combined(other, union)
+    }
+
+    /// Returns the difference of this mesh and `other`, the space inside of this mesh but not of
+    /// `other`.
+    ///
+    /// The inner faces carved by `other` keep its attributes, with flipped normals. Both meshes
+    /// must be closed. Only the floating point attributes present on both meshes are kept.
+    ///
+    /// Returns an error if the topology of either mesh isn't [`PrimitiveTopology::TriangleList`],
+    /// if either has no positions or has morph targets, or if the mesh data has been extracted to
+    /// `RenderWorld`.
+    pub fn difference(&self, other: &Mesh) -> Result<Mesh, MeshCsgError> {
+        self.combined(other, difference)
+    }
+
+    /// Returns the intersection of this mesh and `other`, the space inside of both of them.
+    ///
+    /// Both meshes must be closed. Only the floating point attributes present on both meshes
+    /// are kept.
+    ///
+    /// Returns an error if the topology of either mesh isn't [`PrimitiveTopology::TriangleList`],
+    /// if either has no positions or has morph targets, or if the mesh data has been extracted to
+    /// `RenderWorld`.
+    pub fn intersection(&self, other: &Mesh) -> Result<Mesh, MeshCsgError> {
+        self.combined(other, intersection)
+    }
+
+    fn combined(
+        &self,
+        other: &Mesh,
+        operation: fn(Bsp, Bsp, &mut Vertices) -> Vec<Polygon>,
+    ) -> Result<Mesh, MeshCsgError> {
+        let layout = Layout::of(self)?.common(&Layout::of(other)?);
+        let mut vertices = Vertices::new(layout.stride);
+        let a = vertices.add_mesh(self, &layout)?;
+        let b = vertices.add_mesh(other, &layout)?;
+        let a = Bsp::new(a, &mut vertices);
+        let b = Bsp::new(b, &mut vertices);
+        let polygons = operation(a, b, &mut vertices);
+        Ok(vertices.to_mesh(polygons, &layout, self.asset_usage))
+    }
+}
+
+fn union(mut a: Bsp, mut b: Bsp, vertices: &mut Vertices) -> Vec<Polygon> {
+    a.clip_to(&b, vertices);
+    b.clip_to(&a, vertices);
+    b.invert();
+    b.clip_to(&a, vertices);
+    b.invert();
+    a.build(b.into_polygons(), vertices);
+    a.into_polygons()
+}
+
+fn difference(mut a: Bsp, mut b: Bsp, vertices: &mut Vertices) -> Vec<Polygon> {
+    a.invert();
+    a.clip_to(&b, vertices);
+    b.clip_to(&a, vertices);
+    b.invert();
+    b.clip_to(&a, vertices);
+    b.invert();
+    a.build(b.into_polygons(), vertices);
+    a.invert();
+    a.into_polygons()
+}
+
+fn intersection(mut a: Bsp, mut b: Bsp, vertices: &mut Vertices) -> Vec<Polygon> {
+    a.invert();
+    b.clip_to(&a, vertices);
+    b.invert();
+    a.clip_to(&b, vertices);
+    b.clip_to(&a, vertices);
+    a.build(b.into_polygons(), vertices);
+    a.invert();
+    a.into_polygons()
+}
+
+/// The floating point attributes carried through an operation, besides positions.
+struct Layout {
+    /// The attributes, with their number of components and offset in the vertex data.
+    attributes: Vec<(MeshVertexAttribute, usize, usize)>,
+    stride: usize,
+}
+
+impl Layout {
+    fn of(mesh: &Mesh) -> Result<Self, MeshCsgError> {
+        let attributes = mesh
+            .try_attributes()?
+            .filter(|(attribute, _)| attribute.id != Mesh::ATTRIBUTE_POSITION.id)
+            .filter_map(|(attribute, _)| Some((*attribute, components(attribute.format)?)));
+        Ok(Self::new(attributes))
+    }
+
+    fn new(attributes: impl Iterator<Item = (MeshVertexAttribute, usize)>) -> Self {
+        let mut stride = 0;
+        let attributes = attributes
+            .map(|(attribute, components)| {
+                stride += components;
+                (attribute, components, stride - components)
+            })
+            .collect();
+        Self { attributes, stride }
+    }
+
+    /// Returns the attributes present in both layouts with the same format.
+    fn common(self, other: &Layout) -> Self {
+        Self::new(
+            self.attributes
+                .into_iter()
+                .filter(|(attribute, ..)| {
+                    other.attributes.iter().any(|(other, ..)| {
+                        other.id == attribute.id && other.format == attribute.format
+                    })
+                })
+                .map(|(attribute, components, _)| (attribute, components)),
+        )
+    }
+
+    fn offset(&self, attribute: &MeshVertexAttribute) -> Option<usize> {
+        self.attributes
+            .iter()
+            .find(|(other, ..)| other.id == attribute.id)
+            .map(|&(_, _, offset)| offset)
+    }
+}
+
+/// Returns the number of components of floating point vertex formats.
+fn components(format: VertexFormat) -> Option<usize> {
+    match format {
+        VertexFormat::Float32 => Some(1),
+        VertexFormat::Float32x2 => Some(2),
+        VertexFormat::Float32x3 => Some(3),
+        VertexFormat::Float32x4 => Some(4),
+        _ => None,
+    }
+}
+
+/// Returns the components of the `index`th value of a floating point attribute.
+fn attribute_components(values: &VertexAttributeValues, index: usize) -> &[f32] {
+    match values {
+        VertexAttributeValues::Float32(values) => core::slice::from_ref(&values[index]),
+        VertexAttributeValues::Float32x2(values) => &values[index],
+        VertexAttributeValues::Float32x3(values) => &values[index],
+        VertexAttributeValues::Float32x4(values) => &values[index],
+        _ => &[],
+    }
+}
+
+/// The plane of the caps of a slice, and the basis of their planar UVs.
+struct Cap {
+    origin: Vec3,
+    u: Vec3,
+    v: Vec3,
+}
+
+/// The vertices of all the polygons of an operation, which grows as polygons are split.
+struct Vertices {
+    positions: Vec<Vec3>,
+    /// The attributes of each vertex, as described by a [`Layout`].
+    data: Vec<f32>,
+    stride: usize,
+}
+
+impl Vertices {
+    fn new(stride: usize) -> Self {
+        Self {
+            positions: Vec::new(),
+            data: Vec::new(),
+            stride,
+        }
+    }
+
+    fn push(&mut self, position: Vec3, data: impl IntoIterator<Item = f32>) -> u32 {
+        self.positions.push(position);
+        self.data.extend(data);
+        (self.positions.len() - 1) as u32
+    }
+
+    fn data(&self, vertex: u32) -> &[f32] {
+        let start = vertex as usize * self.stride;
+        &self.data[start..start + self.stride]
+    }
+
+    /// Adds a vertex between `a` and `b`, at `t` from `a` to `b`.
+    fn interpolate(&mut self, a: u32, b: u32, t: f32) -> u32 {
+        let position = self.positions[a as usize].lerp(self.positions[b as usize], t);
+        let (a, b) = (a as usize * self.stride, b as usize * self.stride);
+        for i in 0..self.stride {
+            let value = self.data[a + i] + (self.data[b + i] - self.data[a + i]) * t;
+            self.data.push(value);
+        }
+        self.positions.push(position);
+        (self.positions.len() - 1) as u32
+    }
+
+    /// Adds the vertices of `mesh`, and returns its triangles.
+    fn add_mesh(&mut self, mesh: &Mesh, layout: &Layout) -> Result<Vec<Polygon>, MeshCsgError> {
+        let topology = mesh.primitive_topology();
+        if topology != PrimitiveTopology::TriangleList {
+            return Err(MeshCsgError::UnsupportedTopology(topology));
+        }
+        #[cfg(feature = "morph")]
+        if mesh.try_has_morph_targets()? {
+            return Err(MeshCsgError::MorphTargets);
+        }
+        let positions = mesh
+            .try_attribute_option(Mesh::ATTRIBUTE_POSITION)?
+            .and_then(VertexAttributeValues::as_float3)
+            .ok_or(MeshCsgError::MissingPositions)?;
+        let attributes = layout
+            .attributes
+            .iter()
+            .map(|(attribute, ..)| mesh.try_attribute(attribute.id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let base = self.positions.len() as u32;
+        for (index, &position) in positions.iter().enumerate() {
+            let data = attributes
+                .iter()
+                .flat_map(|values| attribute_components(values, index))
+                .copied();
+            self.push(position.into(), data);
+        }
+
+        let count = positions.len();
+        let indices: Vec<usize> = match mesh.try_indices_option()? {
+            Some(indices) => indices.iter().collect(),
+            None => (0..count).collect(),
+        };
+        let polygons = indices
+            .chunks_exact(3)
+            .filter(|triangle| triangle.iter().all(|&index| index < count))
+            .filter_map(|triangle| {
+                let vertices: Vec<u32> =
+                    triangle.iter().map(|&index| base + index as u32).collect();
+                Polygon::new(vertices, self)
+            })
+            .collect();
+        Ok(polygons)
+    }
+
+    /// Adds a box with the given axes, extending `extent` along the first two in both directions
+    /// and along the third one from the cap plane, and returns its faces.
+    ///
+    /// The axes must be an orthonormal right-handed basis.
+    fn add_box(
+        &mut self,
+        layout: &Layout,
+        cap: &Cap,
+        axes: [Vec3; 3],
+        extent: f32,
+    ) -> Vec<Polygon> {
+        let center = cap.origin + axes[2] * extent * 0.5;
+        let half_sizes = [extent, extent, extent * 0.5];
+        let mut polygons = Vec::new();
+        for axis in 0..3 {
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            let (a, b) = (axes[a] * half_sizes[a], axes[b] * half_sizes[b]);
+            for sign in [1.0, -1.0] {
+                let normal = axes[axis] * sign;
+                let face = center + normal * half_sizes[axis];
+                let mut corners = [face - a - b, face + a - b, face + a + b, face - a + b];
+                if sign < 0.0 {
+                    corners.reverse();
+                }
+                let vertices = corners
+                    .into_iter()
+                    .map(|corner| {
+                        let data = cap_data(layout, cap, corner, normal);
+                        self.push(corner, data)
+                    })
+                    .collect();
+                polygons.extend(Polygon::new(vertices, self));
+            }
+        }
+        polygons
+    }
+
+    /// Returns a mesh of `polygons`, which are triangulated.
+    fn to_mesh(
+        &self,
+        polygons: Vec<Polygon>,
+        layout: &Layout,
+        asset_usage: bevy_asset::RenderAssetUsages,
+    ) -> Mesh {
+        let normal = layout.offset(&Mesh::ATTRIBUTE_NORMAL);
+        let tangent = layout.offset(&Mesh::ATTRIBUTE_TANGENT);
+
+        // Vertices are shared between polygons with the same orientation.
+        let mut new_vertices = HashMap::<(u32, bool), u32>::default();
+        let mut positions = Vec::new();
+        let mut data = Vec::new();
+        let mut indices = Vec::new();
+        for polygon in polygons {
+            let mut vertices = Vec::with_capacity(polygon.vertices.len());
+            for &vertex in &polygon.vertices {
+                let index = match new_vertices.entry((vertex, polygon.flipped)) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let start = data.len();
+                        positions.push(self.positions[vertex as usize].to_array());
+                        data.extend_from_slice(self.data(vertex));
+                        if let Some(offset) = normal {
+                            let normal = &mut data[start + offset..start + offset + 3];
+                            let mut value = Vec3::from_slice(normal).normalize_or_zero();
+                            if polygon.flipped {
+                                value = -value;
+                            }
+                            normal.copy_from_slice(&value.to_array());
+                        }
+                        if let Some(offset) = tangent
+                            && polygon.flipped
+                        {
+                            data[start + offset + 3] = -data[start + offset + 3];
+                        }
+                        *entry.insert((positions.len() - 1) as u32)
+                    }
+                };
+                vertices.push(index);
+            }
+            for i in 1..vertices.len() - 1 {
+                indices.extend([vertices[0], vertices[i], vertices[i + 1]]);
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, asset_usage)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_indices(Indices::U32(indices));
+        for (attribute, components, offset) in &layout.attributes {
+            let values = data
+                .chunks_exact(layout.stride.max(1))
+                .map(|vertex| &vertex[*offset..*offset + *components]);
+            let values = match components {
+                1 => VertexAttributeValues::Float32(values.map(|value| value[0]).collect()),
+                2 => VertexAttributeValues::Float32x2(
+                    values.map(|value| [value[0], value[1]]).collect(),
+                ),
+                3 => VertexAttributeValues::Float32x3(
+                    values.map(|value| [value[0], value[1], value[2]]).collect(),
+                ),
+                _ => VertexAttributeValues::Float32x4(
+                    values
+                        .map(|value| [value[0], value[1], value[2], value[3]])
+                        .collect(),
+                ),
+            };
+            mesh.insert_attribute(*attribute, values);
+        }
+        mesh
+    }
+}
+
+/// Returns the attributes of a vertex of a cap at `position`, facing `normal`.
+fn cap_data(layout: &Layout, cap: &Cap, position: Vec3, normal: Vec3) -> Vec<f32> {
+    let uv = [
+        (position - cap.origin).dot(cap.u),
+        (position - cap.origin).dot(cap.v),
+    ];
+    // The handedness makes the bitangent follow the second UV axis.
+    let handedness = if normal.cross(cap.u).dot(cap.v) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let mut data = Vec::with_capacity(layout.stride);
+    for (attribute, components, _) in &layout.attributes {
+        let value = if attribute.id == Mesh::ATTRIBUTE_NORMAL.id {
+            normal.extend(0.0).to_array()
+        } else if attribute.id == Mesh::ATTRIBUTE_UV_0.id || attribute.id == Mesh::ATTRIBUTE_UV_1.id
+        {
+            [uv[0], uv[1], 0.0, 0.0]
+        } else if attribute.id == Mesh::ATTRIBUTE_TANGENT.id {
+            cap.u.extend(handedness).to_array()
+        } else if attribute.id == Mesh::ATTRIBUTE_COLOR.id {
+            [1.0; 4]
+        } else {
+            [0.0; 4]
+        };
+        data.extend_from_slice(&value[..*components]);
+    }
+    data
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: Vec3,
+    /// The distance of the plane from the origin along its normal.
+    w: f32,
+}
+
+impl Plane {
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+
+    /// Splits `polygons` by this plane.
+    fn split(&self, polygons: Vec<Polygon>, vertices: &mut Vertices) -> Split {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let mut split = Split::default();
+        for polygon in polygons {
+            let sides: Vec<u8> = polygon
+                .vertices
+                .iter()
+                .map(|&vertex| {
+                    let distance = self.normal.dot(vertices.positions[vertex as usize]) - self.w;
+                    if distance < -EPSILON {
+                        BACK
+                    } else if distance > EPSILON {
+                        FRONT
+                    } else {
+                        COPLANAR
+                    }
+                })
+                .collect();
+            match sides.iter().fold(COPLANAR, |sides, side| sides | side) {
+                COPLANAR if self.normal.dot(polygon.plane.normal) > 0.0 => {
+                    split.coplanar_front.push(polygon);
+                }
+                COPLANAR => split.coplanar_back.push(polygon),
+                FRONT => split.front.push(polygon),
+                BACK => split.back.push(polygon),
+                _ => {
+                    let mut front = Vec::new();
+                    let mut back = Vec::new();
+                    let count = polygon.vertices.len();
+                    for i in 0..count {
+                        let j = (i + 1) % count;
+                        let (a, b) = (polygon.vertices[i], polygon.vertices[j]);
+                        if sides[i] != BACK {
+                            front.push(a);
+                        }
+                        if sides[i] != FRONT {
+                            back.push(a);
+                        }
+                        if sides[i] | sides[j] == SPANNING {
+                            let (position_a, position_b) = (
+                                vertices.positions[a as usize],
+                                vertices.positions[b as usize],
+                            );
+                            let t = (self.w - self.normal.dot(position_a))
+                                / self.normal.dot(position_b - position_a);
+                            let vertex = vertices.interpolate(a, b, t);
+                            front.push(vertex);
+                            back.push(vertex);
+                        }
+                    }
+                    if front.len() >= 3 {
+                        split.front.push(Polygon {
+                            vertices: front,
+                            ..polygon
+                        });
+                    }
+                    if back.len() >= 3 {
+                        split.back.push(Polygon {
+                            vertices: back,
+                            ..polygon
+                        });
+                    }
+                }
+            }
+        }
+        split
+    }
+}
+
+#[derive(Default)]
+struct Split {
+    coplanar_front: Vec<Polygon>,
+    coplanar_back: Vec<Polygon>,
+    front: Vec<Polygon>,
+    back: Vec<Polygon>,
+}
+
+/// A convex planar polygon.
+#[derive(Clone, Debug)]
+struct Polygon {
+    vertices: Vec<u32>,
+    plane: Plane,
+    /// Whether the polygon faces away from its original orientation, in which case the normals
+    /// of its vertices are flipped.
+    flipped: bool,
+}
+
+impl Polygon {
+    /// Returns a polygon with the given vertices, or `None` if it's degenerate.
+    fn new(vertices: Vec<u32>, pool: &Vertices) -> Option<Self> {
+        let [a, b, c] = [0, 1, 2].map(|i| pool.positions[vertices[i] as usize]);
+        let normal = (b - a).cross(c - a).try_normalize()?;
+        Some(Self {
+            vertices,
+            plane: Plane {
+                normal,
+                w: normal.dot(a),
+            },
+            flipped: false,
+        })
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane.flip();
+        self.flipped = !self.flipped;
+    }
+}
+
+/// A node of a [`Bsp`] tree.
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<usize>,
+    back: Option<usize>,
+    /// The polygons lying on the plane of the node.
+    polygons: Vec<Polygon>,
+}
+
+/// A binary space partitioning tree of the polygons of a solid.
+///
+/// The back of each plane is considered inside of the solid. The nodes are stored in a flat list
+/// and traversed iteratively, since the trees of convex meshes are as deep as they have faces.
+struct Bsp {
+    /// The nodes of the tree, starting with the root.
+    nodes: Vec<Node>,
+}
+
+impl Bsp {
+    fn new(polygons: Vec<Polygon>, vertices: &mut Vertices) -> Self {
+        let mut bsp = Self {
+            nodes: vec![Node::default()],
+        };
+        bsp.build(polygons, vertices);
+        bsp
+    }
+
+    /// Adds `polygons` to the tree.
+    fn build(&mut self, polygons: Vec<Polygon>, vertices: &mut Vertices) {
+        let mut stack = vec![(0, polygons)];
+        while let Some((node, polygons)) = stack.pop() {
+            let Some(first) = polygons.first() else {
+                continue;
+            };
+            let plane = *self.nodes[node].plane.get_or_insert(first.plane);
+            let split = plane.split(polygons, vertices);
+            self.nodes[node].polygons.extend(split.coplanar_front);
+            self.nodes[node].polygons.extend(split.coplanar_back);
+            if !split.front.is_empty() {
+                stack.push((self.child(node, true), split.front));
+            }
+            if !split.back.is_empty() {
+                stack.push((self.child(node, false), split.back));
+            }
+        }
+    }
+
+    /// Returns the front or back child of `node`, creating it if needed.
+    fn child(&mut self, node: usize, front: bool) -> usize {
+        let index = self.nodes.len();
+        let child = if front {
+            &mut self.nodes[node].front
+        } else {
+            &mut self.nodes[node].back
+        };
+        match *child {
+            Some(child) => child,
+            None => {
+                *child = Some(index);
+                self.nodes.push(Node::default());
+                index
+            }
+        }
+    }
+
+    /// Swaps the inside and outside of the solid.
+    fn invert(&mut self) {
+        for node in &mut self.nodes {
+            node.polygons.iter_mut().for_each(Polygon::flip);
+            if let Some(plane) = &mut node.plane {
+                plane.flip();
+            }
+            mem::swap(&mut node.front, &mut node.back);
+        }
+    }
+
+    /// Returns the parts of `polygons` outside of the solid.
+    fn clip_polygons(&self, polygons: Vec<Polygon>, vertices: &mut Vertices) -> Vec<Polygon> {
+        let mut clipped = Vec::new();
+        let mut stack = vec![(0, polygons)];
+        while let Some((node, polygons)) = stack.pop() {
+            let node = &self.nodes[node];
+            let Some(plane) = node.plane else {
+                clipped.extend(polygons);
+                continue;
+            };
+            let mut split = plane.split(polygons, vertices);
+            split.front.append(&mut split.coplanar_front);
+            split.back.append(&mut split.coplanar_back);
+            match node.front {
+                Some(front) => stack.push((front, split.front)),
+                None => clipped.extend(split.front),
+            }
+            if let Some(back) = node.back {
+                stack.push((back, split.back));
+            }
+        }
+        clipped
+    }
+
+    /// Removes the parts of the polygons of this tree inside of the solid of `other`.
+    fn clip_to(&mut self, other: &Bsp, vertices: &mut Vertices) {
+        for node in &mut self.nodes {
+            let polygons = mem::take(&mut node.polygons);
+            node.polygons = other.clip_polygons(polygons, vertices);
+        }
+    }
+
+    fn into_polygons(self) -> Vec<Polygon> {
+        self.nodes
+            .into_iter()
+            .flat_map(|node| node.polygons)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::RenderAssetUsages;
+    use bevy_math::{
+        primitives::{Cuboid, InfinitePlane3d, Sphere},
+        Vec3,
+    };
+
+    use super::MeshCsgError;
+    use crate::{Mesh, MeshBuilder, Meshable, PrimitiveTopology, VertexAttributeValues};
+
+    fn triangles(mesh: &Mesh) -> Vec<[Vec3; 3]> {
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        indices
+            .chunks_exact(3)
+            .map(|triangle| [0, 1, 2].map(|i| Vec3::from(positions[triangle[i]])))
+            .collect()
+    }
+
+    /// Returns the signed volume enclosed by a closed mesh.
+    fn volume(mesh: &Mesh) -> f32 {
+        triangles(mesh)
+            .iter()
+            .map(|[a, b, c]| a.dot(b.cross(*c)) / 6.0)
+            .sum()
+    }
+
+    fn area(mesh: &Mesh) -> f32 {
+        triangles(mesh)
+            .iter()
+            .map(|[a, b, c]| (*b - *a).cross(*c - *a).length() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn slice() {
+        let cube = Cuboid::new(2.0, 2.0, 2.0).mesh().build();
+        let (front, back) = cube
+            .sliced(InfinitePlane3d::new(Vec3::Y), Vec3::new(0.0, 0.5, 0.0))
+            .unwrap();
+
+        // Both halves are closed boxes.
+        assert!((volume(&front) - 2.0).abs() < 1e-4);
+        assert!((volume(&back) - 6.0).abs() < 1e-4);
+        assert!((area(&front) - (8.0 + 4.0)).abs() < 1e-4);
+        assert!((area(&back) - (8.0 + 12.0)).abs() < 1e-4);
+
+        // Split faces keep their normals and interpolated UVs, and caps face out of the halves.
+        let positions = front
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            front.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("missing normals");
+        };
+        let Some(VertexAttributeValues::Float32x2(uvs)) = front.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("missing UVs");
+        };
+        for ((position, normal), uv) in positions.iter().zip(normals).zip(uvs) {
+            assert!(position[1] >= 0.5 - 1e-5);
+            if normal[0] > 0.5 {
+                assert!((position[0] - 1.0).abs() < 1e-5);
+                assert!((0.0..=1.0).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1]));
+            }
+            if position[1] < 0.5 + 1e-5 && normal[1] != 0.0 {
+                assert!(Vec3::from(*normal).abs_diff_eq(Vec3::NEG_Y, 1e-5));
+            }
+        }
+    }
+
+    #[test]
+    fn slice_sphere() {
+        let sphere = Sphere::new(1.0).mesh().ico(3).unwrap();
+        let (front, back) = sphere
+            .sliced(
+                InfinitePlane3d::new(Vec3::new(1.0, 2.0, 3.0)),
+                Vec3::new(0.1, 0.2, 0.0),
+            )
+            .unwrap();
+        assert!((volume(&front) + volume(&back) - volume(&sphere)).abs() < 1e-3);
+        assert!(volume(&front) > 0.0 && volume(&back) > volume(&front));
+    }
+
+    #[test]
+    fn slice_outside() {
+        let cube = Cuboid::new(2.0, 2.0, 2.0).mesh().build();
+        let (front, back) = cube
+            .sliced(InfinitePlane3d::new(Vec3::X), Vec3::new(5.0, 0.0, 0.0))
+            .unwrap();
+        assert!(triangles(&front).is_empty());
+        assert!((volume(&back) - 8.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn boolean_operations() {
+        let a = Cuboid::new(2.0, 2.0, 2.0).mesh().build();
+        let b = a.clone().translated_by(Vec3::ONE);
+
+        assert!((volume(&a.union(&b).unwrap()) - 15.0).abs() < 1e-4);
+        assert!((volume(&a.intersection(&b).unwrap()) - 1.0).abs() < 1e-4);
+
+        let difference = a.difference(&b).unwrap();
+        assert!((volume(&difference) - 7.0).abs() < 1e-4);
+        // The carved faces come from `b`, with flipped normals facing into the notch.
+        let positions = difference
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            difference.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("missing normals");
+        };
+        assert!(positions.iter().zip(normals).any(|(position, normal)| {
+            position[0].abs() < 1e-5
+                && position[1] > 0.0
+                && Vec3::from(*normal).abs_diff_eq(Vec3::X, 1e-5)
+        }));
+    }
+
+    #[test]
+    fn unsupported_topology() {
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3]; 2]);
+        let cube = Cuboid::default().mesh().build();
+        assert!(matches!(
+            cube.union(&lines),
+            Err(MeshCsgError::UnsupportedTopology(
+                PrimitiveTopology::LineList
+            ))
+        ));
+    }
+}
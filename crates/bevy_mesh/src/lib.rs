@@ -5,13 +5,16 @@ extern crate core;
 
 mod components;
 mod conversions;
+mod csg;
 mod index;
+mod lightmap_uv;
 mod mesh;
 #[cfg(feature = "bevy_mikktspace")]
 mod mikktspace;
 #[cfg(feature = "morph")]
 pub mod morph;
 pub mod primitives;
+mod simplify;
 pub mod skinning;
 mod vertex;
 use bevy_app::{App, Plugin, PostUpdate};
@@ -19,11 +22,14 @@ use bevy_asset::{AssetApp, AssetEventSystems};
 use bevy_ecs::schedule::IntoScheduleConfigs;
 use bitflags::bitflags;
 pub use components::*;
+pub use csg::*;
 pub use index::*;
+pub use lightmap_uv::*;
 pub use mesh::*;
 #[cfg(feature = "bevy_mikktspace")]
 pub use mikktspace::*;
 pub use primitives::*;
+pub use simplify::*;
 pub use vertex::*;
 pub use wgpu_types::VertexFormat;
 
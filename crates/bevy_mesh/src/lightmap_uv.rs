@@ -0,0 +1,609 @@
+//! Generation of lightmap UVs.
+//!
+//! [`Mesh::generate_lightmap_uvs`] lays out [`Mesh::ATTRIBUTE_UV_1`] so that every triangle covers
+//! its own area of a lightmap. Triangles are grouped into charts of connected triangles facing
+//! roughly the same direction, each chart is flattened by projecting it along the normal of its
+//! first triangle, and the charts are packed into rows of a texture. Triangles whose projections
+//! would overlap the rest of their chart, as on a spiral staircase, start a new chart instead.
+
+use core::f32::consts::{FRAC_PI_2, FRAC_PI_3};
+
+use bevy_math::{
+    bounding::{Aabb2d, DynamicBvh},
+    ops, UVec2, Vec2, Vec3,
+};
+use bevy_platform::collections::{hash_map::Entry, HashMap};
+use thiserror::Error;
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Indices, Mesh, MeshAccessError, PrimitiveTopology, VertexAttributeValues};
+
+/// Settings that control how [`Mesh::generate_lightmap_uvs`] lays out lightmap UVs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct LightmapUvSettings {
+    /// The number of lightmap texels per unit of length of the mesh.
+    ///
+    /// Must be finite and positive, otherwise [`LightmapUvError::InvalidTexelDensity`] is
+    /// returned.
+    pub texel_density: f32,
+
+    /// The minimum number of texels between charts, and between charts and the edges of the
+    /// lightmap, which prevents the lighting of a chart from bleeding into its neighbors when the
+    /// lightmap is filtered.
+    pub padding: u32,
+
+    /// The maximum angle in radians between the normal of a triangle and the normal of the first
+    /// triangle of its chart.
+    ///
+    /// Must be less than a right angle, so that the projection of the triangles of a chart
+    /// doesn't fold over itself, otherwise [`LightmapUvError::InvalidChartAngle`] is returned.
+    /// Smaller angles reduce the distortion of the lightmap, at the cost of more charts and seams.
+    pub max_chart_angle: f32,
+}
+
+impl Default for LightmapUvSettings {
+    fn default() -> Self {
+        Self {
+            texel_density: 16.0,
+            padding: 2,
+            max_chart_angle: FRAC_PI_3,
+        }
+    }
+}
+
+/// An error that can occur when generating lightmap UVs for a [`Mesh`].
+#[derive(Error, Debug, Clone)]
+pub enum LightmapUvError {
+    #[error("Only triangle lists are supported, but the mesh uses {0:?}.")]
+    UnsupportedTopology(PrimitiveTopology),
+    #[error("The mesh has no `Float32x3` positions.")]
+    MissingPositions,
+    #[error("Lightmap UVs can't be generated for meshes with morph targets.")]
+    MorphTargets,
+    #[error("The maximum chart angle must be less than a right angle, but is {0} radians.")]
+    InvalidChartAngle(f32),
+    #[error("The texel density must be finite and positive, but is {0}.")]
+    InvalidTexelDensity(f32),
+    #[error("Mesh access error: {0}")]
+    MeshAccessError(#[from] MeshAccessError),
+}
+
+impl Mesh {
+    /// Generates [`Mesh::ATTRIBUTE_UV_1`] for use with lightmaps, as controlled by `settings`, and
+    /// returns the size in texels of the lightmap the UVs are laid out for.
+    ///
+    /// The generated UVs don't overlap, so that every part of the surface gets its own lighting.
+    /// Vertices shared by several charts are duplicated, and an existing
+    /// [`Mesh::ATTRIBUTE_UV_1`] is replaced.
+    ///
+    /// Returns an error if the topology isn't [`PrimitiveTopology::TriangleList`], if the mesh
+    /// has no positions or has morph targets, if the mesh data has been extracted to
+    /// `RenderWorld`, if [`LightmapUvSettings::max_chart_angle`] isn't less than a right angle, or
+    /// if [`LightmapUvSettings::texel_density`] isn't finite and positive.
+    pub fn generate_lightmap_uvs(
+        &mut self,
+        settings: &LightmapUvSettings,
+    ) -> Result<UVec2, LightmapUvError> {
+        let topology = self.primitive_topology();
+        if topology != PrimitiveTopology::TriangleList {
+            return Err(LightmapUvError::UnsupportedTopology(topology));
+        }
+        #[cfg(feature = "morph")]
+        if self.try_has_morph_targets()? {
+            return Err(LightmapUvError::MorphTargets);
+        }
+        if settings.max_chart_angle.is_nan() || settings.max_chart_angle >= FRAC_PI_2 {
+            return Err(LightmapUvError::InvalidChartAngle(settings.max_chart_angle));
+        }
+        if !settings.texel_density.is_finite() || settings.texel_density <= 0.0 {
+            return Err(LightmapUvError::InvalidTexelDensity(settings.texel_density));
+        }
+        let positions: Vec<Vec3> = self
+            .try_attribute_option(Mesh::ATTRIBUTE_POSITION)?
+            .and_then(VertexAttributeValues::as_float3)
+            .ok_or(LightmapUvError::MissingPositions)?
+            .iter()
+            .map(|&position| position.into())
+            .collect();
+        let source_indices = self.try_indices_option()?;
+        let use_u16 = matches!(source_indices, Some(Indices::U16(_)));
+        let mut indices: Vec<u32> = match source_indices {
+            Some(indices) => indices.iter().map(|index| index as u32).collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        indices.truncate(indices.len() / 3 * 3);
+
+        let charts = Charts::new(&positions, &indices, settings);
+        let (layouts, size) = charts.pack(&positions, &indices, settings);
+
+        // Every vertex gets a copy for each chart it's used by.
+        let mut new_vertices = HashMap::<(u32, usize), u32>::default();
+        let mut sources = Vec::new();
+        let mut uvs = Vec::new();
+        let mut new_indices = Vec::with_capacity(indices.len());
+        for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+            let chart = charts.chart_of_triangle[triangle];
+            for &vertex in vertices {
+                let index = match new_vertices.entry((vertex, chart)) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let uv = layouts[chart].uv(positions[vertex as usize]) / size.as_vec2();
+                        sources.push(vertex);
+                        uvs.push(uv.to_array());
+                        *entry.insert((sources.len() - 1) as u32)
+                    }
+                };
+                new_indices.push(index);
+            }
+        }
+
+        // Duplicating vertices with the sources as indices gathers their attributes.
+        self.try_insert_indices(Indices::U32(sources))?;
+        self.try_duplicate_vertices()?;
+        let new_indices = if use_u16 && uvs.len() <= u16::MAX as usize + 1 {
+            Indices::U16(new_indices.into_iter().map(|index| index as u16).collect())
+        } else {
+            Indices::U32(new_indices)
+        };
+        self.try_insert_indices(new_indices)?;
+        self.try_insert_attribute(Mesh::ATTRIBUTE_UV_1, uvs)?;
+        Ok(size)
+    }
+}
+
+/// The triangles of a mesh grouped into charts.
+struct Charts {
+    chart_of_triangle: Vec<usize>,
+    /// The normal each chart is projected along.
+    normals: Vec<Vec3>,
+}
+
+impl Charts {
+    fn new(positions: &[Vec3], indices: &[u32], settings: &LightmapUvSettings) -> Self {
+        let triangle_count = indices.len() / 3;
+        let normals: Vec<Vec3> = indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| positions[triangle[i] as usize]);
+                (b - a).cross(c - a).normalize_or_zero()
+            })
+            .collect();
+
+        // Vertices with the same position are welded, so that seams of other attributes don't
+        // split charts.
+        let mut welded = HashMap::<[u32; 3], usize>::default();
+        let position_ids: Vec<usize> = positions
+            .iter()
+            .map(|position| {
+                let count = welded.len();
+                *welded
+                    .entry(position.to_array().map(f32::to_bits))
+                    .or_insert(count)
+            })
+            .collect();
+        let mut edges = HashMap::<(usize, usize), Vec<usize>>::default();
+        for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+            for i in 0..3 {
+                let a = position_ids[vertices[i] as usize];
+                let b = position_ids[vertices[(i + 1) % 3] as usize];
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push(triangle);
+            }
+        }
+
+        let edge_length = indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                positions[triangle[0] as usize].distance(positions[triangle[1] as usize])
+            })
+            .sum::<f32>()
+            / triangle_count.max(1) as f32;
+        let mut projection = ChartProjection {
+            triangles: DynamicBvh::new(),
+            tolerance: edge_length * 1e-4,
+        };
+
+        let min_cos = ops::cos(settings.max_chart_angle);
+        let mut chart_of_triangle = vec![usize::MAX; triangle_count];
+        let mut chart_normals = Vec::new();
+        for seed in 0..triangle_count {
+            if chart_of_triangle[seed] != usize::MAX {
+                continue;
+            }
+            let chart = chart_normals.len();
+            let normal = normals[seed];
+            chart_normals.push(normal);
+            chart_of_triangle[seed] = chart;
+            let u = normal.any_orthonormal_vector();
+            let v = normal.cross(u);
+            let project = |triangle: usize| {
+                [0, 1, 2].map(|i| {
+                    let position = positions[indices[triangle * 3 + i] as usize];
+                    Vec2::new(position.dot(u), position.dot(v))
+                })
+            };
+            projection.triangles.clear();
+            if normal != Vec3::ZERO {
+                projection.insert(project(seed));
+            }
+            let mut stack = vec![seed];
+            while let Some(triangle) = stack.pop() {
+                let vertices = &indices[triangle * 3..triangle * 3 + 3];
+                for i in 0..3 {
+                    let a = position_ids[vertices[i] as usize];
+                    let b = position_ids[vertices[(i + 1) % 3] as usize];
+                    for &neighbor in &edges[&(a.min(b), a.max(b))] {
+                        if chart_of_triangle[neighbor] != usize::MAX {
+                            continue;
+                        }
+                        // Degenerate triangles join any chart, since they cover no area.
+                        if normals[neighbor] == Vec3::ZERO {
+                            chart_of_triangle[neighbor] = chart;
+                            stack.push(neighbor);
+                            continue;
+                        }
+                        if normals[neighbor].dot(normal) < min_cos {
+                            continue;
+                        }
+                        let projected = project(neighbor);
+                        if projection.overlaps(projected) {
+                            continue;
+                        }
+                        projection.insert(projected);
+                        chart_of_triangle[neighbor] = chart;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        Self {
+            chart_of_triangle,
+            normals: chart_normals,
+        }
+    }
+
+    /// Flattens and packs the charts, and returns their layouts and the size of the lightmap.
+    fn pack(
+        &self,
+        positions: &[Vec3],
+        indices: &[u32],
+        settings: &LightmapUvSettings,
+    ) -> (Vec<ChartLayout>, UVec2) {
+        let mut layouts: Vec<ChartLayout> = self
+            .normals
+            .iter()
+            .map(|&normal| {
+                let normal = if normal == Vec3::ZERO {
+                    Vec3::Z
+                } else {
+                    normal
+                };
+                let u = normal.any_orthonormal_vector() * settings.texel_density;
+                let v = normal.cross(u);
+                ChartLayout {
+                    u,
+                    v,
+                    longest_edge: Vec2::ZERO,
+                    min: Vec2::splat(f32::INFINITY),
+                    max: Vec2::splat(f32::NEG_INFINITY),
+                    offset: Vec2::ZERO,
+                }
+            })
+            .collect();
+
+        // Charts are rotated so that their longest edge is horizontal, which usually makes
+        // their bounding rectangles tighter.
+        for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+            let layout = &mut layouts[self.chart_of_triangle[triangle]];
+            for i in 0..3 {
+                let a = layout.project(positions[vertices[i] as usize]);
+                let b = layout.project(positions[vertices[(i + 1) % 3] as usize]);
+                if (b - a).length_squared() > layout.longest_edge.length_squared() {
+                    layout.longest_edge = b - a;
+                }
+            }
+        }
+        for layout in &mut layouts {
+            let direction = layout.longest_edge.normalize_or(Vec2::X);
+            (layout.u, layout.v) = (
+                layout.u * direction.x + layout.v * direction.y,
+                layout.v * direction.x - layout.u * direction.y,
+            );
+        }
+        for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+            let layout = &mut layouts[self.chart_of_triangle[triangle]];
+            for &vertex in vertices {
+                let point = layout.project(positions[vertex as usize]);
+                layout.min = layout.min.min(point);
+                layout.max = layout.max.max(point);
+            }
+        }
+        for layout in &mut layouts {
+            // Charts are laid out wider than tall, which packs better into rows.
+            let size = layout.max - layout.min;
+            if size.y > size.x {
+                (layout.u, layout.v) = (layout.v, -layout.u);
+                (layout.min, layout.max) = (
+                    Vec2::new(layout.min.y, -layout.max.x),
+                    Vec2::new(layout.max.y, -layout.min.x),
+                );
+            }
+        }
+
+        // The charts are packed into rows, from the tallest to the shortest.
+        let padding = settings.padding as f32;
+        let sizes: Vec<Vec2> = layouts
+            .iter()
+            .map(|layout| (layout.max - layout.min).ceil().max(Vec2::ONE))
+            .collect();
+        let area: f32 = sizes
+            .iter()
+            .map(|size| (size.x + padding) * (size.y + padding))
+            .sum();
+        let widest = sizes.iter().map(|size| size.x).fold(0.0, f32::max);
+        let width = ops::sqrt(area).ceil().max(widest) + padding * 2.0;
+        let mut order: Vec<usize> = (0..layouts.len()).collect();
+        order.sort_by(|&a, &b| sizes[b].y.total_cmp(&sizes[a].y));
+        let mut cursor = Vec2::splat(padding);
+        let mut row_height = 0.0;
+        for chart in order {
+            let size = sizes[chart];
+            if cursor.x + size.x + padding > width {
+                cursor = Vec2::new(padding, cursor.y + row_height + padding);
+                row_height = 0.0;
+            }
+            let layout = &mut layouts[chart];
+            // The chart is centered in its rectangle of whole texels.
+            layout.offset = cursor + (size - (layout.max - layout.min)) * 0.5 - layout.min;
+            cursor.x += size.x + padding;
+            row_height = f32::max(row_height, size.y);
+        }
+        let height = cursor.y + row_height + padding;
+        (layouts, UVec2::new(width as u32, height as u32))
+    }
+}
+
+/// The projected triangles of a chart, in a hierarchy to find those that may overlap a triangle
+/// joining the chart.
+struct ChartProjection {
+    triangles: DynamicBvh<Aabb2d, [Vec2; 3]>,
+    /// Projections that only touch along their edges don't overlap.
+    tolerance: f32,
+}
+
+impl ChartProjection {
+    fn insert(&mut self, triangle: [Vec2; 3]) {
+        self.triangles.insert(Self::bounds(triangle), triangle);
+    }
+
+    /// Returns whether `triangle` overlaps any triangle of the chart by more than touching it.
+    fn overlaps(&self, triangle: [Vec2; 3]) -> bool {
+        self.triangles
+            .overlapping(&Self::bounds(triangle))
+            .any(|(.., &other)| !self.separated(triangle, other))
+    }
+
+    fn bounds(triangle: [Vec2; 3]) -> Aabb2d {
+        Aabb2d {
+            min: triangle[0].min(triangle[1]).min(triangle[2]),
+            max: triangle[0].max(triangle[1]).max(triangle[2]),
+        }
+    }
+
+    /// Returns whether an edge of either triangle separates them, by the separating axis theorem.
+    fn separated(&self, a: [Vec2; 3], b: [Vec2; 3]) -> bool {
+        [a, b].into_iter().any(|triangle| {
+            (0..3).any(|i| {
+                let axis = (triangle[(i + 1) % 3] - triangle[i])
+                    .perp()
+                    .normalize_or_zero();
+                let (a_min, a_max) = Self::interval(a, axis);
+                let (b_min, b_max) = Self::interval(b, axis);
+                a_max <= b_min + self.tolerance || b_max <= a_min + self.tolerance
+            })
+        })
+    }
+
+    fn interval(triangle: [Vec2; 3], axis: Vec2) -> (f32, f32) {
+        let [a, b, c] = triangle.map(|point| point.dot(axis));
+        (a.min(b).min(c), a.max(b).max(c))
+    }
+}
+
+/// How a chart is flattened and placed in the lightmap.
+struct ChartLayout {
+    /// The horizontal axis of the chart, scaled by the texel density.
+    u: Vec3,
+    /// The vertical axis of the chart, scaled by the texel density.
+    v: Vec3,
+    longest_edge: Vec2,
+    min: Vec2,
+    max: Vec2,
+    /// The offset from projected points to texels of the lightmap.
+    offset: Vec2,
+}
+
+impl ChartLayout {
+    fn project(&self, position: Vec3) -> Vec2 {
+        Vec2::new(position.dot(self.u), position.dot(self.v))
+    }
+
+    /// Returns the position of `position` in the lightmap, in texels.
+    fn uv(&self, position: Vec3) -> Vec2 {
+        self.project(position) + self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::{FRAC_PI_2, TAU};
+
+    use bevy_asset::RenderAssetUsages;
+    use bevy_math::{
+        ops,
+        primitives::{Cuboid, Sphere},
+        Vec2,
+    };
+
+    use super::{LightmapUvError, LightmapUvSettings};
+    use crate::{Indices, Mesh, MeshBuilder, Meshable, PrimitiveTopology, VertexAttributeValues};
+
+    /// Returns the lightmap UVs of each triangle, in texels.
+    fn uv_triangles(mesh: &Mesh, size: Vec2) -> Vec<[Vec2; 3]> {
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_1)
+        else {
+            panic!("missing lightmap UVs");
+        };
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        indices
+            .chunks_exact(3)
+            .map(|triangle| [0, 1, 2].map(|i| Vec2::from(uvs[triangle[i]]) * size))
+            .collect()
+    }
+
+    /// Asserts that the triangles are inside of the lightmap and that no texel center is covered
+    /// by several of them.
+    fn assert_no_overlaps(triangles: &[[Vec2; 3]], size: Vec2) {
+        for triangle in triangles {
+            for point in triangle {
+                assert!(point.cmpge(Vec2::ZERO).all() && point.cmple(size).all());
+            }
+        }
+        for x in 0..size.x as u32 {
+            for y in 0..size.y as u32 {
+                let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let covering = triangles
+                    .iter()
+                    .filter(|[a, b, c]| {
+                        let sides = [(a, b), (b, c), (c, a)]
+                            .map(|(from, to)| (*to - *from).perp_dot(center - *from));
+                        sides.iter().all(|side| *side > 1e-4)
+                            || sides.iter().all(|side| *side < -1e-4)
+                    })
+                    .count();
+                assert!(
+                    covering <= 1,
+                    "texel ({x}, {y}) is covered {covering} times"
+                );
+            }
+        }
+    }
+
+    fn area([a, b, c]: &[Vec2; 3]) -> f32 {
+        (*b - *a).perp_dot(*c - *a).abs() / 2.0
+    }
+
+    #[test]
+    fn cube() {
+        let mut cube = Cuboid::new(1.0, 2.0, 3.0).mesh().build();
+        let settings = LightmapUvSettings {
+            texel_density: 8.0,
+            padding: 2,
+            ..Default::default()
+        };
+        let size = cube.generate_lightmap_uvs(&settings).unwrap().as_vec2();
+        let triangles = uv_triangles(&cube, size);
+        assert_eq!(triangles.len(), 12);
+        assert_no_overlaps(&triangles, size);
+
+        // The area of the charts matches the surface of the cube at the texel density.
+        let surface = 2.0 * (1.0 * 2.0 + 2.0 * 3.0 + 1.0 * 3.0);
+        let uv_area: f32 = triangles.iter().map(area).sum();
+        assert!((uv_area - surface * 64.0).abs() < 1e-2);
+        // The charts, with their padding, take up a reasonable part of the lightmap.
+        assert!(uv_area > size.x * size.y * 0.3);
+
+        // The other attributes are kept.
+        assert!(cube.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+        assert!(cube.attribute(Mesh::ATTRIBUTE_UV_0).is_some());
+    }
+
+    #[test]
+    fn sphere() {
+        let mut sphere = Sphere::new(1.0).mesh().uv(16, 8);
+        let triangle_count = sphere.indices().unwrap().len() / 3;
+        let size = sphere
+            .generate_lightmap_uvs(&LightmapUvSettings::default())
+            .unwrap()
+            .as_vec2();
+        let triangles = uv_triangles(&sphere, size);
+        assert_eq!(triangles.len(), triangle_count);
+        assert_no_overlaps(&triangles, size);
+    }
+
+    #[test]
+    fn helical_ramp() {
+        // Two turns of a gently sloped ramp. All of its triangles face about the same way, but the
+        // turns would overlap if they were projected into the same chart.
+        let segments = 64;
+        let positions: Vec<[f32; 3]> = (0..=segments)
+            .flat_map(|i| {
+                let angle = 2.0 * TAU * i as f32 / segments as f32;
+                let (sin, cos) = ops::sin_cos(angle);
+                let height = 0.1 * angle;
+                [[cos, height, sin], [2.0 * cos, height, 2.0 * sin]]
+            })
+            .collect();
+        let indices: Vec<u32> = (0..segments)
+            .flat_map(|i| [2 * i, 2 * i + 1, 2 * i + 2, 2 * i + 2, 2 * i + 1, 2 * i + 3])
+            .collect();
+        let mut ramp = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices));
+
+        let size = ramp
+            .generate_lightmap_uvs(&LightmapUvSettings {
+                texel_density: 8.0,
+                ..Default::default()
+            })
+            .unwrap()
+            .as_vec2();
+        let triangles = uv_triangles(&ramp, size);
+        assert_eq!(triangles.len(), segments as usize * 2);
+        assert_no_overlaps(&triangles, size);
+    }
+
+    #[test]
+    fn invalid_chart_angle() {
+        let mut cube = Cuboid::default().mesh().build();
+        for angle in [FRAC_PI_2, 2.0, f32::NAN] {
+            assert!(matches!(
+                cube.generate_lightmap_uvs(&LightmapUvSettings {
+                    max_chart_angle: angle,
+                    ..Default::default()
+                }),
+                Err(LightmapUvError::InvalidChartAngle(_))
+            ));
+        }
+        for density in [0.0, -1.0, f32::INFINITY, f32::NAN] {
+            assert!(matches!(
+                cube.generate_lightmap_uvs(&LightmapUvSettings {
+                    texel_density: density,
+                    ..Default::default()
+                }),
+                Err(LightmapUvError::InvalidTexelDensity(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn unsupported_topology() {
+        let mut lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3]; 2]);
+        assert!(matches!(
+            lines.generate_lightmap_uvs(&LightmapUvSettings::default()),
+            Err(LightmapUvError::UnsupportedTopology(
+                PrimitiveTopology::LineList
+            ))
+        ));
+    }
+}
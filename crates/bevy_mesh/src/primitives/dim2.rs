@@ -10,8 +10,8 @@ use bevy_math::{
     ops,
     primitives::{
         Annulus, Capsule2d, Circle, CircularSector, CircularSegment, ConvexPolygon, Ellipse,
-        Primitive2d, Rectangle, RegularPolygon, Rhombus, Ring, Segment2d, Triangle2d, Triangle3d,
-        WindingOrder,
+        Polygon, Primitive2d, Rectangle, RegularPolygon, Rhombus, Ring, Segment2d, Triangle2d,
+        Triangle3d, WindingOrder,
     },
     FloatExt, Vec2, Vec3,
 };
@@ -461,6 +461,137 @@ impl From<ConvexPolygon> for Mesh {
     }
 }
 
+/// A builder used for creating a [`Mesh`] with a [`Polygon`] shape.
+///
+/// Unlike [`ConvexPolygonMeshBuilder`], the polygon may be concave and may have holes. It is
+/// triangulated with [`Polygon::triangulate_with_holes`], so it should be simple, and the holes
+/// should lie inside it without overlapping each other.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct PolygonMeshBuilder {
+    /// The outline of the polygon.
+    pub polygon: Polygon,
+    /// The holes cut out of the polygon.
+    pub holes: Vec<Polygon>,
+}
+
+impl PolygonMeshBuilder {
+    /// Creates a new [`PolygonMeshBuilder`] from the outline of a polygon.
+    #[inline]
+    pub fn new(polygon: Polygon) -> Self {
+        Self {
+            polygon,
+            holes: Vec::new(),
+        }
+    }
+
+    /// Cuts a hole with the given outline out of the polygon.
+    #[inline]
+    pub fn hole(mut self, hole: Polygon) -> Self {
+        self.holes.push(hole);
+        self
+    }
+
+    /// Returns the outline of the polygon followed by the outline of each hole.
+    fn rings(&self) -> impl Iterator<Item = &[Vec2]> {
+        core::iter::once(&self.polygon)
+            .chain(&self.holes)
+            .map(|polygon| polygon.vertices.as_slice())
+    }
+}
+
+impl Meshable for Polygon {
+    type Output = PolygonMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolygonMeshBuilder::new(self.clone())
+    }
+}
+
+impl MeshBuilder for PolygonMeshBuilder {
+    fn build(&self) -> Mesh {
+        let positions: Vec<[f32; 3]> = self
+            .rings()
+            .flatten()
+            .map(|vertex| [vertex.x, vertex.y, 0.0])
+            .collect();
+        let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+
+        // Map the bounding rectangle of the outline to the UV square.
+        let (min, max) = self
+            .polygon
+            .vertices
+            .iter()
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), &vertex| {
+                (min.min(vertex), max.max(vertex))
+            });
+        let size = (max - min).max(Vec2::splat(f32::EPSILON));
+        let uvs: Vec<[f32; 2]> = self
+            .rings()
+            .flatten()
+            .map(|&vertex| {
+                let uv = (vertex - min) / size;
+                [uv.x, 1.0 - uv.y]
+            })
+            .collect();
+
+        let indices = self
+            .polygon
+            .triangulate_with_holes(&self.holes)
+            .into_iter()
+            .flatten()
+            .map(|index| index as u32)
+            .collect();
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+}
+
+impl Extrudable for PolygonMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        let mut perimeter = Vec::with_capacity(1 + self.holes.len());
+        let mut offset = 0;
+        for (i, ring) in self.rings().enumerate() {
+            let len = ring.len() as u32;
+            if len >= 3 {
+                // The outside of the mesh must be to the right when walking along the perimeter,
+                // so the outline is walked counterclockwise and the holes clockwise.
+                let counterclockwise = signed_area(ring) > 0.0;
+                let mut indices: Vec<u32> = (offset..offset + len).chain([offset]).collect();
+                if counterclockwise != (i == 0) {
+                    indices.reverse();
+                }
+                perimeter.push(PerimeterSegment::Flat { indices });
+            }
+            offset += len;
+        }
+        perimeter
+    }
+}
+
+/// Returns the signed area of the polygon with the given `vertices`, which is positive if they
+/// are in counterclockwise order.
+fn signed_area(vertices: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, vertex) in vertices.iter().enumerate() {
+        area += vertex.perp_dot(vertices[(i + 1) % vertices.len()]);
+    }
+    area / 2.0
+}
+
+impl From<Polygon> for Mesh {
+    fn from(polygon: Polygon) -> Self {
+        polygon.mesh().build()
+    }
+}
+
 /// A builder used for creating a [`Mesh`] with a [`RegularPolygon`] shape.
 #[derive(Clone, Copy, Debug, Reflect)]
 #[reflect(Default, Debug, Clone)]
@@ -1526,10 +1657,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use bevy_math::{prelude::Annulus, primitives::RegularPolygon, FloatOrd};
+    use bevy_math::{
+        prelude::Annulus,
+        primitives::{Extrusion, Polygon, RegularPolygon},
+        FloatOrd, Vec2, Vec3,
+    };
     use bevy_platform::collections::HashSet;
 
-    use crate::{Mesh, MeshBuilder, Meshable, VertexAttributeValues};
+    use crate::{Extrudable, Mesh, MeshBuilder, Meshable, PerimeterSegment, VertexAttributeValues};
 
     fn count_distinct_positions(points: &[[f32; 3]]) -> usize {
         let mut map = <HashSet<_>>::default();
@@ -1605,4 +1740,58 @@ mod tests {
 
         assert_eq!(&[[0.0, 0.0, 1.0]; 4], &normals[..]);
     }
+
+    #[test]
+    fn test_polygon_with_hole() {
+        let square = |min: Vec2, size: f32| {
+            Polygon::new([
+                min,
+                min + Vec2::new(size, 0.0),
+                min + Vec2::splat(size),
+                min + Vec2::new(0.0, size),
+            ])
+        };
+        let builder = square(Vec2::ZERO, 4.0).mesh().hole(square(Vec2::ONE, 2.0));
+        let mesh = builder.build();
+
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        assert_eq!(positions.len(), 8);
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("Expected uvs f32x2");
+        };
+        assert_eq!([0.25, 0.75], uvs[4]);
+
+        // All triangles face +Z and together cover the square minus the hole.
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        let area: f32 = indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i]]));
+                let normal = (b - a).cross(c - a);
+                assert!(normal.z > 0.0);
+                normal.z / 2.0
+            })
+            .sum();
+        assert!((area - 12.0).abs() < 1e-5);
+
+        // The perimeter walks around the outline and around the hole.
+        let perimeter = builder.perimeter();
+        assert_eq!(perimeter.len(), 2);
+        let PerimeterSegment::Flat { indices } = &perimeter[1] else {
+            panic!("Expected a flat perimeter");
+        };
+        assert_eq!(indices, &[4, 7, 6, 5, 4]);
+
+        let extrusion = Extrusion::new(square(Vec2::ZERO, 4.0), 1.0)
+            .mesh()
+            .hole(square(Vec2::ONE, 2.0))
+            .build();
+        // Two caps, plus two vertices per edge on each side of the mantel.
+        assert_eq!(extrusion.count_vertices(), 2 * 8 + 2 * 2 * 8);
+    }
 }
@@ -1,5 +1,5 @@
 use bevy_math::{
-    primitives::{Annulus, Capsule2d, Circle, Ellipse, Extrusion, Primitive2d},
+    primitives::{Annulus, Capsule2d, Circle, Ellipse, Extrusion, Polygon, Primitive2d},
     Vec2, Vec3,
 };
 
@@ -172,6 +172,14 @@ impl ExtrusionBuilder<Capsule2d> {
     }
 }
 
+impl ExtrusionBuilder<Polygon> {
+    /// Cuts a hole with the given outline through the extrusion.
+    pub fn hole(mut self, hole: Polygon) -> Self {
+        self.base_builder.holes.push(hole);
+        self
+    }
+}
+
 impl<P> MeshBuilder for ExtrusionBuilder<P>
 where
     P: Primitive2d + Meshable,
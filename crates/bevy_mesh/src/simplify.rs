@@ -0,0 +1,781 @@
+//! Mesh simplification and level of detail generation.
+//!
+//! [`Mesh::simplified`] reduces the number of triangles of a mesh by repeatedly collapsing edges,
+//! picking the collapses that move the surface the least according to a quadric error metric.
+//! Collapses only ever move a vertex onto one of its neighbors, so the simplified mesh uses a
+//! subset of the original vertices and keeps all of their attributes.
+//!
+//! [`Mesh::generate_lods`] builds a chain of progressively simpler meshes, and
+//! [`MeshLodGenerator`] does the same as an [`AssetTransformer`](bevy_asset::transformer::AssetTransformer)
+//! so that the chain can be generated when processing assets.
+
+use core::{mem, ops::AddAssign};
+
+use bevy_math::{DVec3, Vec3};
+use bevy_platform::collections::{hash_map, HashMap};
+use thiserror::Error;
+
+#[cfg(feature = "serialize")]
+use {
+    bevy_asset::{
+        transformer::{AssetTransformer, TransformedAsset},
+        uuid::Uuid,
+        Handle, LoadedAsset,
+    },
+    bevy_reflect::TypePath,
+    serde::{Deserialize, Serialize},
+};
+
+use crate::{Indices, Mesh, MeshAccessError, PrimitiveTopology, VertexAttributeValues};
+
+/// The inverse of the precision up to which floating point vertex attributes are compared when
+/// welding vertices.
+const VERTEX_WELD_SCALE: f64 = (1 << 20) as f64;
+
+/// Settings that control how [`Mesh::simplified`] reduces the triangles of a mesh.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshSimplificationSettings {
+    /// The fraction of the triangles of the original mesh to keep, between 0 and 1.
+    ///
+    /// This is a target rather than a guarantee: simplification stops early if going further
+    /// would exceed [`max_error`](Self::max_error), or if the remaining triangles can't be
+    /// collapsed without changing the borders or seams of the mesh.
+    pub target_ratio: f32,
+
+    /// The maximum distance the simplified surface may deviate from the original one, as a
+    /// fraction of the diagonal of the bounding box of the mesh.
+    pub max_error: f32,
+}
+
+impl Default for MeshSimplificationSettings {
+    fn default() -> Self {
+        Self {
+            target_ratio: 0.5,
+            max_error: 0.01,
+        }
+    }
+}
+
+/// Settings for [`Mesh::generate_lods`] and [`MeshLodGenerator`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshLodSettings {
+    /// The settings used to generate each level of detail following the original mesh, from the
+    /// most to the least detailed.
+    ///
+    /// Every level is simplified from the original mesh, so each
+    /// [`target_ratio`](MeshSimplificationSettings::target_ratio) is relative to the original
+    /// triangle count.
+    pub levels: Vec<MeshSimplificationSettings>,
+}
+
+impl Default for MeshLodSettings {
+    fn default() -> Self {
+        Self {
+            levels: vec![
+                MeshSimplificationSettings {
+                    target_ratio: 0.5,
+                    max_error: 0.01,
+                },
+                MeshSimplificationSettings {
+                    target_ratio: 0.25,
+                    max_error: 0.02,
+                },
+                MeshSimplificationSettings {
+                    target_ratio: 0.125,
+                    max_error: 0.04,
+                },
+            ],
+        }
+    }
+}
+
+/// An error that can occur when simplifying a [`Mesh`].
+#[derive(Error, Debug, Clone)]
+pub enum MeshSimplificationError {
+    #[error("Only triangle lists can be simplified, but the mesh uses {0:?}.")]
+    UnsupportedTopology(PrimitiveTopology),
+    #[error("The mesh has no `Float32x3` positions.")]
+    MissingPositions,
+    #[error("Meshes with morph targets can't be simplified.")]
+    MorphTargets,
+    #[error("Mesh access error: {0}")]
+    MeshAccessError(#[from] MeshAccessError),
+}
+
+impl Mesh {
+    /// Returns a copy of this mesh with fewer triangles, as controlled by `settings`.
+    ///
+    /// Vertices on the borders of the mesh and on attribute seams, where vertices with different
+    /// attributes share a position, are never removed, so the outline of the mesh and its texture
+    /// mapping are preserved. Vertices with identical attributes are welded together first, so
+    /// non-indexed meshes are simplified like indexed ones.
+    ///
+    /// Returns an error if the topology isn't [`PrimitiveTopology::TriangleList`], if the mesh
+    /// has no positions or has morph targets, or if the mesh data has been extracted to
+    /// `RenderWorld`.
+    pub fn simplified(
+        &self,
+        settings: &MeshSimplificationSettings,
+    ) -> Result<Mesh, MeshSimplificationError> {
+        let topology = self.primitive_topology();
+        if topology != PrimitiveTopology::TriangleList {
+            return Err(MeshSimplificationError::UnsupportedTopology(topology));
+        }
+        #[cfg(feature = "morph")]
+        if self.try_has_morph_targets()? {
+            return Err(MeshSimplificationError::MorphTargets);
+        }
+        let positions = self
+            .try_attribute_option(Mesh::ATTRIBUTE_POSITION)?
+            .and_then(VertexAttributeValues::as_float3)
+            .ok_or(MeshSimplificationError::MissingPositions)?;
+
+        let source_indices = self.try_indices_option()?;
+        let mut indices: Vec<u32> = match source_indices {
+            Some(indices) => indices.iter().map(|index| index as u32).collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        indices.truncate(indices.len() / 3 * 3);
+        let welded = self.identical_vertices()?;
+        for index in &mut indices {
+            *index = welded[*index as usize];
+        }
+
+        let triangle_count = indices.len() / 3;
+        let target =
+            (settings.target_ratio.clamp(0.0, 1.0) * triangle_count as f32).ceil() as usize;
+        let (min, max) = positions.iter().fold(
+            (Vec3::INFINITY, Vec3::NEG_INFINITY),
+            |(min, max), &position| (min.min(position.into()), max.max(position.into())),
+        );
+        let max_error = (settings.max_error * min.distance(max)) as f64;
+
+        let mut simplifier = Simplifier::new(positions, &indices);
+        simplifier.simplify(target, max_error * max_error);
+
+        // Only keep the vertices used by the remaining triangles.
+        let mut remap = vec![u32::MAX; positions.len()];
+        let mut used = Vec::new();
+        let mut new_indices = match source_indices {
+            Some(Indices::U16(_)) => Indices::U16(Vec::new()),
+            _ => Indices::U32(Vec::new()),
+        };
+        new_indices.extend(simplifier.remaining_indices().map(|index| {
+            let new_index = &mut remap[index as usize];
+            if *new_index == u32::MAX {
+                *new_index = used.len() as u32;
+                used.push(index as usize);
+            }
+            *new_index
+        }));
+
+        let mut mesh = self.clone();
+        for (_, values) in mesh.try_attributes_mut()? {
+            let source = mem::replace(values, VertexAttributeValues::new((&*values).into()));
+            for &index in &used {
+                values.push_from(&source, index);
+            }
+        }
+        mesh.try_insert_indices(new_indices)?;
+        Ok(mesh)
+    }
+
+    /// Maps each vertex to the first vertex with the same attributes.
+    ///
+    /// Floating point attributes are compared up to rounding errors, so that for example the
+    /// normals computed for the faces of a flat surface are considered equal.
+    fn identical_vertices(&self) -> Result<Vec<u32>, MeshAccessError> {
+        let attributes: Vec<(&VertexAttributeValues, usize)> = self
+            .try_attributes()?
+            .map(|(_, values)| (values, values.get_bytes().len() / values.len().max(1)))
+            .collect();
+        let mut key = Vec::new();
+        let mut first_vertex = HashMap::<Vec<u8>, u32>::new();
+        Ok((0..self.count_vertices())
+            .map(|vertex| {
+                key.clear();
+                for &(values, size) in &attributes {
+                    let bytes = &values.get_bytes()[vertex * size..(vertex + 1) * size];
+                    match values {
+                        VertexAttributeValues::Float32(_)
+                        | VertexAttributeValues::Float32x2(_)
+                        | VertexAttributeValues::Float32x3(_)
+                        | VertexAttributeValues::Float32x4(_) => {
+                            for &value in bytemuck::cast_slice::<u8, f32>(bytes) {
+                                let rounded = (value as f64 * VERTEX_WELD_SCALE).round() as i64;
+                                key.extend_from_slice(&rounded.to_ne_bytes());
+                            }
+                        }
+                        _ => key.extend_from_slice(bytes),
+                    }
+                }
+                match first_vertex.entry_ref(key.as_slice()) {
+                    hash_map::EntryRef::Occupied(entry) => *entry.get(),
+                    hash_map::EntryRef::Vacant(entry) => *entry.insert(vertex as u32),
+                }
+            })
+            .collect())
+    }
+
+    /// Generates progressively simpler versions of this mesh, one for each of the
+    /// [`levels`](MeshLodSettings::levels) of `settings`.
+    ///
+    /// The original mesh is level 0 and isn't included. Generation stops at the first level that
+    /// couldn't be simplified further than the previous one.
+    ///
+    /// See [`Mesh::simplified`] for the errors that can occur.
+    pub fn generate_lods(
+        &self,
+        settings: &MeshLodSettings,
+    ) -> Result<Vec<Mesh>, MeshSimplificationError> {
+        let triangle_count = |mesh: &Mesh| -> Result<usize, MeshSimplificationError> {
+            Ok(match mesh.try_indices_option()? {
+                Some(indices) => indices.len() / 3,
+                None => mesh.count_vertices() / 3,
+            })
+        };
+
+        let mut lods = Vec::with_capacity(settings.levels.len());
+        let mut previous_count = triangle_count(self)?;
+        for level in &settings.levels {
+            let lod = self.simplified(level)?;
+            let count = triangle_count(&lod)?;
+            if count >= previous_count {
+                break;
+            }
+            previous_count = count;
+            lods.push(lod);
+        }
+        Ok(lods)
+    }
+}
+
+/// An [`AssetTransformer`] that generates a level of detail chain for a [`Mesh`].
+///
+/// The original mesh is left as is, and each generated level is added as a labeled sub-asset
+/// named by [`MeshLodGenerator::label`]. The levels are only kept by savers that write labeled
+/// sub-assets.
+///
+/// See [`Mesh::generate_lods`].
+#[cfg(feature = "serialize")]
+#[derive(Clone, Copy, Default, Debug, TypePath)]
+pub struct MeshLodGenerator;
+
+#[cfg(feature = "serialize")]
+impl MeshLodGenerator {
+    /// Returns the label of the sub-asset holding the given level of detail, starting at 1.
+    pub fn label(level: usize) -> String {
+        format!("Lod{level}")
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl AssetTransformer for MeshLodGenerator {
+    type AssetInput = Mesh;
+    type AssetOutput = Mesh;
+    type Settings = MeshLodSettings;
+    type Error = MeshSimplificationError;
+
+    async fn transform<'a>(
+        &'a self,
+        mut asset: TransformedAsset<Self::AssetInput>,
+        settings: &'a Self::Settings,
+    ) -> Result<TransformedAsset<Self::AssetOutput>, Self::Error> {
+        let lods = asset.generate_lods(settings)?;
+        for (level, lod) in (1..).zip(lods) {
+            // Every level gets its own random id, so that the levels of different meshes don't share
+            // handles.
+            let handle: Handle<Mesh> = Uuid::new_v4().into();
+            asset.insert_labeled(Self::label(level), handle, LoadedAsset::from(lod));
+        }
+        Ok(asset)
+    }
+}
+
+/// A quadric error metric: the weighted sum of the squared distances from a point to a set of
+/// planes.
+#[derive(Clone, Copy, Default, Debug)]
+struct Quadric {
+    /// The upper triangle of the symmetric 4x4 matrix of the quadric.
+    matrix: [f64; 10],
+    /// The sum of the weights of the planes.
+    weight: f64,
+}
+
+impl Quadric {
+    /// Creates a quadric measuring the squared distance to the plane through `point` with the
+    /// given unit `normal`, scaled by `weight`.
+    fn from_plane(normal: DVec3, point: DVec3, weight: f64) -> Self {
+        let [a, b, c] = normal.to_array();
+        let d = -normal.dot(point);
+        Self {
+            matrix: [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|value| value * weight),
+            weight,
+        }
+    }
+
+    /// Returns the weighted mean of the squared distances from `point` to the planes.
+    fn mean_error(&self, point: DVec3) -> f64 {
+        if self.weight <= 0.0 {
+            return 0.0;
+        }
+        let [a00, a01, a02, a03, a11, a12, a13, a22, a23, a33] = self.matrix;
+        let [x, y, z] = point.to_array();
+        let error = x * (a00 * x + 2.0 * (a01 * y + a02 * z + a03))
+            + y * (a11 * y + 2.0 * (a12 * z + a13))
+            + z * (a22 * z + 2.0 * a23)
+            + a33;
+        error.max(0.0) / self.weight
+    }
+}
+
+impl AddAssign for Quadric {
+    fn add_assign(&mut self, rhs: Self) {
+        for (value, other) in self.matrix.iter_mut().zip(rhs.matrix) {
+            *value += other;
+        }
+        self.weight += rhs.weight;
+    }
+}
+
+/// The triangles around each position of a [`Simplifier`], stored contiguously.
+struct Adjacency {
+    offsets: Vec<usize>,
+    triangles: Vec<usize>,
+}
+
+impl Adjacency {
+    fn triangles(&self, position: usize) -> &[usize] {
+        &self.triangles[self.offsets[position]..self.offsets[position + 1]]
+    }
+}
+
+/// Simplifies a triangle list by collapsing edges.
+///
+/// Vertices with the same position are welded together so that the connectivity of the surface
+/// is known even where the mesh has attribute seams.
+struct Simplifier {
+    /// The vertex indices of the corners of each triangle.
+    indices: Vec<u32>,
+    /// The welded position of each vertex.
+    position_ids: Vec<usize>,
+    positions: Vec<DVec3>,
+    quadrics: Vec<Quadric>,
+    /// Whether each position is on a border or a seam, and so can't be collapsed.
+    locked: Vec<bool>,
+    alive: Vec<bool>,
+    triangle_count: usize,
+}
+
+impl Simplifier {
+    fn new(vertex_positions: &[[f32; 3]], indices: &[u32]) -> Self {
+        // Weld vertices by position, ignoring the sign of zero.
+        let mut position_map = HashMap::<[u32; 3], usize>::new();
+        let mut positions = Vec::new();
+        let position_ids: Vec<usize> = vertex_positions
+            .iter()
+            .map(|&position| {
+                let position = position.map(|coordinate| coordinate + 0.0);
+                match position_map.entry(position.map(f32::to_bits)) {
+                    hash_map::Entry::Occupied(entry) => *entry.get(),
+                    hash_map::Entry::Vacant(entry) => {
+                        positions.push(Vec3::from(position).as_dvec3());
+                        *entry.insert(positions.len() - 1)
+                    }
+                }
+            })
+            .collect();
+
+        let mut locked = vec![false; positions.len()];
+        let mut wedges: Vec<Option<u32>> = vec![None; positions.len()];
+        let mut edges = HashMap::<(usize, usize), u32>::new();
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        let mut alive = Vec::with_capacity(indices.len() / 3);
+        for triangle in indices.chunks_exact(3) {
+            let ids = [0, 1, 2].map(|corner| position_ids[triangle[corner] as usize]);
+            let [a, b, c] = ids.map(|id| positions[id]);
+            let cross = (b - a).cross(c - a);
+            let area = cross.length() / 2.0;
+            // Triangles with repeated positions don't cover any area and are dropped.
+            if ids[0] == ids[1] || ids[1] == ids[2] || ids[2] == ids[0] || area <= 0.0 {
+                alive.push(false);
+                continue;
+            }
+            alive.push(true);
+
+            let quadric = Quadric::from_plane(cross / (2.0 * area), a, area);
+            for (&id, &vertex) in ids.iter().zip(triangle) {
+                quadrics[id] += quadric;
+                // A position used by several vertices is on a seam.
+                match wedges[id] {
+                    Some(wedge) if wedge != vertex => locked[id] = true,
+                    _ => wedges[id] = Some(vertex),
+                }
+            }
+            for (start, end) in [(ids[0], ids[1]), (ids[1], ids[2]), (ids[2], ids[0])] {
+                *edges.entry((start.min(end), start.max(end))).or_default() += 1;
+            }
+        }
+
+        // Edges that aren't shared by exactly two triangles are on a border, or are non-manifold.
+        for (&(start, end), &count) in &edges {
+            if count != 2 {
+                locked[start] = true;
+                locked[end] = true;
+            }
+        }
+
+        let triangle_count = alive.iter().filter(|&&alive| alive).count();
+        Self {
+            indices: indices.to_vec(),
+            position_ids,
+            positions,
+            quadrics,
+            locked,
+            alive,
+            triangle_count,
+        }
+    }
+
+    fn triangle_positions(&self, triangle: usize) -> [usize; 3] {
+        [0, 1, 2].map(|corner| self.position_ids[self.indices[triangle * 3 + corner] as usize])
+    }
+
+    fn adjacency(&self) -> Adjacency {
+        let mut offsets = vec![0; self.positions.len() + 1];
+        for triangle in (0..self.alive.len()).filter(|&triangle| self.alive[triangle]) {
+            for id in self.triangle_positions(triangle) {
+                offsets[id + 1] += 1;
+            }
+        }
+        for id in 0..self.positions.len() {
+            offsets[id + 1] += offsets[id];
+        }
+        let mut cursors = offsets.clone();
+        let mut triangles = vec![0; offsets[self.positions.len()]];
+        for triangle in (0..self.alive.len()).filter(|&triangle| self.alive[triangle]) {
+            for id in self.triangle_positions(triangle) {
+                triangles[cursors[id]] = triangle;
+                cursors[id] += 1;
+            }
+        }
+        Adjacency { offsets, triangles }
+    }
+
+    /// Returns the positions sharing a triangle with `id`.
+    fn neighbors(&self, adjacency: &Adjacency, id: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = adjacency
+            .triangles(id)
+            .iter()
+            .flat_map(|&triangle| self.triangle_positions(triangle))
+            .filter(|&neighbor| neighbor != id)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Collapses edges in order of increasing error until at most `target` triangles remain, or
+    /// until no collapse with a mean squared error of at most `max_error_squared` is left.
+    fn simplify(&mut self, target: usize, max_error_squared: f64) {
+        // Each pass collapses the cheapest edges whose neighborhoods don't overlap, so that the
+        // adjacency only needs to be rebuilt between passes.
+        while self.triangle_count > target {
+            let adjacency = self.adjacency();
+            let mut candidates: Vec<(f64, usize, usize)> = (0..self.positions.len())
+                .filter(|&id| !self.locked[id] && !adjacency.triangles(id).is_empty())
+                .filter_map(|from| {
+                    // Pick the cheapest valid collapse of each position. Collapses in a pass don't
+                    // overlap, so they stay valid until they are performed.
+                    let mut collapses: Vec<(f64, usize, usize)> = self
+                        .neighbors(&adjacency, from)
+                        .into_iter()
+                        .map(|to| (self.collapse_error(from, to), from, to))
+                        .filter(|&(error, ..)| error <= max_error_squared)
+                        .collect();
+                    collapses.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+                    collapses
+                        .into_iter()
+                        .find(|&(_, from, to)| self.can_collapse(&adjacency, from, to))
+                })
+                .collect();
+            candidates.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+            let mut touched = vec![false; self.positions.len()];
+            let mut collapsed = false;
+            for (_, from, to) in candidates {
+                if self.triangle_count <= target {
+                    break;
+                }
+                if touched[from] || touched[to] {
+                    continue;
+                }
+                for neighbor in self.neighbors(&adjacency, from) {
+                    touched[neighbor] = true;
+                }
+                touched[from] = true;
+                self.collapse(&adjacency, from, to);
+                collapsed = true;
+            }
+            if !collapsed {
+                break;
+            }
+        }
+    }
+
+    /// Returns the error of moving the position `from` onto the position `to`.
+    fn collapse_error(&self, from: usize, to: usize) -> f64 {
+        let mut quadric = self.quadrics[from];
+        quadric += self.quadrics[to];
+        quadric.mean_error(self.positions[to])
+    }
+
+    /// Returns whether the position `from` can be moved onto the position `to` without changing
+    /// the topology of the surface or flipping any triangles.
+    fn can_collapse(&self, adjacency: &Adjacency, from: usize, to: usize) -> bool {
+        // The only positions adjacent to both ends of the edge may be the opposite corners of the
+        // triangles sharing the edge, otherwise the collapse would pinch the surface.
+        let opposite: Vec<usize> = adjacency
+            .triangles(from)
+            .iter()
+            .map(|&triangle| self.triangle_positions(triangle))
+            .filter(|ids| ids.contains(&to))
+            .flat_map(|ids| ids.into_iter().find(|&id| id != from && id != to))
+            .collect();
+        let to_neighbors = self.neighbors(adjacency, to);
+        if self
+            .neighbors(adjacency, from)
+            .into_iter()
+            .any(|id| id != to && to_neighbors.contains(&id) && !opposite.contains(&id))
+        {
+            return false;
+        }
+
+        adjacency.triangles(from).iter().all(|&triangle| {
+            let ids = self.triangle_positions(triangle);
+            if ids.contains(&to) {
+                return true;
+            }
+            let [a, b, c] = ids.map(|id| self.positions[id]);
+            let [new_a, new_b, new_c] =
+                ids.map(|id| self.positions[if id == from { to } else { id }]);
+            let normal = (b - a).cross(c - a);
+            let new_normal = (new_b - new_a).cross(new_c - new_a);
+            new_normal.dot(normal) > 0.0
+        })
+    }
+
+    fn collapse(&mut self, adjacency: &Adjacency, from: usize, to: usize) {
+        // The triangles around `from` all use the same vertex, since `from` isn't on a seam, so
+        // they can all switch to the vertex of `to` used by the triangles sharing the edge.
+        let Some(to_vertex) = adjacency.triangles(from).iter().find_map(|&triangle| {
+            (0..3)
+                .map(|corner| self.indices[triangle * 3 + corner])
+                .find(|&vertex| self.position_ids[vertex as usize] == to)
+        }) else {
+            return;
+        };
+
+        for &triangle in adjacency.triangles(from) {
+            let ids = self.triangle_positions(triangle);
+            if ids.contains(&to) {
+                self.alive[triangle] = false;
+                self.triangle_count -= 1;
+            } else {
+                for (corner, id) in ids.into_iter().enumerate() {
+                    if id == from {
+                        self.indices[triangle * 3 + corner] = to_vertex;
+                    }
+                }
+            }
+        }
+        let quadric = self.quadrics[from];
+        self.quadrics[to] += quadric;
+    }
+
+    /// Returns the vertex indices of the remaining triangles.
+    fn remaining_indices(&self) -> impl Iterator<Item = u32> + '_ {
+        self.indices
+            .chunks_exact(3)
+            .zip(&self.alive)
+            .filter(|(_, alive)| **alive)
+            .flat_map(|(triangle, _)| triangle.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::RenderAssetUsages;
+    use bevy_math::{
+        primitives::{Cuboid, Plane3d, Sphere},
+        Vec2, Vec3,
+    };
+
+    use super::{MeshLodSettings, MeshSimplificationError, MeshSimplificationSettings};
+    use crate::{Indices, Mesh, MeshBuilder, Meshable, PrimitiveTopology};
+
+    fn triangle_count(mesh: &Mesh) -> usize {
+        mesh.indices().unwrap().len() / 3
+    }
+
+    #[test]
+    fn simplify_flat_grid() {
+        // A subdivided plane is flat, so its interior can be collapsed without any error, while
+        // its border is kept.
+        let plane = Plane3d::new(Vec3::Y, Vec2::ONE)
+            .mesh()
+            .subdivisions(8)
+            .build();
+        let simplified = plane
+            .simplified(&MeshSimplificationSettings {
+                target_ratio: 0.0,
+                max_error: 0.0,
+            })
+            .unwrap();
+
+        assert!(triangle_count(&simplified) < triangle_count(&plane) / 2);
+        let positions = simplified
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        assert_eq!(positions.len(), simplified.count_vertices());
+        assert!(positions.iter().all(|position| position[1] == 0.0));
+        // All four corners remain.
+        for corner in [
+            [-1.0, 0.0, -1.0],
+            [1.0, 0.0, -1.0],
+            [-1.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+        ] {
+            assert!(positions.contains(&corner));
+        }
+        // Every index refers to an existing vertex, and all attributes have been compacted.
+        assert!(simplified
+            .indices()
+            .unwrap()
+            .iter()
+            .all(|index| index < positions.len()));
+        for (_, values) in simplified.attributes() {
+            assert_eq!(values.len(), positions.len());
+        }
+    }
+
+    #[test]
+    fn simplify_non_indexed_grid() {
+        // Without indices, or with flat normals, every corner of every triangle is its own vertex,
+        // but the vertices of a flat plane still have the same attributes where they meet.
+        let plane = Plane3d::new(Vec3::Y, Vec2::ONE)
+            .mesh()
+            .subdivisions(8)
+            .build();
+        let original = triangle_count(&plane);
+        for mesh in [
+            plane.clone().with_duplicated_vertices(),
+            plane
+                .clone()
+                .with_duplicated_vertices()
+                .with_computed_flat_normals(),
+        ] {
+            let simplified = mesh
+                .simplified(&MeshSimplificationSettings {
+                    target_ratio: 0.0,
+                    max_error: 0.0,
+                })
+                .unwrap();
+            assert!(triangle_count(&simplified) < original / 2);
+            assert!(simplified.count_vertices() < mesh.count_vertices() / 2);
+        }
+    }
+
+    #[test]
+    fn simplify_sphere() {
+        let sphere = Sphere::new(1.0).mesh().ico(4).unwrap();
+        let simplified = sphere
+            .simplified(&MeshSimplificationSettings {
+                target_ratio: 0.25,
+                max_error: 0.05,
+            })
+            .unwrap();
+
+        let original = triangle_count(&sphere);
+        let count = triangle_count(&simplified);
+        assert!(count < original / 2, "{count} of {original} triangles left");
+        assert!(count + 2 >= original / 4);
+
+        // The remaining vertices are original vertices, and the triangles still face outwards.
+        let positions = simplified
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let indices: Vec<usize> = simplified.indices().unwrap().iter().collect();
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|corner| Vec3::from(positions[triangle[corner]]));
+            assert!((b - a).cross(c - a).dot(a + b + c) > 0.0);
+        }
+    }
+
+    #[test]
+    fn max_error_stops_simplification() {
+        // A cube has no faces that can be merged without moving its corners.
+        let cube = Cuboid::default().mesh().build();
+        let simplified = cube
+            .simplified(&MeshSimplificationSettings {
+                target_ratio: 0.0,
+                max_error: 0.01,
+            })
+            .unwrap();
+        assert_eq!(triangle_count(&simplified), triangle_count(&cube));
+    }
+
+    #[test]
+    fn unsupported_meshes() {
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3], [1.0; 3]])
+            .with_inserted_indices(Indices::U32(vec![0, 1]));
+        assert!(matches!(
+            lines.simplified(&MeshSimplificationSettings::default()),
+            Err(MeshSimplificationError::UnsupportedTopology(
+                PrimitiveTopology::LineList
+            ))
+        ));
+
+        let empty = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        assert!(matches!(
+            empty.simplified(&MeshSimplificationSettings::default()),
+            Err(MeshSimplificationError::MissingPositions)
+        ));
+    }
+
+    #[test]
+    fn lod_chain() {
+        let sphere = Sphere::new(1.0).mesh().ico(4).unwrap();
+        let lods = sphere.generate_lods(&MeshLodSettings::default()).unwrap();
+
+        assert_eq!(lods.len(), 3);
+        let mut previous = triangle_count(&sphere);
+        for lod in &lods {
+            let count = triangle_count(lod);
+            assert!(count < previous);
+            previous = count;
+        }
+    }
+}
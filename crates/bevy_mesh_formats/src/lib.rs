@@ -0,0 +1,87 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![forbid(unsafe_code)]
+#![doc(
+    html_logo_url = "https://bevy.org/assets/icon.png",
+    html_favicon_url = "https://bevy.org/assets/icon.png"
+)]
+
+//! Plugin providing [`AssetLoader`](bevy_asset::AssetLoader)s and
+//! [`AssetSaver`](bevy_asset::saver::AssetSaver)s for common mesh interchange formats.
+//!
+//! Each format is enabled by the cargo feature of the same name:
+//!
+//! | Format                                                            | Loader        | Saver        |
+//! |-------------------------------------------------------------------|---------------|--------------|
+//! | [Wavefront OBJ](https://en.wikipedia.org/wiki/Wavefront_.obj_file) with MTL materials | `ObjLoader` | None |
+//! | [STL](https://en.wikipedia.org/wiki/STL_(file_format)), binary and ASCII | `StlLoader` | `StlSaver` |
+//! | [PLY](https://en.wikipedia.org/wiki/PLY_(file_format)), binary and ASCII | `PlyLoader` | `PlySaver` |
+//!
+//! STL and PLY files contain a single mesh, and are loaded directly as a [`Mesh`](bevy_mesh::Mesh).
+//! OBJ files can contain several objects and reference materials, and are loaded as an `Obj`
+//! with a mesh and a `StandardMaterial` for each object, and a scene containing all of them:
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_asset::prelude::*;
+//! # use bevy_scene::prelude::*;
+//! # use bevy_mesh::{Mesh, Mesh3d};
+//! # use bevy_pbr::{MeshMaterial3d, StandardMaterial};
+//! # use bevy_mesh_formats::ObjAssetLabel;
+//! fn spawn_models(
+//!     mut commands: Commands,
+//!     asset_server: Res<AssetServer>,
+//!     mut materials: ResMut<Assets<StandardMaterial>>,
+//! ) {
+//!     // Spawns all the objects of an OBJ file with their materials.
+//!     commands.spawn(SceneRoot(
+//!         asset_server.load(ObjAssetLabel::Scene.from_asset("models/bracket.obj")),
+//!     ));
+//!
+//!     // Spawns the mesh of a scanned part.
+//!     commands.spawn((
+//!         Mesh3d(asset_server.load::<Mesh>("models/part.stl")),
+//!         MeshMaterial3d(materials.add(StandardMaterial::default())),
+//!     ));
+//! }
+//! ```
+
+extern crate alloc;
+
+#[cfg(feature = "obj")]
+mod obj;
+#[cfg(feature = "ply")]
+mod ply;
+#[cfg(feature = "stl")]
+mod stl;
+
+#[cfg(feature = "obj")]
+pub use obj::*;
+#[cfg(feature = "ply")]
+pub use ply::*;
+#[cfg(feature = "stl")]
+pub use stl::*;
+
+use bevy_app::{App, Plugin};
+#[cfg(any(feature = "obj", feature = "ply", feature = "stl"))]
+use bevy_asset::AssetApp;
+
+/// Adds support for loading the mesh formats enabled by cargo features.
+///
+/// See the [crate-level documentation](crate) for the supported formats.
+#[derive(Default)]
+pub struct MeshFormatsPlugin;
+
+impl Plugin for MeshFormatsPlugin {
+    #[cfg_attr(
+        not(any(feature = "obj", feature = "ply", feature = "stl")),
+        expect(unused_variables, reason = "no formats are enabled")
+    )]
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "obj")]
+        app.init_asset::<Obj>().init_asset_loader::<ObjLoader>();
+        #[cfg(feature = "ply")]
+        app.init_asset_loader::<PlyLoader>();
+        #[cfg(feature = "stl")]
+        app.init_asset_loader::<StlLoader>();
+    }
+}
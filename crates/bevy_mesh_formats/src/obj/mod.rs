@@ -0,0 +1,662 @@
+//! Loading of [Wavefront OBJ](https://en.wikipedia.org/wiki/Wavefront_.obj_file) files and their
+//! MTL material libraries.
+
+mod mtl;
+
+use bevy_asset::{
+    io::Reader, Asset, AssetLoader, AssetPath, Handle, LoadContext, ParseAssetPathError,
+    RenderAssetUsages,
+};
+use bevy_camera::visibility::Visibility;
+use bevy_color::{ColorToComponents, LinearRgba, Srgba};
+use bevy_ecs::{name::Name, world::World};
+use bevy_image::ImageLoaderSettings;
+use bevy_mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology};
+use bevy_pbr::{MeshMaterial3d, StandardMaterial};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::TypePath;
+use bevy_scene::Scene;
+use bevy_transform::components::Transform;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use mtl::parse_mtl;
+
+/// Labels that can be used to load part of an OBJ file.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_asset::prelude::*;
+/// # use bevy_scene::prelude::*;
+/// # use bevy_mesh_formats::ObjAssetLabel;
+/// fn load_obj_scene(asset_server: Res<AssetServer>) {
+///     let scene: Handle<Scene> = asset_server.load(ObjAssetLabel::Scene.from_asset("models/bracket.obj"));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjAssetLabel {
+    /// `Scene`: all the meshes of the file with their materials, as a Bevy [`Scene`]
+    Scene,
+    /// `Mesh{}`: the triangles of an object or group using a single material, as a [`Mesh`]
+    Mesh(usize),
+    /// `Material{}`: a material of the MTL files, as a [`StandardMaterial`]
+    Material(usize),
+    /// `DefaultMaterial`: the [`StandardMaterial`] of the meshes without a known material
+    DefaultMaterial,
+}
+
+impl core::fmt::Display for ObjAssetLabel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ObjAssetLabel::Scene => f.write_str("Scene"),
+            ObjAssetLabel::Mesh(index) => write!(f, "Mesh{index}"),
+            ObjAssetLabel::Material(index) => write!(f, "Material{index}"),
+            ObjAssetLabel::DefaultMaterial => f.write_str("DefaultMaterial"),
+        }
+    }
+}
+
+impl ObjAssetLabel {
+    /// Add this label to an asset path.
+    pub fn from_asset(&self, path: impl Into<AssetPath<'static>>) -> AssetPath<'static> {
+        path.into().with_label(self.to_string())
+    }
+}
+
+/// Representation of a loaded OBJ file.
+#[derive(Asset, Debug, TypePath)]
+pub struct Obj {
+    /// A scene with an entity for each mesh.
+    pub scene: Handle<Scene>,
+    /// A mesh for each run of faces sharing an object or group, and a material.
+    pub meshes: Vec<Handle<Mesh>>,
+    /// Meshes by the name of their object or group.
+    pub named_meshes: HashMap<Box<str>, Handle<Mesh>>,
+    /// All materials of the MTL files referenced by the OBJ file.
+    pub materials: Vec<Handle<StandardMaterial>>,
+    /// Materials by name.
+    pub named_materials: HashMap<Box<str>, Handle<StandardMaterial>>,
+}
+
+/// Loads [Wavefront OBJ](https://en.wikipedia.org/wiki/Wavefront_.obj_file) files as an [`Obj`].
+///
+/// Faces are triangulated as fans, and split into a [`Mesh`] for each object (`o`) or group (`g`)
+/// and material (`usemtl`). Vertex colors following positions are supported. Normals are computed
+/// for meshes whose faces don't all have them.
+///
+/// The materials of the MTL files referenced with `mtllib` are converted to
+/// [`StandardMaterial`]s, and meshes whose material can't be found, for example because its MTL
+/// file is missing, use a default material: `Kd`, `Ke`, `Pr`, `Pm`, `d` and `Tr` are used as is, `Ns` is converted
+/// to a roughness when `Pr` is missing, and `map_Kd`, `map_Ke` and `norm` (or `map_Bump`, which
+/// many exporters use for normal maps) are loaded as textures.
+#[derive(Clone, Default, TypePath)]
+pub struct ObjLoader;
+
+/// Settings for [`ObjLoader`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ObjLoaderSettings {
+    /// Where the meshes will be used - see the docs on [`RenderAssetUsages`] for details.
+    pub asset_usage: RenderAssetUsages,
+    /// Whether to load the materials of the MTL files. If `false`, all meshes use the default
+    /// material.
+    pub load_materials: bool,
+    /// Whether to compute smooth normals, instead of flat ones, for the meshes without normals.
+    pub smooth_normals: bool,
+}
+
+impl Default for ObjLoaderSettings {
+    fn default() -> Self {
+        Self {
+            asset_usage: RenderAssetUsages::default(),
+            load_materials: true,
+            smooth_normals: false,
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`ObjLoader`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ObjError {
+    /// I/O Error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A statement of the OBJ file is malformed.
+    #[error("invalid OBJ statement on line {line}: {message}")]
+    InvalidObj {
+        /// The line of the error, starting at 1.
+        line: usize,
+        /// A description of the error.
+        message: &'static str,
+    },
+    /// A statement of an MTL file is malformed.
+    #[error("invalid MTL statement on line {line}: {message}")]
+    InvalidMtl {
+        /// The line of the error, starting at 1.
+        line: usize,
+        /// A description of the error.
+        message: &'static str,
+    },
+    /// The path of an MTL file or texture can't be resolved with respect to the asset path.
+    #[error("invalid path: {0}. asset path error={1}")]
+    InvalidPath(String, ParseAssetPathError),
+}
+
+impl AssetLoader for ObjLoader {
+    type Asset = Obj;
+    type Settings = ObjLoaderSettings;
+    type Error = ObjError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Obj, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let obj = parse_obj(&String::from_utf8_lossy(&bytes))?;
+
+        let mut materials = Vec::new();
+        let mut named_materials = HashMap::default();
+        if settings.load_materials {
+            for library in &obj.material_libraries {
+                let library_path = load_context
+                    .path()
+                    .resolve_embed_str(library)
+                    .map_err(|err| ObjError::InvalidPath(library.clone(), err))?;
+                let bytes = match load_context.read_asset_bytes(&library_path).await {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        warn!(
+                            "Failed to read MTL file {library_path} referenced by {}, its \
+                            materials are replaced with the default material: {err}",
+                            load_context.path()
+                        );
+                        continue;
+                    }
+                };
+                for material in parse_mtl(&String::from_utf8_lossy(&bytes))? {
+                    let standard_material = material.to_standard_material(|path, is_srgb| {
+                        let texture_path = library_path
+                            .resolve_embed_str(path)
+                            .map_err(|err| ObjError::InvalidPath(path.to_owned(), err))?;
+                        Ok(load_context
+                            .loader()
+                            .with_settings(move |settings: &mut ImageLoaderSettings| {
+                                settings.is_srgb = is_srgb;
+                            })
+                            .load(texture_path))
+                    })?;
+                    let handle = load_context.add_labeled_asset(
+                        ObjAssetLabel::Material(materials.len()).to_string(),
+                        standard_material,
+                    );
+                    named_materials.insert(material.name.into(), handle.clone());
+                    materials.push(handle);
+                }
+            }
+        }
+
+        let mut default_material = None;
+        let mut meshes = Vec::new();
+        let mut named_meshes = HashMap::default();
+        let mut world = World::default();
+        let name = load_context
+            .path()
+            .path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut children = Vec::new();
+        for (index, group) in obj.groups.iter().enumerate() {
+            let mesh = load_context.add_labeled_asset(
+                ObjAssetLabel::Mesh(index).to_string(),
+                build_mesh(&obj, group, settings),
+            );
+            let material = match group
+                .material
+                .as_deref()
+                .and_then(|material| named_materials.get(material))
+            {
+                Some(material) => material.clone(),
+                None => default_material
+                    .get_or_insert_with(|| {
+                        load_context.add_labeled_asset(
+                            ObjAssetLabel::DefaultMaterial.to_string(),
+                            StandardMaterial::default(),
+                        )
+                    })
+                    .clone(),
+            };
+            if let Some(name) = &group.name {
+                named_meshes.insert(name.as_str().into(), mesh.clone());
+            }
+            children.push((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material),
+                Name::new(group.name.clone().unwrap_or_else(|| format!("Mesh{index}"))),
+            ));
+            meshes.push(mesh);
+        }
+        world
+            .spawn((Transform::default(), Visibility::default(), Name::new(name)))
+            .with_children(|parent| {
+                for child in children {
+                    parent.spawn(child);
+                }
+            });
+        let scene =
+            load_context.add_labeled_asset(ObjAssetLabel::Scene.to_string(), Scene::new(world));
+
+        Ok(Obj {
+            scene,
+            meshes,
+            named_meshes,
+            materials,
+            named_materials,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+}
+
+/// A vertex of a face, made of zero-based indices into the vertex data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct FaceVertex {
+    position: u32,
+    uv: Option<u32>,
+    normal: Option<u32>,
+}
+
+/// A run of faces sharing an object or group, and a material.
+#[derive(Debug, Default)]
+struct ObjGroup {
+    name: Option<String>,
+    material: Option<String>,
+    triangles: Vec<[FaceVertex; 3]>,
+}
+
+/// The contents of an OBJ file.
+#[derive(Debug, Default)]
+struct ObjData {
+    positions: Vec<[f32; 3]>,
+    /// The sRGB vertex colors, if any vertex has one.
+    colors: Option<Vec<[f32; 3]>>,
+    uvs: Vec<[f32; 2]>,
+    normals: Vec<[f32; 3]>,
+    groups: Vec<ObjGroup>,
+    material_libraries: Vec<String>,
+}
+
+impl ObjData {
+    /// Starts a new group, unless the current one has no faces yet.
+    fn start_group(&mut self, name: Option<String>, material: Option<String>) {
+        match self.groups.last_mut() {
+            Some(group) if group.triangles.is_empty() => {
+                group.name = name;
+                group.material = material;
+            }
+            _ => self.groups.push(ObjGroup {
+                name,
+                material,
+                triangles: Vec::new(),
+            }),
+        }
+    }
+}
+
+fn parse_obj(text: &str) -> Result<ObjData, ObjError> {
+    let mut obj = ObjData::default();
+    let mut face = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message| ObjError::InvalidObj {
+            line: line_number,
+            message,
+        };
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let (keyword, rest) = line
+            .split_once(char::is_whitespace)
+            .map(|(keyword, rest)| (keyword, rest.trim()))
+            .unwrap_or((line, ""));
+        let numbers = || {
+            rest.split_ascii_whitespace()
+                .map(|number| number.parse::<f32>().map_err(|_| error("invalid number")))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match keyword {
+            "v" => {
+                let values = numbers()?;
+                let (position, color) = match values[..] {
+                    // The optional fourth value is a weight for rational curves.
+                    [x, y, z] | [x, y, z, _] => ([x, y, z], None),
+                    [x, y, z, r, g, b] => ([x, y, z], Some([r, g, b])),
+                    _ => return Err(error("expected a position")),
+                };
+                if let Some(color) = color {
+                    obj.colors
+                        .get_or_insert_with(|| vec![[1.0; 3]; obj.positions.len()])
+                        .push(color);
+                } else if let Some(colors) = &mut obj.colors {
+                    colors.push([1.0; 3]);
+                }
+                obj.positions.push(position);
+            }
+            "vt" => match numbers()?[..] {
+                // OBJ texture coordinates start at the bottom of the texture.
+                [u] => obj.uvs.push([u, 1.0]),
+                [u, v, ..] => obj.uvs.push([u, 1.0 - v]),
+                _ => return Err(error("expected texture coordinates")),
+            },
+            "vn" => match numbers()?[..] {
+                [x, y, z] => obj.normals.push([x, y, z]),
+                _ => return Err(error("expected a normal")),
+            },
+            "f" => {
+                let resolve = |index: Option<&str>, count: usize| -> Result<_, ObjError> {
+                    let Some(index) = index.filter(|index| !index.is_empty()) else {
+                        return Ok(None);
+                    };
+                    let index: i64 = index.parse().map_err(|_| error("invalid index"))?;
+                    // Negative indices are relative to the end of the vertex data.
+                    let index = if index < 0 {
+                        count as i64 + index
+                    } else {
+                        index - 1
+                    };
+                    if index < 0 || index >= count as i64 {
+                        return Err(error("index out of bounds"));
+                    }
+                    Ok(Some(index as u32))
+                };
+                face.clear();
+                for vertex in rest.split_ascii_whitespace() {
+                    let mut indices = vertex.split('/');
+                    face.push(FaceVertex {
+                        position: resolve(indices.next(), obj.positions.len())?
+                            .ok_or_else(|| error("missing position index"))?,
+                        uv: resolve(indices.next(), obj.uvs.len())?,
+                        normal: resolve(indices.next(), obj.normals.len())?,
+                    });
+                }
+                if face.len() < 3 {
+                    return Err(error("face with less than 3 vertices"));
+                }
+                if obj.groups.is_empty() {
+                    obj.groups.push(ObjGroup::default());
+                }
+                let group = obj.groups.last_mut().unwrap();
+                for index in 2..face.len() {
+                    group
+                        .triangles
+                        .push([face[0], face[index - 1], face[index]]);
+                }
+            }
+            "o" | "g" => {
+                let material = obj.groups.last().and_then(|group| group.material.clone());
+                obj.start_group((!rest.is_empty()).then(|| rest.to_owned()), material);
+            }
+            "usemtl" => {
+                let name = obj.groups.last().and_then(|group| group.name.clone());
+                obj.start_group(name, Some(rest.to_owned()));
+            }
+            "mtllib" => obj
+                .material_libraries
+                .extend(rest.split_ascii_whitespace().map(str::to_owned)),
+            // Smoothing groups, lines, points, curves and surfaces aren't supported.
+            _ => {}
+        }
+    }
+    // Objects and groups without faces, like the last group of a file ending with `g`, have no
+    // mesh.
+    obj.groups.retain(|group| !group.triangles.is_empty());
+    Ok(obj)
+}
+
+fn build_mesh(obj: &ObjData, group: &ObjGroup, settings: &ObjLoaderSettings) -> Mesh {
+    let has_uvs = group
+        .triangles
+        .iter()
+        .flatten()
+        .any(|vertex| vertex.uv.is_some());
+    let has_normals = group
+        .triangles
+        .iter()
+        .flatten()
+        .all(|vertex| vertex.normal.is_some());
+
+    let mut vertex_indices = HashMap::<FaceVertex, u32>::new();
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::with_capacity(group.triangles.len() * 3);
+    for &vertex in group.triangles.iter().flatten() {
+        let index = *vertex_indices.entry(vertex).or_insert_with(|| {
+            positions.push(obj.positions[vertex.position as usize]);
+            if let Some(obj_colors) = &obj.colors {
+                let [red, green, blue] = obj_colors[vertex.position as usize];
+                colors.push(LinearRgba::from(Srgba::rgb(red, green, blue)).to_f32_array());
+            }
+            if has_uvs {
+                uvs.push(vertex.uv.map_or([0.0; 2], |uv| obj.uvs[uv as usize]));
+            }
+            if has_normals {
+                normals.push(
+                    vertex
+                        .normal
+                        .map_or([0.0; 3], |normal| obj.normals[normal as usize]),
+                );
+            }
+            positions.len() as u32 - 1
+        });
+        indices.push(index);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, settings.asset_usage)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices));
+    if obj.colors.is_some() {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+    if has_uvs {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+    if has_normals {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    } else if settings.smooth_normals {
+        mesh.compute_smooth_normals();
+    } else {
+        mesh.duplicate_vertices();
+        mesh.compute_flat_normals();
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bevy_app::{App, TaskPoolPlugin};
+    use bevy_asset::{
+        io::{
+            memory::{Dir, MemoryAssetReader},
+            AssetSourceBuilder, AssetSourceId,
+        },
+        AssetApp, AssetPlugin, AssetServer, Assets, Handle, LoadState,
+    };
+    use bevy_mesh::{Mesh, MeshPlugin, VertexAttributeValues};
+    use bevy_pbr::StandardMaterial;
+    use bevy_scene::ScenePlugin;
+
+    use super::{build_mesh, parse_obj, Obj, ObjError, ObjLoaderSettings};
+    use crate::MeshFormatsPlugin;
+
+    const QUADS: &str = "# Two quads
+mtllib parts.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+
+o Front
+usemtl Red
+f 1/1/1 2/2/1 3/3/1 4/4/1
+usemtl Blue
+f -4//-1 -2//-1 -1//-1
+g Back
+f 4 3 2 1
+";
+
+    #[test]
+    fn parse_groups() {
+        let obj = parse_obj(QUADS).unwrap();
+        assert_eq!(obj.material_libraries, ["parts.mtl"]);
+        let groups: Vec<_> = obj
+            .groups
+            .iter()
+            .map(|group| {
+                (
+                    group.name.as_deref(),
+                    group.material.as_deref(),
+                    group.triangles.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (Some("Front"), Some("Red"), 2),
+                (Some("Front"), Some("Blue"), 1),
+                (Some("Back"), Some("Blue"), 2),
+            ]
+        );
+
+        // Negative indices are relative to the end.
+        let [a, b, c] = obj.groups[1].triangles[0];
+        assert_eq!([a.position, b.position, c.position], [0, 2, 3]);
+        assert_eq!(a.uv, None);
+        assert_eq!(a.normal, Some(0));
+    }
+
+    #[test]
+    fn material_libraries_and_empty_groups() {
+        let obj = parse_obj(
+            "mtllib a.mtl  b.mtl\no Empty\nv 0 0 0\nv 1 0 0\nv 0 1 0\no Triangle\nf 1 2 3\ng\n",
+        )
+        .unwrap();
+        assert_eq!(obj.material_libraries, ["a.mtl", "b.mtl"]);
+        assert_eq!(obj.groups.len(), 1);
+        assert_eq!(obj.groups[0].name.as_deref(), Some("Triangle"));
+    }
+
+    #[test]
+    fn missing_material_library() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("quads.obj"), QUADS);
+        let reader = MemoryAssetReader { root: dir };
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(move || Box::new(reader.clone())),
+        )
+        .add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin::default(),
+            ScenePlugin,
+            MeshPlugin,
+            MeshFormatsPlugin,
+        ))
+        .init_asset::<StandardMaterial>();
+        app.finish();
+        app.cleanup();
+
+        // `parts.mtl` doesn't exist, so every mesh uses the default material.
+        let handle: Handle<Obj> = app.world().resource::<AssetServer>().load("quads.obj");
+        for _ in 0..1000 {
+            app.update();
+            match app.world().resource::<AssetServer>().load_state(&handle) {
+                LoadState::Loaded => break,
+                LoadState::Failed(err) => panic!("failed to load: {err}"),
+                _ => {}
+            }
+        }
+        let obj = app.world().resource::<Assets<Obj>>().get(&handle).unwrap();
+        assert_eq!(obj.meshes.len(), 3);
+        assert!(obj.materials.is_empty());
+    }
+
+    #[test]
+    fn meshes() {
+        let obj = parse_obj(QUADS).unwrap();
+        let settings = ObjLoaderSettings::default();
+
+        // Vertices are shared between the triangles of a quad.
+        let front = build_mesh(&obj, &obj.groups[0], &settings);
+        assert_eq!(front.count_vertices(), 4);
+        assert_eq!(
+            front.indices().unwrap().iter().collect::<Vec<_>>(),
+            [0, 1, 2, 0, 2, 3]
+        );
+        let Some(VertexAttributeValues::Float32x2(uvs)) = front.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("missing UVs");
+        };
+        assert_eq!(uvs[0], [0.0, 1.0]);
+        assert_eq!(uvs[2], [1.0, 0.0]);
+
+        // Flat normals are computed for faces without normals.
+        let back = build_mesh(&obj, &obj.groups[2], &settings);
+        assert!(back.attribute(Mesh::ATTRIBUTE_UV_0).is_none());
+        assert_eq!(back.count_vertices(), 6);
+        let normals = back.attribute(Mesh::ATTRIBUTE_NORMAL).unwrap();
+        assert_eq!(normals.as_float3().unwrap()[0], [0.0, 0.0, -1.0]);
+
+        let smooth = build_mesh(
+            &obj,
+            &obj.groups[2],
+            &ObjLoaderSettings {
+                smooth_normals: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(smooth.count_vertices(), 4);
+    }
+
+    #[test]
+    fn vertex_colors() {
+        let obj = parse_obj("v 0 0 0\nv 1 0 0 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+        let mesh = build_mesh(&obj, &obj.groups[0], &ObjLoaderSettings::default());
+        let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("missing colors");
+        };
+        assert_eq!(colors, &[[1.0; 4], [1.0, 0.0, 0.0, 1.0], [1.0; 4]]);
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert!(matches!(
+            parse_obj("v 0 0 0\nf 1 2 3\n"),
+            Err(ObjError::InvalidObj { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_obj("v 0 0\n"),
+            Err(ObjError::InvalidObj { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_obj("v 0 0 0\nv 1 0 0\nf 1 2\n"),
+            Err(ObjError::InvalidObj { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+//! Parsing of the MTL material libraries referenced by OBJ files.
+
+use bevy_asset::Handle;
+use bevy_color::{Color, LinearRgba};
+use bevy_image::Image;
+use bevy_material::AlphaMode;
+use bevy_pbr::StandardMaterial;
+
+use super::ObjError;
+
+/// A material of an MTL file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct MtlMaterial {
+    pub name: String,
+    /// `Kd`
+    pub diffuse: Option<[f32; 3]>,
+    /// `Ke`
+    pub emissive: Option<[f32; 3]>,
+    /// `Ns`
+    pub specular_exponent: Option<f32>,
+    /// `Pr`
+    pub roughness: Option<f32>,
+    /// `Pm`
+    pub metallic: Option<f32>,
+    /// `d`, or one minus `Tr`
+    pub dissolve: Option<f32>,
+    /// `map_Kd`
+    pub diffuse_texture: Option<String>,
+    /// `map_Ke`
+    pub emissive_texture: Option<String>,
+    /// `norm`, `map_Bump` or `bump`
+    pub normal_texture: Option<String>,
+}
+
+impl MtlMaterial {
+    /// Converts the material to a [`StandardMaterial`], loading its textures with `load_texture`,
+    /// which is given the path of the texture and whether it stores sRGB colors.
+    pub fn to_standard_material(
+        &self,
+        mut load_texture: impl FnMut(&str, bool) -> Result<Handle<Image>, ObjError>,
+    ) -> Result<StandardMaterial, ObjError> {
+        let [red, green, blue] = self.diffuse.unwrap_or([1.0; 3]);
+        let alpha = self.dissolve.unwrap_or(1.0).clamp(0.0, 1.0);
+        let mut material = StandardMaterial {
+            base_color: Color::srgba(red, green, blue, alpha),
+            alpha_mode: if alpha < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            base_color_texture: self
+                .diffuse_texture
+                .as_deref()
+                .map(|path| load_texture(path, true))
+                .transpose()?,
+            emissive_texture: self
+                .emissive_texture
+                .as_deref()
+                .map(|path| load_texture(path, true))
+                .transpose()?,
+            normal_map_texture: self
+                .normal_texture
+                .as_deref()
+                .map(|path| load_texture(path, false))
+                .transpose()?,
+            metallic: self.metallic.unwrap_or(0.0),
+            ..Default::default()
+        };
+        if let Some([red, green, blue]) = self.emissive {
+            material.emissive = LinearRgba::from(Color::srgb(red, green, blue));
+        }
+        if let Some(roughness) = self.roughness {
+            material.perceptual_roughness = roughness;
+        } else if let Some(exponent) = self.specular_exponent {
+            // The usual conversion from a Blinn-Phong exponent to a GGX roughness.
+            material.perceptual_roughness = (2.0 / (exponent.max(0.0) + 2.0)).sqrt();
+        }
+        Ok(material)
+    }
+}
+
+/// Parses the materials of an MTL file.
+pub(super) fn parse_mtl(text: &str) -> Result<Vec<MtlMaterial>, ObjError> {
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message| ObjError::InvalidMtl {
+            line: line_number,
+            message,
+        };
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((keyword, rest)) = line
+            .split_once(char::is_whitespace)
+            .map(|(keyword, rest)| (keyword, rest.trim()))
+            .or((!line.is_empty()).then_some((line, "")))
+        else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            materials.push(MtlMaterial {
+                name: rest.to_owned(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let material = materials
+            .last_mut()
+            .ok_or_else(|| error("statement before `newmtl`"))?;
+        let number = |text: &str| text.parse::<f32>().map_err(|_| error("invalid number"));
+        let color = || -> Result<[f32; 3], ObjError> {
+            let values = rest
+                .split_ascii_whitespace()
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+            match values[..] {
+                // A single value is a gray.
+                [value] => Ok([value; 3]),
+                [red, green, blue] => Ok([red, green, blue]),
+                _ => Err(error("expected a color")),
+            }
+        };
+        match keyword {
+            "Kd" => material.diffuse = Some(color()?),
+            "Ke" => material.emissive = Some(color()?),
+            "Ns" => material.specular_exponent = Some(number(rest)?),
+            "Pr" => material.roughness = Some(number(rest)?),
+            "Pm" => material.metallic = Some(number(rest)?),
+            "d" => material.dissolve = Some(number(rest)?),
+            "Tr" => material.dissolve = Some(1.0 - number(rest)?),
+            "map_Kd" => material.diffuse_texture = Some(texture_path(rest)),
+            "map_Ke" => material.emissive_texture = Some(texture_path(rest)),
+            "norm" | "map_Bump" | "map_bump" | "bump" => {
+                material.normal_texture = Some(texture_path(rest));
+            }
+            // Legacy and unsupported statements, such as `Ka`, `Ks` and `illum`.
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
+
+/// Returns the path of a texture statement.
+///
+/// Statements without options can have paths containing spaces. Otherwise, the path is the last
+/// argument, since the number of arguments of some options varies.
+fn texture_path(arguments: &str) -> String {
+    if arguments.starts_with('-') {
+        arguments
+            .split_ascii_whitespace()
+            .last()
+            .unwrap_or_default()
+            .to_owned()
+    } else {
+        arguments.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::Handle;
+    use bevy_color::{Color, LinearRgba};
+    use bevy_material::AlphaMode;
+
+    use super::{parse_mtl, MtlMaterial};
+    use crate::ObjError;
+
+    #[test]
+    fn parse() {
+        let mtl = "# Exported materials
+newmtl Painted Steel
+Ka 0.0 0.0 0.0
+Kd 0.8 0.1 0.1
+Ns 98.0
+Pm 1
+d 0.5
+map_Kd textures/paint color.png
+map_Bump -bm 0.5 textures/normal.png
+
+newmtl Lamp
+Ke 1
+Tr 0.0
+";
+        let materials = parse_mtl(mtl).unwrap();
+        assert_eq!(
+            materials,
+            [
+                MtlMaterial {
+                    name: "Painted Steel".into(),
+                    diffuse: Some([0.8, 0.1, 0.1]),
+                    specular_exponent: Some(98.0),
+                    metallic: Some(1.0),
+                    dissolve: Some(0.5),
+                    diffuse_texture: Some("textures/paint color.png".into()),
+                    normal_texture: Some("textures/normal.png".into()),
+                    ..Default::default()
+                },
+                MtlMaterial {
+                    name: "Lamp".into(),
+                    emissive: Some([1.0; 3]),
+                    dissolve: Some(1.0),
+                    ..Default::default()
+                },
+            ]
+        );
+
+        assert!(matches!(
+            parse_mtl("Kd 1 1 1"),
+            Err(ObjError::InvalidMtl { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_mtl("newmtl a\nKd 1 1"),
+            Err(ObjError::InvalidMtl { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn standard_material() {
+        let material = MtlMaterial {
+            diffuse: Some([0.8, 0.1, 0.1]),
+            emissive: Some([1.0; 3]),
+            specular_exponent: Some(0.0),
+            dissolve: Some(0.5),
+            diffuse_texture: Some("color.png".into()),
+            normal_texture: Some("normal.png".into()),
+            ..Default::default()
+        };
+        let mut textures = Vec::new();
+        let material = material
+            .to_standard_material(|path, is_srgb| {
+                textures.push((path.to_owned(), is_srgb));
+                Ok(Handle::default())
+            })
+            .unwrap();
+        assert_eq!(
+            textures,
+            [("color.png".into(), true), ("normal.png".into(), false)]
+        );
+        assert_eq!(material.base_color, Color::srgba(0.8, 0.1, 0.1, 0.5));
+        assert_eq!(material.alpha_mode, AlphaMode::Blend);
+        assert_eq!(material.emissive, LinearRgba::WHITE);
+        assert_eq!(material.perceptual_roughness, 1.0);
+        assert!(material.base_color_texture.is_some());
+        assert!(material.emissive_texture.is_none());
+    }
+}
@@ -0,0 +1,836 @@
+//! Loading and saving of [PLY](https://en.wikipedia.org/wiki/PLY_(file_format)) files.
+
+use core::{fmt::Write as _, str};
+use std::io::Write as _;
+
+use bevy_asset::{
+    io::{Reader, Writer},
+    saver::{AssetSaver, SavedAsset},
+    AssetLoader, AssetPath, AsyncWriteExt, LoadContext, RenderAssetUsages,
+};
+use bevy_color::{ColorToComponents, ColorToPacked, LinearRgba, Srgba};
+use bevy_mesh::{Indices, Mesh, MeshAccessError, PrimitiveTopology, VertexAttributeValues};
+use bevy_reflect::TypePath;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Loads ASCII and binary [PLY](https://en.wikipedia.org/wiki/PLY_(file_format)) files as
+/// [`Mesh`]es.
+///
+/// The vertex properties are mapped to mesh attributes as follows:
+///
+/// | Properties                         | Attribute                    |
+/// |------------------------------------|------------------------------|
+/// | `x`, `y`, `z`                      | [`Mesh::ATTRIBUTE_POSITION`] |
+/// | `nx`, `ny`, `nz`                   | [`Mesh::ATTRIBUTE_NORMAL`]   |
+/// | `red`, `green`, `blue`, `alpha`    | [`Mesh::ATTRIBUTE_COLOR`]    |
+/// | `u`, `v` or `s`, `t`               | [`Mesh::ATTRIBUTE_UV_0`]     |
+///
+/// Other properties and elements are ignored. Colors are assumed to be in sRGB space, and
+/// integer colors are normalized to the range of their type.
+///
+/// Faces are triangulated as fans. Files without faces, such as scans, are loaded as point clouds
+/// with the [`PrimitiveTopology::PointList`] topology.
+#[derive(Clone, Default, TypePath)]
+pub struct PlyLoader;
+
+/// Settings for [`PlyLoader`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct PlyLoaderSettings {
+    /// Where the asset will be used - see the docs on [`RenderAssetUsages`] for details.
+    pub asset_usage: RenderAssetUsages,
+}
+
+/// Possible errors that can be produced by [`PlyLoader`] and [`PlySaver`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum PlyError {
+    /// I/O Error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The header of the file is malformed.
+    #[error("invalid PLY header on line {line}: {message}")]
+    InvalidHeader {
+        /// The line of the error, starting at 1.
+        line: usize,
+        /// A description of the error.
+        message: &'static str,
+    },
+    /// The file ends before all the elements declared in its header.
+    #[error("PLY file ends before all its elements")]
+    UnexpectedEnd,
+    /// A value in an ASCII file isn't a number.
+    #[error("invalid number in PLY file")]
+    InvalidNumber,
+    /// The vertices of the file don't have positions.
+    #[error("PLY file has no vertex positions")]
+    MissingPositions,
+    /// A face refers to a vertex that doesn't exist.
+    #[error("PLY face refers to vertex {index}, but there are only {count} vertices")]
+    InvalidIndex {
+        /// The index of the vertex.
+        index: u32,
+        /// The number of vertices.
+        count: usize,
+    },
+    /// The mesh can't be saved because it isn't made of triangles or points.
+    #[error("mesh with {0:?} topology can't be saved as PLY")]
+    UnsupportedTopology(PrimitiveTopology),
+    /// The mesh can't be saved because it has no `Float32x3` positions.
+    #[error("mesh without `Float32x3` positions can't be saved as PLY")]
+    PositionsFormat,
+    /// The mesh data can't be accessed.
+    #[error("mesh access error: {0}")]
+    MeshAccess(#[from] MeshAccessError),
+}
+
+impl AssetLoader for PlyLoader {
+    type Asset = Mesh;
+    type Settings = PlyLoaderSettings;
+    type Error = PlyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        read_ply(&bytes, settings)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ply"]
+    }
+}
+
+/// The encoding of the PLY files written by [`PlySaver`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    /// The compact binary encoding, in little-endian byte order.
+    #[default]
+    BinaryLittleEndian,
+    /// The human-readable text encoding.
+    Ascii,
+}
+
+/// Settings for [`PlySaver`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy)]
+pub struct PlySaverSettings {
+    /// The encoding of the saved file.
+    pub format: PlyFormat,
+}
+
+/// Saves [`Mesh`]es as [PLY](https://en.wikipedia.org/wiki/PLY_(file_format)) files.
+///
+/// Meshes must have the [`PrimitiveTopology::TriangleList`] topology, or
+/// [`PrimitiveTopology::PointList`] for point clouds. The attributes read by [`PlyLoader`] are
+/// saved, with colors stored as 8-bit sRGB.
+#[derive(Clone, Default, TypePath)]
+pub struct PlySaver;
+
+impl AssetSaver for PlySaver {
+    type Asset = Mesh;
+    type Settings = PlySaverSettings;
+    type OutputLoader = PlyLoader;
+    type Error = PlyError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, '_, Mesh>,
+        settings: &Self::Settings,
+        _asset_path: AssetPath<'_>,
+    ) -> Result<PlyLoaderSettings, Self::Error> {
+        let bytes = write_ply(&asset, settings.format)?;
+        writer.write_all(&bytes).await?;
+        Ok(PlyLoaderSettings::default())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return None,
+        })
+    }
+
+    /// The size of a value of this type in binary files, in bytes.
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// The value that integer colors of this type are divided by to normalize them.
+    fn color_scale(self) -> f64 {
+        match self {
+            Self::I8 => i8::MAX.into(),
+            Self::U8 => u8::MAX.into(),
+            Self::I16 => i16::MAX.into(),
+            Self::U16 => u16::MAX.into(),
+            Self::I32 => i32::MAX.into(),
+            Self::U32 => u32::MAX.into(),
+            Self::F32 | Self::F64 => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PropertyType {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType },
+}
+
+#[derive(Clone, Debug)]
+struct Property {
+    name: String,
+    ty: PropertyType,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// The header of a PLY file.
+#[derive(Debug)]
+struct Header {
+    encoding: Encoding,
+    elements: Vec<Element>,
+    /// The offset of the data following the header.
+    data_offset: usize,
+}
+
+fn read_header(bytes: &[u8]) -> Result<Header, PlyError> {
+    let mut offset = 0;
+    let mut line = 0;
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        let rest = &bytes[offset..];
+        let end = rest
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or(PlyError::UnexpectedEnd)?;
+        offset += end + 1;
+        line += 1;
+        let error = |message| PlyError::InvalidHeader { line, message };
+        let text = str::from_utf8(&rest[..end]).map_err(|_| error("the header isn't ASCII"))?;
+        let mut words = text.split_ascii_whitespace();
+
+        if line == 1 {
+            if words.next() != Some("ply") {
+                return Err(error("the file doesn't start with `ply`"));
+            }
+            continue;
+        }
+
+        match words.next() {
+            Some("format") => {
+                encoding = Some(match words.next() {
+                    Some("ascii") => Encoding::Ascii,
+                    Some("binary_little_endian") => Encoding::BinaryLittleEndian,
+                    Some("binary_big_endian") => Encoding::BinaryBigEndian,
+                    _ => return Err(error("unknown format")),
+                });
+            }
+            Some("element") => {
+                let (Some(name), Some(count)) = (words.next(), words.next()) else {
+                    return Err(error("expected an element name and count"));
+                };
+                elements.push(Element {
+                    name: name.to_owned(),
+                    count: count.parse().map_err(|_| error("invalid element count"))?,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| error("property outside of an element"))?;
+                let scalar = |name: Option<&str>| {
+                    name.and_then(ScalarType::parse)
+                        .ok_or_else(|| error("unknown property type"))
+                };
+                let ty = match words.next() {
+                    Some("list") => PropertyType::List {
+                        count: scalar(words.next())?,
+                        item: scalar(words.next())?,
+                    },
+                    ty => PropertyType::Scalar(scalar(ty)?),
+                };
+                let name = words.next().ok_or_else(|| error("missing property name"))?;
+                element.properties.push(Property {
+                    name: name.to_owned(),
+                    ty,
+                });
+            }
+            Some("end_header") => break,
+            Some("comment" | "obj_info") | None => {}
+            Some(_) => return Err(error("unknown keyword")),
+        }
+    }
+
+    Ok(Header {
+        encoding: encoding.ok_or(PlyError::InvalidHeader {
+            line,
+            message: "missing format",
+        })?,
+        elements,
+        data_offset: offset,
+    })
+}
+
+/// Reads the values of the elements following the header.
+enum DataReader<'a> {
+    Ascii(str::SplitAsciiWhitespace<'a>),
+    Binary { bytes: &'a [u8], big_endian: bool },
+}
+
+impl DataReader<'_> {
+    fn read(&mut self, ty: ScalarType) -> Result<f64, PlyError> {
+        match self {
+            Self::Ascii(tokens) => tokens
+                .next()
+                .ok_or(PlyError::UnexpectedEnd)?
+                .parse()
+                .map_err(|_| PlyError::InvalidNumber),
+            Self::Binary { bytes, big_endian } => {
+                fn take<const N: usize>(bytes: &mut &[u8], big_endian: bool) -> Option<[u8; N]> {
+                    let (value, rest) = bytes.split_first_chunk::<N>()?;
+                    *bytes = rest;
+                    let mut value = *value;
+                    if big_endian {
+                        value.reverse();
+                    }
+                    Some(value)
+                }
+                let big_endian = *big_endian;
+                let value = match ty {
+                    ScalarType::I8 => take(bytes, big_endian).map(|v| i8::from_le_bytes(v).into()),
+                    ScalarType::U8 => take(bytes, big_endian).map(|v| u8::from_le_bytes(v).into()),
+                    ScalarType::I16 => {
+                        take(bytes, big_endian).map(|v| i16::from_le_bytes(v).into())
+                    }
+                    ScalarType::U16 => {
+                        take(bytes, big_endian).map(|v| u16::from_le_bytes(v).into())
+                    }
+                    ScalarType::I32 => {
+                        take(bytes, big_endian).map(|v| i32::from_le_bytes(v).into())
+                    }
+                    ScalarType::U32 => {
+                        take(bytes, big_endian).map(|v| u32::from_le_bytes(v).into())
+                    }
+                    ScalarType::F32 => {
+                        take(bytes, big_endian).map(|v| f32::from_le_bytes(v).into())
+                    }
+                    ScalarType::F64 => take(bytes, big_endian).map(f64::from_le_bytes),
+                };
+                value.ok_or(PlyError::UnexpectedEnd)
+            }
+        }
+    }
+}
+
+/// Returns the component of the vertex data that a vertex property is read into.
+///
+/// The components are the position, the normal, the color and the UV, one after the other.
+fn vertex_component(name: &str) -> Option<usize> {
+    Some(match name {
+        "x" => 0,
+        "y" => 1,
+        "z" => 2,
+        "nx" => 3,
+        "ny" => 4,
+        "nz" => 5,
+        "red" | "r" => 6,
+        "green" | "g" => 7,
+        "blue" | "b" => 8,
+        "alpha" | "a" => 9,
+        "u" | "s" | "texture_u" | "texture_s" => 10,
+        "v" | "t" | "texture_v" | "texture_t" => 11,
+        _ => return None,
+    })
+}
+
+fn read_ply(bytes: &[u8], settings: &PlyLoaderSettings) -> Result<Mesh, PlyError> {
+    let header = read_header(bytes)?;
+    let data = &bytes[header.data_offset..];
+    let mut reader = match header.encoding {
+        Encoding::Ascii => DataReader::Ascii(
+            str::from_utf8(data)
+                .map_err(|_| PlyError::InvalidNumber)?
+                .split_ascii_whitespace(),
+        ),
+        Encoding::BinaryLittleEndian => DataReader::Binary {
+            bytes: data,
+            big_endian: false,
+        },
+        Encoding::BinaryBigEndian => DataReader::Binary {
+            bytes: data,
+            big_endian: true,
+        },
+    };
+
+    let mut vertices: Vec<[f32; 12]> = Vec::new();
+    let mut components = 0u16;
+    let mut indices: Option<Vec<u32>> = None;
+    let mut face = Vec::new();
+    for element in &header.elements {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+
+        // Check the element count against the size of the data before trusting it, so that a
+        // malformed header can't make us allocate or loop without bound. In ASCII files, each
+        // value takes at least one character and a separator.
+        let min_size: usize = element
+            .properties
+            .iter()
+            .map(|property| match (header.encoding, property.ty) {
+                (Encoding::Ascii, _) => 2,
+                (_, PropertyType::Scalar(ty) | PropertyType::List { count: ty, .. }) => ty.size(),
+            })
+            .sum();
+        if min_size == 0 {
+            continue;
+        }
+        if element
+            .count
+            .checked_mul(min_size)
+            .is_none_or(|size| size > data.len() + 1)
+        {
+            return Err(PlyError::UnexpectedEnd);
+        }
+        if is_vertex {
+            vertices.reserve(element.count);
+        }
+        for _ in 0..element.count {
+            // Colors default to opaque.
+            let mut vertex = [0.0; 12];
+            vertex[9] = 1.0;
+            for property in &element.properties {
+                match property.ty {
+                    PropertyType::Scalar(ty) => {
+                        let value = reader.read(ty)?;
+                        if is_vertex && let Some(component) = vertex_component(&property.name) {
+                            let scale = if (6..10).contains(&component) {
+                                ty.color_scale()
+                            } else {
+                                1.0
+                            };
+                            vertex[component] = (value / scale) as f32;
+                            components |= 1 << component;
+                        }
+                    }
+                    PropertyType::List { count, item } => {
+                        let count = reader.read(count)? as usize;
+                        face.clear();
+                        for _ in 0..count {
+                            face.push(reader.read(item)? as u32);
+                        }
+                        if is_face
+                            && matches!(property.name.as_str(), "vertex_indices" | "vertex_index")
+                        {
+                            let indices = indices.get_or_insert_default();
+                            for index in 2..face.len() {
+                                indices.extend([face[0], face[index - 1], face[index]]);
+                            }
+                        }
+                    }
+                }
+            }
+            if is_vertex {
+                vertices.push(vertex);
+            }
+        }
+    }
+
+    let has =
+        |first: usize, count: usize| (first..first + count).all(|c| components & (1 << c) != 0);
+    if !has(0, 3) {
+        return Err(PlyError::MissingPositions);
+    }
+
+    let topology = if indices.is_some() {
+        PrimitiveTopology::TriangleList
+    } else {
+        PrimitiveTopology::PointList
+    };
+    let mut mesh = Mesh::new(topology, settings.asset_usage);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vertices
+            .iter()
+            .map(|vertex| [vertex[0], vertex[1], vertex[2]])
+            .collect::<Vec<_>>(),
+    );
+    if has(3, 3) {
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            vertices
+                .iter()
+                .map(|vertex| [vertex[3], vertex[4], vertex[5]])
+                .collect::<Vec<_>>(),
+        );
+    }
+    if has(6, 3) {
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            vertices
+                .iter()
+                .map(|vertex| {
+                    LinearRgba::from(Srgba::new(vertex[6], vertex[7], vertex[8], vertex[9]))
+                        .to_f32_array()
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    if has(10, 2) {
+        // PLY texture coordinates start at the bottom of the texture.
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_UV_0,
+            vertices
+                .iter()
+                .map(|vertex| [vertex[10], 1.0 - vertex[11]])
+                .collect::<Vec<_>>(),
+        );
+    }
+    if let Some(indices) = indices {
+        if let Some(&index) = indices
+            .iter()
+            .find(|&&index| index as usize >= vertices.len())
+        {
+            return Err(PlyError::InvalidIndex {
+                index,
+                count: vertices.len(),
+            });
+        }
+        mesh.insert_indices(Indices::U32(indices));
+    }
+
+    Ok(mesh)
+}
+
+/// Writes the values of the elements following the header.
+struct DataWriter {
+    format: PlyFormat,
+    bytes: Vec<u8>,
+}
+
+impl DataWriter {
+    fn value(&mut self, text: impl core::fmt::Display, binary: &[u8]) {
+        match self.format {
+            PlyFormat::Ascii => {
+                let _ = write!(self.bytes, "{text} ");
+            }
+            PlyFormat::BinaryLittleEndian => self.bytes.extend_from_slice(binary),
+        }
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.value(value, &value.to_le_bytes());
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.value(value, &value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.value(value, &value.to_le_bytes());
+    }
+
+    fn end_row(&mut self) {
+        if self.format == PlyFormat::Ascii {
+            self.bytes.pop();
+            self.bytes.push(b'\n');
+        }
+    }
+}
+
+fn write_ply(mesh: &Mesh, format: PlyFormat) -> Result<Vec<u8>, PlyError> {
+    let topology = mesh.primitive_topology();
+    if !matches!(
+        topology,
+        PrimitiveTopology::TriangleList | PrimitiveTopology::PointList
+    ) {
+        return Err(PlyError::UnsupportedTopology(topology));
+    }
+    let positions = mesh
+        .try_attribute(Mesh::ATTRIBUTE_POSITION)?
+        .as_float3()
+        .ok_or(PlyError::PositionsFormat)?;
+    let normals = match mesh.try_attribute_option(Mesh::ATTRIBUTE_NORMAL)? {
+        Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+        _ => None,
+    };
+    let colors = match mesh.try_attribute_option(Mesh::ATTRIBUTE_COLOR)? {
+        Some(VertexAttributeValues::Float32x4(colors)) => Some(colors),
+        _ => None,
+    };
+    let uvs = match mesh.try_attribute_option(Mesh::ATTRIBUTE_UV_0)? {
+        Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs),
+        _ => None,
+    };
+    let faces: Option<Vec<u32>> =
+        (topology == PrimitiveTopology::TriangleList).then(|| match mesh.try_indices_option() {
+            Ok(Some(indices)) => indices.iter().map(|index| index as u32).collect(),
+            _ => (0..positions.len() as u32).collect(),
+        });
+
+    let mut header = String::from("ply\n");
+    header.push_str(match format {
+        PlyFormat::Ascii => "format ascii 1.0\n",
+        PlyFormat::BinaryLittleEndian => "format binary_little_endian 1.0\n",
+    });
+    header.push_str("comment Written by Bevy\n");
+    let _ = writeln!(header, "element vertex {}", positions.len());
+    let mut properties = vec!["x", "y", "z"];
+    if normals.is_some() {
+        properties.extend(["nx", "ny", "nz"]);
+    }
+    for property in properties {
+        let _ = writeln!(header, "property float {property}");
+    }
+    if colors.is_some() {
+        for property in ["red", "green", "blue", "alpha"] {
+            let _ = writeln!(header, "property uchar {property}");
+        }
+    }
+    if uvs.is_some() {
+        header.push_str("property float s\nproperty float t\n");
+    }
+    if let Some(faces) = &faces {
+        let _ = writeln!(header, "element face {}", faces.len() / 3);
+        header.push_str("property list uchar uint vertex_indices\n");
+    }
+    header.push_str("end_header\n");
+
+    let mut writer = DataWriter {
+        format,
+        bytes: header.into_bytes(),
+    };
+    for (index, position) in positions.iter().enumerate() {
+        for &value in position {
+            writer.f32(value);
+        }
+        if let Some(normals) = normals {
+            for &value in &normals[index] {
+                writer.f32(value);
+            }
+        }
+        if let Some(colors) = colors {
+            let color = Srgba::from(LinearRgba::from_f32_array(colors[index])).to_u8_array();
+            for value in color {
+                writer.u8(value);
+            }
+        }
+        if let Some(uvs) = uvs {
+            writer.f32(uvs[index][0]);
+            writer.f32(1.0 - uvs[index][1]);
+        }
+        writer.end_row();
+    }
+    for triangle in faces.iter().flat_map(|faces| faces.chunks_exact(3)) {
+        writer.u8(3);
+        for &index in triangle {
+            writer.u32(index);
+        }
+        writer.end_row();
+    }
+
+    Ok(writer.bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::RenderAssetUsages;
+    use bevy_math::primitives::Cuboid;
+    use bevy_mesh::{Mesh, MeshBuilder, Meshable, PrimitiveTopology};
+
+    use super::{read_ply, write_ply, PlyError, PlyFormat, PlyLoaderSettings};
+
+    #[test]
+    fn round_trip() {
+        let colors: Vec<[f32; 4]> = (0..24).map(|_| [1.0, 0.0, 0.0, 1.0]).collect();
+        let cube = Cuboid::default()
+            .mesh()
+            .build()
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+        for format in [PlyFormat::BinaryLittleEndian, PlyFormat::Ascii] {
+            let bytes = write_ply(&cube, format).unwrap();
+            let mesh = read_ply(&bytes, &PlyLoaderSettings::default()).unwrap();
+            assert_eq!(mesh.primitive_topology(), PrimitiveTopology::TriangleList);
+            for attribute in [
+                Mesh::ATTRIBUTE_POSITION,
+                Mesh::ATTRIBUTE_NORMAL,
+                Mesh::ATTRIBUTE_UV_0,
+                Mesh::ATTRIBUTE_COLOR,
+            ] {
+                assert_eq!(
+                    mesh.attribute(attribute).unwrap().get_bytes(),
+                    cube.attribute(attribute).unwrap().get_bytes(),
+                );
+            }
+            assert_eq!(
+                mesh.indices().unwrap().iter().collect::<Vec<_>>(),
+                cube.indices().unwrap().iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn point_cloud() {
+        let ply = "ply
+format ascii 1.0
+comment A scan
+element vertex 2
+property double x
+property double y
+property double z
+property uchar red
+property uchar green
+property uchar blue
+property float intensity
+end_header
+0 0 0 255 255 255 0.5
+1 2 3 0 0 0 1
+";
+        let mesh = read_ply(ply.as_bytes(), &PlyLoaderSettings::default()).unwrap();
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::PointList);
+        assert!(mesh.indices().is_none());
+        let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+        assert_eq!(positions.as_float3().unwrap()[1], [1.0, 2.0, 3.0]);
+        let colors = mesh.attribute(Mesh::ATTRIBUTE_COLOR).unwrap();
+        assert_eq!(
+            colors.get_bytes(),
+            bytemuck_floats(&[1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+        );
+    }
+
+    fn bytemuck_floats(values: &[f32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn big_endian_polygons() {
+        let mut ply = b"ply
+format binary_big_endian 1.0
+element vertex 4
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+"
+        .to_vec();
+        for value in [
+            0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0,
+        ] {
+            ply.extend_from_slice(&value.to_be_bytes());
+        }
+        ply.push(4);
+        for index in [0i32, 1, 2, 3] {
+            ply.extend_from_slice(&index.to_be_bytes());
+        }
+
+        let mesh = read_ply(&ply, &PlyLoaderSettings::default()).unwrap();
+        assert_eq!(
+            mesh.indices().unwrap().iter().collect::<Vec<_>>(),
+            [0, 1, 2, 0, 2, 3]
+        );
+        assert_eq!(
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+                .unwrap()
+                .as_float3()
+                .unwrap()[2],
+            [1.0, 1.0, 0.0]
+        );
+
+        // A truncated file is an error.
+        ply.pop();
+        assert!(matches!(
+            read_ply(&ply, &PlyLoaderSettings::default()),
+            Err(PlyError::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn invalid_files() {
+        let settings = PlyLoaderSettings::default();
+        assert!(matches!(
+            read_ply(b"obj\n", &settings),
+            Err(PlyError::InvalidHeader { line: 1, .. })
+        ));
+        assert!(matches!(
+            read_ply(
+                b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n1\n",
+                &settings
+            ),
+            Err(PlyError::MissingPositions)
+        ));
+        assert!(matches!(
+            read_ply(
+                b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\nproperty float z\nelement face 1\nproperty list uchar uint vertex_indices\nend_header\n0 0 0\n3 0 1 2\n",
+                &settings
+            ),
+            Err(PlyError::InvalidIndex { index: 1, count: 1 })
+        ));
+
+        // The element count is checked against the size of the data before allocating.
+        for format in ["ascii", "binary_little_endian"] {
+            let ply = format!(
+                "ply\nformat {format} 1.0\nelement vertex 4000000000\nproperty float x\nproperty float y\nproperty float z\nend_header\n"
+            );
+            assert!(matches!(
+                read_ply(ply.as_bytes(), &settings),
+                Err(PlyError::UnexpectedEnd)
+            ));
+        }
+
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3]; 2]);
+        assert!(matches!(
+            write_ply(&lines, PlyFormat::Ascii),
+            Err(PlyError::UnsupportedTopology(PrimitiveTopology::LineList))
+        ));
+    }
+}
@@ -0,0 +1,461 @@
+//! Loading and saving of [STL](https://en.wikipedia.org/wiki/STL_(file_format)) files.
+
+use core::{array, fmt::Write as _};
+
+use bevy_asset::{
+    io::{Reader, Writer},
+    saver::{AssetSaver, SavedAsset},
+    AssetLoader, AssetPath, AsyncWriteExt, LoadContext, RenderAssetUsages,
+};
+use bevy_math::Vec3;
+use bevy_mesh::{Indices, Mesh, MeshTrianglesError, PrimitiveTopology};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::TypePath;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The size of the header of a binary STL file, which is followed by the number of triangles.
+const BINARY_HEADER_SIZE: usize = 80;
+
+/// The size of a triangle in a binary STL file: a normal, three vertices and an unused
+/// attribute byte count.
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+/// The header written at the start of binary STL files by [`StlSaver`].
+///
+/// It mustn't start with `solid`, or some programs mistake the file for an ASCII one.
+const BINARY_HEADER: &[u8] = b"Binary STL written by Bevy";
+
+/// Loads binary and ASCII [STL](https://en.wikipedia.org/wiki/STL_(file_format)) files as
+/// [`Mesh`]es.
+///
+/// STL files only contain triangles with a normal each, so the loaded meshes only have positions
+/// and normals.
+#[derive(Clone, Default, TypePath)]
+pub struct StlLoader;
+
+/// Settings for [`StlLoader`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct StlLoaderSettings {
+    /// Where the asset will be used - see the docs on [`RenderAssetUsages`] for details.
+    pub asset_usage: RenderAssetUsages,
+    /// Whether to merge vertices with the same position and compute smooth normals, instead of
+    /// giving each triangle the flat normal stored in the file.
+    pub smooth_normals: bool,
+}
+
+/// Possible errors that can be produced by [`StlLoader`] and [`StlSaver`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum StlError {
+    /// I/O Error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A binary file is shorter than its triangle count says.
+    #[error("binary STL file is truncated, expected {0} triangles")]
+    Truncated(u32),
+    /// An ASCII file doesn't follow the STL grammar.
+    #[error("invalid ASCII STL file on line {line}, expected {expected}")]
+    UnexpectedToken {
+        /// The line of the unexpected token, starting at 1.
+        line: usize,
+        /// A description of the expected token.
+        expected: &'static str,
+    },
+    /// The mesh can't be saved because its triangles can't be read.
+    #[error("mesh can't be saved as STL: {0}")]
+    Triangles(#[from] MeshTrianglesError),
+}
+
+impl AssetLoader for StlLoader {
+    type Asset = Mesh;
+    type Settings = StlLoaderSettings;
+    type Error = StlError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let triangles = read_stl(&bytes)?;
+        Ok(build_mesh(&triangles, settings))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stl"]
+    }
+}
+
+/// The encoding of the STL files written by [`StlSaver`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlFormat {
+    /// The compact binary encoding.
+    #[default]
+    Binary,
+    /// The human-readable text encoding.
+    Ascii,
+}
+
+/// Settings for [`StlSaver`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy)]
+pub struct StlSaverSettings {
+    /// The encoding of the saved file.
+    pub format: StlFormat,
+}
+
+/// Saves [`Mesh`]es as [STL](https://en.wikipedia.org/wiki/STL_(file_format)) files.
+///
+/// Only the triangles of the mesh are saved, each with the flat normal given by its winding.
+#[derive(Clone, Default, TypePath)]
+pub struct StlSaver;
+
+impl AssetSaver for StlSaver {
+    type Asset = Mesh;
+    type Settings = StlSaverSettings;
+    type OutputLoader = StlLoader;
+    type Error = StlError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, '_, Mesh>,
+        settings: &Self::Settings,
+        _asset_path: AssetPath<'_>,
+    ) -> Result<StlLoaderSettings, Self::Error> {
+        let bytes = write_stl(&asset, settings.format)?;
+        writer.write_all(&bytes).await?;
+        Ok(StlLoaderSettings::default())
+    }
+}
+
+/// A triangle read from an STL file.
+#[derive(Clone, Copy, Debug)]
+struct StlTriangle {
+    normal: Vec3,
+    vertices: [Vec3; 3],
+}
+
+fn read_stl(bytes: &[u8]) -> Result<Vec<StlTriangle>, StlError> {
+    if is_binary(bytes) {
+        read_binary(bytes)
+    } else {
+        read_ascii(bytes)
+    }
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    // ASCII files start with `solid`, but so do the headers of some binary files, so a file whose
+    // size matches its triangle count is binary regardless.
+    if let Some(count) = binary_triangle_count(bytes)
+        && (count as usize)
+            .checked_mul(BINARY_TRIANGLE_SIZE)
+            .and_then(|size| size.checked_add(BINARY_HEADER_SIZE + 4))
+            == Some(bytes.len())
+    {
+        return true;
+    }
+    !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+fn binary_triangle_count(bytes: &[u8]) -> Option<u32> {
+    let count = bytes.get(BINARY_HEADER_SIZE..BINARY_HEADER_SIZE + 4)?;
+    Some(u32::from_le_bytes(count.try_into().ok()?))
+}
+
+fn read_binary(bytes: &[u8]) -> Result<Vec<StlTriangle>, StlError> {
+    let count = binary_triangle_count(bytes).ok_or(StlError::Truncated(0))?;
+    let data = &bytes[BINARY_HEADER_SIZE + 4..];
+    if data.len() / BINARY_TRIANGLE_SIZE < count as usize {
+        return Err(StlError::Truncated(count));
+    }
+
+    Ok(data
+        .chunks_exact(BINARY_TRIANGLE_SIZE)
+        .take(count as usize)
+        .map(|triangle| {
+            let vector = |index: usize| {
+                Vec3::from_array(array::from_fn(|axis| {
+                    let offset = index * 12 + axis * 4;
+                    f32::from_le_bytes(array::from_fn(|byte| triangle[offset + byte]))
+                }))
+            };
+            StlTriangle {
+                normal: vector(0),
+                vertices: [vector(1), vector(2), vector(3)],
+            }
+        })
+        .collect())
+}
+
+/// The whitespace-separated tokens of a text file, keeping track of the current line.
+struct Tokens<'a> {
+    lines: core::str::Lines<'a>,
+    words: core::str::SplitAsciiWhitespace<'a>,
+    line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines(),
+            words: "".split_ascii_whitespace(),
+            line: 0,
+        }
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), StlError> {
+        match self.next() {
+            Some(next) if next == token => Ok(()),
+            _ => Err(self.error(token)),
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, StlError> {
+        self.next()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| self.error("a number"))
+    }
+
+    fn vector(&mut self) -> Result<Vec3, StlError> {
+        Ok(Vec3::new(self.number()?, self.number()?, self.number()?))
+    }
+
+    fn error(&self, expected: &'static str) -> StlError {
+        StlError::UnexpectedToken {
+            line: self.line,
+            expected,
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            if let Some(word) = self.words.next() {
+                return Some(word);
+            }
+            self.words = self.lines.next()?.split_ascii_whitespace();
+            self.line += 1;
+        }
+    }
+}
+
+fn read_ascii(bytes: &[u8]) -> Result<Vec<StlTriangle>, StlError> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut tokens = Tokens::new(&text);
+    tokens.expect("solid")?;
+
+    let mut triangles = Vec::new();
+    let mut vertices = Vec::new();
+    loop {
+        match tokens.next() {
+            Some("facet") => {
+                tokens.expect("normal")?;
+                let normal = tokens.vector()?;
+                tokens.expect("outer")?;
+                tokens.expect("loop")?;
+                vertices.clear();
+                loop {
+                    match tokens.next() {
+                        Some("vertex") => vertices.push(tokens.vector()?),
+                        Some("endloop") => break,
+                        _ => return Err(tokens.error("`vertex` or `endloop`")),
+                    }
+                }
+                tokens.expect("endfacet")?;
+
+                // Facets are triangles, but polygons are occasionally found in the wild.
+                for index in 2..vertices.len() {
+                    triangles.push(StlTriangle {
+                        normal,
+                        vertices: [vertices[0], vertices[index - 1], vertices[index]],
+                    });
+                }
+            }
+            // A file may contain several solids, each of which can be followed by its name.
+            Some("endsolid") => loop {
+                match tokens.next() {
+                    Some("solid") => break,
+                    Some(_) => {}
+                    None => return Ok(triangles),
+                }
+            },
+            // Skip the name of the solid.
+            Some(_) => {}
+            None => return Ok(triangles),
+        }
+    }
+}
+
+fn build_mesh(triangles: &[StlTriangle], settings: &StlLoaderSettings) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, settings.asset_usage);
+
+    if settings.smooth_normals {
+        let mut positions = Vec::new();
+        let mut indices = Vec::with_capacity(triangles.len() * 3);
+        let mut vertex_indices = HashMap::<[u32; 3], u32>::new();
+        for &vertex in triangles.iter().flat_map(|triangle| &triangle.vertices) {
+            let index = *vertex_indices
+                .entry(vertex.to_array().map(f32::to_bits))
+                .or_insert_with(|| {
+                    positions.push(vertex.to_array());
+                    positions.len() as u32 - 1
+                });
+            indices.push(index);
+        }
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_indices(Indices::U32(indices));
+        mesh.compute_smooth_normals();
+    } else {
+        let positions: Vec<[f32; 3]> = triangles
+            .iter()
+            .flat_map(|triangle| triangle.vertices.map(|vertex| vertex.to_array()))
+            .collect();
+        // Many programs write zero normals, so fall back to the normal given by the winding.
+        let normals: Vec<[f32; 3]> = triangles
+            .iter()
+            .flat_map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                let normal = triangle
+                    .normal
+                    .try_normalize()
+                    .unwrap_or_else(|| (b - a).cross(c - a).normalize_or_zero());
+                [normal.to_array(); 3]
+            })
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+
+    mesh
+}
+
+fn write_stl(mesh: &Mesh, format: StlFormat) -> Result<Vec<u8>, StlError> {
+    let triangles: Vec<_> = mesh
+        .triangles()?
+        .map(|triangle| {
+            let normal = triangle.normal().map(Vec3::from).unwrap_or(Vec3::ZERO);
+            (normal, triangle.vertices)
+        })
+        .collect();
+
+    match format {
+        StlFormat::Binary => {
+            let mut bytes =
+                Vec::with_capacity(BINARY_HEADER_SIZE + 4 + triangles.len() * BINARY_TRIANGLE_SIZE);
+            bytes.extend_from_slice(BINARY_HEADER);
+            bytes.resize(BINARY_HEADER_SIZE, 0);
+            bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+            for (normal, vertices) in &triangles {
+                for vector in [normal, &vertices[0], &vertices[1], &vertices[2]] {
+                    for coordinate in vector.to_array() {
+                        bytes.extend_from_slice(&coordinate.to_le_bytes());
+                    }
+                }
+                bytes.extend_from_slice(&[0, 0]);
+            }
+            Ok(bytes)
+        }
+        StlFormat::Ascii => {
+            let mut text = String::from("solid mesh\n");
+            for (normal, vertices) in &triangles {
+                let _ = writeln!(text, "facet normal {} {} {}", normal.x, normal.y, normal.z);
+                text.push_str("outer loop\n");
+                for vertex in vertices {
+                    let _ = writeln!(text, "vertex {} {} {}", vertex.x, vertex.y, vertex.z);
+                }
+                text.push_str("endloop\nendfacet\n");
+            }
+            text.push_str("endsolid mesh\n");
+            Ok(text.into_bytes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::{primitives::Cuboid, Vec3};
+    use bevy_mesh::{Mesh, MeshBuilder, Meshable};
+
+    use super::{
+        build_mesh, read_stl, write_stl, StlError, StlFormat, StlLoaderSettings, BINARY_HEADER_SIZE,
+    };
+
+    #[test]
+    fn round_trip() {
+        let cube = Cuboid::default().mesh().build();
+        let expected: Vec<_> = cube.triangles().unwrap().collect();
+
+        for format in [StlFormat::Binary, StlFormat::Ascii] {
+            let bytes = write_stl(&cube, format).unwrap();
+            let triangles = read_stl(&bytes).unwrap();
+            assert_eq!(triangles.len(), 12);
+            for (triangle, expected) in triangles.iter().zip(&expected) {
+                assert_eq!(triangle.vertices, expected.vertices);
+                assert_eq!(triangle.normal, Vec3::from(expected.normal().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let stl = "solid part
+              facet normal 0 0 0
+                outer loop
+                  vertex 0 0 0
+                  vertex 1 0 0
+                  vertex 1 1 0
+                  vertex 0 1 0
+                endloop
+              endfacet
+            endsolid part";
+        let triangles = read_stl(stl.as_bytes()).unwrap();
+        assert_eq!(triangles.len(), 2);
+
+        // Zero normals are replaced by the normal given by the winding.
+        let mesh = build_mesh(&triangles, &StlLoaderSettings::default());
+        let normals = mesh.attribute(Mesh::ATTRIBUTE_NORMAL).unwrap().as_float3();
+        assert_eq!(normals.unwrap(), &[[0.0, 0.0, 1.0]; 6]);
+
+        assert!(matches!(
+            read_stl(b"solid part\nfacet normal 0 0\nouter loop"),
+            Err(StlError::UnexpectedToken {
+                line: 3,
+                expected: "a number"
+            })
+        ));
+    }
+
+    #[test]
+    fn binary_header_starting_with_solid() {
+        let cube = Cuboid::default().mesh().build();
+        let mut bytes = write_stl(&cube, StlFormat::Binary).unwrap();
+        bytes[..5].copy_from_slice(b"solid");
+        assert_eq!(read_stl(&bytes).unwrap().len(), 12);
+
+        bytes.truncate(BINARY_HEADER_SIZE + 4 + 100);
+        bytes[0] = b' ';
+        assert!(matches!(read_stl(&bytes), Err(StlError::Truncated(12))));
+    }
+
+    #[test]
+    fn smooth_normals() {
+        let cube = Cuboid::default().mesh().build();
+        let triangles = read_stl(&write_stl(&cube, StlFormat::Binary).unwrap()).unwrap();
+        let mesh = build_mesh(
+            &triangles,
+            &StlLoaderSettings {
+                smooth_normals: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(mesh.count_vertices(), 8);
+        assert_eq!(mesh.indices().unwrap().len(), 36);
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+    }
+}
@@ -6,6 +6,10 @@
 //! with an addon like [The Lightmapper]. The tools in the [`bevy-baked-gi`]
 //! project support other lightmap baking methods.
 //!
+//! Lightmaps need a second UV layer laid out without overlaps. Meshes that lack one, such as
+//! procedural meshes, can get one with
+//! [`Mesh::generate_lightmap_uvs`](bevy_mesh::Mesh::generate_lightmap_uvs).
+//!
 //! When a [`Lightmap`] component is added to an entity with a [`Mesh3d`] and a
 //! [`MeshMaterial3d<StandardMaterial>`], Bevy applies the lightmap when rendering. The brightness
 //! of the lightmap may be controlled with the `lightmap_exposure` field on
@@ -0,0 +1,419 @@
+//! Area selection: finding every pickable entity inside a screen-space region.
+//!
+//! Pointer picking answers "what is under this pointer?". Area selection answers "what is inside
+//! this region?", which is what editor-style marquee and lasso selection needs. Write an
+//! [`AreaSelectionRequest`] describing a region of a camera's viewport, and read the matching
+//! [`AreaSelection`] message once picking backends have had a chance to run.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::{Rect, Vec2};
+//! # use bevy_picking::area::*;
+//! fn select(camera: Entity, mut requests: MessageWriter<AreaSelectionRequest>) {
+//!     let shape = AreaSelectionShape::Rect(Rect::new(10.0, 10.0, 200.0, 120.0));
+//!     requests.write(AreaSelectionRequest::new(camera, shape).with_mode(AreaSelectionMode::Contain));
+//! }
+//!
+//! fn print_selection(mut selections: MessageReader<AreaSelection>) {
+//!     for selection in selections.read() {
+//!         println!("Selected {:?}", selection.entities);
+//!     }
+//! }
+//! ```
+//!
+//! ## Implementation
+//!
+//! - Backends read [`AreaSelectionRequest`]s in [`PickingSystems::Backend`](crate::PickingSystems::Backend) and write an
+//!   [`AreaSelectionHits`] message for each request that selects at least one of their entities.
+//!   They should honor the same markers, render layers and visibility rules they use for pointers.
+//!
+//! - Regions are in the logical viewport coordinates of the request's camera, the same space as
+//!   [`Camera::world_to_viewport`](bevy_camera::Camera::world_to_viewport). The origin is the top
+//!   left corner of the viewport.
+//!
+//! - Occlusion is ignored: entities hidden behind other entities are still selected, as is usual
+//!   for editor marquee selection.
+//!
+//! - Entities with a [`Pickable`] component that is not [hoverable](Pickable::is_hoverable) are
+//!   removed from the final [`AreaSelection`], so backends do not need to check for it.
+
+use bevy_ecs::{message::MessageId, prelude::*};
+use bevy_math::{Rect, Vec2};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::prelude::*;
+
+use crate::Pickable;
+
+/// A region of a camera's viewport used for area selection, in logical viewport coordinates.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum AreaSelectionShape {
+    /// An axis-aligned rectangle, usually dragged out by a marquee tool.
+    Rect(Rect),
+    /// A closed polygon, usually traced by a lasso tool. The last point connects back to the
+    /// first one, and self-intersecting polygons use the even-odd rule.
+    ///
+    /// Lassos with fewer than three points select nothing.
+    Lasso(Vec<Vec2>),
+}
+
+impl AreaSelectionShape {
+    /// Returns the axis-aligned bounds of this shape.
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Self::Rect(rect) => *rect,
+            Self::Lasso(points) => points
+                .iter()
+                .fold(Rect::EMPTY, |bounds, point| bounds.union_point(*point)),
+        }
+    }
+
+    /// Returns `true` if `point` is inside of this shape.
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        match self {
+            Self::Rect(rect) => rect.contains(point),
+            Self::Lasso(points) => points.len() >= 3 && polygon_contains_point(points, point),
+        }
+    }
+
+    /// Returns `true` if the closed `polygon` overlaps this shape.
+    ///
+    /// `polygon` may be degenerate: a single point or a line segment are also supported.
+    pub fn intersects_polygon(&self, polygon: &[Vec2]) -> bool {
+        if polygon.is_empty() || !self.overlaps_bounds(polygon) {
+            return false;
+        }
+        if polygon.iter().any(|point| self.contains_point(*point)) {
+            return true;
+        }
+        self.with_vertices(|vertices| {
+            if vertices.len() < 3 {
+                return false;
+            }
+            let shape_inside_polygon = polygon.len() >= 3
+                && vertices
+                    .iter()
+                    .any(|vertex| polygon_contains_point(polygon, *vertex));
+            shape_inside_polygon
+                || edges(vertices)
+                    .any(|(a, b)| edges(polygon).any(|(c, d)| segments_intersect(a, b, c, d)))
+        })
+    }
+
+    /// Returns `true` if the closed `polygon` lies entirely inside of this shape.
+    ///
+    /// `polygon` may be degenerate: a single point or a line segment are also supported.
+    pub fn contains_polygon(&self, polygon: &[Vec2]) -> bool {
+        if polygon.is_empty() || !polygon.iter().all(|point| self.contains_point(*point)) {
+            return false;
+        }
+        match self {
+            // Rectangles are convex, so containing every vertex is enough.
+            Self::Rect(_) => true,
+            Self::Lasso(points) => {
+                !edges(points).any(|(a, b)| edges(polygon).any(|(c, d)| segments_cross(a, b, c, d)))
+            }
+        }
+    }
+
+    /// Returns `true` if the closed `polygon` is selected by this shape using `mode`.
+    pub fn selects(&self, mode: AreaSelectionMode, polygon: &[Vec2]) -> bool {
+        match mode {
+            AreaSelectionMode::Intersect => self.intersects_polygon(polygon),
+            AreaSelectionMode::Contain => self.contains_polygon(polygon),
+        }
+    }
+
+    fn overlaps_bounds(&self, polygon: &[Vec2]) -> bool {
+        let bounds = self.bounds();
+        let polygon_bounds = polygon
+            .iter()
+            .fold(Rect::EMPTY, |bounds, point| bounds.union_point(*point));
+        bounds.min.cmple(polygon_bounds.max).all() && polygon_bounds.min.cmple(bounds.max).all()
+    }
+
+    fn with_vertices<R>(&self, f: impl FnOnce(&[Vec2]) -> R) -> R {
+        match self {
+            Self::Rect(rect) => f(&[
+                rect.min,
+                Vec2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vec2::new(rect.min.x, rect.max.y),
+            ]),
+            Self::Lasso(points) => f(points),
+        }
+    }
+}
+
+/// How an [`AreaSelectionShape`] decides whether an entity is selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub enum AreaSelectionMode {
+    /// Select entities that are at least partially inside the shape.
+    #[default]
+    Intersect,
+    /// Select only entities that are entirely inside the shape.
+    Contain,
+}
+
+/// A request to find every pickable entity inside a region of a camera's viewport.
+///
+/// The result is sent as an [`AreaSelection`] message during the same [`PreUpdate`] that picking
+/// backends process the request.
+///
+/// [`PreUpdate`]: bevy_app::PreUpdate
+#[derive(Message, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct AreaSelectionRequest {
+    /// The camera whose viewport [`Self::shape`] is defined in.
+    pub camera: Entity,
+    /// The region to select entities in.
+    pub shape: AreaSelectionShape,
+    /// Whether entities need to be partially or entirely inside of [`Self::shape`].
+    pub mode: AreaSelectionMode,
+}
+
+impl AreaSelectionRequest {
+    /// Construct an [`AreaSelectionRequest`] using [`AreaSelectionMode::Intersect`].
+    pub fn new(camera: Entity, shape: AreaSelectionShape) -> Self {
+        Self {
+            camera,
+            shape,
+            mode: AreaSelectionMode::default(),
+        }
+    }
+
+    /// Set the [`AreaSelectionMode`] of this request.
+    pub fn with_mode(mut self, mode: AreaSelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// A message produced by a picking backend after it has tested its entities against an
+/// [`AreaSelectionRequest`].
+///
+/// Hits from all backends are merged into a single [`AreaSelection`] per request, so the order of
+/// the entities does not matter.
+#[derive(Message, Debug, Clone)]
+pub struct AreaSelectionHits {
+    /// The request these entities were selected by.
+    pub request: MessageId<AreaSelectionRequest>,
+    /// The entities selected by the request.
+    pub entities: Vec<Entity>,
+}
+
+impl AreaSelectionHits {
+    /// Construct [`AreaSelectionHits`].
+    pub fn new(request: MessageId<AreaSelectionRequest>, entities: Vec<Entity>) -> Self {
+        Self { request, entities }
+    }
+}
+
+/// The entities selected by an [`AreaSelectionRequest`], combined across all picking backends.
+///
+/// One of these is sent for every request, even if nothing was selected.
+#[derive(Message, Debug, Clone)]
+pub struct AreaSelection {
+    /// The request that produced this selection.
+    pub request: AreaSelectionRequest,
+    /// The selected entities, sorted and without duplicates.
+    pub entities: Vec<Entity>,
+}
+
+/// Merges the [`AreaSelectionHits`] of all backends into one [`AreaSelection`] per request.
+pub fn merge_area_selection_hits(
+    mut requests: MessageReader<AreaSelectionRequest>,
+    mut hits_reader: MessageReader<AreaSelectionHits>,
+    pickables: Query<&Pickable>,
+    mut selection_writer: MessageWriter<AreaSelection>,
+) {
+    let mut hits_by_request = HashMap::<usize, Vec<Entity>>::default();
+    for hits in hits_reader.read() {
+        hits_by_request
+            .entry(hits.request.id)
+            .or_default()
+            .extend(&hits.entities);
+    }
+
+    for (request, id) in requests.read_with_id() {
+        let mut entities = hits_by_request.remove(&id.id).unwrap_or_default();
+        entities.retain(|entity| {
+            pickables
+                .get(*entity)
+                .ok()
+                .is_none_or(|pickable| pickable.is_hoverable)
+        });
+        entities.sort_unstable();
+        entities.dedup();
+        selection_writer.write(AreaSelection {
+            request: request.clone(),
+            entities,
+        });
+    }
+}
+
+fn edges(polygon: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Even-odd point in polygon test.
+fn polygon_contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Returns `true` if the segments `ab` and `cd` touch or overlap.
+fn segments_intersect(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let (d1, d2) = ((b - a).perp_dot(c - a), (b - a).perp_dot(d - a));
+    let (d3, d4) = ((d - c).perp_dot(a - c), (d - c).perp_dot(b - c));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+    let on_segment = |p: Vec2, q: Vec2, r: Vec2| r.cmpge(p.min(q)).all() && r.cmple(p.max(q)).all();
+    (d1 == 0.0 && on_segment(a, b, c))
+        || (d2 == 0.0 && on_segment(a, b, d))
+        || (d3 == 0.0 && on_segment(c, d, a))
+        || (d4 == 0.0 && on_segment(c, d, b))
+}
+
+/// Returns `true` if the segments `ab` and `cd` cross at a single point inside of both.
+fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    (b - a).perp_dot(c - a) * (b - a).perp_dot(d - a) < 0.0
+        && (d - c).perp_dot(a - c) * (d - c).perp_dot(b - c) < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::prelude::*;
+
+    fn lasso() -> AreaSelectionShape {
+        // A "U" shape opening upwards.
+        AreaSelectionShape::Lasso(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(2.0, 3.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 3.0),
+            Vec2::new(0.0, 3.0),
+        ])
+    }
+
+    fn square(min: Vec2, size: f32) -> [Vec2; 4] {
+        [
+            min,
+            min + Vec2::new(size, 0.0),
+            min + Vec2::splat(size),
+            min + Vec2::new(0.0, size),
+        ]
+    }
+
+    #[test]
+    fn rect_selection() {
+        let shape = AreaSelectionShape::Rect(Rect::new(0.0, 0.0, 10.0, 10.0));
+
+        let inside = square(Vec2::splat(2.0), 2.0);
+        let overlapping = square(Vec2::splat(8.0), 4.0);
+        let outside = square(Vec2::splat(12.0), 2.0);
+        let enclosing = square(Vec2::splat(-5.0), 20.0);
+
+        for (polygon, intersects, contains) in [
+            (&inside, true, true),
+            (&overlapping, true, false),
+            (&outside, false, false),
+            (&enclosing, true, false),
+        ] {
+            assert_eq!(
+                shape.selects(AreaSelectionMode::Intersect, polygon),
+                intersects
+            );
+            assert_eq!(shape.selects(AreaSelectionMode::Contain, polygon), contains);
+        }
+
+        // A triangle whose edge crosses the rect, without any vertex inside of it.
+        let crossing = [
+            Vec2::new(-1.0, 5.0),
+            Vec2::new(11.0, 5.0),
+            Vec2::new(5.0, 20.0),
+        ];
+        assert!(shape.intersects_polygon(&crossing));
+        assert!(!shape.contains_polygon(&crossing));
+    }
+
+    #[test]
+    fn lasso_selection() {
+        let shape = lasso();
+
+        assert!(shape.contains_point(Vec2::new(0.5, 2.0)));
+        assert!(!shape.contains_point(Vec2::new(1.5, 2.0)));
+        assert_eq!(shape.bounds(), Rect::new(0.0, 0.0, 3.0, 3.0));
+
+        // Inside the gap of the "U".
+        let gap = square(Vec2::new(1.25, 1.5), 0.5);
+        assert!(!shape.intersects_polygon(&gap));
+
+        // Bridging the gap: every vertex is inside, but the polygon is not.
+        let bridge = [
+            Vec2::new(0.5, 2.0),
+            Vec2::new(2.5, 2.0),
+            Vec2::new(1.5, 0.5),
+        ];
+        assert!(bridge.iter().all(|point| shape.contains_point(*point)));
+        assert!(shape.intersects_polygon(&bridge));
+        assert!(!shape.contains_polygon(&bridge));
+
+        let leg = square(Vec2::new(0.25, 1.5), 0.5);
+        assert!(shape.contains_polygon(&leg));
+
+        let degenerate = AreaSelectionShape::Lasso(vec![Vec2::ZERO, Vec2::ONE]);
+        assert!(!degenerate.intersects_polygon(&leg));
+    }
+
+    #[test]
+    fn merge_hits() {
+        let mut app = App::new();
+        app.add_message::<AreaSelectionRequest>()
+            .add_message::<AreaSelectionHits>()
+            .add_message::<AreaSelection>()
+            .add_systems(Update, merge_area_selection_hits);
+
+        let camera = app.world_mut().spawn_empty().id();
+        let a = app.world_mut().spawn_empty().id();
+        let b = app.world_mut().spawn_empty().id();
+        let ignored = app.world_mut().spawn(Pickable::IGNORE).id();
+
+        let shape = AreaSelectionShape::Rect(Rect::new(0.0, 0.0, 1.0, 1.0));
+        let first = app
+            .world_mut()
+            .write_message(AreaSelectionRequest::new(camera, shape.clone()))
+            .unwrap();
+        app.world_mut()
+            .write_message(AreaSelectionRequest::new(camera, shape));
+        app.world_mut()
+            .write_message(AreaSelectionHits::new(first, vec![b, ignored]));
+        app.world_mut()
+            .write_message(AreaSelectionHits::new(first, vec![a, b]));
+        app.update();
+
+        let selections = app.world().resource::<Messages<AreaSelection>>();
+        let selections: Vec<_> = selections.iter_current_update_messages().collect();
+        assert_eq!(selections.len(), 2);
+        let mut expected = vec![a, b];
+        expected.sort_unstable();
+        assert_eq!(selections[0].entities, expected);
+        assert!(selections[1].entities.is_empty());
+    }
+}
@@ -41,6 +41,7 @@ use bevy_reflect::Reflect;
 pub mod prelude {
     pub use super::{ray::RayMap, HitData, PointerHits};
     pub use crate::{
+        area::{AreaSelectionHits, AreaSelectionRequest},
         pointer::{PointerId, PointerLocation},
         Pickable, PickingSystems,
     };
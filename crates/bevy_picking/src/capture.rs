@@ -0,0 +1,244 @@
+//! Pointer capture: routing all of a pointer's events to a single entity.
+//!
+//! Normally, pointer events are sent to whatever the pointer is hovering. A slider thumb or a
+//! gizmo handle usually wants to keep receiving [`Move`](crate::events::Move) and
+//! [`Release`](crate::events::Release) events after the pointer has left its bounds. Capturing the
+//! pointer makes the [`HoverMap`] report the capturing entity as the only entity hovered by that
+//! pointer, so every event derived from hover state is sent there instead.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::{capture::PointerCaptures, prelude::*};
+//! # let mut world = World::default();
+//! world.spawn_empty().observe(
+//!     |press: On<Pointer<Press>>, mut captures: ResMut<PointerCaptures>| {
+//!         captures.capture(press.pointer_id, press.entity);
+//!     },
+//! );
+//! ```
+//!
+//! Like on the web, a capture is released automatically when a button of the captured pointer is
+//! released, when the pointer is canceled, or when the capturing entity is despawned.
+//!
+//! Because the capturing entity is the only one the pointer hovers, other entities will not
+//! receive [`DragEnter`](crate::events::DragEnter), [`DragOver`](crate::events::DragOver) or
+//! [`DragDrop`](crate::events::DragDrop) events from a captured pointer. Avoid capturing pointers
+//! that are used for drag and drop.
+
+use bevy_ecs::{entity::Entities, prelude::*};
+use bevy_platform::collections::HashMap;
+
+use crate::{
+    backend::HitData,
+    hover::{HoverMap, PreviousHoverMap},
+    pointer::{PointerAction, PointerId, PointerInput},
+};
+
+/// The pointer captures currently in effect, mapping pointers to the entity that captured them.
+///
+/// See the [module docs](crate::capture) for more details.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PointerCaptures {
+    captures: HashMap<PointerId, PointerCapture>,
+}
+
+#[derive(Debug, Clone)]
+struct PointerCapture {
+    entity: Entity,
+    /// The latest hit between the pointer and the capturing entity, reported to the entity while
+    /// the pointer is outside of it.
+    hit: Option<HitData>,
+}
+
+impl PointerCaptures {
+    /// Routes all events of `pointer_id` to `entity`, replacing any previous capture.
+    ///
+    /// The capture takes effect the next time the [`HoverMap`] is updated.
+    pub fn capture(&mut self, pointer_id: PointerId, entity: Entity) {
+        if self.get(pointer_id) != Some(entity) {
+            self.captures
+                .insert(pointer_id, PointerCapture { entity, hit: None });
+        }
+    }
+
+    /// Releases the capture of `pointer_id`, returning the entity that captured it.
+    pub fn release(&mut self, pointer_id: PointerId) -> Option<Entity> {
+        self.captures
+            .remove(&pointer_id)
+            .map(|capture| capture.entity)
+    }
+
+    /// Releases every pointer captured by `entity`.
+    pub fn release_entity(&mut self, entity: Entity) {
+        self.captures.retain(|_, capture| capture.entity != entity);
+    }
+
+    /// Returns the entity that captured `pointer_id`, if any.
+    pub fn get(&self, pointer_id: PointerId) -> Option<Entity> {
+        self.captures.get(&pointer_id).map(|capture| capture.entity)
+    }
+
+    /// Iterates over all captured pointers and the entities that captured them.
+    pub fn iter(&self) -> impl Iterator<Item = (PointerId, Entity)> + '_ {
+        self.captures
+            .iter()
+            .map(|(pointer_id, capture)| (*pointer_id, capture.entity))
+    }
+}
+
+/// Replaces the hovered entities of each captured pointer with the capturing entity.
+///
+/// The capturing entity keeps the hit data of the latest frame it was actually hovered. Until the
+/// pointer has hovered it at least once, the capture has no effect.
+pub fn apply_pointer_captures(
+    entities: &Entities,
+    previous_hover_map: Res<PreviousHoverMap>,
+    mut captures: ResMut<PointerCaptures>,
+    mut hover_map: ResMut<HoverMap>,
+) {
+    captures
+        .captures
+        .retain(|_, capture| entities.contains(capture.entity));
+
+    for (pointer_id, capture) in captures.captures.iter_mut() {
+        let hovered = hover_map.entry(*pointer_id).or_default();
+        if let Some(hit) = hovered.get(&capture.entity).or_else(|| {
+            previous_hover_map
+                .get(pointer_id)
+                .and_then(|previous| previous.get(&capture.entity))
+        }) {
+            capture.hit = Some(hit.clone());
+        }
+        let Some(hit) = capture.hit.clone() else {
+            continue;
+        };
+        hovered.clear();
+        hovered.insert(capture.entity, hit);
+    }
+}
+
+/// Releases the captures of pointers whose buttons were released or that were canceled.
+///
+/// This runs after [`pointer_events`](crate::events::pointer_events), so the capturing entity still
+/// receives the [`Release`](crate::events::Release) event.
+pub fn release_pointer_captures(
+    mut input_reader: MessageReader<PointerInput>,
+    mut captures: ResMut<PointerCaptures>,
+    mut released: Local<HashMap<PointerId, bool>>,
+) {
+    if captures.captures.is_empty() {
+        input_reader.clear();
+        return;
+    }
+
+    released.clear();
+    for input in input_reader.read() {
+        match input.action {
+            PointerAction::Release(_) | PointerAction::Cancel => {
+                released.insert(input.pointer_id, true);
+            }
+            // A capture made while handling a later press should survive an earlier release.
+            PointerAction::Press(_) => {
+                released.insert(input.pointer_id, false);
+            }
+            _ => {}
+        }
+    }
+    for (pointer_id, released) in released.iter() {
+        if *released {
+            captures.release(*pointer_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pointer::{Location, PointerButton};
+    use bevy_camera::{ManualTextureViewHandle, NormalizedRenderTarget};
+    use bevy_math::Vec2;
+
+    fn hit(camera: Entity) -> HitData {
+        HitData::new(camera, 0.0, None, None)
+    }
+
+    #[test]
+    fn capture_overrides_hover() {
+        let mut world = World::new();
+        world.init_resource::<PointerCaptures>();
+        world.init_resource::<HoverMap>();
+        world.init_resource::<PreviousHoverMap>();
+
+        let camera = world.spawn_empty().id();
+        let captor = world.spawn_empty().id();
+        let other = world.spawn_empty().id();
+
+        world
+            .resource_mut::<PointerCaptures>()
+            .capture(PointerId::Mouse, captor);
+
+        // The captor has never been hovered, so the capture is pending.
+        world.resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            [(other, hit(camera))].into_iter().collect(),
+        );
+        world.run_system_cached(apply_pointer_captures).unwrap();
+        assert!(world.resource::<HoverMap>()[&PointerId::Mouse].contains_key(&other));
+
+        world.resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            [(other, hit(camera)), (captor, hit(camera))]
+                .into_iter()
+                .collect(),
+        );
+        world.run_system_cached(apply_pointer_captures).unwrap();
+        let hovered = &world.resource::<HoverMap>()[&PointerId::Mouse];
+        assert_eq!(hovered.len(), 1);
+        assert!(hovered.contains_key(&captor));
+
+        // The pointer left the captor, but it is still reported as hovered.
+        world.resource_mut::<HoverMap>().clear();
+        world.run_system_cached(apply_pointer_captures).unwrap();
+        let hovered = &world.resource::<HoverMap>()[&PointerId::Mouse];
+        assert_eq!(hovered.len(), 1);
+        assert!(hovered.contains_key(&captor));
+
+        world.despawn(captor);
+        world.run_system_cached(apply_pointer_captures).unwrap();
+        assert_eq!(
+            world.resource::<PointerCaptures>().get(PointerId::Mouse),
+            None
+        );
+    }
+
+    #[test]
+    fn release_on_button_up() {
+        let mut world = World::new();
+        world.init_resource::<PointerCaptures>();
+        world.init_resource::<Messages<PointerInput>>();
+        let captor = world.spawn_empty().id();
+        let location = Location {
+            target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+            position: Vec2::ZERO,
+        };
+        let input = |action| PointerInput::new(PointerId::Mouse, location.clone(), action);
+
+        world
+            .resource_mut::<PointerCaptures>()
+            .capture(PointerId::Mouse, captor);
+        world.write_message(input(PointerAction::Release(PointerButton::Primary)));
+        world.write_message(input(PointerAction::Press(PointerButton::Primary)));
+        world.run_system_cached(release_pointer_captures).unwrap();
+        assert_eq!(
+            world.resource::<PointerCaptures>().get(PointerId::Mouse),
+            Some(captor)
+        );
+
+        world.write_message(input(PointerAction::Release(PointerButton::Primary)));
+        world.run_system_cached(release_pointer_captures).unwrap();
+        assert_eq!(
+            world.resource::<PointerCaptures>().get(PointerId::Mouse),
+            None
+        );
+    }
+}
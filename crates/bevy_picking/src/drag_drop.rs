@@ -0,0 +1,369 @@
+//! Typed payloads for drag and drop.
+//!
+//! The [`DragStart`](crate::events::DragStart) and [`DragDrop`] events only say which entity is
+//! being dragged. To build inventories, outliners and other drag and drop interfaces, attach a
+//! [`DragPayload<T>`] to the entities that can be dragged, and an [`AcceptsDrop<T>`] to the
+//! entities that can receive them. With a [`DragPayloadPlugin<T>`] added, the accepting entities
+//! then receive [`DragPayloadEnter<T>`], [`DragPayloadLeave<T>`] and [`DragPayloadDrop<T>`] events,
+//! regardless of which picking backend reported them.
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::drag_drop::*;
+//! #[derive(Clone, Debug)]
+//! struct Item {
+//!     weight: f32,
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins(DragPayloadPlugin::<Item>::default());
+//!
+//! let world = app.world_mut();
+//! world.spawn(DragPayload(Item { weight: 2.0 }));
+//! world
+//!     .spawn(AcceptsDrop::<Item>::with_filter(|item| item.weight < 10.0))
+//!     .observe(|drop: On<DragPayloadDrop<Item>>| {
+//!         println!("Received an item weighing {}", drop.payload.weight);
+//!     });
+//! ```
+//!
+//! Pointers often hover descendants of a drop target, such as the text of a UI button. The events
+//! are sent to the closest hovered entity or ancestor that accepts the payload, and an entity
+//! never accepts its own payload.
+
+use core::marker::PhantomData;
+use std::collections::HashSet;
+
+use bevy_app::prelude::*;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+
+use crate::{
+    backend::HitData,
+    events::{DragDrop, Pointer, PointerState},
+    pointer::{PointerButton, PointerId},
+    PickingSystems,
+};
+
+/// A payload of type `T` carried by this entity while it is being dragged.
+///
+/// This can also be inserted from a [`DragStart`](crate::events::DragStart) observer, to only
+/// decide on the payload once a drag begins.
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
+pub struct DragPayload<T: Clone + Send + Sync + 'static>(pub T);
+
+/// Declares that this entity accepts [`DragPayload<T>`]s being dropped onto it.
+///
+/// An entity can accept several payload types by having several of these components.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AcceptsDrop<T: Clone + Send + Sync + 'static> {
+    filter: Option<fn(&T) -> bool>,
+}
+
+impl<T: Clone + Send + Sync + 'static> AcceptsDrop<T> {
+    /// Accepts every payload of type `T`.
+    pub fn new() -> Self {
+        Self { filter: None }
+    }
+
+    /// Only accepts payloads of type `T` for which `filter` returns `true`.
+    pub fn with_filter(filter: fn(&T) -> bool) -> Self {
+        Self {
+            filter: Some(filter),
+        }
+    }
+
+    /// Returns `true` if `payload` can be dropped onto this entity.
+    pub fn accepts(&self, payload: &T) -> bool {
+        self.filter.is_none_or(|filter| filter(payload))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for AcceptsDrop<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fires when an entity carrying an accepted [`DragPayload<T>`] is dragged onto the
+/// [target entity](EntityEvent::event_target).
+#[derive(Message, EntityEvent, Clone, Debug)]
+pub struct DragPayloadEnter<T: Clone + Send + Sync + 'static> {
+    /// The entity accepting the payload.
+    pub entity: Entity,
+    /// The pointer dragging the payload.
+    pub pointer_id: PointerId,
+    /// Pointer button pressed while dragging.
+    pub button: PointerButton,
+    /// The entity carrying the payload.
+    pub dragged: Entity,
+    /// Information about the picking intersection.
+    pub hit: HitData,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Fires when an entity carrying an accepted [`DragPayload<T>`] is dragged off of the
+/// [target entity](EntityEvent::event_target), or once it has been dropped.
+#[derive(Message, EntityEvent, Clone, Debug)]
+pub struct DragPayloadLeave<T: Clone + Send + Sync + 'static> {
+    /// The entity that was accepting the payload.
+    pub entity: Entity,
+    /// The pointer dragging the payload.
+    pub pointer_id: PointerId,
+    /// Pointer button pressed while dragging.
+    pub button: PointerButton,
+    /// The entity carrying the payload.
+    pub dragged: Entity,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Fires when an entity carrying an accepted [`DragPayload<T>`] is dropped onto the
+/// [target entity](EntityEvent::event_target).
+#[derive(Message, EntityEvent, Clone, Debug)]
+pub struct DragPayloadDrop<T: Clone + Send + Sync + 'static> {
+    /// The entity accepting the payload.
+    pub entity: Entity,
+    /// The pointer that dropped the payload.
+    pub pointer_id: PointerId,
+    /// Pointer button released to drop.
+    pub button: PointerButton,
+    /// The entity carrying the payload.
+    pub dropped: Entity,
+    /// The dropped payload.
+    pub payload: T,
+    /// Information about the picking intersection.
+    pub hit: HitData,
+}
+
+/// Sends [`DragPayloadEnter<T>`], [`DragPayloadLeave<T>`] and [`DragPayloadDrop<T>`] events to
+/// entities with an [`AcceptsDrop<T>`].
+///
+/// Add one of these for every payload type. Requires the
+/// [`InteractionPlugin`](crate::InteractionPlugin).
+pub struct DragPayloadPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for DragPayloadPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Plugin for DragPayloadPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_message::<DragPayloadEnter<T>>()
+            .add_message::<DragPayloadLeave<T>>()
+            .add_message::<DragPayloadDrop<T>>()
+            .add_systems(
+                PreUpdate,
+                drag_payload_events::<T>.in_set(PickingSystems::PostHover),
+            );
+    }
+}
+
+/// The drop targets accepting each dragged payload, keyed by pointer, button and dragged entity.
+type AcceptingTargets = HashMap<(PointerId, PointerButton, Entity), HashSet<Entity>>;
+
+/// Dispatches the typed drag and drop events of [`DragPayloadPlugin<T>`].
+///
+/// Within a single frame, events are sent in the following order:
+/// [`DragPayloadDrop<T>`] → [`DragPayloadLeave<T>`] → [`DragPayloadEnter<T>`].
+pub fn drag_payload_events<T: Clone + Send + Sync + 'static>(
+    mut drop_reader: MessageReader<Pointer<DragDrop>>,
+    pointer_state: Res<PointerState>,
+    payloads: Query<&DragPayload<T>>,
+    acceptors: Query<&AcceptsDrop<T>>,
+    ancestors_query: Query<&ChildOf>,
+    mut accepting: Local<AcceptingTargets>,
+    mut commands: Commands,
+    mut writers: (
+        MessageWriter<DragPayloadEnter<T>>,
+        MessageWriter<DragPayloadLeave<T>>,
+        MessageWriter<DragPayloadDrop<T>>,
+    ),
+) {
+    // Finds the closest entity, starting at `target` and going up its ancestors, accepting the payload.
+    let find_acceptor = |target: Entity, dragged: Entity, payload: &T| {
+        core::iter::once(target)
+            .chain(ancestors_query.iter_ancestors(target))
+            .find(|&entity| {
+                entity != dragged
+                    && acceptors
+                        .get(entity)
+                        .is_ok_and(|acceptor| acceptor.accepts(payload))
+            })
+    };
+
+    // Several hovered descendants of the same target can all receive `DragDrop`.
+    let mut dropped = HashSet::new();
+    for drop in drop_reader.read() {
+        let Ok(payload) = payloads.get(drop.dropped) else {
+            continue;
+        };
+        let Some(acceptor) = find_acceptor(drop.entity, drop.dropped, payload) else {
+            continue;
+        };
+        if !dropped.insert((drop.pointer_id, drop.dropped, acceptor)) {
+            continue;
+        }
+        let drop_event = DragPayloadDrop {
+            entity: acceptor,
+            pointer_id: drop.pointer_id,
+            button: drop.button,
+            dropped: drop.dropped,
+            payload: payload.0.clone(),
+            hit: drop.hit.clone(),
+        };
+        commands.trigger(drop_event.clone());
+        writers.2.write(drop_event);
+    }
+
+    let mut current = AcceptingTargets::default();
+    let mut entered = Vec::new();
+    for (&(pointer_id, button), state) in pointer_state.pointer_buttons.iter() {
+        for &dragged in state.dragging.keys() {
+            let Ok(payload) = payloads.get(dragged) else {
+                continue;
+            };
+            let targets = current.entry((pointer_id, button, dragged)).or_default();
+            for (&dragged_over, hit) in state.dragging_over.iter() {
+                if let Some(acceptor) = find_acceptor(dragged_over, dragged, payload)
+                    && targets.insert(acceptor)
+                    && !accepting
+                        .get(&(pointer_id, button, dragged))
+                        .is_some_and(|previous| previous.contains(&acceptor))
+                {
+                    entered.push(DragPayloadEnter {
+                        entity: acceptor,
+                        pointer_id,
+                        button,
+                        dragged,
+                        hit: hit.clone(),
+                        _marker: PhantomData,
+                    });
+                }
+            }
+        }
+    }
+
+    for (&(pointer_id, button, dragged), targets) in accepting.iter() {
+        for &target in targets {
+            if current
+                .get(&(pointer_id, button, dragged))
+                .is_some_and(|current| current.contains(&target))
+            {
+                continue;
+            }
+            let leave_event = DragPayloadLeave {
+                entity: target,
+                pointer_id,
+                button,
+                dragged,
+                _marker: PhantomData,
+            };
+            commands.trigger(leave_event.clone());
+            writers.1.write(leave_event);
+        }
+    }
+
+    for enter_event in entered {
+        commands.trigger(enter_event.clone());
+        writers.0.write(enter_event);
+    }
+
+    *accepting = current;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::DragEntry,
+        pointer::{Location, PointerId},
+    };
+    use bevy_camera::{ManualTextureViewHandle, NormalizedRenderTarget};
+    use bevy_math::Vec2;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Item(u32);
+
+    #[derive(Resource, Default)]
+    struct Received(Vec<(&'static str, Entity)>);
+
+    const KEY: (PointerId, PointerButton) = (PointerId::Mouse, PointerButton::Primary);
+
+    #[test]
+    fn payload_events() {
+        let mut app = App::new();
+        app.init_resource::<PointerState>()
+            .init_resource::<Received>()
+            .add_message::<Pointer<DragDrop>>()
+            .add_plugins(DragPayloadPlugin::<Item>::default());
+
+        let world = app.world_mut();
+        let camera = world.spawn_empty().id();
+        let dragged = world.spawn(DragPayload(Item(1))).id();
+        let target = world
+            .spawn(AcceptsDrop::<Item>::new())
+            .observe(|e: On<DragPayloadEnter<Item>>, mut r: ResMut<Received>| {
+                r.0.push(("enter", e.entity));
+            })
+            .observe(|e: On<DragPayloadLeave<Item>>, mut r: ResMut<Received>| {
+                r.0.push(("leave", e.entity));
+            })
+            .observe(|e: On<DragPayloadDrop<Item>>, mut r: ResMut<Received>| {
+                assert_eq!(e.payload, Item(1));
+                r.0.push(("drop", e.entity));
+            })
+            .id();
+        let label = world.spawn(ChildOf(target)).id();
+        let rejecting = world
+            .spawn(AcceptsDrop::<Item>::with_filter(|item| item.0 > 1))
+            .id();
+
+        let hit = HitData::new(camera, 0.0, None, None);
+        let mut pointer_state = world.resource_mut::<PointerState>();
+        let state = pointer_state.get_mut(KEY.0, KEY.1);
+        state.dragging.insert(
+            dragged,
+            DragEntry {
+                start_pos: Vec2::ZERO,
+                latest_pos: Vec2::ZERO,
+            },
+        );
+        state.dragging_over.insert(label, hit.clone());
+        state.dragging_over.insert(target, hit.clone());
+        state.dragging_over.insert(rejecting, hit.clone());
+        app.update();
+        assert_eq!(app.world().resource::<Received>().0, [("enter", target)]);
+
+        // Nothing changed, so no events are sent.
+        app.update();
+        assert_eq!(app.world().resource::<Received>().0.len(), 1);
+
+        // Drop onto both the label and the target, as both are hovered.
+        let location = Location {
+            target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+            position: Vec2::ZERO,
+        };
+        for dragged_over in [label, target] {
+            app.world_mut().write_message(Pointer::new(
+                KEY.0,
+                location.clone(),
+                DragDrop {
+                    button: KEY.1,
+                    dropped: dragged,
+                    hit: hit.clone(),
+                },
+                dragged_over,
+            ));
+        }
+        app.world_mut().resource_mut::<PointerState>().clear(KEY.0);
+        app.update();
+        assert_eq!(
+            app.world().resource::<Received>().0,
+            [("enter", target), ("drop", target), ("leave", target)]
+        );
+    }
+}
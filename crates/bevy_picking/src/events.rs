@@ -36,6 +36,9 @@
 //!
 //! When received by an observer, these events will always be wrapped by the [`Pointer`] type, which contains
 //! general metadata about the pointer event.
+//!
+//! To attach typed data to a drag, or to keep sending a pointer's events to one entity after it
+//! leaves, see [`drag_drop`](crate::drag_drop) and [`capture`](crate::capture).
 
 use core::{fmt::Debug, time::Duration};
 use std::collections::HashSet;
@@ -151,10 +151,24 @@
 //!
 //! Because it is completely agnostic to the earlier stages of the pipeline, you can easily extend
 //! the plugin with arbitrary backends and input methods, yet still use all the high level features.
+//!
+//! Pointers can be captured by an entity with [`PointerCaptures`](capture::PointerCaptures), so
+//! that it keeps receiving events after the pointer leaves it. Typed payloads can be attached to
+//! dragged entities and accepted by drop targets, see [`drag_drop`].
+//!
+//! #### Area Selection ([`area`])
+//!
+//! Alongside pointer hits, backends can also answer [`AreaSelectionRequest`](area::AreaSelectionRequest)s,
+//! which ask for every entity inside a rectangle or lasso drawn over a camera's viewport. The
+//! results of all backends are merged into a single [`AreaSelection`](area::AreaSelection) message
+//! per request, which is useful for editor-style multi-selection.
 
 extern crate alloc;
 
+pub mod area;
 pub mod backend;
+pub mod capture;
+pub mod drag_drop;
 pub mod events;
 pub mod hover;
 pub mod input;
@@ -180,8 +194,16 @@ pub mod prelude {
     };
     #[doc(hidden)]
     pub use crate::{
-        events::*, input::PointerInputPlugin, pointer::PointerButton, DefaultPickingPlugins,
-        InteractionPlugin, Pickable, PickingPlugin,
+        area::{AreaSelection, AreaSelectionMode, AreaSelectionRequest, AreaSelectionShape},
+        capture::PointerCaptures,
+        drag_drop::{
+            AcceptsDrop, DragPayload, DragPayloadDrop, DragPayloadEnter, DragPayloadLeave,
+            DragPayloadPlugin,
+        },
+        events::*,
+        input::PointerInputPlugin,
+        pointer::PointerButton,
+        DefaultPickingPlugins, InteractionPlugin, Pickable, PickingPlugin,
     };
 }
 
@@ -369,6 +391,11 @@ impl Plugin for PickingPlugin {
             // we allow them to send their hits in any order. These are later sorted, so submission
             // order doesn't matter. See `PointerHits` docs for caveats.
             .allow_ambiguous_resource::<Messages<backend::PointerHits>>()
+            .add_message::<area::AreaSelectionRequest>()
+            .add_message::<area::AreaSelectionHits>()
+            .add_message::<area::AreaSelection>()
+            // Area selection hits are merged and sorted as well.
+            .allow_ambiguous_resource::<Messages<area::AreaSelectionHits>>()
             .add_systems(
                 PreUpdate,
                 (
@@ -384,6 +411,12 @@ impl Plugin for PickingPlugin {
                     .run_if(PickingSettings::window_picking_should_run)
                     .in_set(PickingSystems::Backend),
             )
+            .add_systems(
+                PreUpdate,
+                area::merge_area_selection_hits
+                    .after(PickingSystems::Backend)
+                    .before(PickingSystems::Hover),
+            )
             .configure_sets(
                 First,
                 (PickingSystems::Input, PickingSystems::PostInput)
@@ -417,6 +450,7 @@ impl Plugin for InteractionPlugin {
         app.init_resource::<hover::HoverMap>()
             .init_resource::<hover::PreviousHoverMap>()
             .init_resource::<PointerState>()
+            .init_resource::<capture::PointerCaptures>()
             .add_message::<Pointer<Cancel>>()
             .add_message::<Pointer<Click>>()
             .add_message::<Pointer<Press>>()
@@ -438,9 +472,11 @@ impl Plugin for InteractionPlugin {
                 PreUpdate,
                 (
                     generate_hovermap,
+                    capture::apply_pointer_captures,
                     update_interactions,
                     (update_is_hovered, update_is_directly_hovered),
                     pointer_events,
+                    capture::release_pointer_captures,
                 )
                     .chain()
                     .in_set(PickingSystems::Hover),
@@ -13,19 +13,38 @@
 //!
 //! - The `position` reported in `HitData` is in world space. The `normal` is a vector pointing
 //!   away from the face, it is not guaranteed to be normalized for scaled meshes.
+//! - Meshes with many triangles are ray cast using a [`MeshBvh`](ray_cast::MeshBvh), cached in
+//!   the [`MeshBvhCache`].
+//! - In scenes with many entities, insert the [`MeshRayCastSceneBvh`] resource to avoid testing
+//!   the bounds of every entity.
+//! - [Area selection](crate::area) projects each mesh's triangles into the viewport, after culling
+//!   with its projected [`Aabb`]. Triangles crossing the camera's near or far plane are skipped.
 
 pub mod ray_cast;
 
 use crate::{
+    area::{AreaSelectionHits, AreaSelectionMode, AreaSelectionRequest, AreaSelectionShape},
     backend::{ray::RayMap, HitData, PointerHits},
     prelude::*,
     PickingSystems,
 };
+use alloc::vec::Vec;
 use bevy_app::prelude::*;
-use bevy_camera::{visibility::RenderLayers, Camera};
+use bevy_asset::Assets;
+use bevy_camera::{
+    primitives::Aabb,
+    visibility::{InheritedVisibility, RenderLayers, ViewVisibility, VisibilitySystems},
+    Camera,
+};
 use bevy_ecs::prelude::*;
+use bevy_math::{Rect, Vec2, Vec3, Vec3A};
+use bevy_mesh::{Mesh, Mesh2d, Mesh3d};
 use bevy_reflect::prelude::*;
-use ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastVisibility};
+use bevy_transform::{components::GlobalTransform, TransformSystems};
+use ray_cast::{
+    update_mesh_bvh_cache, update_mesh_ray_cast_scene_bvh, MeshBvhCache, MeshFilter, MeshRayCast,
+    MeshRayCastSceneBvh, MeshRayCastSettings, RayCastVisibility, SimplifiedMesh,
+};
 
 /// An optional component that marks cameras that should be used in the [`MeshPickingPlugin`].
 ///
@@ -69,7 +88,22 @@ pub struct MeshPickingPlugin;
 impl Plugin for MeshPickingPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MeshPickingSettings>()
-            .add_systems(PreUpdate, update_hits.in_set(PickingSystems::Backend));
+            .init_resource::<MeshBvhCache>()
+            .add_systems(
+                PreUpdate,
+                (
+                    update_mesh_bvh_cache.before(PickingSystems::Backend),
+                    update_hits.in_set(PickingSystems::Backend),
+                    update_area_selection_hits.in_set(PickingSystems::Backend),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                update_mesh_ray_cast_scene_bvh
+                    .run_if(resource_exists::<MeshRayCastSceneBvh>)
+                    .after(TransformSystems::Propagate)
+                    .after(VisibilitySystems::CalculateBounds),
+            );
     }
 }
 
@@ -133,3 +167,281 @@ pub fn update_hits(
         }
     }
 }
+
+/// Tests meshes against [`AreaSelectionRequest`]s using [`MeshPickingSettings`] and sends
+/// [`AreaSelectionHits`] messages.
+pub fn update_area_selection_hits(
+    backend_settings: Res<MeshPickingSettings>,
+    mut requests: MessageReader<AreaSelectionRequest>,
+    picking_cameras: Query<(
+        &Camera,
+        &GlobalTransform,
+        Has<MeshPickingCamera>,
+        Option<&RenderLayers>,
+    )>,
+    mesh_query: Query<
+        (
+            Entity,
+            (Option<&Mesh2d>, Option<&Mesh3d>, Option<&SimplifiedMesh>),
+            &GlobalTransform,
+            Option<&Aabb>,
+            &InheritedVisibility,
+            &ViewVisibility,
+            Has<Pickable>,
+            Option<&RenderLayers>,
+        ),
+        MeshFilter,
+    >,
+    meshes: Res<Assets<Mesh>>,
+    mut hits_writer: MessageWriter<AreaSelectionHits>,
+) {
+    for (request, request_id) in requests.read_with_id() {
+        let Ok((camera, camera_transform, cam_can_pick, cam_layers)) =
+            picking_cameras.get(request.camera)
+        else {
+            continue;
+        };
+        if !camera.is_active || (backend_settings.require_markers && !cam_can_pick) {
+            continue;
+        }
+
+        let cam_layers = cam_layers.to_owned().unwrap_or_default();
+        let project = |point: Vec3| camera.world_to_viewport(camera_transform, point).ok();
+
+        let entities = mesh_query
+            .iter()
+            .filter(
+                |(
+                    _,
+                    (mesh2d, mesh3d, simplified_mesh),
+                    transform,
+                    aabb,
+                    inherited,
+                    view,
+                    marked,
+                    layers,
+                )| {
+                    let visible = match backend_settings.ray_cast_visibility {
+                        RayCastVisibility::Any => true,
+                        RayCastVisibility::Visible => inherited.get(),
+                        RayCastVisibility::VisibleInView => view.get(),
+                    };
+                    let marker_requirement = !backend_settings.require_markers || *marked;
+                    // Entities missing render layers are on the default layer 0
+                    let render_layers_match =
+                        cam_layers.intersects(layers.unwrap_or(&RenderLayers::default()));
+                    if !(visible && marker_requirement && render_layers_match) {
+                        return false;
+                    }
+
+                    let Some(mesh) = simplified_mesh
+                        .map(|m| &m.0)
+                        .or(mesh3d.map(|m| &m.0).or(mesh2d.map(|m| &m.0)))
+                        .and_then(|handle| meshes.get(handle))
+                    else {
+                        return false;
+                    };
+
+                    mesh_in_area(
+                        &project,
+                        transform,
+                        *aabb,
+                        mesh,
+                        &request.shape,
+                        request.mode,
+                    )
+                },
+            )
+            .map(|(entity, ..)| entity)
+            .collect::<Vec<_>>();
+
+        if !entities.is_empty() {
+            hits_writer.write(AreaSelectionHits::new(request_id, entities));
+        }
+    }
+}
+
+/// Returns `true` if the projected triangles of `mesh` are selected by `shape`.
+fn mesh_in_area(
+    project: &impl Fn(Vec3) -> Option<Vec2>,
+    transform: &GlobalTransform,
+    aabb: Option<&Aabb>,
+    mesh: &Mesh,
+    shape: &AreaSelectionShape,
+    mode: AreaSelectionMode,
+) -> bool {
+    let transform = transform.affine();
+
+    // The projected corners of the bounds enclose the projected mesh, as long as all of them are
+    // in front of the camera.
+    if let Some(aabb) = aabb {
+        let corners = [-1.0, 1.0]
+            .into_iter()
+            .flat_map(|x| [-1.0, 1.0].into_iter().map(move |y| (x, y)))
+            .flat_map(|(x, y)| [-1.0, 1.0].into_iter().map(move |z| Vec3A::new(x, y, z)))
+            .map(|sign| {
+                project(
+                    transform
+                        .transform_point3a(aabb.center + sign * aabb.half_extents)
+                        .into(),
+                )
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(corners) = corners {
+            let bounds = corners
+                .iter()
+                .fold(Rect::EMPTY, |bounds, corner| bounds.union_point(*corner));
+            let shape_bounds = shape.bounds();
+            if !(bounds.min.cmple(shape_bounds.max).all()
+                && shape_bounds.min.cmple(bounds.max).all())
+            {
+                return false;
+            }
+            // Rectangles are convex, so containing the corners means containing the whole mesh.
+            if mode == AreaSelectionMode::Contain
+                && matches!(shape, AreaSelectionShape::Rect(_))
+                && corners.iter().all(|corner| shape.contains_point(*corner))
+            {
+                return true;
+            }
+        }
+    }
+
+    let Some(positions) = mesh
+        .try_attribute(Mesh::ATTRIBUTE_POSITION)
+        .ok()
+        .and_then(|positions| positions.as_float3())
+    else {
+        return false;
+    };
+    let projected = positions
+        .iter()
+        .map(|position| project(transform.transform_point3(Vec3::from(*position))))
+        .collect::<Vec<_>>();
+    let indices = match mesh.try_indices() {
+        Ok(indices) => indices.iter().collect::<Vec<_>>(),
+        Err(_) => (0..positions.len()).collect(),
+    };
+    let mut triangles = indices.chunks_exact(3).map(|triangle| {
+        let vertex = |i: usize| projected.get(triangle[i]).copied().flatten();
+        Some([vertex(0)?, vertex(1)?, vertex(2)?])
+    });
+
+    match mode {
+        AreaSelectionMode::Intersect => triangles
+            .any(|triangle| triangle.is_some_and(|triangle| shape.intersects_polygon(&triangle))),
+        AreaSelectionMode::Contain => {
+            indices.len() >= 3
+                && triangles.all(|triangle| {
+                    triangle.is_some_and(|triangle| shape.contains_polygon(&triangle))
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_asset::Assets;
+    use bevy_camera::{
+        primitives::MeshAabb,
+        visibility::{InheritedVisibility, RenderLayers, ViewVisibility},
+        Camera, CameraProjection, PerspectiveProjection, RenderTargetInfo,
+    };
+    use bevy_ecs::{message::Messages, prelude::*};
+    use bevy_math::{primitives::Cuboid, Rect, UVec2, Vec3};
+    use bevy_mesh::{Mesh, Mesh3d};
+    use bevy_transform::components::{GlobalTransform, Transform};
+
+    use super::{
+        update_area_selection_hits, MeshPickingCamera, MeshPickingSettings, RayCastVisibility,
+    };
+    use crate::{
+        area::{AreaSelectionHits, AreaSelectionRequest, AreaSelectionShape},
+        Pickable,
+    };
+
+    fn select(app: &mut App, camera: Entity) -> Vec<Entity> {
+        app.world_mut().write_message(AreaSelectionRequest::new(
+            camera,
+            AreaSelectionShape::Rect(Rect::new(300.0, 200.0, 500.0, 400.0)),
+        ));
+        app.update();
+        let mut entities = app
+            .world_mut()
+            .resource_mut::<Messages<AreaSelectionHits>>()
+            .drain()
+            .flat_map(|hits| hits.entities)
+            .collect::<Vec<_>>();
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn mesh_area_selection() {
+        let mut app = App::new();
+        app.add_message::<AreaSelectionRequest>()
+            .add_message::<AreaSelectionHits>()
+            .insert_resource(MeshPickingSettings {
+                require_markers: false,
+                ray_cast_visibility: RayCastVisibility::Visible,
+            })
+            .init_resource::<Assets<Mesh>>()
+            .add_systems(Update, update_area_selection_hits);
+
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(800, 600),
+            scale_factor: 1.0,
+        });
+        let mut projection = PerspectiveProjection::default();
+        projection.update(800.0, 600.0);
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+        let camera = app
+            .world_mut()
+            .spawn((camera, GlobalTransform::IDENTITY))
+            .id();
+
+        let mesh = Mesh::from(Cuboid::from_length(1.0));
+        let aabb = mesh.compute_aabb().unwrap();
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        let mut spawn_cube = |translation: Vec3| {
+            app.world_mut()
+                .spawn((
+                    Mesh3d(mesh.clone()),
+                    aabb,
+                    GlobalTransform::from(Transform::from_translation(translation)),
+                    InheritedVisibility::VISIBLE,
+                    ViewVisibility::HIDDEN,
+                ))
+                .id()
+        };
+        let hit = spawn_cube(Vec3::new(0.0, 0.0, -5.0));
+        let marked = spawn_cube(Vec3::new(0.5, 0.0, -6.0));
+        // Projecting through the camera would mirror this cube into the selection, if it weren't
+        // behind the near plane.
+        spawn_cube(Vec3::new(0.0, 0.0, 5.0));
+        spawn_cube(Vec3::new(10.0, 0.0, -5.0));
+        let other_layer = spawn_cube(Vec3::new(0.0, 0.5, -5.0));
+        app.world_mut()
+            .entity_mut(other_layer)
+            .insert(RenderLayers::layer(1));
+        app.world_mut()
+            .entity_mut(marked)
+            .insert(Pickable::default());
+
+        let mut expected = vec![hit, marked];
+        expected.sort();
+        assert_eq!(select(&mut app, camera), expected);
+
+        app.world_mut()
+            .resource_mut::<MeshPickingSettings>()
+            .require_markers = true;
+        assert_eq!(select(&mut app, camera), vec![]);
+        app.world_mut().entity_mut(camera).insert(MeshPickingCamera);
+        assert_eq!(select(&mut app, camera), vec![marked]);
+
+        app.world_mut().get_mut::<Camera>(camera).unwrap().is_active = false;
+        assert_eq!(select(&mut app, camera), vec![]);
+    }
+}
@@ -0,0 +1,413 @@
+use bevy_asset::{AssetEvent, AssetId, Assets};
+use bevy_camera::primitives::Aabb;
+use bevy_ecs::{entity::EntityHashMap, message::MessageCursor, prelude::*};
+use bevy_math::{
+    bounding::{Aabb3d, Bvh, DynamicBvh, DynamicBvhId},
+    Vec3,
+};
+use bevy_mesh::{Indices, Mesh, Mesh2d, Mesh3d, PrimitiveTopology};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_transform::components::GlobalTransform;
+
+use super::{intersections::triangle_vertices, MeshFilter, SimplifiedMesh};
+
+/// A [`Bvh`] over the triangles of a [`Mesh`], used to speed up [ray casts](super::MeshRayCast)
+/// against dense meshes.
+///
+/// Each primitive of the hierarchy is identified by the index of its triangle.
+#[derive(Clone, Debug)]
+pub struct MeshBvh {
+    bvh: Bvh<Aabb3d>,
+}
+
+impl MeshBvh {
+    /// Builds a hierarchy over the triangles of the given `mesh`.
+    ///
+    /// Returns `None` if the mesh is not a [`PrimitiveTopology::TriangleList`] or has no positions.
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let positions = mesh
+            .try_attribute(Mesh::ATTRIBUTE_POSITION)
+            .ok()?
+            .as_float3()?;
+        let bvh = match mesh.try_indices().ok() {
+            Some(Indices::U16(indices)) => triangle_bvh(positions, Some(indices)),
+            Some(Indices::U32(indices)) => triangle_bvh(positions, Some(indices)),
+            None => triangle_bvh::<u32>(positions, None),
+        };
+        Some(Self { bvh })
+    }
+
+    /// Returns the underlying hierarchy.
+    #[inline]
+    pub fn bvh(&self) -> &Bvh<Aabb3d> {
+        &self.bvh
+    }
+
+    /// Returns the number of triangles of the mesh the hierarchy was built from.
+    #[inline]
+    pub fn triangle_count(&self) -> usize {
+        self.bvh.len()
+    }
+}
+
+fn triangle_bvh<I>(positions: &[[f32; 3]], indices: Option<&[I]>) -> Bvh<Aabb3d>
+where
+    I: TryInto<usize> + Clone + Copy,
+{
+    let triangle_count = indices.map_or(positions.len(), <[I]>::len) / 3;
+    Bvh::new((0..triangle_count).map(|tri_idx| {
+        match triangle_vertices(positions, indices, tri_idx) {
+            Some([a, b, c]) => Aabb3d {
+                min: a.min(b).min(c).into(),
+                max: a.max(b).max(c).into(),
+            },
+            // Malformed triangles are never hit, but still need a volume to keep the indices intact.
+            None => Aabb3d::new(Vec3::ZERO, Vec3::ZERO),
+        }
+    }))
+}
+
+/// Caches a [`MeshBvh`] for every [`Mesh`] asset with at least [`MeshBvhCache::MIN_TRIANGLES`]
+/// triangles, which [`MeshRayCast`](super::MeshRayCast) uses instead of testing every triangle.
+///
+/// The cache is kept up to date by [`update_mesh_bvh_cache`], which is added by
+/// [`MeshPickingPlugin`](crate::mesh_picking::MeshPickingPlugin). Until it has handled the
+/// [`AssetEvent`]s of a mesh, [`MeshBvhCache::get_current`] doesn't return the hierarchy of that
+/// mesh, since it may be outdated.
+#[derive(Resource, Default)]
+pub struct MeshBvhCache {
+    bvhs: HashMap<AssetId<Mesh>, MeshBvh>,
+    /// The position of the cache in the [`AssetEvent`]s of meshes.
+    cursor: MessageCursor<AssetEvent<Mesh>>,
+}
+
+impl MeshBvhCache {
+    /// The minimum number of triangles a mesh needs for a hierarchy to be built for it.
+    /// Testing every triangle of smaller meshes is about as fast.
+    pub const MIN_TRIANGLES: usize = 64;
+
+    /// Returns the hierarchy of the given mesh, if one has been built.
+    ///
+    /// The hierarchy may be outdated if the mesh was modified since it was built. Use
+    /// [`MeshBvhCache::get_current`] to only get up to date hierarchies.
+    pub fn get(&self, id: impl Into<AssetId<Mesh>>) -> Option<&MeshBvh> {
+        self.bvhs.get(&id.into())
+    }
+
+    /// Returns the hierarchy of the given mesh, if one has been built and there are no `messages`
+    /// about the mesh that the cache hasn't handled yet.
+    pub fn get_current(
+        &self,
+        id: impl Into<AssetId<Mesh>>,
+        messages: &Messages<AssetEvent<Mesh>>,
+    ) -> Option<&MeshBvh> {
+        let id = id.into();
+        let bvh = self.bvhs.get(&id)?;
+        let mut cursor = self.cursor.clone();
+        let pending = cursor
+            .read(messages)
+            .any(|event| event.is_added(id) || event.is_modified(id));
+        (!pending).then_some(bvh)
+    }
+}
+
+/// Builds or rebuilds the [`MeshBvh`] of every [`Mesh`] asset that has been added or modified,
+/// and discards those of removed meshes.
+pub fn update_mesh_bvh_cache(
+    mut cache: ResMut<MeshBvhCache>,
+    meshes: Res<Assets<Mesh>>,
+    mesh_asset_events: Res<Messages<AssetEvent<Mesh>>>,
+    mut changed_meshes: Local<HashSet<AssetId<Mesh>>>,
+) {
+    let cache = &mut *cache;
+    for event in cache.cursor.read(&mesh_asset_events) {
+        match *event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                changed_meshes.insert(id);
+            }
+            AssetEvent::Removed { id } => {
+                changed_meshes.remove(&id);
+                cache.bvhs.remove(&id);
+            }
+            _ => {}
+        }
+    }
+
+    for id in changed_meshes.drain() {
+        let bvh = meshes
+            .get(id)
+            .and_then(MeshBvh::from_mesh)
+            .filter(|bvh| bvh.triangle_count() >= MeshBvhCache::MIN_TRIANGLES);
+        match bvh {
+            Some(bvh) => cache.bvhs.insert(id, bvh),
+            None => cache.bvhs.remove(&id),
+        };
+    }
+}
+
+/// A [`DynamicBvh`] over the world-space [`Aabb`]s of every entity that
+/// [`MeshRayCast`](super::MeshRayCast) can hit, which it uses instead of testing the bounds of
+/// every entity.
+///
+/// This is opt-in: [`MeshRayCast`](super::MeshRayCast) only uses the hierarchy if this resource
+/// exists, in which case [`MeshPickingPlugin`](crate::mesh_picking::MeshPickingPlugin) updates
+/// it with [`update_mesh_ray_cast_scene_bvh`] in [`PostUpdate`](bevy_app::PostUpdate), moving
+/// only the entities whose bounds changed. Because of this, ray casts don't see entities that
+/// were spawned or moved after the last update until the next one. Picking isn't affected by
+/// this, since it happens at the start of the frame.
+#[derive(Resource, Default)]
+pub struct MeshRayCastSceneBvh {
+    bvh: DynamicBvh<Aabb3d, Entity>,
+    ids: EntityHashMap<DynamicBvhId>,
+}
+
+impl MeshRayCastSceneBvh {
+    /// Returns the underlying hierarchy, whose volumes hold the entity they bound.
+    #[inline]
+    pub fn bvh(&self) -> &DynamicBvh<Aabb3d, Entity> {
+        &self.bvh
+    }
+
+    /// Inserts or moves the volume of `entity`.
+    fn set(&mut self, entity: Entity, volume: Aabb3d) {
+        match self.ids.get(&entity) {
+            Some(&id) => {
+                self.bvh.update(id, volume);
+            }
+            None => {
+                self.ids.insert(entity, self.bvh.insert(volume, entity));
+            }
+        }
+    }
+
+    /// Removes the volume of `entity`, if it has one.
+    fn remove(&mut self, entity: Entity) {
+        if let Some(id) = self.ids.remove(&entity) {
+            self.bvh.remove(id);
+        }
+    }
+}
+
+/// Updates the volumes in the [`MeshRayCastSceneBvh`] of the entities whose [`Aabb`] or
+/// [`GlobalTransform`] has changed, and removes those that can no longer be hit.
+pub fn update_mesh_ray_cast_scene_bvh(
+    mut scene_bvh: ResMut<MeshRayCastSceneBvh>,
+    changed: Query<
+        (Entity, &Aabb, &GlobalTransform),
+        (
+            MeshFilter,
+            Or<(
+                Changed<Aabb>,
+                Changed<GlobalTransform>,
+                Added<Mesh3d>,
+                Added<Mesh2d>,
+                Added<SimplifiedMesh>,
+            )>,
+        ),
+    >,
+    entities: Query<(Entity, &Aabb, &GlobalTransform), MeshFilter>,
+    mut removed_aabbs: RemovedComponents<Aabb>,
+    mut removed_mesh_3ds: RemovedComponents<Mesh3d>,
+    mut removed_mesh_2ds: RemovedComponents<Mesh2d>,
+    mut removed_simplified_meshes: RemovedComponents<SimplifiedMesh>,
+) {
+    if scene_bvh.is_added() {
+        *scene_bvh = MeshRayCastSceneBvh::default();
+        for (entity, aabb, transform) in &entities {
+            scene_bvh.set(entity, world_aabb(aabb, transform));
+        }
+    } else {
+        for (entity, aabb, transform) in &changed {
+            scene_bvh.set(entity, world_aabb(aabb, transform));
+        }
+    }
+
+    for entity in removed_aabbs
+        .read()
+        .chain(removed_mesh_3ds.read())
+        .chain(removed_mesh_2ds.read())
+        .chain(removed_simplified_meshes.read())
+    {
+        if !entities.contains(entity) {
+            scene_bvh.remove(entity);
+        }
+    }
+}
+
+/// Computes an axis-aligned box containing the given local-space [`Aabb`] after transforming it.
+fn world_aabb(aabb: &Aabb, transform: &GlobalTransform) -> Aabb3d {
+    let affine = transform.affine();
+    Aabb3d::new(
+        affine.transform_point3a(aabb.center),
+        affine.matrix3.abs() * aabb.half_extents,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_asset::{AssetEvent, Assets, RenderAssetUsages};
+    use bevy_ecs::{entity::Entity, message::Messages};
+    use bevy_math::{
+        bounding::{Aabb3d, BoundingVolume},
+        Affine3A, Dir3, Quat, Ray3d, Vec3, Vec3A,
+    };
+    use bevy_mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology};
+    use bevy_transform::components::{GlobalTransform, Transform};
+
+    use super::{
+        update_mesh_bvh_cache, update_mesh_ray_cast_scene_bvh, world_aabb, MeshBvh, MeshBvhCache,
+        MeshRayCastSceneBvh,
+    };
+    use crate::mesh_picking::ray_cast::{intersections::ray_intersection_over_mesh, Backfaces};
+    use bevy_camera::primitives::Aabb;
+
+    /// A strip of 200 triangles along the X axis.
+    fn strip_mesh() -> Mesh {
+        let positions: Vec<[f32; 3]> = (0..=100)
+            .flat_map(|i| [[i as f32, 0.0, 0.0], [i as f32, 1.0, 0.0]])
+            .collect();
+        let indices: Vec<u32> = (0..100)
+            .flat_map(|i| [2 * i, 2 * i + 2, 2 * i + 1, 2 * i + 1, 2 * i + 2, 2 * i + 3])
+            .collect();
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+
+    #[test]
+    fn mesh_bvh() {
+        let mesh = strip_mesh();
+        let bvh = MeshBvh::from_mesh(&mesh).unwrap();
+        assert_eq!(bvh.triangle_count(), 200);
+
+        // The hierarchy finds the same hit as testing every triangle.
+        let ray = Ray3d::new(Vec3::new(42.75, 0.75, 1.0), Dir3::NEG_Z);
+        let transform = Affine3A::from_scale(Vec3::splat(2.0));
+        let hit = ray_intersection_over_mesh(&mesh, &transform, ray, Backfaces::Cull, Some(&bvh));
+        let expected = ray_intersection_over_mesh(&mesh, &transform, ray, Backfaces::Cull, None);
+        assert_eq!(hit.as_ref().and_then(|hit| hit.triangle_index), Some(42));
+        assert_eq!(
+            hit.map(|hit| (hit.triangle_index, hit.point)),
+            expected.map(|hit| (hit.triangle_index, hit.point))
+        );
+
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+        assert!(MeshBvh::from_mesh(&lines).is_none());
+    }
+
+    #[test]
+    fn mesh_bvh_cache_skips_modified_meshes() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<MeshBvhCache>()
+            .add_message::<AssetEvent<Mesh>>()
+            .add_systems(Update, update_mesh_bvh_cache);
+
+        let id = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(strip_mesh())
+            .id();
+        app.world_mut().write_message(AssetEvent::Added { id });
+        app.update();
+
+        let bvh_max_y = |app: &App, current: bool| {
+            let cache = app.world().resource::<MeshBvhCache>();
+            let bvh = if current {
+                cache.get_current(id, app.world().resource::<Messages<AssetEvent<Mesh>>>())
+            } else {
+                cache.get(id)
+            };
+            bvh.and_then(|bvh| bvh.bvh().volume())
+                .map(|volume| volume.max.y)
+        };
+        assert_eq!(bvh_max_y(&app, true), Some(1.0));
+
+        // Moving the vertices keeps the triangle count, but the hierarchy is outdated until the
+        // cache handles the modification.
+        let mut meshes = app.world_mut().resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.get_mut_untracked(id).unwrap();
+        mesh.transform_by(Transform::from_xyz(0.0, 5.0, 0.0));
+        app.world_mut().write_message(AssetEvent::Modified { id });
+        assert_eq!(bvh_max_y(&app, false), Some(1.0));
+        assert_eq!(bvh_max_y(&app, true), None);
+
+        app.update();
+        assert_eq!(bvh_max_y(&app, true), Some(6.0));
+    }
+
+    #[test]
+    fn scene_bvh_tracks_entities() {
+        let mut app = App::new();
+        app.init_resource::<MeshRayCastSceneBvh>()
+            .add_systems(Update, update_mesh_ray_cast_scene_bvh);
+
+        let aabb = Aabb::from_min_max(Vec3::splat(-1.0), Vec3::splat(1.0));
+        let spawn = |app: &mut App, x: f32| {
+            app.world_mut()
+                .spawn((
+                    Mesh3d::default(),
+                    aabb,
+                    GlobalTransform::from_xyz(x, 0.0, 0.0),
+                ))
+                .id()
+        };
+        let hits = |app: &App, x: f32| {
+            let region = Aabb3d::new(Vec3::new(x, 0.0, 0.0), Vec3::splat(0.1));
+            let bvh = app.world().resource::<MeshRayCastSceneBvh>().bvh();
+            let mut hits: Vec<Entity> = bvh.overlapping(&region).map(|(.., &e)| e).collect();
+            hits.sort();
+            hits
+        };
+
+        let a = spawn(&mut app, 0.0);
+        let b = spawn(&mut app, 10.0);
+        app.update();
+        assert_eq!(hits(&app, 0.0), [a]);
+        assert_eq!(hits(&app, 10.0), [b]);
+
+        // Moved, spawned and despawned entities are updated.
+        app.world_mut()
+            .entity_mut(b)
+            .insert(GlobalTransform::from_xyz(20.0, 0.0, 0.0));
+        let c = spawn(&mut app, 10.0);
+        app.world_mut().despawn(a);
+        app.update();
+        assert!(hits(&app, 0.0).is_empty());
+        assert_eq!(hits(&app, 10.0), [c]);
+        assert_eq!(hits(&app, 20.0), [b]);
+
+        // Entities that can no longer be hit are removed.
+        app.world_mut().entity_mut(c).remove::<Aabb>();
+        app.update();
+        assert!(hits(&app, 10.0).is_empty());
+        assert_eq!(app.world().resource::<MeshRayCastSceneBvh>().bvh().len(), 1);
+    }
+
+    #[test]
+    fn world_aabb_contains_transformed_corners() {
+        let aabb = Aabb::from_min_max(Vec3::new(-1.0, 0.0, -2.0), Vec3::new(1.0, 3.0, 2.0));
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(5.0, -1.0, 2.0)
+                .with_rotation(Quat::from_rotation_y(0.7))
+                .with_scale(Vec3::new(2.0, 1.0, 0.5)),
+        );
+        let world = world_aabb(&aabb, &transform).grow(Vec3A::splat(1e-5));
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { 0.0 } else { 3.0 },
+                if i & 4 == 0 { -2.0 } else { 2.0 },
+            );
+            let point = Vec3A::from(transform.transform_point(corner));
+            assert!(point.cmpge(world.min).all() && point.cmple(world.max).all());
+        }
+    }
+}
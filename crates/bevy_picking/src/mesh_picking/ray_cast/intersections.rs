@@ -1,8 +1,11 @@
-use bevy_math::{bounding::Aabb3d, Affine3A, Dir3, Ray3d, Vec2, Vec3, Vec3A};
+use bevy_math::{
+    bounding::{Aabb3d, Bvh, RayCast3d},
+    Affine3A, Dir3, Ray3d, Vec2, Vec3, Vec3A,
+};
 use bevy_mesh::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues};
 use bevy_reflect::Reflect;
 
-use super::Backfaces;
+use super::{Backfaces, MeshBvh};
 
 /// Hit data for an intersection between a ray and a mesh.
 #[derive(Debug, Clone, Reflect)]
@@ -36,11 +39,14 @@ pub struct RayTriangleHit {
 }
 
 /// Casts a ray on a mesh, and returns the intersection.
+///
+/// If a [`MeshBvh`] built from the mesh is given, it is used instead of testing every triangle.
 pub(super) fn ray_intersection_over_mesh(
     mesh: &Mesh,
     transform: &Affine3A,
     ray: Ray3d,
     cull: Backfaces,
+    bvh: Option<&MeshBvh>,
 ) -> Option<RayMeshHit> {
     if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
         return None; // ray_mesh_intersection assumes vertices are laid out in a triangle list
@@ -65,14 +71,38 @@ pub(super) fn ray_intersection_over_mesh(
             _ => None,
         });
 
-    match mesh.try_indices().ok() {
-        Some(Indices::U16(indices)) => {
-            ray_mesh_intersection(ray, transform, positions, normals, Some(indices), uvs, cull)
-        }
-        Some(Indices::U32(indices)) => {
-            ray_mesh_intersection(ray, transform, positions, normals, Some(indices), uvs, cull)
-        }
-        None => ray_mesh_intersection::<u32>(ray, transform, positions, normals, None, uvs, cull),
+    let indices = mesh.try_indices().ok();
+
+    // The hierarchy may be outdated if the mesh was modified since it was built.
+    let triangle_count = indices.map_or(positions.len(), Indices::len) / 3;
+    let bvh = bvh
+        .filter(|bvh| bvh.triangle_count() == triangle_count)
+        .map(MeshBvh::bvh);
+
+    match indices {
+        Some(Indices::U16(indices)) => ray_mesh_intersection_with_bvh(
+            ray,
+            transform,
+            positions,
+            normals,
+            Some(indices),
+            uvs,
+            cull,
+            bvh,
+        ),
+        Some(Indices::U32(indices)) => ray_mesh_intersection_with_bvh(
+            ray,
+            transform,
+            positions,
+            normals,
+            Some(indices),
+            uvs,
+            cull,
+            bvh,
+        ),
+        None => ray_mesh_intersection_with_bvh::<u32>(
+            ray, transform, positions, normals, None, uvs, cull, bvh,
+        ),
     }
 }
 
@@ -86,6 +116,37 @@ pub fn ray_mesh_intersection<I>(
     uvs: Option<&[[f32; 2]]>,
     backface_culling: Backfaces,
 ) -> Option<RayMeshHit>
+where
+    I: TryInto<usize> + Clone + Copy,
+{
+    ray_mesh_intersection_with_bvh(
+        ray,
+        mesh_transform,
+        positions,
+        vertex_normals,
+        indices,
+        uvs,
+        backface_culling,
+        None,
+    )
+}
+
+/// Like [`ray_mesh_intersection`], but uses a hierarchy over the triangles of the mesh to find the
+/// closest one if given.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "it extends `ray_mesh_intersection`, which already takes a lot of them"
+)]
+fn ray_mesh_intersection_with_bvh<I>(
+    ray: Ray3d,
+    mesh_transform: &Affine3A,
+    positions: &[[f32; 3]],
+    vertex_normals: Option<&[[f32; 3]]>,
+    indices: Option<&[I]>,
+    uvs: Option<&[[f32; 2]]>,
+    backface_culling: Backfaces,
+    bvh: Option<&Bvh<Aabb3d>>,
+) -> Option<RayMeshHit>
 where
     I: TryInto<usize> + Clone + Copy,
 {
@@ -96,7 +157,28 @@ where
         Dir3::new(world_to_mesh.transform_vector3(*ray.direction)).ok()?,
     );
 
-    let closest_hit = if let Some(indices) = indices {
+    let closest_hit = if let Some(bvh) = bvh {
+        if indices.is_some_and(|indices| indices.len() % 3 != 0) {
+            return None;
+        }
+
+        let mut closest_hit = None;
+        bvh.cast_ray(
+            &RayCast3d::from_ray(ray, f32::MAX),
+            |tri_idx, closest_distance| {
+                let tri_vertices = triangle_vertices(positions, indices, tri_idx)?;
+                match ray_triangle_intersection(&ray, &tri_vertices, backface_culling) {
+                    Some(hit) if hit.distance >= 0. && hit.distance < closest_distance => {
+                        let distance = hit.distance;
+                        closest_hit = Some((tri_idx, hit));
+                        Some(distance)
+                    }
+                    _ => None,
+                }
+            },
+        );
+        closest_hit
+    } else if let Some(indices) = indices {
         // The index list must be a multiple of three. If not, the mesh is malformed and the raycast
         // result might be nonsensical.
         if indices.len() % 3 != 0 {
@@ -233,6 +315,32 @@ where
     })
 }
 
+/// Returns the vertices of the triangle with the given index, if the mesh data is valid.
+pub(super) fn triangle_vertices<I>(
+    positions: &[[f32; 3]],
+    indices: Option<&[I]>,
+    tri_idx: usize,
+) -> Option<[Vec3; 3]>
+where
+    I: TryInto<usize> + Clone + Copy,
+{
+    let [a, b, c] = match indices {
+        Some(indices) => {
+            let triangle = indices.get(tri_idx * 3..tri_idx * 3 + 3)?;
+            [
+                triangle[0].try_into().ok()?,
+                triangle[1].try_into().ok()?,
+                triangle[2].try_into().ok()?,
+            ]
+        }
+        None => [tri_idx * 3, tri_idx * 3 + 1, tri_idx * 3 + 2],
+    };
+    match [positions.get(a), positions.get(b), positions.get(c)] {
+        [Some(a), Some(b), Some(c)] => Some([Vec3::from(*a), Vec3::from(*b), Vec3::from(*c)]),
+        _ => None,
+    }
+}
+
 /// Takes a ray and triangle and computes the intersection.
 #[inline]
 fn ray_triangle_intersection(
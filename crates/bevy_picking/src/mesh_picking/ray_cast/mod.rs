@@ -2,6 +2,7 @@
 //!
 //! See the [`MeshRayCast`] system parameter for more information.
 
+mod bvh;
 mod intersections;
 
 use bevy_derive::{Deref, DerefMut};
@@ -10,14 +11,21 @@ use bevy_camera::{
     primitives::Aabb,
     visibility::{InheritedVisibility, ViewVisibility},
 };
-use bevy_math::{bounding::Aabb3d, Ray3d};
+use bevy_math::{
+    bounding::{Aabb3d, RayCast3d},
+    Ray3d,
+};
 use bevy_mesh::{Mesh, Mesh2d, Mesh3d};
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 
+pub use bvh::{
+    update_mesh_bvh_cache, update_mesh_ray_cast_scene_bvh, MeshBvh, MeshBvhCache,
+    MeshRayCastSceneBvh,
+};
 use intersections::*;
 pub use intersections::{ray_aabb_intersection_3d, ray_mesh_intersection, RayMeshHit};
 
-use bevy_asset::{Assets, Handle};
+use bevy_asset::{AssetEvent, Assets, Handle};
 use bevy_ecs::{prelude::*, system::lifetimeless::Read, system::SystemParam};
 use bevy_math::FloatOrd;
 use bevy_transform::components::GlobalTransform;
@@ -114,7 +122,7 @@ pub struct RayCastBackfaces;
 #[reflect(Component, Debug, Clone)]
 pub struct SimplifiedMesh(pub Handle<Mesh>);
 
-type MeshFilter = Or<(With<Mesh3d>, With<Mesh2d>, With<SimplifiedMesh>)>;
+pub(crate) type MeshFilter = Or<(With<Mesh3d>, With<Mesh2d>, With<SimplifiedMesh>)>;
 
 /// Add this ray casting [`SystemParam`] to your system to cast rays into the world with an
 /// immediate-mode API. Call `cast_ray` to immediately perform a ray cast and get a result.
@@ -122,6 +130,9 @@ type MeshFilter = Or<(With<Mesh3d>, With<Mesh2d>, With<SimplifiedMesh>)>;
 /// Under the hood, this is a collection of regular bevy queries, resources, and local parameters
 /// that are added to your system.
 ///
+/// Ray casts against meshes use the hierarchies in the [`MeshBvhCache`] if it exists, and the
+/// bounds of entities are tested using the [`MeshRayCastSceneBvh`] if it exists.
+///
 /// ## Usage
 ///
 /// The following system casts a ray into the world with the ray positioned at the origin, pointing in
@@ -174,6 +185,12 @@ pub struct MeshRayCast<'w, 's> {
     #[doc(hidden)]
     pub meshes: Res<'w, Assets<Mesh>>,
     #[doc(hidden)]
+    pub mesh_bvhs: Option<Res<'w, MeshBvhCache>>,
+    #[doc(hidden)]
+    pub mesh_asset_events: Option<Res<'w, Messages<AssetEvent<Mesh>>>>,
+    #[doc(hidden)]
+    pub scene_bvh: Option<Res<'w, MeshRayCastSceneBvh>>,
+    #[doc(hidden)]
     pub hits: Local<'s, Vec<(FloatOrd, (Entity, RayMeshHit))>>,
     #[doc(hidden)]
     pub output: Local<'s, Vec<(Entity, RayMeshHit)>>,
@@ -221,29 +238,58 @@ impl<'w, 's> MeshRayCast<'w, 's> {
         self.culled_list.clear();
         self.output.clear();
 
-        // Check all entities to see if the ray intersects the AABB. Use this to build a short list
-        // of entities that are in the path of the ray.
-        let (aabb_hits_tx, aabb_hits_rx) = crossbeam_channel::unbounded::<(FloatOrd, Entity)>();
         let visibility_setting = settings.visibility;
-        self.culling_query.par_iter().for_each(
-            |(inherited_visibility, view_visibility, aabb, transform, entity)| {
-                let should_ray_cast = match visibility_setting {
-                    RayCastVisibility::Any => true,
-                    RayCastVisibility::Visible => inherited_visibility.get(),
-                    RayCastVisibility::VisibleInView => view_visibility.get(),
+        let should_ray_cast = |inherited_visibility: &InheritedVisibility,
+                               view_visibility: &ViewVisibility| {
+            match visibility_setting {
+                RayCastVisibility::Any => true,
+                RayCastVisibility::Visible => inherited_visibility.get(),
+                RayCastVisibility::VisibleInView => view_visibility.get(),
+            }
+        };
+
+        if let Some(scene_bvh) = &self.scene_bvh {
+            // Only check the entities whose world-space bounds intersect the ray.
+            let ray_cast = RayCast3d::from_ray(ray, f32::MAX);
+            let candidates = scene_bvh
+                .bvh()
+                .query(|volume| ray_cast.aabb_intersection_at(volume).is_some())
+                .map(|(_, _, &entity)| entity);
+            for entity in candidates {
+                let Ok((inherited_visibility, view_visibility, aabb, transform, entity)) =
+                    self.culling_query.get(entity)
+                else {
+                    continue;
                 };
-                if should_ray_cast
+                if should_ray_cast(inherited_visibility, view_visibility)
                     && let Some(distance) = ray_aabb_intersection_3d(
                         ray,
                         &Aabb3d::new(aabb.center, aabb.half_extents),
                         &transform.affine(),
                     )
                 {
-                    aabb_hits_tx.send((FloatOrd(distance), entity)).ok();
+                    self.culled_list.push((FloatOrd(distance), entity));
                 }
-            },
-        );
-        *self.culled_list = aabb_hits_rx.try_iter().collect();
+            }
+        } else {
+            // Check all entities to see if the ray intersects the AABB. Use this to build a short
+            // list of entities that are in the path of the ray.
+            let (aabb_hits_tx, aabb_hits_rx) = crossbeam_channel::unbounded::<(FloatOrd, Entity)>();
+            self.culling_query.par_iter().for_each(
+                |(inherited_visibility, view_visibility, aabb, transform, entity)| {
+                    if should_ray_cast(inherited_visibility, view_visibility)
+                        && let Some(distance) = ray_aabb_intersection_3d(
+                            ray,
+                            &Aabb3d::new(aabb.center, aabb.half_extents),
+                            &transform.affine(),
+                        )
+                    {
+                        aabb_hits_tx.send((FloatOrd(distance), entity)).ok();
+                    }
+                },
+            );
+            self.culled_list.extend(aabb_hits_rx.try_iter());
+        }
 
         // Sort by the distance along the ray.
         self.culled_list.sort_by_key(|(aabb_near, _)| *aabb_near);
@@ -291,7 +337,13 @@ impl<'w, 's> MeshRayCast<'w, 's> {
                 // Perform the actual ray cast.
                 let _ray_cast_guard = ray_cast_guard.enter();
                 let transform = transform.affine();
-                let intersection = ray_intersection_over_mesh(mesh, &transform, ray, backfaces);
+                let bvh = self
+                    .mesh_bvhs
+                    .as_ref()
+                    .zip(self.mesh_asset_events.as_ref())
+                    .and_then(|(mesh_bvhs, events)| mesh_bvhs.get_current(mesh_handle, events));
+                let intersection =
+                    ray_intersection_over_mesh(mesh, &transform, ray, backfaces, bvh);
 
                 if let Some(intersection) = intersection {
                     let distance = FloatOrd(intersection.distance);
@@ -9,6 +9,8 @@
 //!
 //! - The `position` reported in `HitData` in world space, and the `normal` is a normalized
 //!   vector provided by the target's `GlobalTransform::back()`.
+//! - [Area selection](bevy_picking::area) uses the projected bounds of each sprite, regardless of
+//!   [`SpritePickingSettings::picking_mode`].
 
 use crate::{Anchor, Sprite};
 use bevy_app::prelude::*;
@@ -79,8 +81,10 @@ pub struct SpritePickingPlugin;
 
 impl Plugin for SpritePickingPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SpritePickingSettings>()
-            .add_systems(PreUpdate, sprite_picking.in_set(PickingSystems::Backend));
+        app.init_resource::<SpritePickingSettings>().add_systems(
+            PreUpdate,
+            (sprite_picking, sprite_area_selection).in_set(PickingSystems::Backend),
+        );
     }
 }
 
@@ -301,3 +305,181 @@ fn sprite_picking(
         pointer_hits_writer.write(PointerHits::new(pointer, picks, order as f32));
     });
 }
+
+fn sprite_area_selection(
+    mut requests: MessageReader<AreaSelectionRequest>,
+    cameras: Query<(
+        &Camera,
+        &GlobalTransform,
+        Has<SpritePickingCamera>,
+        Option<&RenderLayers>,
+    )>,
+    images: Res<Assets<Image>>,
+    texture_atlas_layout: Res<Assets<TextureAtlasLayout>>,
+    settings: Res<SpritePickingSettings>,
+    sprite_query: Query<
+        (
+            Entity,
+            &Sprite,
+            &GlobalTransform,
+            &Anchor,
+            &ViewVisibility,
+            Option<&RenderLayers>,
+        ),
+        With<Pickable>,
+    >,
+    mut hits_writer: MessageWriter<AreaSelectionHits>,
+) {
+    for (request, request_id) in requests.read_with_id() {
+        let Ok((camera, cam_transform, cam_can_pick, cam_render_layers)) =
+            cameras.get(request.camera)
+        else {
+            continue;
+        };
+        if !camera.is_active || (settings.require_markers && !cam_can_pick) {
+            continue;
+        }
+
+        let entities: Vec<Entity> = sprite_query
+            .iter()
+            .filter(
+                |(_, sprite, sprite_transform, anchor, vis, sprite_render_layers)| {
+                    if !vis.get()
+                        || sprite_transform.affine().is_nan()
+                        || !cam_render_layers
+                            .unwrap_or_default()
+                            .intersects(sprite_render_layers.unwrap_or_default())
+                    {
+                        return false;
+                    }
+
+                    let rect = sprite.compute_local_rect(**anchor, &images, &texture_atlas_layout);
+                    let corners = [
+                        rect.min,
+                        Vec2::new(rect.max.x, rect.min.y),
+                        rect.max,
+                        Vec2::new(rect.min.x, rect.max.y),
+                    ]
+                    .map(|corner| {
+                        camera
+                            .world_to_viewport(
+                                cam_transform,
+                                sprite_transform.transform_point(corner.extend(0.0)),
+                            )
+                            .ok()
+                    });
+                    let Some(corners) = corners.into_iter().collect::<Option<Vec<_>>>() else {
+                        return false;
+                    };
+                    request.shape.selects(request.mode, &corners)
+                },
+            )
+            .map(|(entity, ..)| entity)
+            .collect();
+
+        if !entities.is_empty() {
+            hits_writer.write(AreaSelectionHits::new(request_id, entities));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_asset::Assets;
+    use bevy_camera::{
+        visibility::{RenderLayers, ViewVisibility},
+        Camera, CameraProjection, OrthographicProjection, RenderTargetInfo,
+    };
+    use bevy_ecs::{message::Messages, prelude::*};
+    use bevy_image::{Image, TextureAtlasLayout};
+    use bevy_math::{Rect, UVec2, Vec2, Vec3};
+    use bevy_picking::{
+        area::{AreaSelectionHits, AreaSelectionRequest, AreaSelectionShape},
+        Pickable,
+    };
+    use bevy_transform::components::{GlobalTransform, Transform};
+
+    use super::{sprite_area_selection, SpritePickingCamera, SpritePickingSettings};
+    use crate::Sprite;
+
+    fn select(app: &mut App, camera: Entity) -> Vec<Entity> {
+        app.world_mut().write_message(AreaSelectionRequest::new(
+            camera,
+            AreaSelectionShape::Rect(Rect::new(300.0, 200.0, 500.0, 400.0)),
+        ));
+        app.update();
+        let mut entities = app
+            .world_mut()
+            .resource_mut::<Messages<AreaSelectionHits>>()
+            .drain()
+            .flat_map(|hits| hits.entities)
+            .collect::<Vec<_>>();
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn sprite_area_selection_hits() {
+        let mut app = App::new();
+        app.add_message::<AreaSelectionRequest>()
+            .add_message::<AreaSelectionHits>()
+            .init_resource::<SpritePickingSettings>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<Assets<TextureAtlasLayout>>()
+            .add_systems(Update, sprite_area_selection);
+
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(800, 600),
+            scale_factor: 1.0,
+        });
+        let mut projection = OrthographicProjection::default_2d();
+        projection.update(800.0, 600.0);
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+        let camera = app
+            .world_mut()
+            .spawn((camera, GlobalTransform::IDENTITY))
+            .id();
+
+        let spawn_sprite = |app: &mut App, translation: Vec3| {
+            app.world_mut()
+                .spawn((
+                    Sprite::from_color(bevy_color::Color::WHITE, Vec2::splat(20.0)),
+                    GlobalTransform::from(Transform::from_translation(translation)),
+                    ViewVisibility::VISIBLE,
+                    Pickable::default(),
+                ))
+                .id()
+        };
+        let hit = spawn_sprite(&mut app, Vec3::ZERO);
+        // Outside of the selection.
+        spawn_sprite(&mut app, Vec3::new(300.0, 0.0, 0.0));
+        // Behind the near plane of the camera.
+        spawn_sprite(&mut app, Vec3::new(0.0, 0.0, 2000.0));
+        let other_layer = spawn_sprite(&mut app, Vec3::new(50.0, 0.0, 0.0));
+        app.world_mut()
+            .entity_mut(other_layer)
+            .insert(RenderLayers::layer(1));
+        let hidden = spawn_sprite(&mut app, Vec3::new(0.0, 50.0, 0.0));
+        app.world_mut()
+            .entity_mut(hidden)
+            .insert(ViewVisibility::HIDDEN);
+        let unpickable = spawn_sprite(&mut app, Vec3::new(-50.0, 0.0, 0.0));
+        app.world_mut().entity_mut(unpickable).remove::<Pickable>();
+
+        assert_eq!(select(&mut app, camera), vec![hit]);
+
+        app.world_mut()
+            .resource_mut::<SpritePickingSettings>()
+            .require_markers = true;
+        assert_eq!(select(&mut app, camera), vec![]);
+        app.world_mut()
+            .entity_mut(camera)
+            .insert(SpritePickingCamera);
+        assert_eq!(select(&mut app, camera), vec![hit]);
+
+        app.world_mut().get_mut::<Camera>(camera).unwrap().is_active = false;
+        assert_eq!(select(&mut app, camera), vec![]);
+    }
+}
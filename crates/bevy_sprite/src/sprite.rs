@@ -86,27 +86,7 @@ impl Sprite {
         images: &Assets<Image>,
         texture_atlases: &Assets<TextureAtlasLayout>,
     ) -> Result<Vec2, Vec2> {
-        let image_size = images
-            .get(&self.image)
-            .map(Image::size)
-            .unwrap_or(UVec2::ONE);
-
-        let atlas_rect = self
-            .texture_atlas
-            .as_ref()
-            .and_then(|s| s.texture_rect(texture_atlases))
-            .map(|r| r.as_rect());
-        let texture_rect = match (atlas_rect, self.rect) {
-            (None, None) => Rect::new(0.0, 0.0, image_size.x as f32, image_size.y as f32),
-            (None, Some(sprite_rect)) => sprite_rect,
-            (Some(atlas_rect), None) => atlas_rect,
-            (Some(atlas_rect), Some(mut sprite_rect)) => {
-                // Make the sprite rect relative to the atlas rect.
-                sprite_rect.min += atlas_rect.min;
-                sprite_rect.max += atlas_rect.min;
-                sprite_rect
-            }
-        };
+        let texture_rect = self.texture_rect(images, texture_atlases);
 
         let sprite_size = self.custom_size.unwrap_or_else(|| texture_rect.size());
         let sprite_center = -anchor.as_vec() * sprite_size;
@@ -145,6 +125,48 @@ impl Sprite {
             Err(point_relative_to_texture)
         }
     }
+
+    /// Computes the bounds of this sprite in its local frame, taking its `anchor` into account.
+    pub fn compute_local_rect(
+        &self,
+        anchor: Anchor,
+        images: &Assets<Image>,
+        texture_atlases: &Assets<TextureAtlasLayout>,
+    ) -> Rect {
+        let sprite_size = self
+            .custom_size
+            .unwrap_or_else(|| self.texture_rect(images, texture_atlases).size());
+        Rect::from_center_size(-anchor.as_vec() * sprite_size, sprite_size)
+    }
+
+    /// The region of the image sampled by this sprite, in pixels.
+    fn texture_rect(
+        &self,
+        images: &Assets<Image>,
+        texture_atlases: &Assets<TextureAtlasLayout>,
+    ) -> Rect {
+        let image_size = images
+            .get(&self.image)
+            .map(Image::size)
+            .unwrap_or(UVec2::ONE);
+
+        let atlas_rect = self
+            .texture_atlas
+            .as_ref()
+            .and_then(|s| s.texture_rect(texture_atlases))
+            .map(|r| r.as_rect());
+        match (atlas_rect, self.rect) {
+            (None, None) => Rect::new(0.0, 0.0, image_size.x as f32, image_size.y as f32),
+            (None, Some(sprite_rect)) => sprite_rect,
+            (Some(atlas_rect), None) => atlas_rect,
+            (Some(atlas_rect), Some(mut sprite_rect)) => {
+                // Make the sprite rect relative to the atlas rect.
+                sprite_rect.min += atlas_rect.min;
+                sprite_rect.max += atlas_rect.min;
+                sprite_rect
+            }
+        }
+    }
 }
 
 impl From<Handle<Image>> for Sprite {
@@ -601,4 +623,34 @@ mod tests {
         // The pixel is outside the texture atlas, but is still a valid pixel in the image.
         assert_eq!(compute(Vec2::new(0.0, 35.0)), Err(Vec2::new(0.0, -35.0)));
     }
+
+    #[test]
+    fn compute_local_rect() {
+        let mut image_assets = Assets::<Image>::default();
+        let texture_atlas_assets = Assets::<TextureAtlasLayout>::default();
+
+        let image = image_assets.add(make_image(UVec2::new(5, 10)));
+
+        let sprite = Sprite {
+            image,
+            ..Default::default()
+        };
+        assert_eq!(
+            sprite.compute_local_rect(Anchor::default(), &image_assets, &texture_atlas_assets),
+            Rect::new(-2.5, -5.0, 2.5, 5.0)
+        );
+        assert_eq!(
+            sprite.compute_local_rect(Anchor::BOTTOM_LEFT, &image_assets, &texture_atlas_assets),
+            Rect::new(0.0, 0.0, 5.0, 10.0)
+        );
+
+        let sprite = Sprite {
+            custom_size: Some(Vec2::new(4.0, 2.0)),
+            ..sprite
+        };
+        assert_eq!(
+            sprite.compute_local_rect(Anchor::TOP_RIGHT, &image_assets, &texture_atlas_assets),
+            Rect::new(-4.0, -2.0, 0.0, 0.0)
+        );
+    }
 }
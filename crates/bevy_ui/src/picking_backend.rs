@@ -20,12 +20,17 @@
 //! - The `position` reported in `HitData` is normalized relative to the node, with
 //!   `(-0.5, -0.5, 0.)` at the top left and `(0.5, 0.5, 0.)` in the bottom right. Coordinates are
 //!   relative to the entire node, not just the visible region. This backend does not provide a `normal`.
+//! - [Area selection](bevy_picking::area) tests the rect of each node, ignoring rounded corners and
+//!   text sections. Clipping is only applied exactly to nodes that are not rotated; rotated nodes
+//!   are only skipped when they are clipped entirely.
 
-use crate::{clip_check_recursive, prelude::*, ui_transform::UiGlobalTransform, UiStack};
+use crate::{
+    clip_check_recursive, prelude::*, ui_transform::UiGlobalTransform, CalculatedClip, UiStack,
+};
 use bevy_app::prelude::*;
 use bevy_camera::{visibility::InheritedVisibility, Camera, RenderTarget};
 use bevy_ecs::{prelude::*, query::QueryData};
-use bevy_math::Vec2;
+use bevy_math::{Rect, Vec2};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use bevy_text::{ComputedTextBlock, TextLayoutInfo};
@@ -76,8 +81,10 @@ impl Default for UiPickingSettings {
 pub struct UiPickingPlugin;
 impl Plugin for UiPickingPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<UiPickingSettings>()
-            .add_systems(PreUpdate, ui_picking.in_set(PickingSystems::Backend));
+        app.init_resource::<UiPickingSettings>().add_systems(
+            PreUpdate,
+            (ui_picking, ui_area_selection).in_set(PickingSystems::Backend),
+        );
     }
 }
 
@@ -279,6 +286,84 @@ pub fn ui_picking(
     }
 }
 
+/// Computes the UI node entities inside each [`AreaSelectionRequest`].
+pub fn ui_area_selection(
+    mut requests: MessageReader<AreaSelectionRequest>,
+    camera_query: Query<(&Camera, Has<UiPickingCamera>)>,
+    settings: Res<UiPickingSettings>,
+    node_query: Query<(
+        Entity,
+        &ComputedNode,
+        &UiGlobalTransform,
+        &ComputedUiTargetCamera,
+        Option<&InheritedVisibility>,
+        Has<Pickable>,
+        Option<&CalculatedClip>,
+    )>,
+    mut output: MessageWriter<AreaSelectionHits>,
+) {
+    for (request, request_id) in requests.read_with_id() {
+        let Ok((camera, cam_can_pick)) = camera_query.get(request.camera) else {
+            continue;
+        };
+        if !camera.is_active || (settings.require_markers && !cam_can_pick) {
+            continue;
+        }
+        // Nodes are laid out in physical pixels, while the request is in logical pixels.
+        let scale_factor = camera.target_scaling_factor().unwrap_or(1.);
+
+        let entities: Vec<Entity> = node_query
+            .iter()
+            .filter(
+                |(_, node, transform, target_camera, inherited_visibility, pickable, clip)| {
+                    // Nodes with Display::None have a (0., 0.) logical rect and can be ignored
+                    if target_camera.get() != Some(request.camera)
+                        || node.size() == Vec2::ZERO
+                        || !inherited_visibility.is_some_and(|visibility| visibility.get())
+                        || (settings.require_markers && !pickable)
+                    {
+                        return false;
+                    }
+
+                    let corners = [
+                        Vec2::new(-0.5, -0.5),
+                        Vec2::new(0.5, -0.5),
+                        Vec2::new(0.5, 0.5),
+                        Vec2::new(-0.5, 0.5),
+                    ]
+                    .map(|corner| transform.transform_point2(corner * node.size()));
+                    let bounds = Rect::from_corners(corners[0], corners[2])
+                        .union(Rect::from_corners(corners[1], corners[3]));
+
+                    let polygon = match clip {
+                        Some(clip) if clip.clip.intersect(bounds).is_empty() => return false,
+                        Some(clip)
+                            if transform.matrix2.x_axis.y == 0.
+                                && transform.matrix2.y_axis.x == 0. =>
+                        {
+                            let visible = clip.clip.intersect(bounds);
+                            [
+                                visible.min,
+                                Vec2::new(visible.max.x, visible.min.y),
+                                visible.max,
+                                Vec2::new(visible.min.x, visible.max.y),
+                            ]
+                        }
+                        _ => corners,
+                    }
+                    .map(|corner| corner / scale_factor);
+                    request.shape.selects(request.mode, &polygon)
+                },
+            )
+            .map(|(entity, ..)| entity)
+            .collect();
+
+        if !entities.is_empty() {
+            output.write(AreaSelectionHits::new(request_id, entities));
+        }
+    }
+}
+
 fn pick_ui_text_section(
     uinode: &ComputedNode,
     global_transform: &UiGlobalTransform,
@@ -301,3 +386,109 @@ fn pick_ui_text_section(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_camera::{visibility::InheritedVisibility, Camera, RenderTargetInfo};
+    use bevy_ecs::{message::Messages, prelude::*};
+    use bevy_math::{Rect, UVec2, Vec2};
+    use bevy_picking::{
+        area::{AreaSelectionHits, AreaSelectionRequest, AreaSelectionShape},
+        Pickable,
+    };
+
+    use super::{ui_area_selection, UiPickingCamera, UiPickingSettings};
+    use crate::{CalculatedClip, ComputedNode, ComputedUiTargetCamera, UiGlobalTransform};
+
+    fn select(app: &mut App, camera: Entity) -> Vec<Entity> {
+        app.world_mut().write_message(AreaSelectionRequest::new(
+            camera,
+            AreaSelectionShape::Rect(Rect::new(100.0, 100.0, 200.0, 200.0)),
+        ));
+        app.update();
+        let mut entities = app
+            .world_mut()
+            .resource_mut::<Messages<AreaSelectionHits>>()
+            .drain()
+            .flat_map(|hits| hits.entities)
+            .collect::<Vec<_>>();
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn ui_area_selection_hits() {
+        let mut app = App::new();
+        app.add_message::<AreaSelectionRequest>()
+            .add_message::<AreaSelectionHits>()
+            .init_resource::<UiPickingSettings>()
+            .add_systems(Update, ui_area_selection);
+
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(800, 600),
+            scale_factor: 2.0,
+        });
+        let camera = app.world_mut().spawn(camera).id();
+        let other_camera = app.world_mut().spawn(Camera::default()).id();
+
+        // Nodes are positioned and sized in physical pixels.
+        let spawn_node = |app: &mut App, center: Vec2, size: f32| {
+            app.world_mut()
+                .spawn((
+                    ComputedNode {
+                        size: Vec2::splat(size),
+                        ..ComputedNode::DEFAULT
+                    },
+                    UiGlobalTransform::from_translation(center),
+                    ComputedUiTargetCamera { camera },
+                    InheritedVisibility::VISIBLE,
+                    Pickable::default(),
+                ))
+                .id()
+        };
+        let hit = spawn_node(&mut app, Vec2::splat(300.0), 40.0);
+        // Inside the selection in physical pixels, but not in logical pixels.
+        spawn_node(&mut app, Vec2::splat(150.0), 20.0);
+        // Overlaps the selection, but the overlapping part is clipped.
+        let clipped = spawn_node(&mut app, Vec2::new(450.0, 300.0), 200.0);
+        app.world_mut().entity_mut(clipped).insert(CalculatedClip {
+            clip: Rect::new(420.0, 0.0, 800.0, 600.0),
+        });
+        let other_target = spawn_node(&mut app, Vec2::splat(300.0), 40.0);
+        app.world_mut()
+            .entity_mut(other_target)
+            .insert(ComputedUiTargetCamera {
+                camera: other_camera,
+            });
+        let hidden = spawn_node(&mut app, Vec2::splat(300.0), 40.0);
+        app.world_mut()
+            .entity_mut(hidden)
+            .insert(InheritedVisibility::HIDDEN);
+        let unmarked = spawn_node(&mut app, Vec2::splat(320.0), 40.0);
+
+        let mut expected = vec![hit, unmarked];
+        expected.sort();
+        assert_eq!(select(&mut app, camera), expected);
+        app.world_mut()
+            .entity_mut(clipped)
+            .remove::<CalculatedClip>();
+        expected.push(clipped);
+        expected.sort();
+        assert_eq!(select(&mut app, camera), expected);
+
+        app.world_mut().entity_mut(unmarked).remove::<Pickable>();
+        app.world_mut()
+            .resource_mut::<UiPickingSettings>()
+            .require_markers = true;
+        assert_eq!(select(&mut app, camera), vec![]);
+        app.world_mut().entity_mut(camera).insert(UiPickingCamera);
+        let mut expected = vec![hit, clipped];
+        expected.sort();
+        assert_eq!(select(&mut app, camera), expected);
+
+        app.world_mut().get_mut::<Camera>(camera).unwrap().is_active = false;
+        assert_eq!(select(&mut app, camera), vec![]);
+    }
+}